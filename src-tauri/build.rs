@@ -1,3 +1,13 @@
+/// Ge'ez font copied into the app's resources; see fonts/README.md.
+const BUNDLED_FONT: &str = "fonts/AbyssinicaSIL-Regular.ttf";
+
 fn main() {
+    println!("cargo:rerun-if-changed={BUNDLED_FONT}");
+    // Debug builds fall back to installed fonts; a release shouldn't ship without it
+    if std::env::var("PROFILE").as_deref() == Ok("release")
+        && !std::path::Path::new(BUNDLED_FONT).is_file()
+    {
+        println!("cargo:warning={BUNDLED_FONT} is missing, so Ge'ez text may render as boxes on machines without an Ethiopic font; see fonts/README.md");
+    }
     tauri_build::build()
 }
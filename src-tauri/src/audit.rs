@@ -0,0 +1,67 @@
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, SqliteExecutor};
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::db;
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub id: i64,
+    pub at: String,
+    pub operation: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub detail_json: String,
+}
+
+/// Append an entry to `audit_log`. Best-effort: a failure is reported on
+/// stderr and never fails the operation being logged.
+///
+/// Pass the operation's transaction when it has one, so a rolled-back change
+/// leaves no entry behind.
+pub async fn record<'e>(
+    executor: impl SqliteExecutor<'e>,
+    operation: &str,
+    entity_type: &str,
+    entity_id: &str,
+    detail: Value,
+) {
+    let result = sqlx::query(
+        "INSERT INTO audit_log (at, operation, entity_type, entity_id, detail_json)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(db::now())
+    .bind(operation)
+    .bind(entity_type)
+    .bind(entity_id)
+    .bind(detail.to_string())
+    .execute(executor)
+    .await;
+    if let Err(e) = result {
+        eprintln!("Failed to write audit entry for {operation} {entity_type} {entity_id}: {e}");
+    }
+}
+
+/// Audit entries, newest first, optionally only those about `entity_id`.
+#[tauri::command]
+pub async fn get_audit_log(
+    db: State<'_, DbInstances>,
+    entity_id: Option<String>,
+    limit: u32,
+) -> Result<Vec<AuditEntry>, String> {
+    let pool = db::pool(&db).await?;
+    sqlx::query_as::<_, AuditEntry>(
+        "SELECT * FROM audit_log
+         WHERE ?1 IS NULL OR entity_id = ?1
+         ORDER BY id DESC
+         LIMIT ?2",
+    )
+    .bind(entity_id)
+    .bind(limit)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,229 @@
+pub mod scheduler;
+
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::json;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection, SqlitePool};
+use tauri::{AppHandle, State};
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::audit;
+use crate::db;
+
+/// Write a transactionally consistent copy of `kidase.db` to `dest_path`.
+///
+/// Uses `VACUUM INTO` rather than copying bytes, which is unsafe while the
+/// database is open. An existing file is only replaced when `overwrite` is set.
+#[tauri::command]
+pub async fn backup_database(
+    db: State<'_, DbInstances>,
+    dest_path: String,
+    overwrite: Option<bool>,
+) -> Result<(), String> {
+    let pool = db::pool(&db).await?;
+    vacuum_into(&pool, Path::new(&dest_path), overwrite.unwrap_or(false)).await
+}
+
+pub async fn vacuum_into(pool: &SqlitePool, dest: &Path, overwrite: bool) -> Result<(), String> {
+    if dest.exists() && !overwrite {
+        return Err(format!("{} already exists", dest.display()));
+    }
+
+    // VACUUM INTO refuses to write over a file, so snapshot beside it and swap
+    let tmp = dest.with_extension("backup-tmp");
+    if tmp.exists() {
+        std::fs::remove_file(&tmp).map_err(|e| e.to_string())?;
+    }
+    sqlx::query("VACUUM INTO ?")
+        .bind(tmp.to_string_lossy().as_ref())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Backup failed: {e}"))?;
+    std::fs::rename(&tmp, dest).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        format!("Failed to write {}: {e}", dest.display())
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreSummary {
+    /// Where the database as it was before the restore was saved.
+    pub safety_copy: String,
+    /// Schema version of the restored database; the frontend's next load
+    /// migrates it if it's behind.
+    pub schema_version: i64,
+}
+
+/// Replace `kidase.db` with the backup at `src_path`.
+///
+/// The backup must pass `PRAGMA integrity_check` and not be from a newer
+/// schema, or nothing changes. The current database is first saved beside
+/// itself as `kidase.pre-restore.db`. The plugin's pool is then closed, the
+/// file swapped and a new pool opened in its place; the frontend should
+/// reload so it runs any pending migrations.
+#[tauri::command]
+pub async fn restore_database(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    src_path: String,
+) -> Result<RestoreSummary, String> {
+    restore_from(&app, &db, Path::new(&src_path)).await
+}
+
+/// What [`restore_database`] does, for callers that have already found the
+/// backup.
+pub async fn restore_from(
+    app: &AppHandle,
+    db: &DbInstances,
+    src: &Path,
+) -> Result<RestoreSummary, String> {
+    if !src.is_file() {
+        return Err(format!("{} does not exist", src.display()));
+    }
+    let path = db::db_path(app)?;
+    let pool = db::writable_pool(db).await?;
+
+    // Checked copy beside the live file, so the swap is a rename
+    let staged = path.with_extension("restore-tmp");
+    let schema_version = stage(src, &staged).await.inspect_err(|_| {
+        let _ = std::fs::remove_file(&staged);
+    })?;
+    let safety_copy = path.with_extension("pre-restore.db");
+    if let Err(e) = vacuum_into(&pool, &safety_copy, true).await {
+        let _ = std::fs::remove_file(&staged);
+        return Err(e);
+    }
+
+    let mut instances = db.0.write().await;
+    if let Some(DbPool::Sqlite(pool)) = instances.remove(db::DB_URL) {
+        pool.close().await;
+    }
+    let swapped = swap(&staged, &path);
+    // Reopened either way: on failure the old file is still in place
+    let reopened = SqlitePool::connect_with(db::connect_options(&path))
+        .await
+        .map_err(|e| format!("Failed to reopen {}: {e}", path.display()))?;
+    instances.insert(db::DB_URL.to_string(), DbPool::Sqlite(reopened.clone()));
+    drop(instances);
+    swapped?;
+
+    let detail = json!({ "source": src, "safetyCopy": safety_copy });
+    audit::record(&reopened, "restore", "database", db::DB_URL, detail).await;
+    Ok(RestoreSummary {
+        safety_copy: safety_copy.to_string_lossy().into_owned(),
+        schema_version,
+    })
+}
+
+/// Check the backup at `src` and write a compacted copy of it to `dest`,
+/// returning its schema version.
+async fn stage(src: &Path, dest: &Path) -> Result<i64, String> {
+    let options = SqliteConnectOptions::new().filename(src).read_only(true);
+    let mut connection = SqliteConnection::connect_with(&options)
+        .await
+        .map_err(|e| format!("{} is not a database: {e}", src.display()))?;
+
+    let problems: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_all(&mut connection)
+        .await
+        .map_err(|e| format!("{} is not a database: {e}", src.display()))?;
+    if problems != ["ok"] {
+        return Err(format!(
+            "{} failed its integrity check: {}",
+            src.display(),
+            problems.join("; ")
+        ));
+    }
+    let version = db::stored_schema_version(&mut connection).await?;
+    let expected = crate::maintenance::get_expected_schema_version();
+    if version > expected {
+        return Err(format!(
+            "{} is at schema version {version} but this build only knows up to {expected}",
+            src.display()
+        ));
+    }
+
+    if dest.exists() {
+        std::fs::remove_file(dest).map_err(|e| e.to_string())?;
+    }
+    sqlx::query("VACUUM INTO ?")
+        .bind(dest.to_string_lossy().as_ref())
+        .execute(&mut connection)
+        .await
+        .map_err(|e| format!("Failed to copy {}: {e}", src.display()))?;
+    connection.close().await.map_err(|e| e.to_string())?;
+    Ok(version)
+}
+
+/// Put `staged` in place of the database at `path`, dropping the old WAL so
+/// none of it replays over the restored file.
+fn swap(staged: &Path, path: &Path) -> Result<(), String> {
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        let sidecar = Path::new(&sidecar);
+        if sidecar.exists() {
+            std::fs::remove_file(sidecar)
+                .map_err(|e| format!("Failed to remove {}: {e}", sidecar.display()))?;
+        }
+    }
+    std::fs::rename(staged, path).map_err(|e| {
+        let _ = std::fs::remove_file(staged);
+        format!("Failed to replace {}: {e}", path.display())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    async fn count(connection: &mut SqliteConnection, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+            .fetch_one(connection)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn a_backup_holds_the_same_rows() {
+        let pool = test_support::pool().await;
+        for id in ["p1", "p2", "p3"] {
+            test_support::presentation(&pool, id).await;
+            test_support::slide(&pool, &format!("{id}-s1"), id, 1).await;
+        }
+        let dest = test_support::temp_path("kidase-backup.db");
+        vacuum_into(&pool, &dest, false).await.unwrap();
+
+        let options = SqliteConnectOptions::new().filename(&dest).read_only(true);
+        let mut backup = SqliteConnection::connect_with(&options).await.unwrap();
+        let mut live = pool.acquire().await.unwrap();
+        for table in ["presentations", "slides", "templates"] {
+            assert_eq!(
+                count(&mut backup, table).await,
+                count(&mut live, table).await,
+                "{table}"
+            );
+        }
+        assert_eq!(count(&mut backup, "presentations").await, 3);
+    }
+
+    #[tokio::test]
+    async fn an_existing_file_is_only_replaced_on_request() {
+        let pool = test_support::pool().await;
+        let dest = test_support::temp_path("kidase-backup.db");
+        std::fs::write(&dest, b"not a backup").unwrap();
+
+        let error = vacuum_into(&pool, &dest, false).await.unwrap_err();
+        assert!(error.contains("already exists"), "{error}");
+        assert_eq!(std::fs::read(&dest).unwrap(), b"not a backup");
+
+        vacuum_into(&pool, &dest, true).await.unwrap();
+        assert!(std::fs::read(&dest)
+            .unwrap()
+            .starts_with(b"SQLite format 3"));
+    }
+}
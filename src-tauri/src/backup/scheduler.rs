@@ -0,0 +1,419 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_sql::DbInstances;
+
+use super::{restore_from, vacuum_into, RestoreSummary};
+use crate::db;
+
+/// `app_settings` keys configuring automatic backups.
+const INTERVAL_KEY: &str = "backupIntervalHours";
+const DIR_KEY: &str = "backupDir";
+const KEEP_KEY: &str = "backupKeepCount";
+const ON_EXIT_KEY: &str = "backupOnExit";
+const DEFAULT_KEEP_COUNT: u32 = 10;
+
+/// How often the scheduler checks whether a backup is due.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// Pause after a failed backup so a bad folder isn't retried every poll.
+const RETRY_DELAY: Duration = Duration::from_secs(15 * 60);
+
+/// Automatic backups are `kidase-<UTC timestamp>.db`; nothing else in the
+/// folder is ever pruned.
+const FILE_PREFIX: &str = "kidase-";
+const FILE_SUFFIX: &str = ".db";
+
+/// Event emitted after each automatic backup, carrying its [`BackupInfo`].
+pub const BACKUP_COMPLETED: &str = "backup-completed";
+/// Event emitted when an automatic backup fails, carrying the error.
+pub const BACKUP_FAILED: &str = "backup-failed";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoBackupConfig {
+    /// Hours between backups; 0 turns automatic backups off.
+    pub interval_hours: u32,
+    pub backup_dir: Option<String>,
+    /// Automatic backups kept in `backup_dir`; older ones are deleted.
+    pub keep_count: u32,
+    /// Also back up when the app closes, whatever the interval.
+    pub on_exit: bool,
+}
+
+/// An automatic backup in the configured folder.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    /// The file name, which `restore_backup` takes.
+    pub id: String,
+    pub path: String,
+    pub size: u64,
+    pub modified: Option<String>,
+}
+
+/// Background thread taking rolling backups, started from `run()`'s setup and
+/// stopped when the app exits.
+#[derive(Default)]
+pub struct BackupScheduler {
+    stopping: Mutex<bool>,
+    wake: Condvar,
+    /// Set while a backup runs, scheduled or manual, so two never overlap.
+    busy: AtomicBool,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// Clears [`BackupScheduler::busy`] however the backup ends.
+struct Busy<'a>(&'a AtomicBool);
+
+impl Drop for Busy<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl BackupScheduler {
+    pub fn start(app: &AppHandle) -> Result<(), String> {
+        let handle = app.clone();
+        let worker = std::thread::Builder::new()
+            .name("auto-backup".to_string())
+            .spawn(move || run_loop(handle))
+            .map_err(|e| format!("Failed to start automatic backups: {e}"))?;
+        *app.state::<BackupScheduler>()
+            .worker
+            .lock()
+            .map_err(|e| e.to_string())? = Some(worker);
+        Ok(())
+    }
+
+    /// Stop the thread, waiting for a backup in progress to finish.
+    pub fn shutdown(&self) {
+        if let Ok(mut stopping) = self.stopping.lock() {
+            *stopping = true;
+        }
+        self.wake.notify_all();
+        let worker = self.worker.lock().ok().and_then(|mut worker| worker.take());
+        if let Some(worker) = worker {
+            let _ = worker.join();
+        }
+    }
+
+    /// Take a backup before the app closes when `backupOnExit` is set. Called
+    /// while the database is still open; a failure is only reported.
+    pub fn backup_on_exit(&self, app: &AppHandle) {
+        let result = tauri::async_runtime::block_on(async {
+            let Ok(pool) = db::pool(&app.state::<DbInstances>()).await else {
+                return Ok(None);
+            };
+            let config = load_config(&pool).await?;
+            let Some(dir) = config.backup_dir.filter(|_| config.on_exit) else {
+                return Ok(None);
+            };
+            let busy = self.begin()?;
+            run_backup(&pool, Path::new(&dir), config.keep_count, busy)
+                .await
+                .map(Some)
+        });
+        report(app, result);
+    }
+
+    /// Re-check the schedule now rather than at the next poll.
+    fn nudge(&self) {
+        self.wake.notify_all();
+    }
+
+    /// Sleep for up to `timeout`. False once the scheduler is shutting down.
+    fn wait(&self, timeout: Duration) -> bool {
+        let Ok(stopping) = self.stopping.lock() else {
+            return false;
+        };
+        if *stopping {
+            return false;
+        }
+        match self.wake.wait_timeout(stopping, timeout) {
+            Ok((stopping, _)) => !*stopping,
+            Err(_) => false,
+        }
+    }
+
+    fn begin(&self) -> Result<Busy<'_>, String> {
+        if self.busy.swap(true, Ordering::AcqRel) {
+            return Err("A backup is already running".to_string());
+        }
+        Ok(Busy(&self.busy))
+    }
+}
+
+fn run_loop(app: AppHandle) {
+    let scheduler = app.state::<BackupScheduler>();
+    while scheduler.wait(POLL_INTERVAL) {
+        let result = tauri::async_runtime::block_on(backup_if_due(&app, &scheduler));
+        if !report(&app, result) && !scheduler.wait(RETRY_DELAY) {
+            break;
+        }
+    }
+}
+
+/// Emit [`BACKUP_COMPLETED`] or [`BACKUP_FAILED`] for a backup that ran,
+/// returning false if it failed.
+fn report(app: &AppHandle, result: Result<Option<PathBuf>, String>) -> bool {
+    match result.and_then(|dest| dest.map(|dest| info(&dest)).transpose()) {
+        Ok(Some(backup)) => {
+            let _ = app.emit(BACKUP_COMPLETED, &backup);
+            true
+        }
+        Ok(None) => true,
+        Err(e) => {
+            eprintln!("Automatic backup failed: {e}");
+            let _ = app.emit(BACKUP_FAILED, &e);
+            false
+        }
+    }
+}
+
+async fn backup_if_due(
+    app: &AppHandle,
+    scheduler: &BackupScheduler,
+) -> Result<Option<PathBuf>, String> {
+    // Nothing to back up until the frontend has opened the database
+    let Ok(pool) = db::pool(&app.state::<DbInstances>()).await else {
+        return Ok(None);
+    };
+    let config = load_config(&pool).await?;
+    let Some(dir) = config
+        .backup_dir
+        .as_deref()
+        .filter(|_| config.interval_hours > 0)
+    else {
+        return Ok(None);
+    };
+
+    let interval = Duration::from_secs(u64::from(config.interval_hours) * 60 * 60);
+    let dir = Path::new(dir);
+    let last = backups(dir)?
+        .into_iter()
+        .filter_map(|(_, modified)| modified)
+        .max();
+    if last.is_some_and(|last| last.elapsed().unwrap_or_default() < interval) {
+        return Ok(None);
+    }
+    match scheduler.begin() {
+        Ok(busy) => run_backup(&pool, dir, config.keep_count, busy)
+            .await
+            .map(Some),
+        // A manual backup is underway; it counts as this one
+        Err(_) => Ok(None),
+    }
+}
+
+/// Snapshot into a new timestamped file in `dir`, then prune past `keep_count`.
+async fn run_backup(
+    pool: &SqlitePool,
+    dir: &Path,
+    keep_count: u32,
+    _busy: Busy<'_>,
+) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let stamp = Utc::now().format("%Y%m%d-%H%M%S-%3f");
+    let dest = dir.join(format!("{FILE_PREFIX}{stamp}{FILE_SUFFIX}"));
+    vacuum_into(pool, &dest, false).await?;
+
+    let mut existing = backups(dir)?;
+    existing.sort_by(|a, b| b.0.cmp(&a.0));
+    for (path, _) in existing.into_iter().skip(keep_count.max(1) as usize) {
+        std::fs::remove_file(&path)
+            .map_err(|e| format!("Failed to remove old backup {}: {e}", path.display()))?;
+    }
+    Ok(dest)
+}
+
+/// Automatic backups in `dir` with their modification times. A missing folder
+/// has none.
+fn backups(dir: &Path) -> Result<Vec<(PathBuf, Option<SystemTime>)>, String> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {e}", dir.display())),
+    };
+    Ok(entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with(FILE_PREFIX) && name.ends_with(FILE_SUFFIX)
+        })
+        .map(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).ok();
+            (entry.path(), modified)
+        })
+        .collect())
+}
+
+fn info(path: &Path) -> Result<BackupInfo, String> {
+    let metadata =
+        std::fs::metadata(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    Ok(BackupInfo {
+        id: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        path: path.to_string_lossy().into_owned(),
+        size: metadata.len(),
+        modified: metadata.modified().ok().map(|modified| {
+            DateTime::<Utc>::from(modified).to_rfc3339_opts(SecondsFormat::Millis, true)
+        }),
+    })
+}
+
+async fn load_config(pool: &SqlitePool) -> Result<AutoBackupConfig, String> {
+    let rows: Vec<(String, String)> =
+        sqlx::query_as("SELECT key, value FROM app_settings WHERE key IN (?, ?, ?, ?)")
+            .bind(INTERVAL_KEY)
+            .bind(DIR_KEY)
+            .bind(KEEP_KEY)
+            .bind(ON_EXIT_KEY)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let mut config = AutoBackupConfig {
+        interval_hours: 0,
+        backup_dir: None,
+        keep_count: DEFAULT_KEEP_COUNT,
+        on_exit: false,
+    };
+    for (key, value) in rows {
+        let value = value.trim();
+        match key.as_str() {
+            INTERVAL_KEY => config.interval_hours = value.parse().unwrap_or(0),
+            DIR_KEY if !value.is_empty() => config.backup_dir = Some(value.to_string()),
+            KEEP_KEY => config.keep_count = value.parse().unwrap_or(DEFAULT_KEEP_COUNT).max(1),
+            ON_EXIT_KEY => config.on_exit = value == "true",
+            _ => {}
+        }
+    }
+    Ok(config)
+}
+
+/// Save the automatic backup settings and apply them straight away.
+///
+/// `interval_hours` of 0 turns automatic backups off; otherwise `backup_dir`
+/// is required. `keep_count` defaults to 10. With `on_exit` a backup is also
+/// taken whenever the app closes, which needs `backup_dir` too.
+#[tauri::command]
+pub async fn configure_auto_backup(
+    db: State<'_, DbInstances>,
+    scheduler: State<'_, BackupScheduler>,
+    interval_hours: u32,
+    backup_dir: Option<String>,
+    keep_count: Option<u32>,
+    on_exit: Option<bool>,
+) -> Result<AutoBackupConfig, String> {
+    let backup_dir = backup_dir
+        .map(|dir| dir.trim().to_string())
+        .filter(|dir| !dir.is_empty());
+    let on_exit = on_exit.unwrap_or(false);
+    if (interval_hours > 0 || on_exit) && backup_dir.is_none() {
+        return Err("Choose a folder for automatic backups".to_string());
+    }
+    let keep_count = keep_count.unwrap_or(DEFAULT_KEEP_COUNT);
+    if keep_count == 0 {
+        return Err("Keep at least one backup".to_string());
+    }
+
+    let pool = db::writable_pool(&db).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for (key, value) in [
+        (INTERVAL_KEY, interval_hours.to_string()),
+        (DIR_KEY, backup_dir.clone().unwrap_or_default()),
+        (KEEP_KEY, keep_count.to_string()),
+        (ON_EXIT_KEY, on_exit.to_string()),
+    ] {
+        sqlx::query(
+            "INSERT INTO app_settings (key, value) VALUES (?, ?)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        )
+        .bind(key)
+        .bind(value)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    scheduler.nudge();
+    Ok(AutoBackupConfig {
+        interval_hours,
+        backup_dir,
+        keep_count,
+        on_exit,
+    })
+}
+
+/// Take an automatic backup now, whatever the interval, returning its path.
+/// Old backups are pruned as after a scheduled one.
+#[tauri::command]
+pub async fn trigger_backup_now(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    scheduler: State<'_, BackupScheduler>,
+) -> Result<String, String> {
+    let pool = db::pool(&db).await?;
+    let config = load_config(&pool).await?;
+    let dir = config
+        .backup_dir
+        .ok_or("Choose a folder for automatic backups first")?;
+    let busy = scheduler.begin()?;
+    let result = run_backup(&pool, Path::new(&dir), config.keep_count, busy).await;
+    report(&app, result.clone().map(Some));
+    Ok(result?.to_string_lossy().into_owned())
+}
+
+/// The automatic backups in the configured folder, newest first.
+#[tauri::command]
+pub async fn list_backups(db: State<'_, DbInstances>) -> Result<Vec<BackupInfo>, String> {
+    let pool = db::pool(&db).await?;
+    let Some(dir) = load_config(&pool).await?.backup_dir else {
+        return Ok(Vec::new());
+    };
+    let mut paths: Vec<PathBuf> = backups(Path::new(&dir))?
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+    // Names are timestamps, so they sort by age
+    paths.sort_by(|a, b| b.cmp(a));
+    paths.iter().map(|path| info(path)).collect()
+}
+
+/// Restore the automatic backup `id`, as listed by [`list_backups`], the way
+/// `restore_database` restores any other file.
+#[tauri::command]
+pub async fn restore_backup(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    scheduler: State<'_, BackupScheduler>,
+    id: String,
+) -> Result<RestoreSummary, String> {
+    let pool = db::pool(&db).await?;
+    let dir = load_config(&pool)
+        .await?
+        .backup_dir
+        .ok_or("No folder is set for automatic backups")?;
+    let is_backup = id.starts_with(FILE_PREFIX)
+        && id.ends_with(FILE_SUFFIX)
+        && !id.contains(['/', '\\'])
+        && !id.contains("..");
+    let src = Path::new(&dir).join(&id);
+    if !is_backup || !src.is_file() {
+        return Err(format!("No automatic backup named {id}"));
+    }
+    // Keep a scheduled backup from snapshotting the pool mid-swap
+    let _busy = scheduler.begin()?;
+    restore_from(&app, &db, &src).await
+}
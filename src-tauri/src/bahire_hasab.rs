@@ -0,0 +1,246 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::Serialize;
+
+use crate::calendar::EthiopianDate;
+
+/// Years from creation to the Incarnation in the Amete Alem reckoning.
+const AMETE_ALEM_OFFSET: i32 = 5500;
+
+/// Days after Nineveh's Monday on which each movable feast and fast falls.
+const FEAST_OFFSETS: [(&str, i64); 11] = [
+    ("nineveh", 0),
+    ("abiyTsom", 14),
+    ("debreZeit", 41),
+    ("hosanna", 62),
+    ("siklet", 67),
+    ("fasika", 69),
+    ("rikbeKahnat", 93),
+    ("erget", 108),
+    ("paraclete", 118),
+    ("tsomeHawaryat", 119),
+    ("tsomeDihnet", 121),
+];
+
+/// The Bahire Hasab reckoning of an Ethiopian year, from which its movable
+/// feasts follow.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BahireHasab {
+    pub year: i32,
+    pub amete_alem: i32,
+    /// Place of the year in the 19-year lunar cycle.
+    pub wenber: u32,
+    /// Epact: the moon's age at the start of the year.
+    pub abakte: u32,
+    pub metqi: u32,
+    /// The day Metqi names, in Meskerem or Tikimt.
+    pub beale_metqi: EthiopianDate,
+    /// Day of the month Nineveh starts on, before any carry into the next.
+    pub mebaja_hamer: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MovableFeast {
+    /// `nineveh`, `abiyTsom`, `debreZeit`, `hosanna`, `siklet`, `fasika`,
+    /// `rikbeKahnat`, `erget`, `paraclete`, `tsomeHawaryat` or `tsomeDihnet`.
+    pub name: &'static str,
+    pub ethiopian: EthiopianDate,
+    /// `YYYY-MM-DD`.
+    pub gregorian: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MovableFeasts {
+    pub bahire_hasab: BahireHasab,
+    /// In date order, starting with Nineveh.
+    pub feasts: Vec<MovableFeast>,
+}
+
+impl BahireHasab {
+    pub fn for_year(year: i32) -> Result<Self, String> {
+        if year < 1 {
+            return Err(format!("Invalid Ethiopian year: {year}"));
+        }
+        let amete_alem = AMETE_ALEM_OFFSET + year;
+        let wenber = (amete_alem - 1).rem_euclid(19) as u32;
+        let abakte = wenber * 11 % 30;
+        let metqi = wenber * 19 % 30;
+
+        // Metqi after the 14th falls in Meskerem, otherwise in Tikimt
+        let beale_metqi = match metqi {
+            0 => EthiopianDate::new(year, 1, 30)?,
+            15.. => EthiopianDate::new(year, 1, metqi)?,
+            _ => EthiopianDate::new(year, 2, metqi)?,
+        };
+        let weekday = beale_metqi.to_gregorian()?.weekday().num_days_from_sunday();
+        Ok(Self {
+            year,
+            amete_alem,
+            wenber,
+            abakte,
+            metqi,
+            beale_metqi,
+            mebaja_hamer: beale_metqi.day + tewsak(weekday),
+        })
+    }
+
+    /// Monday of the Fast of Nineveh: the Mebaja Hamer day of Tir after a
+    /// Meskerem Beale Metqi, of Yekatit after a Tikimt one, carried into the
+    /// next month past the 30th.
+    pub fn nineveh(&self) -> Result<EthiopianDate, String> {
+        let month = if self.beale_metqi.month == 1 { 5 } else { 6 };
+        if self.mebaja_hamer > 30 {
+            EthiopianDate::new(self.year, month + 1, self.mebaja_hamer - 30)
+        } else {
+            EthiopianDate::new(self.year, month, self.mebaja_hamer)
+        }
+    }
+}
+
+/// Days added to Beale Metqi's day by the weekday it falls on, Sunday first.
+fn tewsak(weekday_from_sunday: u32) -> u32 {
+    [7, 6, 5, 4, 3, 2, 8][weekday_from_sunday as usize]
+}
+
+/// Every movable feast and fast of an Ethiopian year.
+pub fn movable_feasts(year: i32) -> Result<MovableFeasts, String> {
+    let bahire_hasab = BahireHasab::for_year(year)?;
+    let nineveh = bahire_hasab.nineveh()?.to_gregorian()?;
+    let feasts = FEAST_OFFSETS
+        .iter()
+        .map(|&(name, offset)| feast(name, nineveh + Duration::days(offset)))
+        .collect();
+    Ok(MovableFeasts {
+        bahire_hasab,
+        feasts,
+    })
+}
+
+fn feast(name: &'static str, date: NaiveDate) -> MovableFeast {
+    MovableFeast {
+        name,
+        ethiopian: EthiopianDate::from_gregorian(date),
+        gregorian: date.format("%Y-%m-%d").to_string(),
+    }
+}
+
+#[tauri::command]
+pub fn get_bahire_hasab(year: i32) -> Result<BahireHasab, String> {
+    BahireHasab::for_year(year)
+}
+
+/// Nineveh, Abiy Tsom, Fasika and the rest of the movable feasts of an
+/// Ethiopian year, reckoned by Bahire Hasab.
+#[tauri::command]
+pub fn get_movable_feasts(year: i32) -> Result<MovableFeasts, String> {
+    movable_feasts(year)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fasika(year: i32) -> String {
+        let feasts = movable_feasts(year).unwrap().feasts;
+        let fasika = feasts.iter().find(|f| f.name == "fasika").unwrap();
+        fasika.gregorian.clone()
+    }
+
+    #[test]
+    fn fasika_falls_on_the_published_dates() {
+        for (year, expected) in [
+            (2008, "2016-05-01"),
+            (2009, "2017-04-16"),
+            (2010, "2018-04-08"),
+            (2011, "2019-04-28"),
+            (2012, "2020-04-19"),
+            (2013, "2021-05-02"),
+            (2014, "2022-04-24"),
+            (2015, "2023-04-16"),
+            (2016, "2024-05-05"),
+            (2017, "2025-04-20"),
+            (2018, "2026-04-12"),
+        ] {
+            assert_eq!(fasika(year), expected, "{year}");
+        }
+    }
+
+    #[test]
+    fn every_feast_keeps_its_distance_from_nineveh() {
+        let feasts: Vec<(&str, String)> = movable_feasts(2016)
+            .unwrap()
+            .feasts
+            .into_iter()
+            .map(|f| (f.name, f.gregorian))
+            .collect();
+        let expected = [
+            ("nineveh", "2024-02-26"),
+            ("abiyTsom", "2024-03-11"),
+            ("debreZeit", "2024-04-07"),
+            ("hosanna", "2024-04-28"),
+            ("siklet", "2024-05-03"),
+            ("fasika", "2024-05-05"),
+            ("rikbeKahnat", "2024-05-29"),
+            ("erget", "2024-06-13"),
+            ("paraclete", "2024-06-23"),
+            ("tsomeHawaryat", "2024-06-24"),
+            ("tsomeDihnet", "2024-06-26"),
+        ];
+        assert_eq!(feasts.len(), expected.len());
+        for ((name, date), (expected_name, expected_date)) in feasts.iter().zip(expected) {
+            assert_eq!((*name, date.as_str()), (expected_name, expected_date));
+        }
+    }
+
+    #[test]
+    fn a_metqi_of_zero_is_the_thirtieth_of_meskerem() {
+        let reckoning = BahireHasab::for_year(2006).unwrap();
+        assert_eq!(reckoning.amete_alem, 7506);
+        assert_eq!((reckoning.wenber, reckoning.abakte), (0, 0));
+        assert_eq!(reckoning.metqi, 0);
+        assert_eq!(
+            reckoning.beale_metqi,
+            EthiopianDate::new(2006, 1, 30).unwrap()
+        );
+        assert_eq!(fasika(2006), "2014-04-20");
+    }
+
+    #[test]
+    fn a_mebaja_hamer_past_the_thirtieth_carries_into_the_next_month() {
+        let reckoning = BahireHasab::for_year(2017).unwrap();
+        assert_eq!(
+            (reckoning.wenber, reckoning.abakte, reckoning.metqi),
+            (11, 1, 29)
+        );
+        // Meskerem 29 2017 was a Wednesday, which adds 4
+        assert_eq!(
+            reckoning.beale_metqi,
+            EthiopianDate::new(2017, 1, 29).unwrap()
+        );
+        assert_eq!(reckoning.mebaja_hamer, 33);
+        // So the 33rd of Tir is the 3rd of Yekatit
+        assert_eq!(
+            reckoning.nineveh().unwrap(),
+            EthiopianDate::new(2017, 6, 3).unwrap()
+        );
+
+        // A Tikimt Beale Metqi counts from Yekatit instead
+        let reckoning = BahireHasab::for_year(2016).unwrap();
+        assert_eq!(
+            reckoning.beale_metqi,
+            EthiopianDate::new(2016, 2, 10).unwrap()
+        );
+        assert_eq!(
+            reckoning.nineveh().unwrap(),
+            EthiopianDate::new(2016, 6, 18).unwrap()
+        );
+    }
+
+    #[test]
+    fn years_before_the_first_are_refused() {
+        assert!(BahireHasab::for_year(0).is_err());
+        assert!(movable_feasts(-5).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use serde_json::{json, Map, Value};
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::audit;
+use crate::db::{self, LANG_SLOTS};
+
+/// Layout of `blocks_json` the renderers read: an array of block objects keyed
+/// by language slot, e.g. `[{"Lang1": "…", "Lang2": "…"}]`.
+pub const BLOCKS_VERSION: u32 = 2;
+
+/// A slide's `blocks_json`, parsed and tagged with the layout it was stored in.
+///
+/// The stored JSON carries no version; it's told by shape. Version 1 documents
+/// hold bare text where blocks now go: a lone string, or an array whose items
+/// are strings (Lang1 text) or arrays of strings (one per language slot).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlocksDocument {
+    pub version: u32,
+    pub blocks: Vec<Map<String, Value>>,
+}
+
+impl BlocksDocument {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| format!("Invalid blocks JSON: {e}"))?;
+        let (version, nodes) = match value {
+            Value::Array(nodes) if nodes.iter().all(Value::is_object) => (BLOCKS_VERSION, nodes),
+            Value::Array(nodes) => (1, nodes),
+            Value::String(text) => (1, vec![Value::String(text)]),
+            other => return Err(format!("Unrecognised blocks layout: {other}")),
+        };
+        let blocks = nodes
+            .into_iter()
+            .map(block_from_node)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { version, blocks })
+    }
+
+    /// The document in the current layout.
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(&self.blocks).map_err(|e| e.to_string())
+    }
+}
+
+fn block_from_node(node: Value) -> Result<Map<String, Value>, String> {
+    match node {
+        Value::Object(block) => Ok(block),
+        Value::Null => Ok(Map::new()),
+        Value::String(text) => Ok(Map::from_iter([(
+            LANG_SLOTS[0].to_string(),
+            Value::String(text),
+        )])),
+        Value::Array(texts) => {
+            if texts.len() > LANG_SLOTS.len() {
+                return Err(format!(
+                    "Block has {} languages; at most {} are supported",
+                    texts.len(),
+                    LANG_SLOTS.len()
+                ));
+            }
+            texts
+                .into_iter()
+                .zip(LANG_SLOTS)
+                .filter(|(text, _)| !text.is_null())
+                .map(|(text, slot)| match text {
+                    Value::String(_) => Ok((slot.to_string(), text)),
+                    other => Err(format!("Expected text for {slot}, got {other}")),
+                })
+                .collect()
+        }
+        other => Err(format!("Unrecognised block: {other}")),
+    }
+}
+
+/// Upgrade a `blocks_json` document to [`BLOCKS_VERSION`]. A document that is
+/// already current comes back exactly as given.
+pub fn migrate_blocks(json: String) -> Result<String, String> {
+    let document = BlocksDocument::parse(&json)?;
+    if document.version == BLOCKS_VERSION {
+        return Ok(json);
+    }
+    document.to_json()
+}
+
+/// Upgrade every slide of a presentation to the current `blocks_json` layout
+/// in one transaction, returning how many slides changed. Running it again is
+/// a no-op.
+#[tauri::command]
+pub async fn migrate_presentation_blocks(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<u32, String> {
+    let pool = db::writable_pool(&db).await?;
+    db::fetch_presentation(&pool, &presentation_id).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let slides: Vec<(String, i64, String)> = sqlx::query_as(
+        "SELECT id, slide_order, blocks_json FROM slides
+         WHERE presentation_id = ? ORDER BY slide_order",
+    )
+    .bind(&presentation_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut upgraded = 0;
+    for (id, slide_order, blocks_json) in slides {
+        let migrated =
+            migrate_blocks(blocks_json.clone()).map_err(|e| format!("Slide {slide_order}: {e}"))?;
+        if migrated == blocks_json {
+            continue;
+        }
+        sqlx::query("UPDATE slides SET blocks_json = ? WHERE id = ?")
+            .bind(&migrated)
+            .bind(&id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        upgraded += 1;
+    }
+
+    if upgraded > 0 {
+        let detail = json!({ "slidesUpgraded": upgraded, "blocksVersion": BLOCKS_VERSION });
+        audit::record(
+            &mut *tx,
+            "migrate_blocks",
+            "presentation",
+            &presentation_id,
+            detail,
+        )
+        .await;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(upgraded)
+}
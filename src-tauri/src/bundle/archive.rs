@@ -0,0 +1,263 @@
+use std::io::{Read, Write};
+
+use ::zip::write::SimpleFileOptions;
+use ::zip::{ZipArchive, ZipWriter};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use super::import::{self, BundleImport};
+use super::{build_bundle, checksum};
+use crate::db;
+use crate::export::filename::export_filename;
+use crate::export::ExportFormat;
+
+/// Entry listing what an archive holds.
+const MANIFEST_ENTRY: &str = "manifest.json";
+/// Folder of the archive the presentation bundles go in.
+const PRESENTATIONS_DIR: &str = "presentations";
+/// Bumped whenever the shape of [`Manifest`] changes.
+const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub format_version: u32,
+    pub exported_at: String,
+    pub entries: Vec<ArchiveEntry>,
+}
+
+/// One presentation in an archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEntry {
+    /// The presentation's id when it was exported.
+    pub id: String,
+    pub name: String,
+    /// Archive path of its bundle.
+    pub entry: String,
+    /// The bundle's own checksum.
+    pub checksum: String,
+}
+
+/// A presentation left out of an export, or an entry that didn't import.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveFailure {
+    pub id: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+    pub exported: Vec<ArchiveEntry>,
+    pub failed: Vec<ArchiveFailure>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveImport {
+    pub imported: Vec<RestoredEntry>,
+    pub failed: Vec<ArchiveFailure>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredEntry {
+    /// The id the manifest lists it under.
+    pub original_id: String,
+    #[serde(flatten)]
+    pub import: BundleImport,
+}
+
+/// Write several presentations into one zip, each as the bundle
+/// `export_presentation` produces under `presentations/`, with a
+/// `manifest.json` listing their names, ids and checksums. An empty `ids`
+/// exports every presentation outside the trash.
+///
+/// A presentation that can't be exported is recorded in the summary and the
+/// rest still go in; only failing to write the archive itself is an error.
+#[tauri::command]
+pub async fn export_presentations(
+    db: State<'_, DbInstances>,
+    ids: Vec<String>,
+    dest_path: String,
+) -> Result<ExportSummary, String> {
+    let pool = db::pool(&db).await?;
+    let ids = if ids.is_empty() {
+        sqlx::query_scalar(
+            "SELECT id FROM presentations WHERE deleted_at IS NULL ORDER BY created_at, id",
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+    } else {
+        ids
+    };
+
+    let file = std::fs::File::create(&dest_path)
+        .map_err(|e| format!("Failed to write {dest_path}: {e}"))?;
+    let mut zip = ZipWriter::new(file);
+    let mut entry = |name: &str, content: &[u8]| -> Result<(), String> {
+        zip.start_file(name, SimpleFileOptions::default())
+            .map_err(|e| e.to_string())?;
+        zip.write_all(content).map_err(|e| e.to_string())
+    };
+
+    let mut exported = Vec::new();
+    let mut failed = Vec::new();
+    for id in ids {
+        let sealed = match build_bundle(&pool, &id).await {
+            Ok(bundle) => {
+                let name = bundle.presentation.name.clone();
+                checksum::seal(bundle).map(|json| (name, json))
+            }
+            Err(e) => Err(e),
+        };
+        let (name, json) = match sealed {
+            Ok(sealed) => sealed,
+            Err(error) => {
+                failed.push(ArchiveFailure { id, error });
+                continue;
+            }
+        };
+        let filename = match export_filename(&pool, &id, ExportFormat::Json, None).await {
+            Ok(filename) => filename,
+            Err(error) => {
+                failed.push(ArchiveFailure { id, error });
+                continue;
+            }
+        };
+        let checksum = serde_json::from_str::<Value>(&json)
+            .ok()
+            .and_then(|payload| payload.get("checksum")?.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let path = format!("{PRESENTATIONS_DIR}/{filename}");
+        entry(&path, json.as_bytes())?;
+        exported.push(ArchiveEntry {
+            id,
+            name,
+            entry: path,
+            checksum,
+        });
+    }
+
+    let manifest = Manifest {
+        format_version: ARCHIVE_FORMAT_VERSION,
+        exported_at: db::now(),
+        entries: exported.clone(),
+    };
+    let manifest = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    entry(MANIFEST_ENTRY, manifest.as_bytes())?;
+    zip.finish()
+        .map_err(|e| format!("Failed to write {dest_path}: {e}"))?;
+
+    Ok(ExportSummary { exported, failed })
+}
+
+/// The manifest of an archive written by [`export_presentations`], so the UI
+/// can offer its entries before anything is imported.
+#[tauri::command]
+pub fn read_archive_manifest(src_path: String) -> Result<Manifest, String> {
+    let mut archive = open(&src_path)?;
+    read_manifest(&mut archive, &src_path)
+}
+
+/// Import the entries of an archive whose original ids are in `ids`, or every
+/// entry when `ids` is empty, each under fresh ids as `import_presentation`
+/// would. Each entry is its own transaction, so one that fails is recorded and
+/// the rest are still restored.
+#[tauri::command]
+pub async fn import_archive(
+    db: State<'_, DbInstances>,
+    src_path: String,
+    ids: Vec<String>,
+) -> Result<ArchiveImport, String> {
+    let mut archive = open(&src_path)?;
+    let manifest = read_manifest(&mut archive, &src_path)?;
+    if let Some(missing) = ids
+        .iter()
+        .find(|id| !manifest.entries.iter().any(|entry| entry.id == **id))
+    {
+        return Err(format!("{src_path} has no presentation {missing}"));
+    }
+
+    let pool = db::writable_pool(&db).await?;
+    let mut imported = Vec::new();
+    let mut failed = Vec::new();
+    for entry in manifest.entries {
+        if !ids.is_empty() && !ids.contains(&entry.id) {
+            continue;
+        }
+        let parsed = read_entry(&mut archive, &entry).and_then(|json| import::parse(&json));
+        let result = match parsed {
+            Ok(parsed) => import::import_parsed(&pool, parsed, "archive").await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(import) => imported.push(RestoredEntry {
+                original_id: entry.id,
+                import,
+            }),
+            Err(error) => failed.push(ArchiveFailure {
+                id: entry.id,
+                error,
+            }),
+        }
+    }
+    Ok(ArchiveImport { imported, failed })
+}
+
+pub(super) fn open(src_path: &str) -> Result<ZipArchive<std::fs::File>, String> {
+    let file =
+        std::fs::File::open(src_path).map_err(|e| format!("Failed to open {src_path}: {e}"))?;
+    ZipArchive::new(file).map_err(|e| format!("{src_path} is not a presentation archive: {e}"))
+}
+
+fn read_manifest(
+    archive: &mut ZipArchive<std::fs::File>,
+    src_path: &str,
+) -> Result<Manifest, String> {
+    let mut json = String::new();
+    archive
+        .by_name(MANIFEST_ENTRY)
+        .map_err(|_| format!("{src_path} has no {MANIFEST_ENTRY}"))?
+        .read_to_string(&mut json)
+        .map_err(|e| format!("Failed to read {MANIFEST_ENTRY}: {e}"))?;
+    let manifest: Manifest =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid {MANIFEST_ENTRY}: {e}"))?;
+    if manifest.format_version > ARCHIVE_FORMAT_VERSION {
+        return Err(format!(
+            "Archive format version {} is newer than supported version {ARCHIVE_FORMAT_VERSION}",
+            manifest.format_version
+        ));
+    }
+    Ok(manifest)
+}
+
+/// The bundle JSON of `entry`, refused when it isn't the bundle the manifest
+/// listed.
+pub(super) fn read_entry(
+    archive: &mut ZipArchive<std::fs::File>,
+    entry: &ArchiveEntry,
+) -> Result<String, String> {
+    let mut json = String::new();
+    archive
+        .by_name(&entry.entry)
+        .map_err(|e| format!("Failed to read {}: {e}", entry.entry))?
+        .read_to_string(&mut json)
+        .map_err(|e| format!("Failed to read {}: {e}", entry.entry))?;
+    let listed = serde_json::from_str::<Value>(&json)
+        .ok()
+        .and_then(|payload| payload.get("checksum")?.as_str().map(str::to_string));
+    if listed.as_deref() != Some(entry.checksum.as_str()) {
+        return Err(format!(
+            "{}: {} doesn't match the checksum in {MANIFEST_ENTRY}",
+            checksum::CHECKSUM_MISMATCH,
+            entry.entry
+        ));
+    }
+    Ok(json)
+}
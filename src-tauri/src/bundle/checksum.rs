@@ -0,0 +1,119 @@
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::PresentationBundle;
+use crate::slides::canonicalize;
+
+/// Bumped whenever what the checksum covers, or how it's computed, changes.
+pub const CHECKSUM_FORMAT_VERSION: u32 = 1;
+
+/// Leads the error returned for a bundle whose contents don't match its
+/// checksum, so callers can tell corruption apart from other import failures.
+pub const CHECKSUM_MISMATCH: &str = "ChecksumMismatch";
+
+/// Outcome of checking a bundle that didn't fail verification.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verification {
+    Verified,
+    /// Exported before bundles carried a checksum.
+    Unsigned,
+}
+
+/// Serialize a bundle with its `format_version` and a `checksum` over
+/// everything else.
+pub fn seal(mut bundle: PresentationBundle) -> Result<String, String> {
+    bundle.format_version = Some(CHECKSUM_FORMAT_VERSION);
+    bundle.checksum = None;
+    let payload = serde_json::to_value(&bundle).map_err(|e| e.to_string())?;
+    bundle.checksum = Some(checksum(&payload));
+    serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())
+}
+
+/// Check a parsed bundle against its own checksum.
+pub fn verify(payload: &Value) -> Result<Verification, String> {
+    let Some(expected) = payload.get("checksum") else {
+        return Ok(Verification::Unsigned);
+    };
+    let expected = expected
+        .as_str()
+        .ok_or_else(|| format!("{CHECKSUM_MISMATCH}: bundle checksum is not a string"))?;
+
+    let format_version = payload.get("format_version").and_then(Value::as_u64);
+    match format_version {
+        Some(version) if version <= u64::from(CHECKSUM_FORMAT_VERSION) => {}
+        Some(version) => {
+            return Err(format!(
+                "Bundle format version {version} is newer than supported version {CHECKSUM_FORMAT_VERSION}"
+            ))
+        }
+        None => return Err(format!("{CHECKSUM_MISMATCH}: bundle has a checksum but no format version")),
+    }
+
+    if checksum(payload).eq_ignore_ascii_case(expected) {
+        Ok(Verification::Verified)
+    } else {
+        Err(format!(
+            "{CHECKSUM_MISMATCH}: bundle contents don't match its checksum; the file may be corrupted"
+        ))
+    }
+}
+
+/// Hex SHA-256 of the payload with sorted keys and no whitespace, ignoring
+/// any `checksum` field it already has.
+pub fn checksum(payload: &Value) -> String {
+    let mut canonical = String::new();
+    match payload {
+        Value::Object(fields) if fields.contains_key("checksum") => {
+            let mut fields = fields.clone();
+            fields.remove("checksum");
+            canonicalize(&Value::Object(fields), false, &mut canonical);
+        }
+        other => canonicalize(other, false, &mut canonical),
+    }
+    let digest = Sha256::digest(canonical.as_bytes());
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::{build_bundle, import};
+    use crate::test_support;
+
+    async fn sealed() -> String {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        test_support::slide(&pool, "s1", "p1", 1).await;
+        seal(build_bundle(&pool, "p1").await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn a_sealed_bundle_verifies() {
+        let payload: Value = serde_json::from_str(&sealed().await).unwrap();
+        assert_eq!(verify(&payload), Ok(Verification::Verified));
+    }
+
+    #[tokio::test]
+    async fn a_single_changed_byte_is_detected() {
+        let json = sealed().await;
+        let at = json.find(r#""name": "p1""#).unwrap() + r#""name": "p"#.len();
+        let mut bytes = json.into_bytes();
+        bytes[at] = b'2';
+        let mutated = String::from_utf8(bytes).unwrap();
+
+        let error = import::parse(&mutated).err().unwrap();
+        assert!(error.starts_with(CHECKSUM_MISMATCH), "{error}");
+    }
+
+    #[tokio::test]
+    async fn an_unsigned_bundle_imports_with_a_warning() {
+        let mut payload: Value = serde_json::from_str(&sealed().await).unwrap();
+        let fields = payload.as_object_mut().unwrap();
+        fields.remove("checksum");
+        fields.remove("format_version");
+        assert_eq!(verify(&payload), Ok(Verification::Unsigned));
+
+        let parsed = import::parse(&payload.to_string()).unwrap();
+        assert_eq!(parsed.warnings.len(), 1);
+    }
+}
@@ -0,0 +1,131 @@
+use serde_json::json;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use super::{build_bundle, import::insert_bundle};
+use crate::audit;
+use crate::db;
+
+/// Copy a presentation with its slides, variables and rules under fresh ids,
+/// returning the new presentation id.
+///
+/// The copy keeps the source's template, slide order and `is_primary` flag but
+/// is never active.
+#[tauri::command]
+pub async fn duplicate_presentation(
+    db: State<'_, DbInstances>,
+    source_id: String,
+    new_name: String,
+) -> Result<String, String> {
+    let pool = db::writable_pool(&db).await?;
+    duplicate(&pool, &source_id, &new_name).await
+}
+
+async fn duplicate(pool: &SqlitePool, source_id: &str, new_name: &str) -> Result<String, String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err("Presentation name cannot be empty".to_string());
+    }
+
+    let mut bundle = build_bundle(pool, source_id).await?;
+    bundle.presentation.name = new_name.to_string();
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let presentation_id = insert_bundle(&mut tx, &bundle, &mut Vec::new()).await?;
+    let detail = json!({ "name": new_name, "duplicatedFrom": source_id });
+    audit::record(&mut *tx, "create", "presentation", &presentation_id, detail).await;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(presentation_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::test_support;
+
+    async fn ids(pool: &SqlitePool, sql: &str, presentation_id: &str) -> HashSet<String> {
+        sqlx::query_scalar(sql)
+            .bind(presentation_id)
+            .fetch_all(pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn the_copy_has_the_same_slides_under_new_ids() {
+        let pool = test_support::pool().await;
+        for id in ["main", "p1"] {
+            test_support::presentation(&pool, id).await;
+        }
+        for (id, order) in [("s1", 1), ("s2", 2), ("s3", 5)] {
+            test_support::slide(&pool, id, "p1", order).await;
+        }
+        test_support::exec(
+            &pool,
+            "UPDATE presentations SET is_active = 1, is_primary = 0, parent_id = 'main'
+                 WHERE id = 'p1';
+             INSERT INTO variables (id, presentation_id, name, value)
+                 VALUES ('v1', 'p1', 'SAINT', 'Mary');
+             INSERT INTO rule_definitions (id, name, scope, presentation_id, slide_id, rule_json, created_at)
+                 VALUES ('r1', 'hide', 'slide', 'p1', 's2', '{}', '2026-01-01T00:00:00.000Z')",
+        )
+        .await;
+
+        let copy = duplicate(&pool, "p1", " Next week ").await.unwrap();
+        assert_ne!(copy, "p1");
+
+        let (name, is_active, is_primary, parent): (String, bool, bool, Option<String>) =
+            sqlx::query_as(
+                "SELECT name, is_active, is_primary, parent_id FROM presentations WHERE id = ?",
+            )
+            .bind(&copy)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(name, "Next week");
+        assert!(!is_active);
+        assert!(!is_primary);
+        assert_eq!(parent.as_deref(), Some("main"));
+
+        let slides = "SELECT id FROM slides WHERE presentation_id = ?";
+        let source_slides = ids(&pool, slides, "p1").await;
+        let copied_slides = ids(&pool, slides, &copy).await;
+        assert_eq!(copied_slides.len(), source_slides.len());
+        assert!(copied_slides.is_disjoint(&source_slides));
+        let orders: Vec<i64> = test_support::slide_orders(&pool, &copy)
+            .await
+            .into_iter()
+            .map(|(_, order)| order)
+            .collect();
+        assert_eq!(orders, [1, 2, 5]);
+
+        let variables = "SELECT id FROM variables WHERE presentation_id = ?";
+        assert_eq!(ids(&pool, variables, &copy).await.len(), 1);
+        assert!(ids(&pool, variables, &copy)
+            .await
+            .is_disjoint(&ids(&pool, variables, "p1").await));
+
+        // The copied rule follows the copied slide, not the source's
+        let rule_slide: String =
+            sqlx::query_scalar("SELECT slide_id FROM rule_definitions WHERE presentation_id = ?")
+                .bind(&copy)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(copied_slides.contains(&rule_slide));
+    }
+
+    #[tokio::test]
+    async fn a_blank_name_or_unknown_source_is_refused() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        assert!(duplicate(&pool, "p1", "  ").await.is_err());
+        assert!(duplicate(&pool, "missing", "Copy").await.is_err());
+    }
+}
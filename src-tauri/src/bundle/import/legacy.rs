@@ -0,0 +1,313 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::bundle::BUNDLE_SCHEMA_VERSION;
+
+/// Shape of an export, told apart by the fields it carries.
+///
+/// Exports from before `schema_version` existed are dumps of the tables as
+/// they were then, so each is named after the schema change it predates and
+/// its adapter fills in what that and later migrations added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BundleVersion {
+    /// Slides have no `footer_json` (schema v1).
+    NoFooters,
+    /// Variables have a single `value` and no `value_langN` (schema v2–v5).
+    SingleValueVariables,
+    /// Every column is there but `schema_version` isn't.
+    Unversioned,
+    /// Carries `schema_version`; those before [`BUNDLE_SCHEMA_VERSION`] get
+    /// the fields added since, empty.
+    Versioned(u32),
+    /// Not a presentation export.
+    Unknown,
+}
+
+/// The shape of an export without importing it, so the UI can say up front
+/// that it will be converted.
+#[tauri::command]
+pub fn detect_bundle_version(json: &str) -> BundleVersion {
+    serde_json::from_str::<Value>(json).map_or(BundleVersion::Unknown, |payload| detect(&payload))
+}
+
+pub fn detect(payload: &Value) -> BundleVersion {
+    if let Some(version) = payload.get("schema_version") {
+        return version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .map_or(BundleVersion::Unknown, BundleVersion::Versioned);
+    }
+    if !["presentation", "template"]
+        .iter()
+        .all(|key| payload.get(key).is_some_and(Value::is_object))
+    {
+        return BundleVersion::Unknown;
+    }
+
+    let lacks = |table: &str, column: &str| {
+        payload
+            .get(table)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .any(|record| record.get(column).is_none())
+    };
+    if lacks("slides", "footer_json") {
+        BundleVersion::NoFooters
+    } else if lacks("variables", "value_lang1") {
+        BundleVersion::SingleValueVariables
+    } else {
+        BundleVersion::Unversioned
+    }
+}
+
+/// Bring a payload of `version` up to the current bundle shape. Payloads of
+/// the current version come back untouched.
+pub fn upgrade(mut payload: Value, version: BundleVersion) -> Result<Value, String> {
+    match version {
+        BundleVersion::Versioned(v) if v < BUNDLE_SCHEMA_VERSION => {
+            add_v2_fields(&mut payload);
+            if let Some(fields) = payload.as_object_mut() {
+                fields.insert("schema_version".to_string(), BUNDLE_SCHEMA_VERSION.into());
+            }
+            return Ok(payload);
+        }
+        BundleVersion::Versioned(_) => return Ok(payload),
+        BundleVersion::Unknown => return Err("Unrecognised bundle format".to_string()),
+        BundleVersion::NoFooters => {
+            add_footers(&mut payload);
+            add_language_values(&mut payload);
+        }
+        BundleVersion::SingleValueVariables => add_language_values(&mut payload),
+        BundleVersion::Unversioned => {}
+    }
+    complete(&mut payload);
+    Ok(payload)
+}
+
+/// Schema v2 gave slides an optional footer.
+fn add_footers(payload: &mut Value) {
+    for slide in records(payload, "slides") {
+        slide.entry("footer_json").or_insert(Value::Null);
+    }
+}
+
+/// Schema v6 split variable values per language, starting them empty.
+fn add_language_values(payload: &mut Value) {
+    for variable in records(payload, "variables") {
+        for column in ["value_lang1", "value_lang2", "value_lang3", "value_lang4"] {
+            variable
+                .entry(column)
+                .or_insert_with(|| Value::String(String::new()));
+        }
+    }
+}
+
+/// Bundle v2 carried the presentation's display scale and parent, the
+/// templates its slides override with and the per-language variable values.
+fn add_v2_fields(payload: &mut Value) {
+    if let Some(presentation) = payload
+        .get_mut("presentation")
+        .and_then(Value::as_object_mut)
+    {
+        presentation.entry("display_scale").or_insert(Value::Null);
+        presentation.entry("parent_id").or_insert(Value::Null);
+    }
+    if let Some(fields) = payload.as_object_mut() {
+        for key in ["override_templates", "variable_values"] {
+            fields
+                .entry(key)
+                .or_insert_with(|| Value::Array(Vec::new()));
+        }
+    }
+}
+
+/// Fill the columns added since with their migration defaults, turn SQLite's
+/// 0/1 flags into booleans and stamp the current schema version.
+fn complete(payload: &mut Value) {
+    if let Some(presentation) = payload
+        .get_mut("presentation")
+        .and_then(Value::as_object_mut)
+    {
+        presentation
+            .entry("language_settings")
+            .or_insert(Value::Null);
+        presentation
+            .entry("is_primary")
+            .or_insert(Value::Bool(true));
+        presentation
+            .entry("is_active")
+            .or_insert(Value::Bool(false));
+        to_bools(presentation, &["is_primary", "is_active"]);
+    }
+    if let Some(template) = payload.get_mut("template").and_then(Value::as_object_mut) {
+        template.entry("max_lang_count").or_insert(4.into());
+    }
+    for slide in records(payload, "slides") {
+        slide.entry("is_dynamic").or_insert(Value::Bool(false));
+        slide.entry("is_disabled").or_insert(Value::Bool(false));
+        slide.entry("template_override_id").or_insert(Value::Null);
+        to_bools(slide, &["is_disabled", "is_dynamic"]);
+    }
+    for rule in records(payload, "rules") {
+        to_bools(rule, &["is_enabled"]);
+    }
+    add_v2_fields(payload);
+
+    if let Some(fields) = payload.as_object_mut() {
+        // Rules arrived in schema v5, after the oldest exports
+        fields
+            .entry("rules")
+            .or_insert_with(|| Value::Array(Vec::new()));
+        fields
+            .entry("variables")
+            .or_insert_with(|| Value::Array(Vec::new()));
+        fields
+            .entry("slides")
+            .or_insert_with(|| Value::Array(Vec::new()));
+        // When a legacy export was made isn't recorded
+        fields
+            .entry("exported_at")
+            .or_insert_with(|| Value::String(String::new()));
+        fields.insert("schema_version".to_string(), BUNDLE_SCHEMA_VERSION.into());
+    }
+}
+
+fn records<'a>(
+    payload: &'a mut Value,
+    table: &str,
+) -> impl Iterator<Item = &'a mut Map<String, Value>> {
+    payload
+        .get_mut(table)
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object_mut)
+}
+
+fn to_bools(record: &mut Map<String, Value>, columns: &[&str]) {
+    for column in columns {
+        if let Some(value) = record.get_mut(*column) {
+            if let Some(flag) = value.as_i64() {
+                *value = Value::Bool(flag != 0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::bundle::import::{self, import_parsed};
+    use crate::test_support;
+
+    /// An export from schema v1: no footers, one value per variable, integer
+    /// flags and no rules.
+    fn no_footers() -> Value {
+        json!({
+            "presentation": {
+                "id": "old", "name": "Old service", "type": "kidase", "template_id": "t-old",
+                "language_map": "{\"Lang1\":\"Geez\"}", "is_active": 1,
+                "created_at": "2020-01-01T00:00:00.000Z"
+            },
+            "template": {
+                "id": "t-old", "name": "Old template", "definition_json": "{}",
+                "created_at": "2020-01-01T00:00:00.000Z"
+            },
+            "slides": [{
+                "id": "s1", "slide_order": 1, "line_id": null, "title_json": null,
+                "blocks_json": "[]", "notes": null, "is_disabled": 0
+            }],
+            "variables": [{ "id": "v1", "name": "SAINT", "value": "Mary" }]
+        })
+    }
+
+    /// An export from schema v2–v5: footers, but one value per variable.
+    fn single_value_variables() -> Value {
+        let mut payload = no_footers();
+        payload["slides"][0]["footer_json"] = Value::Null;
+        payload
+    }
+
+    #[test]
+    fn legacy_shapes_are_told_apart() {
+        assert_eq!(detect(&no_footers()), BundleVersion::NoFooters);
+        assert_eq!(
+            detect(&single_value_variables()),
+            BundleVersion::SingleValueVariables
+        );
+
+        let mut unversioned = single_value_variables();
+        for column in ["value_lang1", "value_lang2", "value_lang3", "value_lang4"] {
+            unversioned["variables"][0][column] = json!("");
+        }
+        assert_eq!(detect(&unversioned), BundleVersion::Unversioned);
+        unversioned["schema_version"] = json!(1);
+        assert_eq!(detect(&unversioned), BundleVersion::Versioned(1));
+
+        assert_eq!(detect(&json!({ "name": "x" })), BundleVersion::Unknown);
+        assert_eq!(detect_bundle_version("not json"), BundleVersion::Unknown);
+    }
+
+    #[test]
+    fn legacy_shapes_upgrade_to_the_current_one() {
+        for payload in [no_footers(), single_value_variables()] {
+            let version = detect(&payload);
+            let upgraded = upgrade(payload, version).unwrap();
+            assert_eq!(upgraded["schema_version"], BUNDLE_SCHEMA_VERSION);
+            assert_eq!(upgraded["slides"][0]["footer_json"], Value::Null);
+            assert_eq!(upgraded["slides"][0]["is_disabled"], false);
+            assert_eq!(upgraded["variables"][0]["value_lang1"], "");
+            assert_eq!(upgraded["presentation"]["is_active"], true);
+            assert_eq!(upgraded["rules"], json!([]));
+        }
+        assert!(upgrade(json!({}), BundleVersion::Unknown).is_err());
+    }
+
+    #[test]
+    fn a_v1_bundle_gains_the_v2_fields() {
+        let mut payload = upgrade(
+            single_value_variables(),
+            BundleVersion::SingleValueVariables,
+        )
+        .unwrap();
+        let fields = payload.as_object_mut().unwrap();
+        fields.remove("override_templates");
+        fields.remove("variable_values");
+        fields.insert("schema_version".to_string(), json!(1));
+        payload["presentation"]
+            .as_object_mut()
+            .unwrap()
+            .remove("parent_id");
+
+        let upgraded = upgrade(payload, BundleVersion::Versioned(1)).unwrap();
+        assert_eq!(upgraded["schema_version"], BUNDLE_SCHEMA_VERSION);
+        assert_eq!(upgraded["presentation"]["parent_id"], Value::Null);
+        assert_eq!(upgraded["override_templates"], json!([]));
+        assert_eq!(upgraded["variable_values"], json!([]));
+        assert!(serde_json::from_value::<crate::bundle::PresentationBundle>(upgraded).is_ok());
+    }
+
+    #[tokio::test]
+    async fn a_legacy_export_imports_and_reports_its_shape() {
+        let pool = test_support::pool().await;
+        let parsed = import::parse(&no_footers().to_string()).unwrap();
+        let imported = import_parsed(&pool, parsed, "test").await.unwrap();
+
+        assert_eq!(imported.detected_version, BundleVersion::NoFooters);
+        assert!(imported.warnings.iter().any(|w| w.contains("legacy")));
+        let presentation = crate::db::fetch_presentation(&pool, &imported.presentation_id)
+            .await
+            .unwrap();
+        assert_eq!(presentation.name, "Old service");
+        assert_eq!(
+            test_support::slide_orders(&pool, &imported.presentation_id)
+                .await
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,498 @@
+pub mod legacy;
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::audit;
+use crate::bundle::checksum::{self, Verification};
+use crate::bundle::{PresentationBundle, TemplateRecord, BUNDLE_SCHEMA_VERSION};
+use crate::db;
+use legacy::BundleVersion;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImport {
+    pub presentation_id: String,
+    /// Shape the bundle was read as; anything but `versioned` was converted.
+    pub detected_version: BundleVersion,
+    pub warnings: Vec<String>,
+}
+
+/// A bundle checked and brought up to the current shape, not yet written.
+pub struct ParsedBundle {
+    pub bundle: PresentationBundle,
+    pub detected_version: BundleVersion,
+    pub warnings: Vec<String>,
+}
+
+/// Import a bundle under fresh ids, returning the new presentation id and any
+/// warnings.
+///
+/// The checksum is verified before anything is written; a mismatch fails with
+/// an error starting [`checksum::CHECKSUM_MISMATCH`]. Bundles exported before
+/// checksums existed still import, with a warning, and exports from before
+/// `schema_version` are converted by [`legacy`] first. Everything is inserted
+/// in one transaction so a malformed bundle leaves the database untouched.
+#[tauri::command]
+pub async fn import_presentation(
+    db: State<'_, DbInstances>,
+    bundle_json: String,
+) -> Result<BundleImport, String> {
+    let parsed = parse(&bundle_json)?;
+    let pool = db::writable_pool(&db).await?;
+    import_parsed(&pool, parsed, "bundle").await
+}
+
+/// Verify and upgrade a bundle as [`import_presentation`] does, without
+/// touching the database.
+pub fn parse(bundle_json: &str) -> Result<ParsedBundle, String> {
+    let payload: Value =
+        serde_json::from_str(bundle_json).map_err(|e| format!("Invalid bundle: {e}"))?;
+    let mut warnings = Vec::new();
+    if checksum::verify(&payload)? == Verification::Unsigned {
+        warnings
+            .push("Bundle has no checksum, so it couldn't be checked for corruption".to_string());
+    }
+
+    let detected_version = legacy::detect(&payload);
+    if !matches!(detected_version, BundleVersion::Versioned(_)) {
+        warnings.push(format!(
+            "Converted from a legacy bundle ({detected_version:?})"
+        ));
+    }
+    let payload = legacy::upgrade(payload, detected_version)?;
+
+    let bundle: PresentationBundle =
+        serde_json::from_value(payload).map_err(|e| format!("Invalid bundle: {e}"))?;
+    if bundle.schema_version > BUNDLE_SCHEMA_VERSION {
+        return Err(format!(
+            "Bundle schema version {} is newer than supported version {BUNDLE_SCHEMA_VERSION}",
+            bundle.schema_version
+        ));
+    }
+    Ok(ParsedBundle {
+        bundle,
+        detected_version,
+        warnings,
+    })
+}
+
+/// Insert a parsed bundle in one transaction. `source` is recorded in the
+/// audit log as where it came from.
+pub async fn import_parsed(
+    pool: &SqlitePool,
+    parsed: ParsedBundle,
+    source: &str,
+) -> Result<BundleImport, String> {
+    let ParsedBundle {
+        bundle,
+        detected_version,
+        mut warnings,
+    } = parsed;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let presentation_id = insert_bundle(&mut tx, &bundle, &mut warnings).await?;
+    let detail = json!({
+        "name": bundle.presentation.name,
+        "importedFrom": source,
+        "slides": bundle.slides.len(),
+    });
+    audit::record(&mut *tx, "create", "presentation", &presentation_id, detail).await;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(BundleImport {
+        presentation_id,
+        detected_version,
+        warnings,
+    })
+}
+
+/// Insert a bundle under fresh ids, returning the new presentation id. What
+/// couldn't be carried over as it was is added to `warnings`.
+pub async fn insert_bundle(
+    tx: &mut Transaction<'_, Sqlite>,
+    bundle: &PresentationBundle,
+    warnings: &mut Vec<String>,
+) -> Result<String, String> {
+    let template_id = import_template(tx, &bundle.template).await?;
+    let mut template_ids: HashMap<&str, String> = HashMap::new();
+    template_ids.insert(bundle.template.id.as_str(), template_id.clone());
+    for template in &bundle.override_templates {
+        let id = import_template(tx, template).await?;
+        template_ids.insert(template.id.as_str(), id);
+    }
+
+    let presentation_id = db::new_id();
+    let p = &bundle.presentation;
+    // A secondary needs its primary here; without it, it stands on its own
+    let parent_id = match p.parent_id.as_deref() {
+        _ if p.is_primary => None,
+        Some(id) if primary_exists(tx, id).await? => Some(id.to_string()),
+        Some(id) => {
+            warnings.push(format!(
+                "Primary presentation {id} isn't here, so {} was imported as a primary \
+                 presentation",
+                p.name
+            ));
+            None
+        }
+        None => {
+            warnings.push(format!(
+                "{} was exported as a secondary presentation without its primary, so it was \
+                 imported as a primary presentation",
+                p.name
+            ));
+            None
+        }
+    };
+    let is_primary = parent_id.is_none();
+
+    sqlx::query(
+        "INSERT INTO presentations
+         (id, name, type, template_id, language_map, language_settings, is_primary, is_active,
+          created_at, display_scale, parent_id)
+         VALUES (?, ?, ?, ?, ?, ?, ?, 0, ?, ?, ?)",
+    )
+    .bind(&presentation_id)
+    .bind(&p.name)
+    .bind(&p.presentation_type)
+    .bind(&template_id)
+    .bind(&p.language_map)
+    .bind(&p.language_settings)
+    .bind(is_primary)
+    .bind(db::now())
+    .bind(p.display_scale)
+    .bind(parent_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut slide_ids: HashMap<&str, String> = HashMap::new();
+    for slide in &bundle.slides {
+        let new_id = db::new_id();
+        let override_id = match slide.template_override_id.as_deref() {
+            Some(id) if template_ids.contains_key(id) => Some(template_ids[id].clone()),
+            Some(id) if template_exists(tx, id).await? => Some(id.to_string()),
+            Some(id) => {
+                warnings.push(format!(
+                    "Slide {} overrides its template with {id}, which isn't here, so it uses \
+                     the presentation's template",
+                    slide.slide_order
+                ));
+                None
+            }
+            None => None,
+        };
+        sqlx::query(
+            "INSERT INTO slides
+             (id, presentation_id, slide_order, line_id, title_json, blocks_json, footer_json,
+              notes, is_disabled, is_dynamic, template_override_id)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&new_id)
+        .bind(&presentation_id)
+        .bind(slide.slide_order)
+        .bind(&slide.line_id)
+        .bind(&slide.title_json)
+        .bind(&slide.blocks_json)
+        .bind(&slide.footer_json)
+        .bind(&slide.notes)
+        .bind(slide.is_disabled)
+        .bind(slide.is_dynamic)
+        .bind(override_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        slide_ids.insert(slide.id.as_str(), new_id);
+    }
+
+    let mut variable_ids: HashMap<&str, String> = HashMap::new();
+    for variable in &bundle.variables {
+        let new_id = db::new_id();
+        sqlx::query(
+            "INSERT INTO variables
+             (id, presentation_id, name, value, value_lang1, value_lang2, value_lang3, value_lang4)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&new_id)
+        .bind(&presentation_id)
+        .bind(&variable.name)
+        .bind(&variable.value)
+        .bind(&variable.value_lang1)
+        .bind(&variable.value_lang2)
+        .bind(&variable.value_lang3)
+        .bind(&variable.value_lang4)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        variable_ids.insert(variable.id.as_str(), new_id);
+    }
+
+    // The sync trigger has filled 1..4 from the legacy columns with '' for a
+    // NULL; the exported rows have the last word, and carry Lang5 onwards
+    for value in &bundle.variable_values {
+        let variable_id = variable_ids
+            .get(value.variable_id.as_str())
+            .ok_or_else(|| {
+                format!(
+                    "Variable value targets unknown variable {}",
+                    value.variable_id
+                )
+            })?;
+        sqlx::query(
+            "INSERT INTO variable_values (variable_id, language_index, value) VALUES (?, ?, ?)
+             ON CONFLICT(variable_id, language_index) DO UPDATE SET value = excluded.value",
+        )
+        .bind(variable_id)
+        .bind(value.language_index)
+        .bind(&value.value)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    for rule in &bundle.rules {
+        let slide_id = match rule.slide_id.as_deref() {
+            Some(old) => Some(
+                slide_ids
+                    .get(old)
+                    .cloned()
+                    .ok_or_else(|| format!("Rule {} targets unknown slide {old}", rule.name))?,
+            ),
+            None => None,
+        };
+        sqlx::query(
+            "INSERT INTO rule_definitions
+             (id, name, scope, presentation_id, slide_id, gitsawe_id, rule_json, is_enabled, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(db::new_id())
+        .bind(&rule.name)
+        .bind(&rule.scope)
+        .bind(&presentation_id)
+        .bind(slide_id)
+        // Gitsawes are shared reference data, not part of the bundle
+        .bind(&rule.gitsawe_id)
+        .bind(&rule.rule_json)
+        .bind(rule.is_enabled)
+        .bind(&rule.created_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(presentation_id)
+}
+
+/// Reuse a local template with the same name and definition, else insert a copy.
+async fn import_template(
+    tx: &mut Transaction<'_, Sqlite>,
+    template: &TemplateRecord,
+) -> Result<String, String> {
+    let existing: Option<String> =
+        sqlx::query_scalar("SELECT id FROM templates WHERE name = ? AND definition_json = ?")
+            .bind(&template.name)
+            .bind(&template.definition_json)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    if let Some(id) = existing {
+        return Ok(id);
+    }
+
+    let id = db::new_id();
+    sqlx::query(
+        "INSERT INTO templates (id, name, max_lang_count, definition_json, created_at)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(&template.name)
+    .bind(template.max_lang_count)
+    .bind(&template.definition_json)
+    .bind(db::now())
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+async fn template_exists(tx: &mut Transaction<'_, Sqlite>, id: &str) -> Result<bool, String> {
+    let found: Option<String> = sqlx::query_scalar("SELECT id FROM templates WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(found.is_some())
+}
+
+async fn primary_exists(tx: &mut Transaction<'_, Sqlite>, id: &str) -> Result<bool, String> {
+    let found: Option<String> = sqlx::query_scalar(
+        "SELECT id FROM presentations WHERE id = ? AND is_primary = 1 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(found.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::build_bundle;
+    use crate::test_support;
+
+    #[tokio::test]
+    async fn a_bundle_carries_its_overrides_scale_parent_and_every_language() {
+        let source = test_support::pool().await;
+        for id in ["primary", "p1"] {
+            test_support::presentation(&source, id).await;
+        }
+        test_support::template(&source, "wide").await;
+        test_support::slide(&source, "s1", "p1", 1).await;
+        test_support::exec(
+            &source,
+            "UPDATE presentations SET is_primary = 0, parent_id = 'primary', display_scale = 1.25
+                 WHERE id = 'p1';
+             UPDATE slides SET template_override_id = 'wide' WHERE id = 's1';
+             INSERT INTO variables (id, presentation_id, name, value)
+                 VALUES ('v1', 'p1', 'SAINT', 'Mary');
+             INSERT INTO variable_values (variable_id, language_index, value)
+                 VALUES ('v1', 5, 'Maryam')",
+        )
+        .await;
+        let json = checksum::seal(build_bundle(&source, "p1").await.unwrap()).unwrap();
+
+        let pool = test_support::pool().await;
+        let imported = import_parsed(&pool, parse(&json).unwrap(), "test")
+            .await
+            .unwrap();
+        let id = imported.presentation_id;
+        // The parent stayed behind, so the import stands on its own and says so
+        assert!(imported
+            .warnings
+            .iter()
+            .any(|w| w.contains("imported as a primary")));
+
+        let (scale, is_primary, parent): (Option<f64>, bool, Option<String>) = sqlx::query_as(
+            "SELECT display_scale, is_primary, parent_id FROM presentations WHERE id = ?",
+        )
+        .bind(&id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(scale, Some(1.25));
+        assert!(is_primary);
+        assert_eq!(parent, None);
+
+        let override_name: String = sqlx::query_scalar(
+            "SELECT t.name FROM slides s JOIN templates t ON t.id = s.template_override_id
+             WHERE s.presentation_id = ?",
+        )
+        .bind(&id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(override_name, "wide");
+
+        let lang5: Option<String> = sqlx::query_scalar(
+            "SELECT vv.value FROM variable_values vv JOIN variables v ON v.id = vv.variable_id
+             WHERE v.presentation_id = ? AND vv.language_index = 5",
+        )
+        .bind(&id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(lang5.as_deref(), Some("Maryam"));
+    }
+
+    #[tokio::test]
+    async fn a_missing_override_template_is_warned_about() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        test_support::slide(&pool, "s1", "p1", 3).await;
+        let mut bundle = build_bundle(&pool, "p1").await.unwrap();
+        bundle.slides[0].template_override_id = Some("gone".to_string());
+        let json = checksum::seal(bundle).unwrap();
+
+        let imported = import_parsed(&pool, parse(&json).unwrap(), "test")
+            .await
+            .unwrap();
+        assert!(
+            imported
+                .warnings
+                .iter()
+                .any(|w| w.starts_with("Slide 3") && w.contains("gone")),
+            "{:?}",
+            imported.warnings
+        );
+        let override_id: Option<String> =
+            sqlx::query_scalar("SELECT template_override_id FROM slides WHERE presentation_id = ?")
+                .bind(&imported.presentation_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(override_id, None);
+    }
+
+    #[tokio::test]
+    async fn a_secondary_keeps_a_parent_that_is_here() {
+        let pool = test_support::pool().await;
+        for id in ["primary", "p1"] {
+            test_support::presentation(&pool, id).await;
+        }
+        test_support::exec(
+            &pool,
+            "UPDATE presentations SET is_primary = 0, parent_id = 'primary' WHERE id = 'p1'",
+        )
+        .await;
+        let json = checksum::seal(build_bundle(&pool, "p1").await.unwrap()).unwrap();
+
+        let imported = import_parsed(&pool, parse(&json).unwrap(), "test")
+            .await
+            .unwrap();
+        assert!(imported.warnings.is_empty(), "{:?}", imported.warnings);
+        let parent: Option<String> =
+            sqlx::query_scalar("SELECT parent_id FROM presentations WHERE id = ?")
+                .bind(&imported.presentation_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(parent.as_deref(), Some("primary"));
+    }
+
+    #[tokio::test]
+    async fn a_secondary_without_a_parent_is_imported_as_primary() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        test_support::exec(
+            &pool,
+            "UPDATE presentations SET is_primary = 0 WHERE id = 'p1'",
+        )
+        .await;
+        let json = checksum::seal(build_bundle(&pool, "p1").await.unwrap()).unwrap();
+
+        let imported = import_parsed(&pool, parse(&json).unwrap(), "test")
+            .await
+            .unwrap();
+        assert!(
+            imported
+                .warnings
+                .iter()
+                .any(|w| w.contains("imported as a primary")),
+            "{:?}",
+            imported.warnings
+        );
+        let link: (bool, Option<String>) =
+            sqlx::query_as("SELECT is_primary, parent_id FROM presentations WHERE id = ?")
+                .bind(&imported.presentation_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(link, (true, None));
+    }
+}
@@ -0,0 +1,212 @@
+pub mod archive;
+pub mod checksum;
+pub mod duplicate;
+pub mod import;
+pub mod year;
+pub mod zip;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::db;
+
+/// Bumped whenever the shape of [`PresentationBundle`] changes; older versions
+/// are brought up to it by [`import::legacy::upgrade`].
+pub const BUNDLE_SCHEMA_VERSION: u32 = 2;
+
+/// Self-contained export of one presentation. Records keep their column names
+/// so a bundle reads like the tables it came from.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PresentationBundle {
+    pub schema_version: u32,
+    pub exported_at: String,
+    pub presentation: PresentationRecord,
+    pub template: TemplateRecord,
+    /// Templates slides override `template` with, other than it (schema v2).
+    pub override_templates: Vec<TemplateRecord>,
+    pub slides: Vec<SlideRecord>,
+    pub variables: Vec<VariableRecord>,
+    /// Every language's value of every variable, those past `value_lang4`
+    /// included (schema v2).
+    pub variable_values: Vec<VariableValueRecord>,
+    pub rules: Vec<RuleRecord>,
+    /// See [`checksum::CHECKSUM_FORMAT_VERSION`]; absent in older exports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format_version: Option<u32>,
+    /// Hex SHA-256 over the rest of the bundle; absent in older exports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PresentationRecord {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub presentation_type: String,
+    pub template_id: String,
+    pub language_map: String,
+    pub language_settings: Option<String>,
+    pub is_primary: bool,
+    pub is_active: bool,
+    pub created_at: String,
+    /// Schema v2.
+    pub display_scale: Option<f64>,
+    /// The primary presentation a secondary one belongs to (schema v2).
+    pub parent_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TemplateRecord {
+    pub id: String,
+    pub name: String,
+    pub max_lang_count: i64,
+    pub definition_json: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SlideRecord {
+    pub id: String,
+    pub slide_order: i64,
+    pub line_id: Option<String>,
+    pub title_json: Option<String>,
+    pub blocks_json: String,
+    pub footer_json: Option<String>,
+    pub notes: Option<String>,
+    pub is_disabled: bool,
+    pub is_dynamic: bool,
+    pub template_override_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct VariableRecord {
+    pub id: String,
+    pub name: String,
+    pub value: String,
+    pub value_lang1: String,
+    pub value_lang2: String,
+    pub value_lang3: String,
+    pub value_lang4: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct VariableValueRecord {
+    pub variable_id: String,
+    pub language_index: i64,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RuleRecord {
+    pub id: String,
+    pub name: String,
+    pub scope: String,
+    pub presentation_id: Option<String>,
+    pub slide_id: Option<String>,
+    pub gitsawe_id: Option<String>,
+    pub rule_json: String,
+    pub is_enabled: bool,
+    pub created_at: String,
+}
+
+/// Serialize a presentation with its templates, slides, variables and rules,
+/// sealed with a checksum that import verifies.
+#[tauri::command]
+pub async fn export_presentation(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<String, String> {
+    let pool = db::pool(&db).await?;
+    let bundle = build_bundle(&pool, &presentation_id).await?;
+    checksum::seal(bundle)
+}
+
+pub async fn build_bundle(
+    pool: &SqlitePool,
+    presentation_id: &str,
+) -> Result<PresentationBundle, String> {
+    let presentation =
+        sqlx::query_as::<_, PresentationRecord>("SELECT * FROM presentations WHERE id = ?")
+            .bind(presentation_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Presentation not found: {presentation_id}"))?;
+
+    let template = sqlx::query_as::<_, TemplateRecord>("SELECT * FROM templates WHERE id = ?")
+        .bind(&presentation.template_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Template not found: {}", presentation.template_id))?;
+
+    let slides = sqlx::query_as::<_, SlideRecord>(
+        "SELECT * FROM slides WHERE presentation_id = ? ORDER BY slide_order, id",
+    )
+    .bind(presentation_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // A dangling override renders with the presentation's template, so it's
+    // left for import to drop
+    let override_templates = sqlx::query_as::<_, TemplateRecord>(
+        "SELECT * FROM templates
+         WHERE id <> ? AND id IN (SELECT template_override_id FROM slides WHERE presentation_id = ?)
+         ORDER BY created_at, id",
+    )
+    .bind(&presentation.template_id)
+    .bind(presentation_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let variables = sqlx::query_as::<_, VariableRecord>(
+        "SELECT * FROM variables WHERE presentation_id = ? ORDER BY name, id",
+    )
+    .bind(presentation_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let variable_values = sqlx::query_as::<_, VariableValueRecord>(
+        "SELECT vv.* FROM variable_values vv
+         JOIN variables v ON v.id = vv.variable_id
+         WHERE v.presentation_id = ?
+         ORDER BY vv.variable_id, vv.language_index",
+    )
+    .bind(presentation_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let rules = sqlx::query_as::<_, RuleRecord>(
+        "SELECT * FROM rule_definitions
+         WHERE presentation_id = ?
+            OR slide_id IN (SELECT id FROM slides WHERE presentation_id = ?)
+         ORDER BY created_at, id",
+    )
+    .bind(presentation_id)
+    .bind(presentation_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(PresentationBundle {
+        schema_version: BUNDLE_SCHEMA_VERSION,
+        exported_at: db::now(),
+        presentation,
+        template,
+        override_templates,
+        slides,
+        variables,
+        variable_values,
+        rules,
+        format_version: None,
+        checksum: None,
+    })
+}
@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use ::zip::write::SimpleFileOptions;
+use ::zip::{ZipArchive, ZipWriter};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use super::archive::{self, ArchiveEntry, ArchiveFailure, RestoredEntry};
+use super::import::{self, BundleImport};
+use super::{build_bundle, checksum};
+use crate::audit;
+use crate::bahire_hasab;
+use crate::calendar::{self, EthiopianDate};
+use crate::db;
+use crate::export::filename::export_filename;
+use crate::export::ExportFormat;
+use crate::gitsawe::GitsaweSelector;
+use crate::schedule;
+
+/// Entry listing the days of a year archive and what was prepared for each.
+const MANIFEST_ENTRY: &str = "year.json";
+/// Bumped whenever the shape of [`YearManifest`] changes.
+const YEAR_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YearManifest {
+    pub format_version: u32,
+    pub exported_at: String,
+    /// The Ethiopian year.
+    pub year: i32,
+    pub days: Vec<ArchivedDay>,
+    /// Each presentation once, in the folder of the first day it's scheduled.
+    pub entries: Vec<ArchiveEntry>,
+}
+
+/// A scheduled day and the liturgical day it falls on.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedDay {
+    pub date: EthiopianDate,
+    /// The same day as `YYYY-MM-DD`.
+    pub gregorian_date: String,
+    /// Movable feasts falling on the day, by [`crate::bahire_hasab`] name.
+    pub feasts: Vec<String>,
+    /// The gitsawe selected for the day, when one is.
+    pub gitsawe_line_id: Option<String>,
+    /// Ids, as exported, of the presentations scheduled for the day.
+    pub presentation_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YearArchiveReport {
+    pub year: i32,
+    /// Scheduled days in the archive.
+    pub days: u32,
+    pub exported: Vec<ArchiveEntry>,
+    pub failed: Vec<ArchiveFailure>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YearArchiveImport {
+    pub year: i32,
+    pub imported: Vec<RestoredEntry>,
+    /// Schedule entries restored for the imported presentations.
+    pub scheduled: u32,
+    pub failed: Vec<ArchiveFailure>,
+}
+
+/// Write every presentation scheduled in an Ethiopian year into one zip, each
+/// as the bundle `export_presentation` produces in a folder named for the
+/// first day it's scheduled, with a `year.json` giving the liturgical day of
+/// every scheduled date.
+///
+/// A presentation that can't be exported is recorded in the report and the
+/// rest still go in; only failing to write the archive itself is an error.
+#[tauri::command]
+pub async fn export_year_archive(
+    db: State<'_, DbInstances>,
+    year: i32,
+    dest_path: String,
+) -> Result<YearArchiveReport, String> {
+    let pool = db::pool(&db).await?;
+    export_year(&pool, year, &dest_path).await
+}
+
+async fn export_year(
+    pool: &SqlitePool,
+    year: i32,
+    dest_path: &str,
+) -> Result<YearArchiveReport, String> {
+    let scheduled = schedule::for_year(pool, year).await?;
+    let feasts = bahire_hasab::movable_feasts(year)?.feasts;
+    let selector = GitsaweSelector::load(pool).await?;
+
+    let mut days: Vec<ArchivedDay> = Vec::new();
+    for service in &scheduled {
+        if let Some(day) = days
+            .last_mut()
+            .filter(|day| day.gregorian_date == service.service_date)
+        {
+            day.presentation_ids.push(service.presentation_id.clone());
+            continue;
+        }
+        let date = calendar::parse_gregorian(&service.service_date)?;
+        days.push(ArchivedDay {
+            date: EthiopianDate::from_gregorian(date),
+            gregorian_date: service.service_date.clone(),
+            feasts: feasts
+                .iter()
+                .filter(|feast| feast.gregorian == service.service_date)
+                .map(|feast| feast.name.to_string())
+                .collect(),
+            gitsawe_line_id: selector.select(date)?.map(|g| g.line_id.clone()),
+            presentation_ids: vec![service.presentation_id.clone()],
+        });
+    }
+
+    let file = std::fs::File::create(dest_path)
+        .map_err(|e| format!("Failed to write {dest_path}: {e}"))?;
+    let mut zip = ZipWriter::new(file);
+    let mut entry = |name: &str, content: &[u8]| -> Result<(), String> {
+        zip.start_file(name, SimpleFileOptions::default())
+            .map_err(|e| e.to_string())?;
+        zip.write_all(content).map_err(|e| e.to_string())
+    };
+
+    let mut exported: Vec<ArchiveEntry> = Vec::new();
+    let mut failed: Vec<ArchiveFailure> = Vec::new();
+    for day in &days {
+        for id in &day.presentation_ids {
+            if exported.iter().any(|e| e.id == *id) || failed.iter().any(|f| f.id == *id) {
+                continue;
+            }
+            let sealed = match build_bundle(pool, id).await {
+                Ok(bundle) => {
+                    let name = bundle.presentation.name.clone();
+                    checksum::seal(bundle).map(|json| (name, json))
+                }
+                Err(e) => Err(e),
+            };
+            let (name, json) = match sealed {
+                Ok(sealed) => sealed,
+                Err(error) => {
+                    failed.push(ArchiveFailure {
+                        id: id.clone(),
+                        error,
+                    });
+                    continue;
+                }
+            };
+            let date = calendar::parse_gregorian(&day.gregorian_date)?;
+            let filename = match export_filename(pool, id, ExportFormat::Json, Some(date)).await {
+                Ok(filename) => filename,
+                Err(error) => {
+                    failed.push(ArchiveFailure {
+                        id: id.clone(),
+                        error,
+                    });
+                    continue;
+                }
+            };
+            let checksum = serde_json::from_str::<Value>(&json)
+                .ok()
+                .and_then(|payload| payload.get("checksum")?.as_str().map(str::to_string))
+                .unwrap_or_default();
+            let path = format!("{}/{filename}", day.gregorian_date);
+            entry(&path, json.as_bytes())?;
+            exported.push(ArchiveEntry {
+                id: id.clone(),
+                name,
+                entry: path,
+                checksum,
+            });
+        }
+    }
+
+    let report = YearArchiveReport {
+        year,
+        days: days.len() as u32,
+        exported: exported.clone(),
+        failed,
+    };
+    let manifest = YearManifest {
+        format_version: YEAR_FORMAT_VERSION,
+        exported_at: db::now(),
+        year,
+        days,
+        entries: exported,
+    };
+    let manifest = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    entry(MANIFEST_ENTRY, manifest.as_bytes())?;
+    zip.finish()
+        .map_err(|e| format!("Failed to write {dest_path}: {e}"))?;
+
+    Ok(report)
+}
+
+/// Import every presentation of an archive written by [`export_year_archive`]
+/// under fresh ids, as `import_presentation` would, and schedule each again on
+/// its days. A presentation and its schedule go in one transaction, so one
+/// that fails is recorded and the rest are still restored.
+#[tauri::command]
+pub async fn import_year_archive(
+    db: State<'_, DbInstances>,
+    src_path: String,
+) -> Result<YearArchiveImport, String> {
+    let pool = db::writable_pool(&db).await?;
+    import_year(&pool, &src_path).await
+}
+
+async fn import_year(pool: &SqlitePool, src_path: &str) -> Result<YearArchiveImport, String> {
+    let mut archive = archive::open(src_path)?;
+    let manifest = read_manifest(&mut archive, src_path)?;
+
+    let mut dates: HashMap<&str, Vec<&str>> = HashMap::new();
+    for day in &manifest.days {
+        for id in &day.presentation_ids {
+            dates
+                .entry(id.as_str())
+                .or_default()
+                .push(day.gregorian_date.as_str());
+        }
+    }
+
+    let mut imported = Vec::new();
+    let mut scheduled = 0;
+    let mut failed = Vec::new();
+    for entry in &manifest.entries {
+        let dates = dates.get(entry.id.as_str()).map_or(&[][..], Vec::as_slice);
+        let result =
+            match archive::read_entry(&mut archive, entry).and_then(|json| import::parse(&json)) {
+                Ok(parsed) => restore(pool, parsed, dates).await,
+                Err(e) => Err(e),
+            };
+        match result {
+            Ok(import) => {
+                scheduled += dates.len() as u32;
+                imported.push(RestoredEntry {
+                    original_id: entry.id.clone(),
+                    import,
+                });
+            }
+            Err(error) => failed.push(ArchiveFailure {
+                id: entry.id.clone(),
+                error,
+            }),
+        }
+    }
+    Ok(YearArchiveImport {
+        year: manifest.year,
+        imported,
+        scheduled,
+        failed,
+    })
+}
+
+/// Insert a bundle and schedule it on `dates`, all or nothing.
+async fn restore(
+    pool: &SqlitePool,
+    parsed: import::ParsedBundle,
+    dates: &[&str],
+) -> Result<BundleImport, String> {
+    let mut warnings = parsed.warnings;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let presentation_id = import::insert_bundle(&mut tx, &parsed.bundle, &mut warnings).await?;
+    for date in dates {
+        let date = calendar::parse_gregorian(date)?
+            .format("%Y-%m-%d")
+            .to_string();
+        sqlx::query(
+            "INSERT OR IGNORE INTO scheduled_services (service_date, presentation_id, created_at)
+             VALUES (?, ?, ?)",
+        )
+        .bind(date)
+        .bind(&presentation_id)
+        .bind(db::now())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    let detail = json!({
+        "name": parsed.bundle.presentation.name,
+        "importedFrom": "yearArchive",
+        "slides": parsed.bundle.slides.len(),
+        "scheduledFor": dates,
+    });
+    audit::record(&mut *tx, "create", "presentation", &presentation_id, detail).await;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(BundleImport {
+        presentation_id,
+        detected_version: parsed.detected_version,
+        warnings,
+    })
+}
+
+fn read_manifest(
+    archive: &mut ZipArchive<std::fs::File>,
+    src_path: &str,
+) -> Result<YearManifest, String> {
+    let mut json = String::new();
+    archive
+        .by_name(MANIFEST_ENTRY)
+        .map_err(|_| format!("{src_path} has no {MANIFEST_ENTRY}; is it a year archive?"))?
+        .read_to_string(&mut json)
+        .map_err(|e| format!("Failed to read {MANIFEST_ENTRY}: {e}"))?;
+    let manifest: YearManifest =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid {MANIFEST_ENTRY}: {e}"))?;
+    if manifest.format_version > YEAR_FORMAT_VERSION {
+        return Err(format!(
+            "Year archive format version {} is newer than supported version {YEAR_FORMAT_VERSION}",
+            manifest.format_version
+        ));
+    }
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    async fn schedule(pool: &SqlitePool, date: &str, presentation_id: &str) {
+        sqlx::query(
+            "INSERT INTO scheduled_services (service_date, presentation_id, created_at)
+             VALUES (?, ?, '2026-01-01T00:00:00.000Z')",
+        )
+        .bind(date)
+        .bind(presentation_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_year_round_trips_with_its_schedule() {
+        let pool = test_support::pool().await;
+        for id in ["meskel", "fasika", "next-year"] {
+            test_support::presentation(&pool, id).await;
+            test_support::slide(&pool, &format!("{id}-s1"), id, 1).await;
+        }
+        // Fasika 2016 fell on 2024-05-05
+        schedule(&pool, "2023-09-28", "meskel").await;
+        schedule(&pool, "2024-05-05", "fasika").await;
+        schedule(&pool, "2024-05-05", "meskel").await;
+        schedule(&pool, "2024-09-11", "next-year").await;
+
+        let path = test_support::temp_path("2016.zip");
+        let report = export_year(&pool, 2016, path.to_str().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(report.days, 2);
+        assert!(report.failed.is_empty());
+        // Named for the day they're in the folder of
+        let entries: Vec<&str> = report.exported.iter().map(|e| e.entry.as_str()).collect();
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].starts_with("2023-09-28/meskel_kidase_2016-01-17_"));
+        assert!(entries[1].starts_with("2024-05-05/fasika_kidase_2016-08-27_"));
+
+        let mut archive = archive::open(path.to_str().unwrap()).unwrap();
+        let manifest = read_manifest(&mut archive, "2016.zip").unwrap();
+        assert_eq!(
+            manifest.days[0].date,
+            EthiopianDate::new(2016, 1, 17).unwrap()
+        );
+        assert_eq!(manifest.days[1].feasts, ["fasika"]);
+        assert_eq!(manifest.days[1].presentation_ids, ["fasika", "meskel"]);
+
+        let restored = import_year(&pool, path.to_str().unwrap()).await.unwrap();
+        assert!(restored.failed.is_empty());
+        assert_eq!(restored.imported.len(), 2);
+        assert_eq!(restored.scheduled, 3);
+
+        let copies: Vec<(String, String)> = sqlx::query_as(
+            "SELECT s.service_date, p.name FROM scheduled_services s
+             JOIN presentations p ON p.id = s.presentation_id
+             WHERE p.id NOT IN ('meskel', 'fasika', 'next-year')
+             ORDER BY s.service_date, p.name",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            copies,
+            [
+                ("2023-09-28".to_string(), "meskel".to_string()),
+                ("2024-05-05".to_string(), "fasika".to_string()),
+                ("2024-05-05".to_string(), "meskel".to_string())
+            ]
+        );
+    }
+}
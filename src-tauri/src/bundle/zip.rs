@@ -0,0 +1,313 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use ::zip::write::SimpleFileOptions;
+use ::zip::{ZipArchive, ZipWriter};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_sql::DbInstances;
+
+use super::import::{self, BundleImport};
+use super::{build_bundle, checksum};
+use crate::db;
+
+/// Entry holding the sealed bundle JSON in a `.kidase` file.
+const BUNDLE_ENTRY: &str = "bundle.json";
+/// Entry describing the rest of a `.kidase` file; absent in the first ones.
+const MANIFEST_ENTRY: &str = "manifest.json";
+/// Bumped whenever the layout of a `.kidase` file changes.
+const PACK_FORMAT_VERSION: u32 = 1;
+/// Folder of the archive, and of the app data dir, that media files go in.
+const MEDIA_DIR: &str = "media";
+/// Block keys whose string value is the path of a media file.
+const MEDIA_KEYS: [&str; 2] = ["image", "src"];
+
+/// What a `.kidase` file holds, readable without parsing the bundle.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackManifest {
+    pub format_version: u32,
+    /// The bundle's [`super::BUNDLE_SCHEMA_VERSION`].
+    pub schema_version: u32,
+    pub exported_at: String,
+    pub presentation_id: String,
+    pub presentation_name: String,
+    /// Archive paths of the media files, as the blocks refer to them.
+    pub media: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleZipExport {
+    pub media_files: u32,
+    /// Media the slides reference that couldn't be read; the references are
+    /// kept as they were.
+    pub warnings: Vec<String>,
+}
+
+/// Write a presentation as a `.kidase` zip: the bundle `export_presentation`
+/// produces, plus every media file its blocks reference under `media/`, with
+/// a `manifest.json` listing them.
+///
+/// References are rewritten to their place in the archive before the bundle
+/// is sealed. Relative paths are read from the app data dir; URLs are left
+/// alone.
+#[tauri::command]
+pub async fn export_bundle_zip(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    dest_path: String,
+) -> Result<BundleZipExport, String> {
+    let data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let pool = db::pool(&db).await?;
+    let mut bundle = build_bundle(&pool, &presentation_id).await?;
+
+    // Archive name by original reference, so a file used twice is stored once
+    let mut media: BTreeMap<String, (String, Vec<u8>)> = BTreeMap::new();
+    let mut warnings = Vec::new();
+    for slide in &mut bundle.slides {
+        let Ok(mut blocks) = serde_json::from_str::<Value>(&slide.blocks_json) else {
+            continue;
+        };
+        let mut changed = false;
+        visit_media(&mut blocks, &mut |reference| {
+            if let Some((name, _)) = media.get(reference.as_str()) {
+                *reference = name.clone();
+                changed = true;
+                return;
+            }
+            let path = resolve(&data_dir, reference);
+            match std::fs::read(&path) {
+                Ok(bytes) => {
+                    let name = archive_name(media.len(), &path);
+                    media.insert(reference.clone(), (name.clone(), bytes));
+                    *reference = name;
+                    changed = true;
+                }
+                Err(e) => warnings.push(format!(
+                    "Slide {}: couldn't read {}: {e}",
+                    slide.slide_order,
+                    path.display()
+                )),
+            }
+        });
+        if changed {
+            slide.blocks_json = serde_json::to_string(&blocks).map_err(|e| e.to_string())?;
+        }
+    }
+    let manifest = PackManifest {
+        format_version: PACK_FORMAT_VERSION,
+        schema_version: bundle.schema_version,
+        exported_at: bundle.exported_at.clone(),
+        presentation_id: bundle.presentation.id.clone(),
+        presentation_name: bundle.presentation.name.clone(),
+        media: media.values().map(|(name, _)| name.clone()).collect(),
+    };
+    let manifest = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    let json = checksum::seal(bundle)?;
+
+    let file = std::fs::File::create(&dest_path)
+        .map_err(|e| format!("Failed to write {dest_path}: {e}"))?;
+    let mut zip = ZipWriter::new(file);
+    let mut entry = |name: &str, content: &[u8]| -> Result<(), String> {
+        zip.start_file(name, SimpleFileOptions::default())
+            .map_err(|e| e.to_string())?;
+        zip.write_all(content).map_err(|e| e.to_string())
+    };
+    entry(MANIFEST_ENTRY, manifest.as_bytes())?;
+    entry(BUNDLE_ENTRY, json.as_bytes())?;
+    for (name, bytes) in media.values() {
+        entry(name, bytes)?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to write {dest_path}: {e}"))?;
+
+    Ok(BundleZipExport {
+        media_files: media.len() as u32,
+        warnings,
+    })
+}
+
+/// Import a `.kidase` zip written by [`export_bundle_zip`].
+///
+/// The bundle is checked as `import_presentation` checks it before anything
+/// happens. Media files are then extracted to a new folder under the app data
+/// dir and the blocks pointed at them, and the presentation is inserted in one
+/// transaction; if that fails the extracted files are removed again. Media the
+/// blocks reference that the archive lacks only add a warning.
+#[tauri::command]
+pub async fn import_bundle_zip(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    src_path: String,
+) -> Result<BundleImport, String> {
+    let file =
+        std::fs::File::open(&src_path).map_err(|e| format!("Failed to open {src_path}: {e}"))?;
+    let mut archive =
+        ZipArchive::new(file).map_err(|e| format!("{src_path} is not a .kidase bundle: {e}"))?;
+    if let Some(manifest) = read_manifest(&mut archive)? {
+        if manifest.format_version > PACK_FORMAT_VERSION {
+            return Err(format!(
+                "{src_path} has format version {} but this build only reads up to {PACK_FORMAT_VERSION}",
+                manifest.format_version
+            ));
+        }
+    }
+    let mut json = String::new();
+    archive
+        .by_name(BUNDLE_ENTRY)
+        .map_err(|_| format!("{src_path} has no {BUNDLE_ENTRY}"))?
+        .read_to_string(&mut json)
+        .map_err(|e| format!("Failed to read {BUNDLE_ENTRY}: {e}"))?;
+    let mut parsed = import::parse(&json)?;
+
+    let media_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(MEDIA_DIR)
+        .join(db::new_id());
+    let mut extracted = false;
+    let mut warnings = Vec::new();
+    for slide in &mut parsed.bundle.slides {
+        let Ok(mut blocks) = serde_json::from_str::<Value>(&slide.blocks_json) else {
+            continue;
+        };
+        let mut failure = None;
+        let mut changed = false;
+        visit_media(&mut blocks, &mut |reference| {
+            if failure.is_some() || !reference.starts_with(&format!("{MEDIA_DIR}/")) {
+                return;
+            }
+            match extract(&mut archive, reference, &media_dir) {
+                Ok(Some(path)) => {
+                    *reference = path.to_string_lossy().into_owned();
+                    extracted = true;
+                    changed = true;
+                }
+                Ok(None) => warnings.push(format!(
+                    "Slide {}: {reference} is missing from the bundle",
+                    slide.slide_order
+                )),
+                Err(e) => failure = Some(e),
+            }
+        });
+        if let Some(e) = failure {
+            let _ = std::fs::remove_dir_all(&media_dir);
+            return Err(e);
+        }
+        if changed {
+            slide.blocks_json = serde_json::to_string(&blocks).map_err(|e| e.to_string())?;
+        }
+    }
+    parsed.warnings.extend(warnings);
+
+    let pool = db::writable_pool(&db).await?;
+    let result = import::import_parsed(&pool, parsed, "bundleZip").await;
+    if result.is_err() && extracted {
+        let _ = std::fs::remove_dir_all(&media_dir);
+    }
+    result
+}
+
+/// The manifest of a `.kidase` file, or `None` for one written before they
+/// were added.
+fn read_manifest(archive: &mut ZipArchive<std::fs::File>) -> Result<Option<PackManifest>, String> {
+    let mut entry = match archive.by_name(MANIFEST_ENTRY) {
+        Ok(entry) => entry,
+        Err(::zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {MANIFEST_ENTRY}: {e}")),
+    };
+    let mut json = String::new();
+    entry
+        .read_to_string(&mut json)
+        .map_err(|e| format!("Failed to read {MANIFEST_ENTRY}: {e}"))?;
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| format!("Invalid {MANIFEST_ENTRY}: {e}"))
+}
+
+/// Call `f` with every media reference in a blocks document.
+fn visit_media(node: &mut Value, f: &mut impl FnMut(&mut String)) {
+    match node {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                match child {
+                    Value::String(reference) if MEDIA_KEYS.contains(&key.as_str()) => {
+                        if !is_url(reference) {
+                            f(reference);
+                        }
+                    }
+                    _ => visit_media(child, f),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                visit_media(item, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_url(reference: &str) -> bool {
+    ["http://", "https://", "data:", "asset:"]
+        .iter()
+        .any(|scheme| reference.starts_with(scheme))
+}
+
+fn resolve(data_dir: &Path, reference: &str) -> PathBuf {
+    let path = Path::new(reference);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        data_dir.join(path)
+    }
+}
+
+/// `media/<n>-<file name>`; the index keeps files that share a name apart.
+fn archive_name(index: usize, path: &Path) -> String {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "file".to_string());
+    format!("{MEDIA_DIR}/{index}-{file_name}")
+}
+
+/// Write the archive entry `name` into `dir`, returning where it went, or
+/// `None` when the archive has no such entry. Names that would escape `dir`
+/// are refused.
+fn extract(
+    archive: &mut ZipArchive<std::fs::File>,
+    name: &str,
+    dir: &Path,
+) -> Result<Option<PathBuf>, String> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(::zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {name}: {e}")),
+    };
+    let relative = entry
+        .enclosed_name()
+        .and_then(|path| path.strip_prefix(MEDIA_DIR).ok().map(Path::to_path_buf))
+        .filter(|path| !path.as_os_str().is_empty())
+        .ok_or_else(|| format!("Unsafe path in bundle: {name}"))?;
+    let dest = dir.join(relative);
+    if dest.exists() {
+        return Ok(Some(dest));
+    }
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let mut bytes = Vec::new();
+    entry
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read {name}: {e}"))?;
+    std::fs::write(&dest, bytes).map_err(|e| format!("Failed to write {}: {e}", dest.display()))?;
+    Ok(Some(dest))
+}
@@ -0,0 +1,172 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Julian day number of 1 Meskerem 1 in the Amete Mihret era.
+const ETHIOPIAN_EPOCH_JDN: i64 = 1_724_221;
+
+/// Julian day number of 1 January 1 CE, chrono's day zero minus one.
+const CE_EPOCH_JDN: i64 = 1_721_425;
+
+/// Twelve 30-day months followed by Pagumē.
+pub const PAGUME: u32 = 13;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EthiopianDate {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+/// The year before a Julian leap year, so Pagumē has a sixth day.
+pub fn is_leap_year(year: i32) -> bool {
+    year.rem_euclid(4) == 3
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        PAGUME if is_leap_year(year) => 6,
+        PAGUME => 5,
+        _ => 30,
+    }
+}
+
+impl EthiopianDate {
+    pub fn new(year: i32, month: u32, day: u32) -> Result<Self, String> {
+        if !(1..=PAGUME).contains(&month) {
+            return Err(format!("Invalid Ethiopian month: {month}"));
+        }
+        let max_day = days_in_month(year, month);
+        if !(1..=max_day).contains(&day) {
+            return Err(format!(
+                "Invalid day {day} for Ethiopian month {month} of {year} (has {max_day} days)"
+            ));
+        }
+        Ok(Self { year, month, day })
+    }
+
+    pub fn from_gregorian(date: NaiveDate) -> Self {
+        let jdn = date.num_days_from_ce() as i64 + CE_EPOCH_JDN;
+        let year = (4 * (jdn - ETHIOPIAN_EPOCH_JDN) + 1463).div_euclid(1461) as i32;
+        let month = ((jdn - julian_day(year, 1, 1)) / 30 + 1) as u32;
+        let day = (jdn - julian_day(year, month, 1) + 1) as u32;
+        Self { year, month, day }
+    }
+
+    pub fn to_gregorian(self) -> Result<NaiveDate, String> {
+        i32::try_from(julian_day(self.year, self.month, self.day) - CE_EPOCH_JDN)
+            .ok()
+            .and_then(NaiveDate::from_num_days_from_ce_opt)
+            .ok_or_else(|| format!("Ethiopian date out of range: {self}"))
+    }
+}
+
+fn julian_day(year: i32, month: u32, day: u32) -> i64 {
+    let year = year as i64;
+    ETHIOPIAN_EPOCH_JDN - 1
+        + 365 * (year - 1)
+        + year.div_euclid(4)
+        + 30 * (month as i64 - 1)
+        + day as i64
+}
+
+impl std::fmt::Display for EthiopianDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Parse the `YYYY-MM-DD` form used by `meta.date` on the frontend.
+pub fn parse_gregorian(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date {date}: {e}"))
+}
+
+#[tauri::command]
+pub fn gregorian_to_ethiopian(date: String) -> Result<EthiopianDate, String> {
+    parse_gregorian(&date).map(EthiopianDate::from_gregorian)
+}
+
+/// Gregorian `YYYY-MM-DD` for an Ethiopian date, rejecting days Pagumē lacks.
+#[tauri::command]
+pub fn ethiopian_to_gregorian(date: EthiopianDate) -> Result<String, String> {
+    let date = EthiopianDate::new(date.year, date.month, date.day)?;
+    Ok(date.to_gregorian()?.format("%Y-%m-%d").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gregorian(date: &str) -> NaiveDate {
+        parse_gregorian(date).unwrap()
+    }
+
+    #[test]
+    fn known_dates_convert_both_ways() {
+        for ((year, month, day), expected) in [
+            ((2016, 1, 1), "2023-09-12"),
+            ((2015, 13, 6), "2023-09-11"),
+            ((2016, 4, 29), "2024-01-08"),
+            ((2017, 1, 1), "2024-09-11"),
+            ((2018, 1, 1), "2025-09-11"),
+            ((1992, 4, 22), "2000-01-01"),
+        ] {
+            let date = EthiopianDate::new(year, month, day).unwrap();
+            assert_eq!(date.to_gregorian().unwrap(), gregorian(expected), "{date}");
+            assert_eq!(EthiopianDate::from_gregorian(gregorian(expected)), date);
+        }
+    }
+
+    #[test]
+    fn pagume_has_a_sixth_day_only_before_a_julian_leap_year() {
+        assert!(is_leap_year(2015) && !is_leap_year(2016) && is_leap_year(2019));
+        assert!(EthiopianDate::new(2015, PAGUME, 6).is_ok());
+        assert!(EthiopianDate::new(2016, PAGUME, 5).is_ok());
+        assert!(EthiopianDate::new(2016, PAGUME, 6).is_err());
+        assert!(EthiopianDate::new(2016, PAGUME, 7).is_err());
+        assert!(EthiopianDate::new(2016, 1, 31).is_err());
+        assert!(EthiopianDate::new(2016, 14, 1).is_err());
+        assert!(EthiopianDate::new(2016, 1, 0).is_err());
+
+        let error = ethiopian_to_gregorian(EthiopianDate {
+            year: 2016,
+            month: PAGUME,
+            day: 6,
+        })
+        .unwrap_err();
+        assert!(error.contains("has 5 days"), "{error}");
+    }
+
+    #[test]
+    fn every_day_round_trips_and_follows_the_last_across_new_year() {
+        // Four Ethiopian new years, a sixth day of Pagumē among them
+        let first = gregorian("2022-09-01");
+        let mut previous = EthiopianDate::from_gregorian(first);
+        for date in first.iter_days().skip(1).take(4 * 366) {
+            let ethiopian = EthiopianDate::from_gregorian(date);
+            assert_eq!(ethiopian.to_gregorian().unwrap(), date);
+            assert!(EthiopianDate::new(ethiopian.year, ethiopian.month, ethiopian.day).is_ok());
+
+            let expected = if previous.day < days_in_month(previous.year, previous.month) {
+                EthiopianDate {
+                    day: previous.day + 1,
+                    ..previous
+                }
+            } else if previous.month < PAGUME {
+                EthiopianDate {
+                    month: previous.month + 1,
+                    day: 1,
+                    ..previous
+                }
+            } else {
+                EthiopianDate {
+                    year: previous.year + 1,
+                    month: 1,
+                    day: 1,
+                }
+            };
+            assert_eq!(ethiopian, expected, "after {previous}");
+            previous = ethiopian;
+        }
+    }
+}
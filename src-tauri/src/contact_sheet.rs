@@ -0,0 +1,188 @@
+use std::fmt::Write as _;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_sql::DbInstances;
+use tiny_skia::Pixmap;
+
+use crate::db::{self, Slide};
+use crate::export::pdf;
+use crate::fonts;
+use crate::markup::escape;
+use crate::render;
+
+const THUMB_WIDTH: u32 = 320;
+const THUMB_HEIGHT: u32 = 180;
+const GUTTER: u32 = 24;
+const CAPTION_HEIGHT: u32 = 22;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ContactSheetProgress {
+    current: usize,
+    total: usize,
+}
+
+/// A slide rendered for the sheet, with the number it's captioned with.
+pub(crate) struct Thumbnail {
+    pub(crate) number: i64,
+    pub(crate) pixmap: Pixmap,
+}
+
+/// Tile every visible slide into a grid of thumbnails with slide numbers, in
+/// `slide_order`.
+///
+/// Thumbnails are rasterized as the filmstrip's are (see
+/// [`render::render_slide_thumbnail`]). `dest_path` picks the format: `.svg`
+/// lays them all on one sheet as embedded PNGs, `.pdf` pages them on A4
+/// landscape. Emits `contact-sheet-progress` after each slide is rendered.
+/// Missing fonts refuse the export unless `allow_missing_fonts`; see
+/// [`fonts::check_export_fonts`].
+#[tauri::command]
+pub async fn export_contact_sheet(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    dest_path: String,
+    columns: u32,
+    allow_missing_fonts: Option<bool>,
+) -> Result<(), String> {
+    if columns == 0 {
+        return Err("Columns must be at least 1".to_string());
+    }
+    let lower = dest_path.to_lowercase();
+    let as_pdf = if lower.ends_with(".pdf") {
+        true
+    } else if lower.ends_with(".svg") {
+        false
+    } else {
+        return Err("Contact sheets are written as .svg or .pdf files".to_string());
+    };
+
+    let pool = db::pool(&db).await?;
+    let presentation = db::fetch_presentation(&pool, &presentation_id).await?;
+    fonts::check_export_fonts(
+        &pool,
+        &presentation_id,
+        allow_missing_fonts.unwrap_or(false),
+    )
+    .await?;
+    let slides: Vec<Slide> = db::fetch_slides(&pool, &presentation_id)
+        .await?
+        .into_iter()
+        .filter(|s| !s.is_disabled)
+        .collect();
+
+    let total = slides.len();
+    let mut thumbnails = Vec::with_capacity(total);
+    for (index, slide) in slides.iter().enumerate() {
+        let pixmap =
+            render::slide_pixmap(&pool, slide, &presentation, THUMB_WIDTH, THUMB_HEIGHT).await?;
+        thumbnails.push(Thumbnail {
+            number: slide.slide_order,
+            pixmap,
+        });
+        let _ = app.emit(
+            "contact-sheet-progress",
+            ContactSheetProgress {
+                current: index + 1,
+                total,
+            },
+        );
+    }
+
+    let bytes = if as_pdf {
+        let name = presentation.name;
+        tauri::async_runtime::spawn_blocking(move || {
+            let (ethiopic, latin) = fonts::ethiopic_font_data(fonts::font_database())?;
+            pdf::render_contact_sheet(
+                &name,
+                &thumbnails,
+                columns as usize,
+                &ethiopic,
+                latin.as_deref(),
+            )
+        })
+        .await
+        .map_err(|e| e.to_string())??
+    } else {
+        svg(&presentation.name, &thumbnails, columns)?.into_bytes()
+    };
+
+    std::fs::write(&dest_path, bytes).map_err(|e| format!("Failed to write {dest_path}: {e}"))
+}
+
+fn svg(name: &str, thumbnails: &[Thumbnail], columns: u32) -> Result<String, String> {
+    let rows = (thumbnails.len() as u32).div_ceil(columns).max(1);
+    let width = columns * THUMB_WIDTH + (columns + 1) * GUTTER;
+    let height = rows * (THUMB_HEIGHT + CAPTION_HEIGHT) + (rows + 1) * GUTTER + CAPTION_HEIGHT;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="Nyala, serif">"#
+    );
+    let _ = writeln!(
+        svg,
+        r##"<rect width="100%" height="100%" fill="#FFFFFF"/>"##
+    );
+    let _ = writeln!(
+        svg,
+        r##"<text x="{GUTTER}" y="{}" font-size="16" font-weight="bold" fill="#222222">{}</text>"##,
+        GUTTER,
+        escape(name)
+    );
+
+    for (index, thumbnail) in thumbnails.iter().enumerate() {
+        let col = index as u32 % columns;
+        let row = index as u32 / columns;
+        let x = GUTTER + col * (THUMB_WIDTH + GUTTER);
+        let y = CAPTION_HEIGHT + GUTTER + row * (THUMB_HEIGHT + CAPTION_HEIGHT + GUTTER);
+
+        let png = thumbnail.pixmap.encode_png().map_err(|e| e.to_string())?;
+        let _ = writeln!(
+            svg,
+            r#"<image x="{x}" y="{y}" width="{THUMB_WIDTH}" height="{THUMB_HEIGHT}" href="data:image/png;base64,{}"/>"#,
+            STANDARD.encode(png)
+        );
+        let _ = writeln!(
+            svg,
+            r##"<rect x="{x}" y="{y}" width="{THUMB_WIDTH}" height="{THUMB_HEIGHT}" fill="none" stroke="#999999"/>"##
+        );
+        let _ = writeln!(
+            svg,
+            r##"<text x="{}" y="{}" font-size="12" text-anchor="middle" fill="#444444">{}</text>"##,
+            x + THUMB_WIDTH / 2,
+            y + THUMB_HEIGHT + 16,
+            thumbnail.number
+        );
+    }
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_svg_embeds_each_thumbnail_under_its_number() {
+        let thumbnails: Vec<Thumbnail> = [3, 7]
+            .into_iter()
+            .map(|number| Thumbnail {
+                number,
+                pixmap: Pixmap::new(THUMB_WIDTH, THUMB_HEIGHT).unwrap(),
+            })
+            .collect();
+        let svg = svg("ቅዳሴ <A&B>", &thumbnails, 1).unwrap();
+
+        assert_eq!(svg.matches("data:image/png;base64,").count(), 2);
+        assert!(svg.contains("ቅዳሴ &lt;A&amp;B&gt;"));
+        assert!(svg.contains(">3</text>") && svg.contains(">7</text>"));
+        // One column, so two rows
+        let height = 2 * (THUMB_HEIGHT + CAPTION_HEIGHT) + 3 * GUTTER + CAPTION_HEIGHT;
+        assert!(svg.contains(&format!(r#"height="{height}""#)));
+    }
+}
@@ -0,0 +1,468 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqliteSynchronous};
+use sqlx::{Connection, FromRow, SqliteConnection, SqlitePool};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+/// Connection string shared with the frontend's `Database.load` call.
+pub const DB_URL: &str = "sqlite:kidase.db";
+
+/// Where `tauri_plugin_sql` keeps `kidase.db`.
+pub fn db_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(DB_URL.trim_start_matches("sqlite:")))
+}
+
+/// Event emitted at startup when `kidase.db` is ahead of this build, carrying
+/// its [`DbCompatibility`].
+pub const SCHEMA_TOO_NEW: &str = "schema-too-new";
+
+/// Whether this build can open `kidase.db`, worked out once at startup.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbCompatibility {
+    /// Highest migration recorded in the file; 0 for a new database.
+    pub stored_version: i64,
+    /// Highest migration this build knows.
+    pub expected_version: i64,
+    /// False when the file was migrated by a newer version of the app. The
+    /// migrations aren't registered then, so nothing rewrites it.
+    pub compatible: bool,
+}
+
+/// Switch `kidase.db` to WAL before the frontend opens it, check its schema
+/// isn't newer than this build's, and leave at most one presentation active.
+///
+/// The journal mode is stored in the file, so it carries over to the plugin's
+/// pool. The other pragmas here only last for this connection: the plugin
+/// opens its own with sqlx's defaults, which already enforce foreign keys and
+/// wait 5s on a busy database.
+pub async fn prepare(app: &AppHandle) -> Result<DbCompatibility, String> {
+    let path = db_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let mut connection = SqliteConnection::connect_with(&connect_options(&path))
+        .await
+        .map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    let stored_version = stored_schema_version(&mut connection).await?;
+    let expected_version = crate::maintenance::get_expected_schema_version();
+    // Only on a current schema: older ones may lack the columns it reads, and
+    // a database migrated this session gets repaired at the next start. Not
+    // in present mode either, whose triggers refuse the write.
+    if stored_version == expected_version && !crate::present_mode::is_on(&mut connection).await? {
+        let cleared = crate::presentations::clear_extra_active(&mut connection).await?;
+        if cleared > 0 {
+            eprintln!("Cleared the active flag of {cleared} presentation(s)");
+        }
+    }
+    connection.close().await.map_err(|e| e.to_string())?;
+
+    Ok(DbCompatibility {
+        stored_version,
+        expected_version,
+        compatible: stored_version <= expected_version,
+    })
+}
+
+/// How this app connects to `kidase.db` outside the plugin.
+pub fn connect_options(path: &Path) -> SqliteConnectOptions {
+    SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .synchronous(SqliteSynchronous::Normal)
+        .foreign_keys(true)
+        .busy_timeout(Duration::from_secs(5))
+}
+
+/// The newest migration sqlx has recorded, or 0 before the first has run.
+pub async fn stored_schema_version(connection: &mut SqliteConnection) -> Result<i64, String> {
+    let tracked: Option<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'",
+    )
+    .fetch_optional(&mut *connection)
+    .await
+    .map_err(|e| e.to_string())?;
+    if tracked.is_none() {
+        return Ok(0);
+    }
+    let version: Option<i64> = sqlx::query_scalar("SELECT max(version) FROM _sqlx_migrations")
+        .fetch_one(&mut *connection)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(version.unwrap_or(0))
+}
+
+/// The schema check made at startup. The frontend calls this before loading
+/// the database: the [`SCHEMA_TOO_NEW`] event may fire before it's listening.
+#[tauri::command]
+pub fn can_open_database(
+    compatibility: State<'_, DbCompatibility>,
+) -> Result<DbCompatibility, String> {
+    Ok(compatibility.inner().clone())
+}
+
+/// Borrow the SQLite pool opened by `tauri_plugin_sql` for `kidase.db`.
+pub async fn pool(instances: &DbInstances) -> Result<SqlitePool, String> {
+    let instances = instances.0.read().await;
+    match instances.get(DB_URL) {
+        Some(DbPool::Sqlite(pool)) => Ok(pool.clone()),
+        None => Err("Database is not loaded".to_string()),
+    }
+}
+
+/// [`pool`] for a command that writes, refused while present mode is on. The
+/// database refuses such writes too; checking here first gives the command a
+/// plain [`crate::present_mode::PRESENT_MODE_ACTIVE`] error.
+pub async fn writable_pool(instances: &DbInstances) -> Result<SqlitePool, String> {
+    let pool = pool(instances).await?;
+    crate::present_mode::ensure_writable(&pool).await?;
+    Ok(pool)
+}
+
+/// Fresh primary key: a UUID v7, so ids sort by creation time and ids made in
+/// the same millisecond still come out increasing. The frontend's `uuidv7()`
+/// produces the same format; this is also exposed as a command for callers
+/// that want the backend to mint it.
+#[tauri::command]
+pub fn new_id() -> String {
+    uuid::Uuid::now_v7().to_string()
+}
+
+/// Timestamp matching JavaScript's `new Date().toISOString()`.
+pub fn now() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Presentation {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub presentation_type: String,
+    pub template_id: String,
+    pub language_map: String,
+    pub language_settings: Option<String>,
+    pub is_primary: bool,
+    pub is_active: bool,
+    pub created_at: String,
+    /// Factor applied to the template's font sizes; `None` renders as 1.0.
+    pub display_scale: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Slide {
+    pub id: String,
+    pub presentation_id: String,
+    pub slide_order: i64,
+    pub line_id: Option<String>,
+    pub title_json: Option<String>,
+    pub blocks_json: String,
+    pub footer_json: Option<String>,
+    pub notes: Option<String>,
+    pub is_disabled: bool,
+    pub is_dynamic: bool,
+    pub template_override_id: Option<String>,
+    /// Bumped whenever the content changes, though not on renumbering; see
+    /// [`crate::slides::update_slide`].
+    /// Undo snapshots from before it existed read as 0.
+    #[serde(default)]
+    pub version: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Template {
+    pub id: String,
+    pub name: String,
+    pub max_lang_count: i64,
+    pub definition_json: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleDefinition {
+    pub id: String,
+    pub name: String,
+    pub scope: String,
+    pub presentation_id: Option<String>,
+    pub slide_id: Option<String>,
+    pub gitsawe_id: Option<String>,
+    pub rule_json: String,
+    pub is_enabled: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Verse {
+    pub id: String,
+    pub segment_id: String,
+    pub verse_order: i64,
+    pub title_lang1: Option<String>,
+    pub title_lang2: Option<String>,
+    pub title_lang3: Option<String>,
+    pub title_lang4: Option<String>,
+    pub text_lang1: Option<String>,
+    pub text_lang2: Option<String>,
+    pub text_lang3: Option<String>,
+    pub text_lang4: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Variable {
+    pub id: String,
+    pub presentation_id: String,
+    pub name: String,
+    pub value: String,
+    pub value_lang1: String,
+    pub value_lang2: String,
+    pub value_lang3: String,
+    pub value_lang4: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Gitsawe {
+    pub id: String,
+    pub line_id: String,
+    pub name: Option<String>,
+    pub additional_info: Option<String>,
+    pub message_st_paul: Option<String>,
+    pub message_apostle: Option<String>,
+    pub message_book_of_acts: Option<String>,
+    pub misbak: Option<String>,
+    pub wengel: Option<String>,
+    pub kidase_type: Option<String>,
+    pub evangelist: Option<String>,
+    pub message_apostle_evangelist: Option<String>,
+    pub gitsawe_type: Option<String>,
+    pub priority: i64,
+    pub created_at: String,
+}
+
+pub async fn fetch_presentation(pool: &SqlitePool, id: &str) -> Result<Presentation, String> {
+    sqlx::query_as::<_, Presentation>("SELECT * FROM presentations WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Presentation not found: {id}"))
+}
+
+pub async fn fetch_template(pool: &SqlitePool, id: &str) -> Result<Template, String> {
+    sqlx::query_as::<_, Template>("SELECT * FROM templates WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Template not found: {id}"))
+}
+
+pub async fn fetch_slide(pool: &SqlitePool, id: &str) -> Result<Slide, String> {
+    sqlx::query_as::<_, Slide>("SELECT * FROM slides WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Slide not found: {id}"))
+}
+
+pub async fn fetch_slides(pool: &SqlitePool, presentation_id: &str) -> Result<Vec<Slide>, String> {
+    sqlx::query_as::<_, Slide>(
+        "SELECT * FROM slides WHERE presentation_id = ? ORDER BY slide_order",
+    )
+    .bind(presentation_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(feature = "legacy-variable-columns")]
+pub async fn fetch_variables(
+    pool: &SqlitePool,
+    presentation_id: &str,
+) -> Result<Vec<Variable>, String> {
+    sqlx::query_as::<_, Variable>("SELECT * FROM variables WHERE presentation_id = ? ORDER BY name")
+        .bind(presentation_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Variables with their first four language values taken from `variable_values`.
+#[cfg(not(feature = "legacy-variable-columns"))]
+pub async fn fetch_variables(
+    pool: &SqlitePool,
+    presentation_id: &str,
+) -> Result<Vec<Variable>, String> {
+    sqlx::query_as::<_, Variable>(
+        "SELECT v.id, v.presentation_id, v.name, v.value,
+                COALESCE((SELECT value FROM variable_values
+                          WHERE variable_id = v.id AND language_index = 1), '') AS value_lang1,
+                COALESCE((SELECT value FROM variable_values
+                          WHERE variable_id = v.id AND language_index = 2), '') AS value_lang2,
+                COALESCE((SELECT value FROM variable_values
+                          WHERE variable_id = v.id AND language_index = 3), '') AS value_lang3,
+                COALESCE((SELECT value FROM variable_values
+                          WHERE variable_id = v.id AND language_index = 4), '') AS value_lang4
+         FROM variables v WHERE v.presentation_id = ? ORDER BY v.name",
+    )
+    .bind(presentation_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+pub async fn fetch_enabled_rules(
+    pool: &SqlitePool,
+    presentation_id: &str,
+) -> Result<Vec<RuleDefinition>, String> {
+    sqlx::query_as::<_, RuleDefinition>(
+        "SELECT * FROM rule_definitions WHERE presentation_id = ? AND is_enabled = 1 ORDER BY created_at",
+    )
+    .bind(presentation_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+pub async fn fetch_segment_verses(
+    pool: &SqlitePool,
+    segment_id: &str,
+) -> Result<Vec<Verse>, String> {
+    sqlx::query_as::<_, Verse>("SELECT * FROM verses WHERE segment_id = ? ORDER BY verse_order")
+        .bind(segment_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Range [`Presentation::display_scale`] is clamped to.
+pub const DISPLAY_SCALE_RANGE: (f64, f64) = (0.5, 3.0);
+
+impl Presentation {
+    /// The stored display scale, clamped, or 1.0 when unset.
+    pub fn font_scale(&self) -> f64 {
+        self.display_scale
+            .filter(|scale| scale.is_finite())
+            .map_or(1.0, |scale| {
+                scale.clamp(DISPLAY_SCALE_RANGE.0, DISPLAY_SCALE_RANGE.1)
+            })
+    }
+
+    /// Enabled language slots in display order, mirroring `getOrderedLanguages`.
+    ///
+    /// `language_settings` decides when present; otherwise every slot named in
+    /// `language_map` is shown in slot order.
+    pub fn ordered_languages(&self) -> Vec<&'static str> {
+        let settings = self
+            .language_settings
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(json).ok())
+            .filter(serde_json::Value::is_object);
+        match settings {
+            Some(settings) => {
+                let mut enabled: Vec<(&'static str, f64)> = LANG_SLOTS
+                    .into_iter()
+                    .filter_map(|slot| {
+                        let config = settings.get(slot)?;
+                        config
+                            .get("enabled")
+                            .and_then(serde_json::Value::as_bool)
+                            .filter(|enabled| *enabled)?;
+                        let order = config
+                            .get("order")
+                            .and_then(serde_json::Value::as_f64)
+                            .unwrap_or_default();
+                        Some((slot, order))
+                    })
+                    .collect();
+                enabled.sort_by(|a, b| a.1.total_cmp(&b.1));
+                enabled.into_iter().map(|(slot, _)| slot).collect()
+            }
+            None => {
+                let map: serde_json::Value =
+                    serde_json::from_str(&self.language_map).unwrap_or_default();
+                LANG_SLOTS
+                    .into_iter()
+                    .filter(|slot| {
+                        map.get(slot)
+                            .and_then(serde_json::Value::as_str)
+                            .is_some_and(|name| !name.is_empty())
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+impl Slide {
+    /// Template the slide renders with: its override, else the presentation's.
+    pub fn template_id<'a>(&'a self, presentation_template_id: &'a str) -> &'a str {
+        self.template_override_id
+            .as_deref()
+            .unwrap_or(presentation_template_id)
+    }
+
+    /// First non-empty title text, mirroring `getSlideTitle` on the frontend.
+    pub fn title_text(&self) -> Option<String> {
+        self.title_json.as_deref().and_then(first_lang_text)
+    }
+
+    /// First non-empty block text, mirroring `getSlidePreviewText` on the frontend.
+    pub fn preview_text(&self) -> Option<String> {
+        let blocks: Vec<serde_json::Value> = serde_json::from_str(&self.blocks_json).ok()?;
+        blocks.iter().find_map(lang_text)
+    }
+}
+
+/// Language slot keys used by `title_json`, `blocks_json` and `language_map`.
+pub const LANG_SLOTS: [&str; 4] = ["Lang1", "Lang2", "Lang3", "Lang4"];
+
+fn first_lang_text(json: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    lang_text(&value)
+}
+
+fn lang_text(value: &serde_json::Value) -> Option<String> {
+    LANG_SLOTS.iter().find_map(|slot| {
+        value
+            .get(slot)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_ids_are_version_7_uuids() {
+        let id = new_id();
+        let parsed = uuid::Uuid::parse_str(&id).unwrap();
+        assert_eq!(parsed.get_version_num(), 7);
+        assert_eq!(parsed.to_string(), id);
+    }
+
+    #[test]
+    fn new_ids_increase_over_time() {
+        let mut ids: Vec<String> = (0..1000).map(|_| new_id()).collect();
+        std::thread::sleep(Duration::from_millis(2));
+        ids.push(new_id());
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
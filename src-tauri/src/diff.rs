@@ -0,0 +1,237 @@
+use serde::Serialize;
+use serde_json::Value;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::db::{self, Slide};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresentationDiff {
+    /// Slides only in `b`.
+    pub added: Vec<SlideRef>,
+    /// Slides only in `a`.
+    pub removed: Vec<SlideRef>,
+    pub modified: Vec<ModifiedSlide>,
+    /// Unchanged slides whose place relative to the others changed.
+    pub moved: Vec<SlidePair>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlideRef {
+    pub slide_id: String,
+    pub line_id: Option<String>,
+    pub slide_order: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlidePair {
+    pub a: SlideRef,
+    pub b: SlideRef,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModifiedSlide {
+    #[serde(flatten)]
+    pub slides: SlidePair,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    /// Column name, e.g. `blocks_json` or `is_disabled`.
+    pub field: &'static str,
+    /// JSON columns are parsed, so only content differences show up.
+    pub before: Value,
+    pub after: Value,
+}
+
+/// Compare presentation `a` (before) with `b` (after) slide by slide.
+///
+/// Slides pair up by `line_id`, then by identical content, then by
+/// `slide_order`. Paired slides that differ are modified; identical ones are
+/// moved when they fall outside the longest run that kept its relative order,
+/// so inserting one slide doesn't report every slide after it.
+#[tauri::command]
+pub async fn diff_presentations(
+    db: State<'_, DbInstances>,
+    a_id: String,
+    b_id: String,
+) -> Result<PresentationDiff, String> {
+    let pool = db::pool(&db).await?;
+    db::fetch_presentation(&pool, &a_id).await?;
+    db::fetch_presentation(&pool, &b_id).await?;
+    let a = db::fetch_slides(&pool, &a_id).await?;
+    let b = db::fetch_slides(&pool, &b_id).await?;
+
+    let pairs = align(&a, &b);
+
+    let mut unchanged = Vec::new();
+    let mut modified = Vec::new();
+    for &(i, j) in &pairs {
+        let changes = field_changes(&a[i], &b[j]);
+        if changes.is_empty() {
+            unchanged.push((i, j));
+        } else {
+            modified.push(ModifiedSlide {
+                slides: pair(&a[i], &b[j]),
+                changes,
+            });
+        }
+    }
+
+    let in_order = longest_ordered_run(&unchanged);
+    let moved = unchanged
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !in_order.contains(index))
+        .map(|(_, &(i, j))| pair(&a[i], &b[j]))
+        .collect();
+
+    let removed = (0..a.len())
+        .filter(|i| !pairs.iter().any(|(p, _)| p == i))
+        .map(|i| slide_ref(&a[i]))
+        .collect();
+    let added = (0..b.len())
+        .filter(|j| !pairs.iter().any(|(_, q)| q == j))
+        .map(|j| slide_ref(&b[j]))
+        .collect();
+
+    Ok(PresentationDiff {
+        added,
+        removed,
+        modified,
+        moved,
+    })
+}
+
+/// Index pairs `(a, b)` of matched slides, sorted by position in `a`.
+fn align(a: &[Slide], b: &[Slide]) -> Vec<(usize, usize)> {
+    let mut a_used = vec![false; a.len()];
+    let mut b_used = vec![false; b.len()];
+    let mut pairs = Vec::new();
+
+    let mut pass = |matches: &dyn Fn(&Slide, &Slide) -> bool| {
+        for (i, slide) in a.iter().enumerate() {
+            if a_used[i] {
+                continue;
+            }
+            let found = b
+                .iter()
+                .enumerate()
+                .find(|(j, other)| !b_used[*j] && matches(slide, other));
+            if let Some((j, _)) = found {
+                a_used[i] = true;
+                b_used[j] = true;
+                pairs.push((i, j));
+            }
+        }
+    };
+    pass(&|x, y| x.line_id.is_some() && x.line_id == y.line_id);
+    pass(&|x, y| field_changes(x, y).is_empty());
+    pass(&|x, y| x.slide_order == y.slide_order);
+
+    pairs.sort_unstable();
+    pairs
+}
+
+fn field_changes(a: &Slide, b: &Slide) -> Vec<FieldChange> {
+    let json = |text: Option<&str>| {
+        text.map_or(Value::Null, |t| {
+            serde_json::from_str(t).unwrap_or_else(|_| Value::from(t))
+        })
+    };
+    let candidates = [
+        (
+            "line_id",
+            Value::from(a.line_id.clone()),
+            Value::from(b.line_id.clone()),
+        ),
+        (
+            "title_json",
+            json(a.title_json.as_deref()),
+            json(b.title_json.as_deref()),
+        ),
+        (
+            "blocks_json",
+            json(Some(&a.blocks_json)),
+            json(Some(&b.blocks_json)),
+        ),
+        (
+            "footer_json",
+            json(a.footer_json.as_deref()),
+            json(b.footer_json.as_deref()),
+        ),
+        (
+            "notes",
+            Value::from(a.notes.clone()),
+            Value::from(b.notes.clone()),
+        ),
+        (
+            "is_disabled",
+            Value::from(a.is_disabled),
+            Value::from(b.is_disabled),
+        ),
+        (
+            "is_dynamic",
+            Value::from(a.is_dynamic),
+            Value::from(b.is_dynamic),
+        ),
+        (
+            "template_override_id",
+            Value::from(a.template_override_id.clone()),
+            Value::from(b.template_override_id.clone()),
+        ),
+    ];
+    candidates
+        .into_iter()
+        .filter(|(_, before, after)| before != after)
+        .map(|(field, before, after)| FieldChange {
+            field,
+            before,
+            after,
+        })
+        .collect()
+}
+
+/// Indexes into `pairs` (sorted by `a` position) forming the longest run whose
+/// `b` positions also increase.
+fn longest_ordered_run(pairs: &[(usize, usize)]) -> Vec<usize> {
+    let mut length = vec![1; pairs.len()];
+    let mut previous: Vec<Option<usize>> = vec![None; pairs.len()];
+    for k in 0..pairs.len() {
+        for m in 0..k {
+            if pairs[m].1 < pairs[k].1 && length[m] + 1 > length[k] {
+                length[k] = length[m] + 1;
+                previous[k] = Some(m);
+            }
+        }
+    }
+
+    let mut run = Vec::new();
+    let mut cursor = (0..pairs.len()).max_by_key(|&k| length[k]);
+    while let Some(k) = cursor {
+        run.push(k);
+        cursor = previous[k];
+    }
+    run
+}
+
+fn slide_ref(slide: &Slide) -> SlideRef {
+    SlideRef {
+        slide_id: slide.id.clone(),
+        line_id: slide.line_id.clone(),
+        slide_order: slide.slide_order,
+    }
+}
+
+fn pair(a: &Slide, b: &Slide) -> SlidePair {
+    SlidePair {
+        a: slide_ref(a),
+        b: slide_ref(b),
+    }
+}
@@ -0,0 +1,151 @@
+use serde::Serialize;
+use serde_json::json;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::db::{self, Slide, Verse};
+
+/// Joins a dynamic slide's id to its verse's in the id of a verse slide.
+const VERSE_ID_SEPARATOR: &str = "__verse_";
+
+/// One slide of the present-time sequence.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedSlide {
+    /// The slide's own id, or `<slide id>__verse_<verse id>` for a verse of a
+    /// dynamic slide, as `expandDynamicSlides` names them.
+    pub id: String,
+    pub source_slide_id: String,
+    pub slide_order: i64,
+    /// The verse shown; `None` for static slides and placeholders.
+    pub verse_id: Option<String>,
+    pub title_json: Option<String>,
+    pub blocks_json: String,
+    pub footer_json: Option<String>,
+    pub notes: Option<String>,
+    pub template_override_id: Option<String>,
+    /// A dynamic slide whose segment has no verses, kept as itself so the
+    /// service doesn't silently skip it.
+    pub placeholder: bool,
+}
+
+/// The slides of a presentation as presented: disabled slides left out and
+/// every dynamic slide replaced by one slide per verse of its segment, in
+/// `slide_order` then `verse_order`. Nothing is written.
+///
+/// A dynamic slide's segment is its `line_id`. `@meta.` references only
+/// resolve against the live rule context, so like an empty segment they give
+/// a single placeholder slide.
+#[tauri::command]
+pub async fn expand_dynamic_slides(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<Vec<RenderedSlide>, String> {
+    let pool = db::pool(&db).await?;
+    db::fetch_presentation(&pool, &presentation_id).await?;
+    let slides = db::fetch_slides(&pool, &presentation_id).await?;
+
+    let mut rendered = Vec::with_capacity(slides.len());
+    for slide in slides.into_iter().filter(|slide| !slide.is_disabled) {
+        let segment_id = slide
+            .line_id
+            .as_deref()
+            .filter(|_| slide.is_dynamic)
+            .filter(|id| !id.starts_with("@meta."));
+        let verses = match segment_id {
+            Some(segment_id) => db::fetch_segment_verses(&pool, segment_id).await?,
+            None => Vec::new(),
+        };
+        if verses.is_empty() {
+            rendered.push(as_is(slide));
+            continue;
+        }
+        for verse in &verses {
+            rendered.push(for_verse(&slide, verse));
+        }
+    }
+    Ok(rendered)
+}
+
+/// A slide of the present-time sequence by the id [`expand_dynamic_slides`]
+/// gives it: the stored slide, or for a verse slide its dynamic slide with the
+/// verse's title and text in place.
+pub async fn fetch_presented(pool: &SqlitePool, id: &str) -> Result<Slide, String> {
+    let Some((slide_id, verse_id)) = id.split_once(VERSE_ID_SEPARATOR) else {
+        return db::fetch_slide(pool, id).await;
+    };
+    let mut slide = db::fetch_slide(pool, slide_id).await?;
+    let verse: Verse = sqlx::query_as("SELECT * FROM verses WHERE id = ?")
+        .bind(verse_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Verse not found: {verse_id}"))?;
+    let rendered = for_verse(&slide, &verse);
+    slide.title_json = rendered.title_json;
+    slide.blocks_json = rendered.blocks_json;
+    Ok(slide)
+}
+
+fn as_is(slide: Slide) -> RenderedSlide {
+    RenderedSlide {
+        id: slide.id.clone(),
+        source_slide_id: slide.id,
+        slide_order: slide.slide_order,
+        verse_id: None,
+        title_json: slide.title_json,
+        blocks_json: slide.blocks_json,
+        footer_json: slide.footer_json,
+        notes: slide.notes,
+        template_override_id: slide.template_override_id,
+        placeholder: slide.is_dynamic,
+    }
+}
+
+/// The verse's text as the slide's only block, under the verse title when it
+/// has one and the slide's own title otherwise.
+fn for_verse(slide: &Slide, verse: &Verse) -> RenderedSlide {
+    let titles = [
+        &verse.title_lang1,
+        &verse.title_lang2,
+        &verse.title_lang3,
+        &verse.title_lang4,
+    ];
+    let title_json = if titles
+        .iter()
+        .any(|title| title.as_deref().is_some_and(|t| !t.is_empty()))
+    {
+        Some(
+            json!({
+                "Lang1": verse.title_lang1,
+                "Lang2": verse.title_lang2,
+                "Lang3": verse.title_lang3,
+                "Lang4": verse.title_lang4,
+            })
+            .to_string(),
+        )
+    } else {
+        slide.title_json.clone()
+    };
+    let blocks_json = json!([{
+        "Lang1": verse.text_lang1,
+        "Lang2": verse.text_lang2,
+        "Lang3": verse.text_lang3,
+        "Lang4": verse.text_lang4,
+    }])
+    .to_string();
+
+    RenderedSlide {
+        id: format!("{}{VERSE_ID_SEPARATOR}{}", slide.id, verse.id),
+        source_slide_id: slide.id.clone(),
+        slide_order: slide.slide_order,
+        verse_id: Some(verse.id.clone()),
+        title_json,
+        blocks_json,
+        footer_json: slide.footer_json.clone(),
+        notes: slide.notes.clone(),
+        template_override_id: slide.template_override_id.clone(),
+        placeholder: false,
+    }
+}
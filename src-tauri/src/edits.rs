@@ -0,0 +1,331 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::db::{self, Slide};
+use crate::language_map;
+
+/// `app_settings` key holding how many edits each presentation keeps.
+const UNDO_DEPTH_KEY: &str = "undoDepth";
+const DEFAULT_UNDO_DEPTH: i64 = 100;
+
+/// One reversible step, stored as JSON in `edits.undo_json` / `edits.redo_json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum EditOp {
+    /// Write the whole row back, re-inserting it if it was deleted.
+    PutSlide {
+        slide: Slide,
+    },
+    DeleteSlide {
+        id: String,
+    },
+    /// `(slide id, slide_order)` pairs.
+    SetOrder {
+        orders: Vec<(String, i64)>,
+    },
+    /// Move the presentation's languages between slots by `(old index, new
+    /// index)` pairs; see [`crate::language_map::reorder_languages`]. Slides
+    /// are put back by their own ops.
+    RemapLanguages {
+        presentation_id: String,
+        moves: Vec<(i64, i64)>,
+    },
+}
+
+/// Push an edit onto `presentation_id`'s undo stack inside the caller's
+/// transaction. Anything that was undone is no longer redoable, and the oldest
+/// entries past the `undoDepth` setting are dropped.
+pub async fn record(
+    tx: &mut Transaction<'_, Sqlite>,
+    presentation_id: &str,
+    kind: &str,
+    undo: &[EditOp],
+    redo: &[EditOp],
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM edits WHERE presentation_id = ? AND is_undone = 1")
+        .bind(presentation_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    sqlx::query(
+        "INSERT INTO edits (presentation_id, kind, undo_json, redo_json, created_at)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(presentation_id)
+    .bind(kind)
+    .bind(serde_json::to_string(undo).map_err(|e| e.to_string())?)
+    .bind(serde_json::to_string(redo).map_err(|e| e.to_string())?)
+    .bind(db::now())
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let depth: Option<String> = sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+        .bind(UNDO_DEPTH_KEY)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    let depth = depth
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .unwrap_or(DEFAULT_UNDO_DEPTH)
+        .max(0);
+    sqlx::query(
+        "DELETE FROM edits WHERE presentation_id = ? AND id NOT IN
+         (SELECT id FROM edits WHERE presentation_id = ? ORDER BY id DESC LIMIT ?)",
+    )
+    .bind(presentation_id)
+    .bind(presentation_id)
+    .bind(depth)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Current `(id, slide_order)` of every slide in the presentation.
+pub async fn slide_orders(
+    tx: &mut Transaction<'_, Sqlite>,
+    presentation_id: &str,
+) -> Result<Vec<(String, i64)>, String> {
+    sqlx::query_as(
+        "SELECT id, slide_order FROM slides WHERE presentation_id = ? ORDER BY slide_order",
+    )
+    .bind(presentation_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Revert the latest edit of the presentation. Returns its kind, or `None`
+/// when there is nothing left to undo.
+#[tauri::command]
+pub async fn undo(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<Option<String>, String> {
+    let pool = db::writable_pool(&db).await?;
+    step(&pool, &presentation_id, Direction::Undo).await
+}
+
+/// Re-apply the edit most recently undone. Returns its kind, or `None` when
+/// there is nothing to redo.
+#[tauri::command]
+pub async fn redo(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<Option<String>, String> {
+    let pool = db::writable_pool(&db).await?;
+    step(&pool, &presentation_id, Direction::Redo).await
+}
+
+pub(crate) enum Direction {
+    Undo,
+    Redo,
+}
+
+pub(crate) async fn step(
+    pool: &SqlitePool,
+    presentation_id: &str,
+    direction: Direction,
+) -> Result<Option<String>, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let query = match direction {
+        Direction::Undo => {
+            "SELECT id, kind, undo_json FROM edits
+             WHERE presentation_id = ? AND is_undone = 0 ORDER BY id DESC LIMIT 1"
+        }
+        Direction::Redo => {
+            "SELECT id, kind, redo_json FROM edits
+             WHERE presentation_id = ? AND is_undone = 1 ORDER BY id LIMIT 1"
+        }
+    };
+    let edit: Option<(i64, String, String)> = sqlx::query_as(query)
+        .bind(presentation_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    let Some((id, kind, ops_json)) = edit else {
+        return Ok(None);
+    };
+
+    let ops: Vec<EditOp> =
+        serde_json::from_str(&ops_json).map_err(|e| format!("Edit {id} is unreadable: {e}"))?;
+    for op in &ops {
+        apply(&mut tx, op).await?;
+    }
+
+    sqlx::query("UPDATE edits SET is_undone = ? WHERE id = ?")
+        .bind(matches!(direction, Direction::Undo))
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(Some(kind))
+}
+
+async fn apply(tx: &mut Transaction<'_, Sqlite>, op: &EditOp) -> Result<(), String> {
+    match op {
+        // A re-inserted slide keeps its version; overwriting one bumps it
+        // (slides_bump_version), as any other change to its content does
+        EditOp::PutSlide { slide } => {
+            sqlx::query(
+                "INSERT INTO slides
+                 (id, presentation_id, slide_order, line_id, title_json, blocks_json, footer_json,
+                  notes, is_disabled, is_dynamic, template_override_id, version)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                 ON CONFLICT(id) DO UPDATE SET
+                    presentation_id = excluded.presentation_id,
+                    slide_order = excluded.slide_order,
+                    line_id = excluded.line_id,
+                    title_json = excluded.title_json,
+                    blocks_json = excluded.blocks_json,
+                    footer_json = excluded.footer_json,
+                    notes = excluded.notes,
+                    is_disabled = excluded.is_disabled,
+                    is_dynamic = excluded.is_dynamic,
+                    template_override_id = excluded.template_override_id",
+            )
+            .bind(&slide.id)
+            .bind(&slide.presentation_id)
+            .bind(slide.slide_order)
+            .bind(&slide.line_id)
+            .bind(&slide.title_json)
+            .bind(&slide.blocks_json)
+            .bind(&slide.footer_json)
+            .bind(&slide.notes)
+            .bind(slide.is_disabled)
+            .bind(slide.is_dynamic)
+            .bind(&slide.template_override_id)
+            // Snapshots from before versions read as 0
+            .bind(slide.version.max(1))
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+        EditOp::DeleteSlide { id } => {
+            sqlx::query("DELETE FROM slides WHERE id = ?")
+                .bind(id)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        EditOp::SetOrder { orders } => {
+            for (id, order) in orders {
+                sqlx::query("UPDATE slides SET slide_order = ? WHERE id = ?")
+                    .bind(order)
+                    .bind(id)
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        EditOp::RemapLanguages {
+            presentation_id,
+            moves,
+        } => language_map::remap_languages(tx, presentation_id, moves).await?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slides;
+    use crate::test_support;
+
+    #[tokio::test]
+    async fn a_deleted_slide_comes_back_whole_and_goes_again() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        for (id, order) in [("s1", 1), ("s2", 2), ("s3", 3)] {
+            test_support::slide(&pool, id, "p1", order).await;
+        }
+        test_support::exec(
+            &pool,
+            "UPDATE slides SET notes = 'Read slowly', version = 4 WHERE id = 's2'",
+        )
+        .await;
+        let deleted = db::fetch_slide(&pool, "s2").await.unwrap();
+
+        slides::delete(&pool, "s2".to_string()).await.unwrap();
+        assert_eq!(
+            test_support::slide_orders(&pool, "p1").await,
+            [("s1".to_string(), 1), ("s3".to_string(), 2)]
+        );
+
+        let kind = step(&pool, "p1", Direction::Undo).await.unwrap();
+        assert_eq!(kind.as_deref(), Some("delete"));
+        assert_eq!(
+            test_support::slide_orders(&pool, "p1").await,
+            [
+                ("s1".to_string(), 1),
+                ("s2".to_string(), 2),
+                ("s3".to_string(), 3)
+            ]
+        );
+        let restored = db::fetch_slide(&pool, "s2").await.unwrap();
+        assert_eq!(restored.notes.as_deref(), Some("Read slowly"));
+        assert_eq!(restored.version, deleted.version);
+        assert!(step(&pool, "p1", Direction::Undo).await.unwrap().is_none());
+
+        let kind = step(&pool, "p1", Direction::Redo).await.unwrap();
+        assert_eq!(kind.as_deref(), Some("delete"));
+        assert!(db::fetch_slide(&pool, "s2").await.is_err());
+        assert_eq!(
+            test_support::slide_orders(&pool, "p1").await,
+            [("s1".to_string(), 1), ("s3".to_string(), 2)]
+        );
+        assert!(step(&pool, "p1", Direction::Redo).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn only_the_latest_edits_up_to_the_undo_depth_are_kept() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        test_support::slide(&pool, "s1", "p1", 1).await;
+        test_support::exec(
+            &pool,
+            "INSERT INTO app_settings (key, value) VALUES ('undoDepth', '2')",
+        )
+        .await;
+
+        for order in 2..=4 {
+            let mut tx = pool.begin().await.unwrap();
+            let undo = [EditOp::SetOrder {
+                orders: vec![("s1".to_string(), order - 1)],
+            }];
+            let redo = [EditOp::SetOrder {
+                orders: vec![("s1".to_string(), order)],
+            }];
+            apply(&mut tx, &redo[0]).await.unwrap();
+            record(&mut tx, "p1", &format!("move {order}"), &undo, &redo)
+                .await
+                .unwrap();
+            tx.commit().await.unwrap();
+        }
+
+        let kinds: Vec<String> =
+            sqlx::query_scalar("SELECT kind FROM edits WHERE presentation_id = 'p1' ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(kinds, ["move 3", "move 4"]);
+
+        for kind in ["move 4", "move 3"] {
+            let undone = step(&pool, "p1", Direction::Undo).await.unwrap();
+            assert_eq!(undone.as_deref(), Some(kind));
+        }
+        assert!(step(&pool, "p1", Direction::Undo).await.unwrap().is_none());
+        // The oldest edit was dropped, so its move stays
+        assert_eq!(
+            test_support::slide_orders(&pool, "p1").await,
+            [("s1".to_string(), 2)]
+        );
+    }
+}
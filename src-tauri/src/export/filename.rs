@@ -0,0 +1,183 @@
+use chrono::NaiveDate;
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use super::ExportFormat;
+use crate::calendar::{self, EthiopianDate};
+use crate::db;
+
+const MAX_SLUG_CHARS: usize = 60;
+
+/// Consonant for each eight-syllable row of the Ethiopic block, starting at U+1200.
+const ETHIOPIC_CONSONANTS: [&str; 43] = [
+    "h", "l", "h", "m", "s", "r", "s", "sh", "q", "qw", "q", "qw", "b", "v", "t", "ch", "h", "hw",
+    "n", "ny", "", "k", "kw", "kh", "khw", "w", "", "z", "zh", "y", "d", "dd", "j", "g", "gw",
+    "gg", "t", "ch", "p", "ts", "ts", "f", "p",
+];
+
+/// Vowel orders of an Ethiopic syllable row; the sixth order (ə) is handled separately.
+const ETHIOPIC_VOWELS: [&str; 8] = ["e", "u", "i", "a", "e", "i", "o", "wa"];
+
+/// Device names Windows refuses as file stems regardless of extension.
+const WINDOWS_RESERVED: [&str; 22] = [
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8",
+    "com9", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// Deterministic, filesystem-safe export filename for a presentation. `date`
+/// is the Gregorian `YYYY-MM-DD` of the service, as `meta.date` has it.
+#[tauri::command]
+pub async fn suggest_export_filename(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    format: ExportFormat,
+    date: Option<String>,
+) -> Result<String, String> {
+    let date = date.as_deref().map(calendar::parse_gregorian).transpose()?;
+    let pool = db::pool(&db).await?;
+    export_filename(&pool, &presentation_id, format, date).await
+}
+
+/// `<name>_<type>_<date>_<hash>.<ext>`, where the hash covers the id and slide
+/// content so same-named presentations never collide and unchanged decks keep
+/// their name.
+///
+/// The date is the liturgical one, in the Ethiopian calendar: that of `date`,
+/// else of the first day the presentation is scheduled, and left out when it
+/// isn't scheduled.
+pub async fn export_filename(
+    pool: &SqlitePool,
+    presentation_id: &str,
+    format: ExportFormat,
+    date: Option<NaiveDate>,
+) -> Result<String, String> {
+    let presentation = db::fetch_presentation(pool, presentation_id).await?;
+    let slides = db::fetch_slides(pool, presentation_id).await?;
+    let date = match date {
+        Some(date) => Some(date),
+        None => {
+            let first: Option<String> = sqlx::query_scalar(
+                "SELECT MIN(service_date) FROM scheduled_services WHERE presentation_id = ?",
+            )
+            .bind(presentation_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            first
+                .as_deref()
+                .map(calendar::parse_gregorian)
+                .transpose()?
+        }
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(presentation.id.as_bytes());
+    for slide in &slides {
+        hasher.update(slide.slide_order.to_le_bytes());
+        for part in [
+            slide.title_json.as_deref(),
+            Some(slide.blocks_json.as_str()),
+            slide.footer_json.as_deref(),
+        ] {
+            hasher.update(part.unwrap_or("").as_bytes());
+            hasher.update([0]);
+        }
+    }
+    let digest = hasher.finalize();
+    let hash: String = digest[..4].iter().map(|b| format!("{b:02x}")).collect();
+
+    let mut stem = slugify(&presentation.name);
+    if stem.is_empty() {
+        stem = "presentation".to_string();
+    }
+    let kind = slugify(&presentation.presentation_type);
+    if !kind.is_empty() {
+        stem = format!("{stem}_{kind}");
+    }
+    if let Some(date) = date {
+        stem = format!("{stem}_{}", EthiopianDate::from_gregorian(date));
+    }
+    if WINDOWS_RESERVED.contains(&stem.as_str()) {
+        stem = format!("_{stem}");
+    }
+
+    Ok(format!("{stem}_{hash}.{}", format.extension()))
+}
+
+/// Lowercase ASCII slug, transliterating Ge'ez syllables to Latin.
+pub fn slugify(text: &str) -> String {
+    let mut latin = String::new();
+    let chars: Vec<char> = text.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        match transliterate(c) {
+            Some((consonant, order)) => {
+                latin.push_str(consonant);
+                let word_end = chars.get(i + 1).is_none_or(|n| transliterate(*n).is_none());
+                // A sixth-order syllable closing a word is usually unvoiced
+                if !(order == 5 && word_end) {
+                    latin.push_str(ETHIOPIC_VOWELS[order]);
+                }
+            }
+            None => latin.extend(c.to_lowercase()),
+        }
+    }
+
+    let mut slug = String::new();
+    for c in latin.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug
+        .trim_end_matches('-')
+        .chars()
+        .take(MAX_SLUG_CHARS)
+        .collect();
+    slug.trim_end_matches('-').to_string()
+}
+
+fn transliterate(c: char) -> Option<(&'static str, usize)> {
+    let offset = (c as u32).checked_sub(0x1200)? as usize;
+    let consonant = ETHIOPIC_CONSONANTS.get(offset / 8)?;
+    Some((consonant, offset % 8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[tokio::test]
+    async fn the_liturgical_date_is_given_or_scheduled() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "ቅዳሴ").await;
+        let name = |date| export_filename(&pool, "ቅዳሴ", ExportFormat::Pdf, date);
+
+        let undated = name(None).await.unwrap();
+        assert!(undated.starts_with("qidase_kidase_"), "{undated}");
+        assert!(undated.ends_with(".pdf"), "{undated}");
+
+        test_support::exec(
+            &pool,
+            "INSERT INTO scheduled_services (service_date, presentation_id, created_at)
+             VALUES ('2024-05-05', 'ቅዳሴ', '2026-01-01T00:00:00.000Z'),
+                    ('2024-01-07', 'ቅዳሴ', '2026-01-01T00:00:00.000Z')",
+        )
+        .await;
+        let scheduled = name(None).await.unwrap();
+        assert!(
+            scheduled.starts_with("qidase_kidase_2016-04-28_"),
+            "{scheduled}"
+        );
+
+        let given = calendar::parse_gregorian("2024-05-05").unwrap();
+        let dated = name(Some(given)).await.unwrap();
+        assert!(dated.starts_with("qidase_kidase_2016-08-27_"), "{dated}");
+        // Only the date part differs
+        assert_eq!(dated.rsplit('_').next(), undated.rsplit('_').next());
+    }
+}
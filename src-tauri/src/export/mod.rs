@@ -0,0 +1,134 @@
+pub mod filename;
+pub mod pdf;
+pub mod pptx;
+
+use std::collections::BTreeSet;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::db::{Slide, LANG_SLOTS};
+use crate::placeholders::Substituter;
+
+/// Output formats produced by the presentation exporters.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Pdf,
+    Pptx,
+    Json,
+    Kidase,
+    Svg,
+    Dot,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Pdf => "pdf",
+            ExportFormat::Pptx => "pptx",
+            ExportFormat::Json => "json",
+            ExportFormat::Kidase => "kidase",
+            ExportFormat::Svg => "svg",
+            ExportFormat::Dot => "dot",
+        }
+    }
+}
+
+/// Map 1-based language indices to slots, keeping their order and dropping
+/// repeats.
+pub fn language_slots(indices: &[u8]) -> Result<Vec<&'static str>, String> {
+    let mut slots: Vec<&'static str> = Vec::new();
+    for &index in indices {
+        let slot = index
+            .checked_sub(1)
+            .and_then(|i| LANG_SLOTS.get(i as usize))
+            .ok_or_else(|| format!("Invalid language index: {index}"))?;
+        if !slots.contains(slot) {
+            slots.push(slot);
+        }
+    }
+    Ok(slots)
+}
+
+/// Text of one slide for the exported languages, variables already expanded.
+pub struct SlideText {
+    pub title: Vec<String>,
+    /// One entry per language that has block text, in display order.
+    pub bodies: Vec<Vec<String>>,
+    pub footer: Vec<String>,
+}
+
+/// Collect a slide's non-empty title, block and footer text for `languages`, in that order.
+pub fn slide_text(slide: &Slide, languages: &[&str], substituter: &Substituter) -> SlideText {
+    let mut unknown = BTreeSet::new();
+    let mut expand = |node: Option<&Value>, slot: &str| -> Option<String> {
+        let text = node?.get(slot)?.as_str()?.trim();
+        (!text.is_empty()).then(|| substituter.replace(text, slot, &mut unknown))
+    };
+
+    let parse = |json: &str| serde_json::from_str::<Value>(json).ok();
+    let title = slide.title_json.as_deref().and_then(parse);
+    let footer = slide.footer_json.as_deref().and_then(parse);
+    let blocks: Vec<Value> = serde_json::from_str(&slide.blocks_json).unwrap_or_default();
+
+    let mut text = SlideText {
+        title: Vec::new(),
+        bodies: Vec::new(),
+        footer: Vec::new(),
+    };
+    for slot in languages {
+        text.title.extend(expand(title.as_ref(), slot));
+        let body: Vec<String> = blocks
+            .iter()
+            .filter_map(|block| expand(Some(block), slot))
+            .collect();
+        if !body.is_empty() {
+            text.bodies.push(body);
+        }
+        let footer = footer.as_ref();
+        text.footer
+            .extend(expand(footer.and_then(|f| f.get("title")), slot));
+        text.footer
+            .extend(expand(footer.and_then(|f| f.get("text")), slot));
+    }
+    text
+}
+
+/// Break `text` into lines no wider than `max_width`, measured by `width`.
+///
+/// Lines break after spaces and the Ethiopic word and sentence separators; a
+/// single word wider than the line, common in long Ge'ez runs without `፡`, is
+/// split between characters. Each source line break starts a new line.
+pub fn wrap_lines(text: &str, max_width: f32, width: impl Fn(&str) -> f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for source in text.lines() {
+        let mut current = String::new();
+        let mut current_width = 0.0;
+        for token in source.split_inclusive([' ', '፡', '።']) {
+            let fits = current_width + width(token.trim_end()) <= max_width;
+            if !fits && !current.is_empty() {
+                lines.push(current.trim_end().to_string());
+                current.clear();
+                current_width = 0.0;
+            }
+            let token_width = width(token);
+            if token_width <= max_width {
+                current.push_str(token);
+                current_width += token_width;
+                continue;
+            }
+            for c in token.chars() {
+                let c_width = width(c.encode_utf8(&mut [0; 4]));
+                if current_width + c_width > max_width && !current.is_empty() {
+                    lines.push(std::mem::take(&mut current));
+                    current_width = 0.0;
+                }
+                current.push(c);
+                current_width += c_width;
+            }
+        }
+        lines.push(current.trim_end().to_string());
+    }
+    lines
+}
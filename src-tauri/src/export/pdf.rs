@@ -0,0 +1,716 @@
+use printpdf::path::PaintMode;
+use printpdf::{
+    Color, ColorBits, ColorSpace, Greyscale, Image, ImageTransform, ImageXObject, IndirectFontRef,
+    Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Pt, Px, Rect,
+};
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+use ttf_parser::Face;
+
+use super::{language_slots, slide_text, wrap_lines, SlideText};
+use crate::contact_sheet::Thumbnail;
+use crate::db::{self, Slide};
+use crate::fonts;
+use crate::placeholders::Substituter;
+
+/// 16:9 page in points, the same proportions as the presenter window.
+const PAGE_WIDTH: f32 = 960.0;
+const PAGE_HEIGHT: f32 = 540.0;
+const MARGIN: f32 = 40.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+const GAP: f32 = 14.0;
+const LINE_SPACING: f32 = 1.35;
+
+const COVER_SIZE: f32 = 40.0;
+const TITLE_SIZE: f32 = 26.0;
+const BODY_SIZE: f32 = 20.0;
+const FOOTER_SIZE: f32 = 12.0;
+
+/// Handouts print on A4 portrait, with a grid of 16:9 cells below a header.
+const HANDOUT_WIDTH: f32 = 595.0;
+const HANDOUT_HEIGHT: f32 = 842.0;
+const HANDOUT_MARGIN: f32 = 36.0;
+const HEADER_SIZE: f32 = 11.0;
+const CAPTION_SIZE: f32 = 8.0;
+const CELL_GAP: f32 = 14.0;
+const CELL_PADDING: f32 = 6.0;
+/// Body text size as a fraction of a cell's text width, before shrinking to fit.
+const CELL_BODY_RATIO: f32 = 1.0 / 24.0;
+/// Text in a cell never shrinks below this, in points.
+const MIN_CELL_SIZE: f32 = 4.0;
+/// Notes start at this size and shrink like cell text to fit beside the slide.
+const NOTES_SIZE: f32 = 10.0;
+/// Share of the row a slide takes when its notes sit beside it.
+const NOTES_CELL_SHARE: f32 = 0.55;
+
+/// Contact sheets print on A4 landscape, with the handout's margins and header.
+const SHEET_WIDTH: f32 = 842.0;
+const SHEET_HEIGHT: f32 = 595.0;
+
+/// A font embedded in the document, with the metrics used for wrapping.
+struct Typeface<'a> {
+    font: IndirectFontRef,
+    face: Face<'a>,
+}
+
+impl<'a> Typeface<'a> {
+    fn embed(doc: &PdfDocumentReference, data: &'a [u8]) -> Result<Self, String> {
+        let face = Face::parse(data, 0).map_err(|e| e.to_string())?;
+        let font = doc.add_external_font(data).map_err(|e| e.to_string())?;
+        Ok(Self { font, face })
+    }
+
+    fn covers(&self, text: &str) -> bool {
+        text.chars()
+            .filter(|c| !c.is_whitespace())
+            .all(|c| self.face.glyph_index(c).is_some())
+    }
+
+    fn width(&self, text: &str, size: f32) -> f32 {
+        let units: f32 = text
+            .chars()
+            .filter_map(|c| self.face.glyph_index(c))
+            .filter_map(|glyph| self.face.glyph_hor_advance(glyph))
+            .map(f32::from)
+            .sum();
+        units * size / f32::from(self.face.units_per_em())
+    }
+
+    fn line(&self, text: &str, size: f32) -> Line {
+        Line {
+            text: text.to_string(),
+            font: self.font.clone(),
+            size,
+            width: self.width(text, size),
+            ascent: f32::from(self.face.ascender()) * size / f32::from(self.face.units_per_em()),
+        }
+    }
+}
+
+/// One wrapped line, measured and ready to draw.
+struct Line {
+    text: String,
+    font: IndirectFontRef,
+    size: f32,
+    width: f32,
+    ascent: f32,
+}
+
+impl Line {
+    fn height(&self) -> f32 {
+        self.size * LINE_SPACING
+    }
+
+    /// Draw centered with the top of the line `top` points below the page edge.
+    fn draw(&self, layer: &PdfLayerReference, top: f32) {
+        self.draw_in(layer, PAGE_HEIGHT, MARGIN, CONTENT_WIDTH, top);
+    }
+
+    /// Draw centered in the column `left..left + width` of a page
+    /// `page_height` points tall.
+    fn draw_in(
+        &self,
+        layer: &PdfLayerReference,
+        page_height: f32,
+        left: f32,
+        width: f32,
+        top: f32,
+    ) {
+        let x = left + (width - self.width).max(0.0) / 2.0;
+        self.draw_at(layer, page_height, x, top);
+    }
+
+    /// Draw starting `x` points from the left edge of a page `page_height`
+    /// points tall.
+    fn draw_at(&self, layer: &PdfLayerReference, page_height: f32, x: f32, top: f32) {
+        if self.text.is_empty() {
+            return;
+        }
+        let y = page_height - top - self.ascent;
+        layer.use_text(
+            self.text.as_str(),
+            self.size,
+            Mm::from(Pt(x)),
+            Mm::from(Pt(y)),
+            &self.font,
+        );
+    }
+}
+
+struct Typefaces<'a> {
+    ethiopic: Typeface<'a>,
+    latin: Option<Typeface<'a>>,
+}
+
+impl Typefaces<'_> {
+    /// The Ge'ez font when it has every glyph of `text`, else the Latin one.
+    fn pick(&self, text: &str) -> &Typeface<'_> {
+        match &self.latin {
+            Some(latin) if !self.ethiopic.covers(text) => latin,
+            _ => &self.ethiopic,
+        }
+    }
+
+    /// Break `text` into lines no wider than `max_width`.
+    fn wrap(&self, text: &str, size: f32, max_width: f32) -> Vec<Line> {
+        let typeface = self.pick(text);
+        wrap_lines(text, max_width, |s| typeface.width(s, size))
+            .iter()
+            .map(|line| typeface.line(line, size))
+            .collect()
+    }
+}
+
+/// Write a presentation as a PDF: a cover page with its name, then one page per
+/// enabled slide showing only the requested languages (1–4), in the order given.
+///
+/// Text is expanded with the presentation's variables. A slide whose text runs
+/// past the page continues on the next one, repeating its footer. Ge'ez text
+/// needs an Ethiopic font installed or bundled with the app; the export fails
+/// rather than silently dropping glyphs when none is found. Fonts the
+/// presentation names that are missing refuse it too, unless
+/// `allow_missing_fonts`; see [`fonts::check_export_fonts`].
+#[tauri::command]
+pub async fn export_pdf(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    languages: Vec<u8>,
+    dest_path: String,
+    allow_missing_fonts: Option<bool>,
+) -> Result<(), String> {
+    if !dest_path.to_lowercase().ends_with(".pdf") {
+        return Err("PDF exports are written as .pdf files".to_string());
+    }
+    let slots = language_slots(&languages)?;
+    if slots.is_empty() {
+        return Err("Choose at least one language to export".to_string());
+    }
+
+    let pool = db::pool(&db).await?;
+    let presentation = db::fetch_presentation(&pool, &presentation_id).await?;
+    fonts::check_export_fonts(
+        &pool,
+        &presentation_id,
+        allow_missing_fonts.unwrap_or(false),
+    )
+    .await?;
+    let variables = db::fetch_variables(&pool, &presentation_id).await?;
+    let slides: Vec<Slide> = db::fetch_slides(&pool, &presentation_id)
+        .await?
+        .into_iter()
+        .filter(|s| !s.is_disabled)
+        .collect();
+
+    let substituter = Substituter::new(&variables)?;
+    let texts: Vec<SlideText> = slides
+        .iter()
+        .map(|slide| slide_text(slide, &slots, &substituter))
+        .collect();
+
+    let name = presentation.name;
+    let bytes = tauri::async_runtime::spawn_blocking(move || {
+        let (ethiopic, latin) = fonts::ethiopic_font_data(fonts::font_database())?;
+        render(&name, &texts, &ethiopic, latin.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    std::fs::write(&dest_path, bytes).map_err(|e| format!("Failed to write {dest_path}: {e}"))
+}
+
+fn render(
+    name: &str,
+    slides: &[SlideText],
+    ethiopic: &[u8],
+    latin: Option<&[u8]>,
+) -> Result<Vec<u8>, String> {
+    let (width, height) = (Mm::from(Pt(PAGE_WIDTH)), Mm::from(Pt(PAGE_HEIGHT)));
+    let (doc, cover_page, cover_layer) = PdfDocument::new(name, width, height, "Cover");
+    let typefaces = Typefaces {
+        ethiopic: Typeface::embed(&doc, ethiopic)?,
+        latin: latin.map(|data| Typeface::embed(&doc, data)).transpose()?,
+    };
+
+    let cover = doc.get_page(cover_page).get_layer(cover_layer);
+    let lines = typefaces.wrap(name, COVER_SIZE, CONTENT_WIDTH);
+    let mut top = (PAGE_HEIGHT - lines.iter().map(Line::height).sum::<f32>()) / 2.0;
+    for line in &lines {
+        line.draw(&cover, top);
+        top += line.height();
+    }
+
+    for slide in slides {
+        let footer: Vec<Line> = slide
+            .footer
+            .iter()
+            .flat_map(|text| typefaces.wrap(text, FOOTER_SIZE, CONTENT_WIDTH))
+            .collect();
+        let footer_height: f32 = footer.iter().map(Line::height).sum();
+        let bottom = if footer.is_empty() {
+            PAGE_HEIGHT - MARGIN
+        } else {
+            PAGE_HEIGHT - MARGIN - footer_height - GAP
+        };
+
+        let new_page = || {
+            let (page, layer) = doc.add_page(width, height, "Slide");
+            let layer = doc.get_page(page).get_layer(layer);
+            let mut top = PAGE_HEIGHT - MARGIN - footer_height;
+            for line in &footer {
+                line.draw(&layer, top);
+                top += line.height();
+            }
+            layer
+        };
+
+        let mut layer = new_page();
+        let mut top = MARGIN;
+        for text in &slide.title {
+            for line in typefaces.wrap(text, TITLE_SIZE, CONTENT_WIDTH) {
+                line.draw(&layer, top);
+                top += line.height();
+            }
+        }
+        if !slide.title.is_empty() {
+            top += GAP;
+        }
+
+        for body in &slide.bodies {
+            for text in body {
+                for line in typefaces.wrap(text, BODY_SIZE, CONTENT_WIDTH) {
+                    if top + line.height() > bottom && top > MARGIN {
+                        layer = new_page();
+                        top = MARGIN;
+                    }
+                    line.draw(&layer, top);
+                    top += line.height();
+                }
+            }
+            top += GAP;
+        }
+    }
+
+    doc.save_to_bytes().map_err(|e| e.to_string())
+}
+
+/// Write a printable handout: `slides_per_page` (1, 2, 4 or 6) enabled slides
+/// per A4 page in reading order, each in an outlined 16:9 cell captioned with
+/// its slide number, under a header with the presentation name.
+///
+/// Only the requested languages (1–4) are shown, in the order given. Text wraps
+/// within its cell and shrinks until the whole slide fits, so long Ge'ez
+/// readings aren't cut off. With `notes`, each slide's notes are printed
+/// beside it, one slide per row, or below it on a one-slide page.
+///
+/// Missing fonts refuse the export as [`export_pdf`]'s do.
+#[tauri::command]
+pub async fn export_handout_pdf(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    slides_per_page: u8,
+    languages: Vec<u8>,
+    dest_path: String,
+    notes: Option<bool>,
+    allow_missing_fonts: Option<bool>,
+) -> Result<(), String> {
+    if !dest_path.to_lowercase().ends_with(".pdf") {
+        return Err("PDF exports are written as .pdf files".to_string());
+    }
+    let notes = notes.unwrap_or(false);
+    let grid = match (slides_per_page, notes) {
+        (1, _) => (1, 1),
+        (2, _) => (1, 2),
+        (4, false) => (2, 2),
+        (6, false) => (2, 3),
+        // The notes take the second column
+        (4 | 6, true) => (1, usize::from(slides_per_page)),
+        _ => return Err("Handouts hold 1, 2, 4 or 6 slides per page".to_string()),
+    };
+    let slots = language_slots(&languages)?;
+    if slots.is_empty() {
+        return Err("Choose at least one language to export".to_string());
+    }
+
+    let pool = db::pool(&db).await?;
+    let presentation = db::fetch_presentation(&pool, &presentation_id).await?;
+    fonts::check_export_fonts(
+        &pool,
+        &presentation_id,
+        allow_missing_fonts.unwrap_or(false),
+    )
+    .await?;
+    let variables = db::fetch_variables(&pool, &presentation_id).await?;
+    let slides: Vec<Slide> = db::fetch_slides(&pool, &presentation_id)
+        .await?
+        .into_iter()
+        .filter(|s| !s.is_disabled)
+        .collect();
+
+    let substituter = Substituter::new(&variables)?;
+    // Numbered by position among all slides, as the editor shows them
+    let texts: Vec<HandoutSlide> = slides
+        .iter()
+        .map(|slide| HandoutSlide {
+            number: slide.slide_order,
+            text: slide_text(slide, &slots, &substituter),
+            notes: slide
+                .notes
+                .as_deref()
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(str::to_string),
+        })
+        .collect();
+
+    let name = presentation.name;
+    let bytes = tauri::async_runtime::spawn_blocking(move || {
+        let (ethiopic, latin) = fonts::ethiopic_font_data(fonts::font_database())?;
+        render_handout(&name, &texts, grid, notes, &ethiopic, latin.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    std::fs::write(&dest_path, bytes).map_err(|e| format!("Failed to write {dest_path}: {e}"))
+}
+
+/// One cell of a handout.
+struct HandoutSlide {
+    number: i64,
+    text: SlideText,
+    /// Printed only when the handout asks for notes.
+    notes: Option<String>,
+}
+
+fn render_handout(
+    name: &str,
+    slides: &[HandoutSlide],
+    (columns, rows): (usize, usize),
+    notes: bool,
+    ethiopic: &[u8],
+    latin: Option<&[u8]>,
+) -> Result<Vec<u8>, String> {
+    let (width, height) = (Mm::from(Pt(HANDOUT_WIDTH)), Mm::from(Pt(HANDOUT_HEIGHT)));
+    let (doc, first_page, first_layer) = PdfDocument::new(name, width, height, "Handout");
+    let typefaces = Typefaces {
+        ethiopic: Typeface::embed(&doc, ethiopic)?,
+        latin: latin.map(|data| Typeface::embed(&doc, data)).transpose()?,
+    };
+
+    let content_width = HANDOUT_WIDTH - 2.0 * HANDOUT_MARGIN;
+    let header: Vec<Line> = typefaces.wrap(name, HEADER_SIZE, content_width);
+    let grid_top = HANDOUT_MARGIN + header.iter().map(Line::height).sum::<f32>() + GAP;
+    let grid_height = HANDOUT_HEIGHT - HANDOUT_MARGIN - grid_top;
+
+    // Every cell is the largest 16:9 box that fits its share of the grid,
+    // leaving room for its notes beside it, or below it alone on a page
+    let caption_height = CAPTION_SIZE * LINE_SPACING;
+    let slot_width = (content_width - CELL_GAP * (columns - 1) as f32) / columns as f32;
+    let slot_height = (grid_height - CELL_GAP * (rows - 1) as f32) / rows as f32;
+    let notes_below = notes && rows == 1;
+    let notes_beside = notes && !notes_below;
+    let (width_share, height_share) = match (notes_beside, notes_below) {
+        (true, _) => (NOTES_CELL_SHARE, 1.0),
+        (_, true) => (1.0, 0.5),
+        _ => (1.0, 1.0),
+    };
+    let cell_width =
+        (slot_width * width_share).min((slot_height * height_share - caption_height) * 16.0 / 9.0);
+    let cell_height = cell_width * 9.0 / 16.0;
+
+    let per_page = columns * rows;
+    let page_count = slides.len().div_ceil(per_page).max(1);
+    for page_index in 0..page_count {
+        let layer = if page_index == 0 {
+            doc.get_page(first_page).get_layer(first_layer)
+        } else {
+            let (page, layer) = doc.add_page(width, height, "Handout");
+            doc.get_page(page).get_layer(layer)
+        };
+        let mut top = HANDOUT_MARGIN;
+        for line in &header {
+            line.draw_in(&layer, HANDOUT_HEIGHT, HANDOUT_MARGIN, content_width, top);
+            top += line.height();
+        }
+
+        let page_slides = slides.iter().skip(page_index * per_page).take(per_page);
+        for (index, slide) in page_slides.enumerate() {
+            let (column, row) = (index % columns, index / columns);
+            let slot_left = HANDOUT_MARGIN + column as f32 * (slot_width + CELL_GAP);
+            let left = if notes_beside {
+                slot_left
+            } else {
+                slot_left + (slot_width - cell_width) / 2.0
+            };
+            let top = grid_top + row as f32 * (slot_height + CELL_GAP);
+
+            let number = slide.number.to_string();
+            let caption = typefaces.pick(&number).line(&number, CAPTION_SIZE);
+            caption.draw_in(&layer, HANDOUT_HEIGHT, left, cell_width, top);
+            let cell_top = top + caption_height;
+            layer.set_outline_color(Color::Greyscale(Greyscale::new(0.6, None)));
+            layer.set_outline_thickness(0.5);
+            layer.add_rect(
+                Rect::new(
+                    Mm::from(Pt(left)),
+                    Mm::from(Pt(HANDOUT_HEIGHT - cell_top - cell_height)),
+                    Mm::from(Pt(left + cell_width)),
+                    Mm::from(Pt(HANDOUT_HEIGHT - cell_top)),
+                )
+                .with_mode(PaintMode::Stroke),
+            );
+            draw_cell(
+                &layer,
+                &typefaces,
+                &slide.text,
+                left + CELL_PADDING,
+                cell_top + CELL_PADDING,
+                cell_width - 2.0 * CELL_PADDING,
+                cell_height - 2.0 * CELL_PADDING,
+            );
+
+            let Some(text) = slide.notes.as_deref().filter(|_| notes) else {
+                continue;
+            };
+            if notes_beside {
+                let notes_left = left + cell_width + CELL_GAP;
+                let notes_width = slot_left + slot_width - notes_left;
+                draw_notes(
+                    &layer,
+                    &typefaces,
+                    text,
+                    notes_left,
+                    cell_top,
+                    notes_width,
+                    cell_height,
+                );
+            } else {
+                let notes_top = cell_top + cell_height + CELL_GAP;
+                draw_notes(
+                    &layer,
+                    &typefaces,
+                    text,
+                    slot_left,
+                    notes_top,
+                    slot_width,
+                    top + slot_height - notes_top,
+                );
+            }
+        }
+    }
+
+    doc.save_to_bytes().map_err(|e| e.to_string())
+}
+
+/// A slide's wrapped lines at one text size.
+struct CellLayout {
+    title: Vec<Line>,
+    bodies: Vec<Vec<Line>>,
+    footer: Vec<Line>,
+    gap: f32,
+}
+
+impl CellLayout {
+    fn new(typefaces: &Typefaces, slide: &SlideText, body_size: f32, width: f32) -> Self {
+        let scale = body_size / BODY_SIZE;
+        let wrap_all = |texts: &[String], size: f32| -> Vec<Line> {
+            texts
+                .iter()
+                .flat_map(|text| typefaces.wrap(text, size, width))
+                .collect()
+        };
+        CellLayout {
+            title: wrap_all(&slide.title, TITLE_SIZE * scale),
+            bodies: slide
+                .bodies
+                .iter()
+                .map(|body| wrap_all(body, body_size))
+                .collect(),
+            footer: wrap_all(&slide.footer, FOOTER_SIZE * scale),
+            gap: GAP * scale,
+        }
+    }
+
+    fn height(&self) -> f32 {
+        let lines: f32 = self
+            .title
+            .iter()
+            .chain(self.bodies.iter().flatten())
+            .chain(&self.footer)
+            .map(Line::height)
+            .sum();
+        let gaps = self.bodies.len()
+            + usize::from(!self.title.is_empty())
+            + usize::from(!self.footer.is_empty());
+        lines + self.gap * gaps as f32
+    }
+}
+
+/// Lay a slide out in a `width` × `height` box, shrinking every size together
+/// until the title, bodies and footer fit.
+fn draw_cell(
+    layer: &PdfLayerReference,
+    typefaces: &Typefaces,
+    slide: &SlideText,
+    left: f32,
+    top: f32,
+    width: f32,
+    height: f32,
+) {
+    let mut size = width * CELL_BODY_RATIO;
+    let mut layout = CellLayout::new(typefaces, slide, size, width);
+    while layout.height() > height && size > MIN_CELL_SIZE {
+        size = (size * 0.9).max(MIN_CELL_SIZE);
+        layout = CellLayout::new(typefaces, slide, size, width);
+    }
+
+    let mut y = top;
+    for line in &layout.title {
+        line.draw_in(layer, HANDOUT_HEIGHT, left, width, y);
+        y += line.height();
+    }
+    if !layout.title.is_empty() {
+        y += layout.gap;
+    }
+    for body in &layout.bodies {
+        for line in body {
+            line.draw_in(layer, HANDOUT_HEIGHT, left, width, y);
+            y += line.height();
+        }
+        y += layout.gap;
+    }
+
+    // The footer sits at the bottom of the cell, as on the full-page export
+    let footer_height: f32 = layout.footer.iter().map(Line::height).sum();
+    let mut y = (top + height - footer_height).max(y);
+    for line in &layout.footer {
+        line.draw_in(layer, HANDOUT_HEIGHT, left, width, y);
+        y += line.height();
+    }
+}
+
+/// Print a slide's notes left-aligned in a `width` × `height` box, shrinking
+/// them until they fit.
+fn draw_notes(
+    layer: &PdfLayerReference,
+    typefaces: &Typefaces,
+    notes: &str,
+    left: f32,
+    top: f32,
+    width: f32,
+    height: f32,
+) {
+    let mut size = NOTES_SIZE;
+    let mut lines = typefaces.wrap(notes, size, width);
+    let total = |lines: &[Line]| lines.iter().map(Line::height).sum::<f32>();
+    while total(&lines) > height && size > MIN_CELL_SIZE {
+        size = (size * 0.9).max(MIN_CELL_SIZE);
+        lines = typefaces.wrap(notes, size, width);
+    }
+
+    let mut y = top;
+    for line in &lines {
+        line.draw_at(layer, HANDOUT_HEIGHT, left, y);
+        y += line.height();
+    }
+}
+
+/// Lay rendered thumbnails out `columns` to a row on A4 landscape pages, each
+/// outlined and captioned with its slide number, under a header with the
+/// presentation name. Rows that don't fit start a new page.
+pub(crate) fn render_contact_sheet(
+    name: &str,
+    thumbnails: &[Thumbnail],
+    columns: usize,
+    ethiopic: &[u8],
+    latin: Option<&[u8]>,
+) -> Result<Vec<u8>, String> {
+    let (width, height) = (Mm::from(Pt(SHEET_WIDTH)), Mm::from(Pt(SHEET_HEIGHT)));
+    let (doc, first_page, first_layer) = PdfDocument::new(name, width, height, "Contact sheet");
+    let typefaces = Typefaces {
+        ethiopic: Typeface::embed(&doc, ethiopic)?,
+        latin: latin.map(|data| Typeface::embed(&doc, data)).transpose()?,
+    };
+
+    let content_width = SHEET_WIDTH - 2.0 * HANDOUT_MARGIN;
+    let header: Vec<Line> = typefaces.wrap(name, HEADER_SIZE, content_width);
+    let grid_top = HANDOUT_MARGIN + header.iter().map(Line::height).sum::<f32>() + GAP;
+    let caption_height = CAPTION_SIZE * LINE_SPACING;
+    let cell_width = (content_width - CELL_GAP * (columns - 1) as f32) / columns as f32;
+    let cell_height = cell_width * 9.0 / 16.0;
+    let row_height = caption_height + cell_height + CELL_GAP;
+    let rows = ((SHEET_HEIGHT - HANDOUT_MARGIN - grid_top + CELL_GAP) / row_height) as usize;
+
+    let per_page = columns * rows.max(1);
+    let page_count = thumbnails.len().div_ceil(per_page).max(1);
+    for page_index in 0..page_count {
+        let layer = if page_index == 0 {
+            doc.get_page(first_page).get_layer(first_layer)
+        } else {
+            let (page, layer) = doc.add_page(width, height, "Contact sheet");
+            doc.get_page(page).get_layer(layer)
+        };
+        let mut top = HANDOUT_MARGIN;
+        for line in &header {
+            line.draw_in(&layer, SHEET_HEIGHT, HANDOUT_MARGIN, content_width, top);
+            top += line.height();
+        }
+
+        let page_thumbnails = thumbnails.iter().skip(page_index * per_page).take(per_page);
+        for (index, thumbnail) in page_thumbnails.enumerate() {
+            let (column, row) = (index % columns, index / columns);
+            let left = HANDOUT_MARGIN + column as f32 * (cell_width + CELL_GAP);
+            let top = grid_top + row as f32 * row_height;
+
+            let number = thumbnail.number.to_string();
+            let caption = typefaces.pick(&number).line(&number, CAPTION_SIZE);
+            caption.draw_in(&layer, SHEET_HEIGHT, left, cell_width, top);
+            let cell_top = top + caption_height;
+            let cell_bottom = SHEET_HEIGHT - cell_top - cell_height;
+
+            // Thumbnails are opaque, so their premultiplied pixels are plain RGB
+            let pixmap = &thumbnail.pixmap;
+            let image_data = pixmap
+                .pixels()
+                .iter()
+                .flat_map(|pixel| [pixel.red(), pixel.green(), pixel.blue()])
+                .collect();
+            Image::from(ImageXObject {
+                width: Px(pixmap.width() as usize),
+                height: Px(pixmap.height() as usize),
+                color_space: ColorSpace::Rgb,
+                bits_per_component: ColorBits::Bit8,
+                interpolate: true,
+                image_data,
+                image_filter: None,
+                smask: None,
+                clipping_bbox: None,
+            })
+            .add_to_layer(
+                layer.clone(),
+                ImageTransform {
+                    translate_x: Some(Mm::from(Pt(left))),
+                    translate_y: Some(Mm::from(Pt(cell_bottom))),
+                    scale_x: Some(cell_width / pixmap.width() as f32),
+                    scale_y: Some(cell_height / pixmap.height() as f32),
+                    // One pixel to the point before scaling
+                    dpi: Some(72.0),
+                    ..ImageTransform::default()
+                },
+            );
+
+            layer.set_outline_color(Color::Greyscale(Greyscale::new(0.6, None)));
+            layer.set_outline_thickness(0.5);
+            layer.add_rect(
+                Rect::new(
+                    Mm::from(Pt(left)),
+                    Mm::from(Pt(cell_bottom)),
+                    Mm::from(Pt(left + cell_width)),
+                    Mm::from(Pt(SHEET_HEIGHT - cell_top)),
+                )
+                .with_mode(PaintMode::Stroke),
+            );
+        }
+    }
+
+    doc.save_to_bytes().map_err(|e| e.to_string())
+}
@@ -0,0 +1,451 @@
+use std::fmt::Write as _;
+use std::io::Write as _;
+
+use serde::Deserialize;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use super::language_slots;
+use crate::db::{self, Slide};
+use crate::fonts::{self, parse_font_stack};
+use crate::markup::escape;
+use crate::render::{resolve, Content, TITLE_GAP, TITLE_LINE_HEIGHT};
+use crate::template::{
+    LanguageStyle, TemplateDefinition, TextAlignment, TitleAlignment, VerticalAlign,
+};
+use crate::theme::Rgb;
+
+/// 16:9 slide size in EMUs.
+const SLIDE_WIDTH: i64 = 12_192_000;
+const SLIDE_HEIGHT: i64 = 6_858_000;
+
+/// Templates lay out in the presenter's 1920×1080 design space; one design
+/// pixel is 6350 EMUs, or half a point.
+const DESIGN_WIDTH: f64 = 1920.0;
+const DESIGN_HEIGHT: f64 = 1080.0;
+const EMU_PER_PIXEL: f64 = SLIDE_WIDTH as f64 / DESIGN_WIDTH;
+/// Font sizes are written in hundredths of a point.
+const SIZE_PER_PIXEL: f64 = 50.0;
+/// The presenter never lets blocks touch.
+const MIN_GAP: f64 = 16.0;
+
+const NAMESPACES: &str = r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main""#;
+
+const GROUP_PROPERTIES: &str = r#"<p:nvGrpSpPr><p:cNvPr id="1" name=""/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="0" cy="0"/><a:chOff x="0" y="0"/><a:chExt cx="0" cy="0"/></a:xfrm></p:grpSpPr>"#;
+
+/// What [`export_pptx`] writes; every field may be left out.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PptxOptions {
+    /// 1-based language indices, in display order; the presentation's display
+    /// languages when empty.
+    pub languages: Vec<u8>,
+    /// Keep disabled slides, as hidden slides.
+    pub include_disabled: bool,
+    /// Export even though fonts the presentation uses aren't installed, for
+    /// the viewer to substitute.
+    pub allow_missing_fonts: bool,
+}
+
+/// Write a presentation as a `.pptx` with one slide per enabled slide row.
+///
+/// Each slide is laid out with its effective template, as the presenter
+/// shows it: the template's background, margins and title, and one text box
+/// per language in the region its layout gives that language, with the
+/// language's font, size, colour, alignment and line height. Text is expanded
+/// with the presentation's variables, and sizes follow its display scale.
+/// Dynamic slides export the text stored on the row, not their verse
+/// expansion. Missing fonts refuse the export; see
+/// [`fonts::check_export_fonts`].
+#[tauri::command]
+pub async fn export_pptx(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    dest_path: String,
+    options: Option<PptxOptions>,
+) -> Result<(), String> {
+    if !dest_path.to_lowercase().ends_with(".pptx") {
+        return Err("PowerPoint exports are written as .pptx files".to_string());
+    }
+    let options = options.unwrap_or_default();
+
+    let pool = db::pool(&db).await?;
+    let presentation = db::fetch_presentation(&pool, &presentation_id).await?;
+    fonts::check_export_fonts(&pool, &presentation_id, options.allow_missing_fonts).await?;
+    let slots = if options.languages.is_empty() {
+        presentation.ordered_languages()
+    } else {
+        language_slots(&options.languages)?
+    };
+    let slides: Vec<Slide> = db::fetch_slides(&pool, &presentation_id)
+        .await?
+        .into_iter()
+        .filter(|s| options.include_disabled || !s.is_disabled)
+        .collect();
+
+    let mut resolved = Vec::with_capacity(slides.len());
+    for slide in &slides {
+        resolved.push(resolve(&pool, slide, &presentation, &slots).await?);
+    }
+
+    let file = std::fs::File::create(&dest_path)
+        .map_err(|e| format!("Failed to write {dest_path}: {e}"))?;
+    write_package(file, &resolved).map_err(|e| format!("Failed to write {dest_path}: {e}"))
+}
+
+fn write_package(
+    file: std::fs::File,
+    slides: &[(TemplateDefinition, Content)],
+) -> Result<(), String> {
+    let mut zip = ZipWriter::new(file);
+    let mut part = |name: &str, content: &str| -> Result<(), String> {
+        zip.start_file(name, SimpleFileOptions::default())
+            .map_err(|e| e.to_string())?;
+        zip.write_all(content.as_bytes()).map_err(|e| e.to_string())
+    };
+
+    part("[Content_Types].xml", &content_types(slides.len()))?;
+    part("_rels/.rels", ROOT_RELS)?;
+    part("ppt/presentation.xml", &presentation_xml(slides.len()))?;
+    part(
+        "ppt/_rels/presentation.xml.rels",
+        &presentation_rels(slides.len()),
+    )?;
+    part("ppt/slideMasters/slideMaster1.xml", &slide_master())?;
+    part(
+        "ppt/slideMasters/_rels/slideMaster1.xml.rels",
+        SLIDE_MASTER_RELS,
+    )?;
+    part("ppt/slideLayouts/slideLayout1.xml", &slide_layout())?;
+    part(
+        "ppt/slideLayouts/_rels/slideLayout1.xml.rels",
+        SLIDE_LAYOUT_RELS,
+    )?;
+    part("ppt/theme/theme1.xml", THEME)?;
+    for (index, (definition, content)) in slides.iter().enumerate() {
+        let number = index + 1;
+        part(
+            &format!("ppt/slides/slide{number}.xml"),
+            &slide_xml(definition, content),
+        )?;
+        part(
+            &format!("ppt/slides/_rels/slide{number}.xml.rels"),
+            SLIDE_RELS,
+        )?;
+    }
+
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// A box in design pixels.
+#[derive(Debug, Clone, Copy)]
+struct Region {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// How a text box's paragraphs are drawn.
+struct TextStyle<'a> {
+    size: f64,
+    color: &'a str,
+    align: &'static str,
+    line_height: f64,
+    bold: bool,
+    anchor: &'static str,
+    font: Option<String>,
+}
+
+fn slide_xml(definition: &TemplateDefinition, content: &Content) -> String {
+    let mut shapes = String::new();
+    let mut shape_id = 2;
+    let margins = &definition.margins;
+    let title = &definition.title;
+    let left = margins.left;
+    let width = DESIGN_WIDTH - margins.left - margins.right;
+    let title_align = match title.alignment {
+        TitleAlignment::Left => "l",
+        TitleAlignment::Center => "ctr",
+        TitleAlignment::Right => "r",
+    };
+    let heading_height = title.font_size * f64::from(TITLE_LINE_HEIGHT);
+    let gap = f64::from(TITLE_GAP);
+
+    let mut top = margins.top;
+    if let Some(text) = &content.title {
+        let region = Region {
+            x: left,
+            y: top,
+            width,
+            height: heading_height,
+        };
+        let style = TextStyle {
+            size: title.font_size,
+            color: &title.color,
+            align: title_align,
+            line_height: f64::from(TITLE_LINE_HEIGHT),
+            bold: true,
+            anchor: "t",
+            font: None,
+        };
+        text_box(
+            &mut shapes,
+            shape_id,
+            region,
+            std::slice::from_ref(text),
+            &style,
+        );
+        shape_id += 1;
+        top += heading_height + gap;
+    }
+
+    let mut bottom = DESIGN_HEIGHT - margins.bottom;
+    if !content.footer.is_empty() {
+        let height = heading_height * content.footer.len() as f64;
+        bottom -= height;
+        let region = Region {
+            x: left,
+            y: bottom,
+            width,
+            height,
+        };
+        let style = TextStyle {
+            size: title.font_size,
+            color: &title.color,
+            align: "l",
+            line_height: f64::from(TITLE_LINE_HEIGHT),
+            bold: false,
+            anchor: "b",
+            font: None,
+        };
+        text_box(&mut shapes, shape_id, region, &content.footer, &style);
+        shape_id += 1;
+        bottom -= gap;
+    }
+
+    // Text sits at the top under a title or above a footer, as it does live
+    let anchor = if content.title.is_some() || !content.footer.is_empty() {
+        "t"
+    } else {
+        match definition.layout.vertical_align {
+            Some(VerticalAlign::Top) => "t",
+            Some(VerticalAlign::Bottom) => "b",
+            Some(VerticalAlign::Center) | None => "ctr",
+        }
+    };
+    let area = Region {
+        x: left,
+        y: top,
+        width,
+        height: (bottom - top).max(0.0),
+    };
+    let regions = language_regions(definition, content.bodies.len(), area);
+    for ((language, text), region) in content.bodies.iter().zip(regions) {
+        text_box(
+            &mut shapes,
+            shape_id,
+            region,
+            std::slice::from_ref(text),
+            &language_style(language, anchor),
+        );
+        shape_id += 1;
+    }
+
+    let background = Rgb::parse(&definition.background.color)
+        .unwrap_or(Rgb::BLACK)
+        .to_hex();
+    // Disabled slides only come along when asked for, and stay out of the show
+    let hidden = if content.disabled { r#" show="0""# } else { "" };
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sld {NAMESPACES}{hidden}><p:cSld><p:bg><p:bgPr><a:solidFill><a:srgbClr val="{}"/></a:solidFill><a:effectLst/></p:bgPr></p:bg><p:spTree>{GROUP_PROPERTIES}{shapes}</p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:sld>"#,
+        &background[1..]
+    )
+}
+
+/// Where each of `count` languages goes in `area`, mirroring the presenter's
+/// `SlideRenderer`: stacked in one column, side by side in a multi-column
+/// layout, and with more than one row, the first language across the top
+/// half and the rest side by side below it.
+fn language_regions(definition: &TemplateDefinition, count: usize, area: Region) -> Vec<Region> {
+    let gap = definition.layout.gap.max(MIN_GAP);
+    let split = |area: Region, parts: usize, across: bool| -> Vec<Region> {
+        let parts_f = parts.max(1) as f64;
+        let gaps = gap * (parts_f - 1.0);
+        (0..parts)
+            .map(|i| {
+                let i = i as f64;
+                if across {
+                    let width = (area.width - gaps) / parts_f;
+                    Region {
+                        x: area.x + i * (width + gap),
+                        width,
+                        ..area
+                    }
+                } else {
+                    let height = (area.height - gaps) / parts_f;
+                    Region {
+                        y: area.y + i * (height + gap),
+                        height,
+                        ..area
+                    }
+                }
+            })
+            .collect()
+    };
+
+    if count <= 1 || definition.layout.columns <= 1 {
+        return split(area, count, false);
+    }
+    if definition.layout.rows <= 1 {
+        return split(area, count, true);
+    }
+    let rows = split(area, 2, false);
+    let mut regions = vec![rows[0]];
+    regions.extend(split(rows[1], count - 1, true));
+    regions
+}
+
+fn language_style<'a>(language: &'a LanguageStyle, anchor: &'static str) -> TextStyle<'a> {
+    TextStyle {
+        size: language.font_size,
+        color: &language.color,
+        align: match language.alignment {
+            TextAlignment::Left => "l",
+            TextAlignment::Center => "ctr",
+            TextAlignment::Right => "r",
+            TextAlignment::Justify => "just",
+        },
+        line_height: language.line_height,
+        bold: false,
+        anchor,
+        font: parse_font_stack(&language.font_family).into_iter().next(),
+    }
+}
+
+/// A text box shrinking its text to fit; each entry of `paragraphs` may span
+/// several lines.
+fn text_box(xml: &mut String, id: u32, region: Region, paragraphs: &[String], style: &TextStyle) {
+    let emu = |pixels: f64| (pixels * EMU_PER_PIXEL).round() as i64;
+    let (x, y, cx, cy) = (
+        emu(region.x),
+        emu(region.y),
+        emu(region.width).max(0),
+        emu(region.height).max(0),
+    );
+    let anchor = style.anchor;
+    let _ = write!(
+        xml,
+        r#"<p:sp><p:nvSpPr><p:cNvPr id="{id}" name="Text {id}"/><p:cNvSpPr txBox="1"/><p:nvPr/></p:nvSpPr><p:spPr><a:xfrm><a:off x="{x}" y="{y}"/><a:ext cx="{cx}" cy="{cy}"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom></p:spPr><p:txBody><a:bodyPr wrap="square" lIns="0" tIns="0" rIns="0" bIns="0" anchor="{anchor}"><a:normAutofit/></a:bodyPr><a:lstStyle/>"#
+    );
+
+    // PowerPoint takes sizes from 1 to 4000 points
+    let size = (style.size * SIZE_PER_PIXEL)
+        .round()
+        .clamp(100.0, 400_000.0) as u32;
+    let bold = if style.bold { r#" b="1""# } else { "" };
+    let color = Rgb::parse(style.color).unwrap_or(Rgb::WHITE).to_hex();
+    let spacing = (style.line_height * 100_000.0).round() as i64;
+    let font = style.font.as_deref().map_or(String::new(), |font| {
+        let font = escape(font);
+        format!(r#"<a:latin typeface="{font}"/><a:ea typeface="{font}"/><a:cs typeface="{font}"/>"#)
+    });
+    for line in paragraphs.iter().flat_map(|p| p.lines()) {
+        let _ = write!(
+            xml,
+            r#"<a:p><a:pPr algn="{}"><a:lnSpc><a:spcPct val="{spacing}"/></a:lnSpc></a:pPr><a:r><a:rPr lang="en-US" sz="{size}"{bold} dirty="0"><a:solidFill><a:srgbClr val="{}"/></a:solidFill>{font}</a:rPr><a:t>{}</a:t></a:r></a:p>"#,
+            style.align,
+            &color[1..],
+            escape(line)
+        );
+    }
+    xml.push_str("</p:txBody></p:sp>");
+}
+
+fn content_types(slide_count: usize) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/ppt/presentation.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml"/><Override PartName="/ppt/slideMasters/slideMaster1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideMaster+xml"/><Override PartName="/ppt/slideLayouts/slideLayout1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideLayout+xml"/><Override PartName="/ppt/theme/theme1.xml" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/>"#,
+    );
+    for number in 1..=slide_count {
+        let _ = write!(
+            xml,
+            r#"<Override PartName="/ppt/slides/slide{number}.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slide+xml"/>"#
+        );
+    }
+    xml.push_str("</Types>");
+    xml
+}
+
+fn presentation_xml(slide_count: usize) -> String {
+    let mut slide_ids = String::new();
+    for index in 0..slide_count {
+        let _ = write!(
+            slide_ids,
+            r#"<p:sldId id="{}" r:id="rId{}"/>"#,
+            256 + index,
+            index + 3
+        );
+    }
+    // An empty sldIdLst is invalid, so it is left out for a presentation with no slides
+    let slide_list = if slide_ids.is_empty() {
+        String::new()
+    } else {
+        format!("<p:sldIdLst>{slide_ids}</p:sldIdLst>")
+    };
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:presentation {NAMESPACES}><p:sldMasterIdLst><p:sldMasterId id="2147483648" r:id="rId1"/></p:sldMasterIdLst>{slide_list}<p:sldSz cx="{SLIDE_WIDTH}" cy="{SLIDE_HEIGHT}"/><p:notesSz cx="6858000" cy="9144000"/></p:presentation>"#
+    )
+}
+
+fn presentation_rels(slide_count: usize) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideMaster" Target="slideMasters/slideMaster1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="theme/theme1.xml"/>"#,
+    );
+    for number in 1..=slide_count {
+        let _ = write!(
+            xml,
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide{number}.xml"/>"#,
+            number + 2
+        );
+    }
+    xml.push_str("</Relationships>");
+    xml
+}
+
+fn slide_master() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sldMaster {NAMESPACES}><p:cSld><p:spTree>{GROUP_PROPERTIES}</p:spTree></p:cSld><p:clrMap bg1="lt1" tx1="dk1" bg2="lt2" tx2="dk2" accent1="accent1" accent2="accent2" accent3="accent3" accent4="accent4" accent5="accent5" accent6="accent6" hlink="hlink" folHlink="folHlink"/><p:sldLayoutIdLst><p:sldLayoutId id="2147483649" r:id="rId1"/></p:sldLayoutIdLst></p:sldMaster>"#
+    )
+}
+
+fn slide_layout() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sldLayout {NAMESPACES} type="blank" preserve="1"><p:cSld name="Blank"><p:spTree>{GROUP_PROPERTIES}</p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:sldLayout>"#
+    )
+}
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="ppt/presentation.xml"/></Relationships>"#;
+
+const SLIDE_MASTER_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../slideLayouts/slideLayout1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="../theme/theme1.xml"/></Relationships>"#;
+
+const SLIDE_LAYOUT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideMaster" Target="../slideMasters/slideMaster1.xml"/></Relationships>"#;
+
+const SLIDE_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../slideLayouts/slideLayout1.xml"/></Relationships>"#;
+
+/// Smallest theme PowerPoint accepts: every scheme must be present and each
+/// style list needs three entries.
+const THEME: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<a:theme xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" name="Kidase"><a:themeElements><a:clrScheme name="Kidase"><a:dk1><a:srgbClr val="000000"/></a:dk1><a:lt1><a:srgbClr val="FFFFFF"/></a:lt1><a:dk2><a:srgbClr val="1F1F1F"/></a:dk2><a:lt2><a:srgbClr val="EEEEEE"/></a:lt2><a:accent1><a:srgbClr val="4472C4"/></a:accent1><a:accent2><a:srgbClr val="ED7D31"/></a:accent2><a:accent3><a:srgbClr val="A5A5A5"/></a:accent3><a:accent4><a:srgbClr val="FFC000"/></a:accent4><a:accent5><a:srgbClr val="5B9BD5"/></a:accent5><a:accent6><a:srgbClr val="70AD47"/></a:accent6><a:hlink><a:srgbClr val="0563C1"/></a:hlink><a:folHlink><a:srgbClr val="954F72"/></a:folHlink></a:clrScheme><a:fontScheme name="Kidase"><a:majorFont><a:latin typeface="Nyala"/><a:ea typeface=""/><a:cs typeface=""/></a:majorFont><a:minorFont><a:latin typeface="Nyala"/><a:ea typeface=""/><a:cs typeface=""/></a:minorFont></a:fontScheme><a:fmtScheme name="Kidase"><a:fillStyleLst><a:solidFill><a:schemeClr val="phClr"/></a:solidFill><a:solidFill><a:schemeClr val="phClr"/></a:solidFill><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:fillStyleLst><a:lnStyleLst><a:ln w="6350"><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:ln><a:ln w="12700"><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:ln><a:ln w="19050"><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:ln></a:lnStyleLst><a:effectStyleLst><a:effectStyle><a:effectLst/></a:effectStyle><a:effectStyle><a:effectLst/></a:effectStyle><a:effectStyle><a:effectLst/></a:effectStyle></a:effectStyleLst><a:bgFillStyleLst><a:solidFill><a:schemeClr val="phClr"/></a:solidFill><a:solidFill><a:schemeClr val="phClr"/></a:solidFill><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:bgFillStyleLst></a:fmtScheme></a:themeElements></a:theme>"#;
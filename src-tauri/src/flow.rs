@@ -0,0 +1,356 @@
+use std::fmt::Write as _;
+
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::db::{self, RuleDefinition, Slide, Verse};
+
+const LABEL_MAX_CHARS: usize = 40;
+
+/// Slide plus the verses it expands into, when it is dynamic.
+struct FlowNode {
+    slide: Slide,
+    expansion: Option<Vec<Verse>>,
+}
+
+/// Write the presentation's service flow as a GraphViz DOT graph.
+#[tauri::command]
+pub async fn export_flow_dot(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    dest_path: String,
+) -> Result<(), String> {
+    let pool = db::pool(&db).await?;
+    let dot = build_flow_dot(&pool, &presentation_id).await?;
+    std::fs::write(&dest_path, dot).map_err(|e| format!("Failed to write {dest_path}: {e}"))
+}
+
+async fn build_flow_dot(pool: &SqlitePool, presentation_id: &str) -> Result<String, String> {
+    let presentation = db::fetch_presentation(pool, presentation_id).await?;
+    let slides = db::fetch_slides(pool, presentation_id).await?;
+    let rules: Vec<RuleDefinition> = db::fetch_enabled_rules(pool, presentation_id)
+        .await?
+        .into_iter()
+        .filter(|r| r.scope == "slide")
+        .collect();
+
+    let mut nodes = Vec::with_capacity(slides.len());
+    for slide in slides {
+        let expansion = match (slide.is_dynamic, slide.line_id.as_deref()) {
+            // `@meta.` segment ids only resolve against the live rule context
+            (true, Some(segment_id)) if !segment_id.starts_with("@meta.") => {
+                Some(db::fetch_segment_verses(pool, segment_id).await?)
+            }
+            (true, _) => Some(Vec::new()),
+            _ => None,
+        };
+        nodes.push(FlowNode { slide, expansion });
+    }
+
+    Ok(render_dot(&presentation.name, &nodes, &rules))
+}
+
+fn render_dot(name: &str, nodes: &[FlowNode], rules: &[RuleDefinition]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "digraph \"{}\" {{", escape(name));
+    out.push_str("  rankdir=TB;\n");
+    out.push_str("  node [shape=box, style=rounded, fontname=\"Helvetica\"];\n");
+    let _ = writeln!(out, "  label=\"{}\";", escape(name));
+
+    // (entry, exit) node ids per slide so edges can skip over a whole expansion
+    let mut anchors: Vec<(String, String)> = Vec::with_capacity(nodes.len());
+
+    for (index, node) in nodes.iter().enumerate() {
+        let slide = &node.slide;
+        let id = format!("s{index}");
+        let label = format!("{}. {}", slide.slide_order, slide_label(slide));
+        let style = if slide.is_disabled {
+            ", style=\"rounded,dashed\", fontcolor=gray"
+        } else {
+            ""
+        };
+
+        match &node.expansion {
+            None => {
+                let _ = writeln!(out, "  {id} [label=\"{}\"{style}];", escape(&label));
+                anchors.push((id.clone(), id));
+            }
+            Some(verses) => {
+                let _ = writeln!(out, "  subgraph cluster_{id} {{");
+                let _ = writeln!(out, "    label=\"{}\";", escape(&label));
+                out.push_str("    style=dashed;\n");
+                if verses.is_empty() {
+                    let segment = slide.line_id.as_deref().unwrap_or("(none)");
+                    let _ = writeln!(
+                        out,
+                        "    {id}_v0 [label=\"{}\", shape=note{style}];",
+                        escape(&format!("segment {segment}"))
+                    );
+                    out.push_str("  }\n");
+                    anchors.push((format!("{id}_v0"), format!("{id}_v0")));
+                    continue;
+                }
+                for (v, verse) in verses.iter().enumerate() {
+                    let _ = writeln!(
+                        out,
+                        "    {id}_v{v} [label=\"{}\"{style}];",
+                        escape(&verse_label(verse))
+                    );
+                    if v > 0 {
+                        let _ = writeln!(out, "    {id}_v{} -> {id}_v{v};", v - 1);
+                    }
+                }
+                out.push_str("  }\n");
+                anchors.push((format!("{id}_v0"), format!("{id}_v{}", verses.len() - 1)));
+            }
+        }
+    }
+
+    for pair in anchors.windows(2) {
+        let _ = writeln!(out, "  {} -> {};", pair[0].1, pair[1].0);
+    }
+
+    for (index, node) in nodes.iter().enumerate() {
+        for rule in rules.iter().filter(|r| rule_targets(r, &node.slide)) {
+            let Ok(entry) = serde_json::from_str::<Value>(&rule.rule_json) else {
+                continue;
+            };
+            let condition = entry.get("when").map(compact).unwrap_or_default();
+            for (branch, prefix) in [("then", "when"), ("otherwise", "unless")] {
+                let Some(visible) = entry
+                    .pointer(&format!("/{branch}/visible"))
+                    .and_then(Value::as_bool)
+                else {
+                    continue;
+                };
+                let label = format!(
+                    "{}: {} {prefix} {condition}",
+                    rule.name,
+                    if visible { "show" } else { "hide" }
+                );
+                let (entry_id, exit_id) = &anchors[index];
+                if visible {
+                    let from = index
+                        .checked_sub(1)
+                        .map(|i| anchors[i].1.as_str())
+                        .unwrap_or(entry_id);
+                    let _ = writeln!(
+                        out,
+                        "  {from} -> {entry_id} [style=dotted, color=darkgreen, label=\"{}\"];",
+                        escape(&label)
+                    );
+                } else {
+                    let from = index.checked_sub(1).map(|i| anchors[i].1.as_str());
+                    let to = anchors.get(index + 1).map(|a| a.0.as_str());
+                    let (from, to) = match (from, to) {
+                        (Some(from), Some(to)) => (from, to),
+                        (Some(from), None) => (from, exit_id.as_str()),
+                        (None, Some(to)) => (entry_id.as_str(), to),
+                        (None, None) => (entry_id.as_str(), exit_id.as_str()),
+                    };
+                    let _ = writeln!(
+                        out,
+                        "  {from} -> {to} [style=dashed, color=firebrick, label=\"{}\"];",
+                        escape(&label)
+                    );
+                }
+            }
+        }
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// Slide rules without a `slide_id` apply to every slide, as in `useRules`.
+fn rule_targets(rule: &RuleDefinition, slide: &Slide) -> bool {
+    rule.slide_id.as_deref().is_none_or(|id| id == slide.id)
+}
+
+fn slide_label(slide: &Slide) -> String {
+    let text = slide
+        .title_text()
+        .or_else(|| slide.preview_text())
+        .unwrap_or_else(|| "(empty)".to_string());
+    truncate(&text)
+}
+
+fn verse_label(verse: &Verse) -> String {
+    let text = [
+        &verse.title_lang1,
+        &verse.title_lang2,
+        &verse.text_lang1,
+        &verse.text_lang2,
+    ]
+    .into_iter()
+    .flatten()
+    .map(|s| s.trim())
+    .find(|s| !s.is_empty())
+    .unwrap_or("");
+    truncate(&format!("{}. {text}", verse.verse_order))
+}
+
+fn compact(value: &Value) -> String {
+    truncate(&value.to_string())
+}
+
+fn truncate(text: &str) -> String {
+    let line = text.lines().next().unwrap_or("");
+    if line.chars().count() > LABEL_MAX_CHARS {
+        let cut: String = line.chars().take(LABEL_MAX_CHARS).collect();
+        format!("{cut}…")
+    } else {
+        line.to_string()
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slide(id: &str, slide_order: i64, title: &str, line_id: Option<&str>) -> Slide {
+        Slide {
+            id: id.to_string(),
+            presentation_id: "p1".to_string(),
+            slide_order,
+            line_id: line_id.map(str::to_string),
+            title_json: Some(serde_json::json!({ "Lang1": title }).to_string()),
+            blocks_json: "[]".to_string(),
+            footer_json: None,
+            notes: None,
+            is_disabled: false,
+            is_dynamic: line_id.is_some(),
+            template_override_id: None,
+            version: 1,
+        }
+    }
+
+    fn verse(verse_order: i64, text: &str) -> Verse {
+        Verse {
+            id: format!("v{verse_order}"),
+            segment_id: "seg-1".to_string(),
+            verse_order,
+            title_lang1: None,
+            title_lang2: None,
+            title_lang3: None,
+            title_lang4: None,
+            text_lang1: Some(text.to_string()),
+            text_lang2: None,
+            text_lang3: None,
+            text_lang4: None,
+            created_at: "2026-01-01T00:00:00.000Z".to_string(),
+        }
+    }
+
+    fn rule(name: &str, slide_id: &str, visible: bool) -> RuleDefinition {
+        let entry = serde_json::json!({
+            "when": { "meta.isFeast": { "$eq": true } },
+            "then": { "visible": visible },
+        });
+        RuleDefinition {
+            id: name.to_string(),
+            name: name.to_string(),
+            scope: "slide".to_string(),
+            presentation_id: Some("p1".to_string()),
+            slide_id: Some(slide_id.to_string()),
+            gitsawe_id: None,
+            rule_json: entry.to_string(),
+            is_enabled: true,
+            created_at: "2026-01-01T00:00:00.000Z".to_string(),
+        }
+    }
+
+    fn fixture() -> Vec<FlowNode> {
+        let mut last = slide("last", 4, "Dismissal", None);
+        last.is_disabled = true;
+        vec![
+            FlowNode {
+                slide: slide("first", 1, "Opening \"Hymn\"", None),
+                expansion: None,
+            },
+            FlowNode {
+                slide: slide("psalm", 2, "Psalm", Some("seg-1")),
+                expansion: Some(vec![verse(1, "First verse"), verse(2, "Second verse")]),
+            },
+            FlowNode {
+                slide: slide("reading", 3, "Reading", Some("@meta.reading")),
+                expansion: Some(Vec::new()),
+            },
+            FlowNode {
+                slide: last,
+                expansion: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn slides_and_expansions_are_chained_in_order() {
+        let dot = render_dot("Kidase \"Sunday\"", &fixture(), &[]);
+
+        assert!(dot.starts_with("digraph \"Kidase \\\"Sunday\\\"\" {\n"));
+        assert!(dot.contains("  s0 [label=\"1. Opening \\\"Hymn\\\"\"];\n"));
+        let positions: Vec<usize> = [
+            "  s0 [",
+            "  subgraph cluster_s1 {",
+            "  subgraph cluster_s2 {",
+            "  s3 [",
+        ]
+        .iter()
+        .map(|needle| {
+            dot.find(needle)
+                .unwrap_or_else(|| panic!("{needle}\n{dot}"))
+        })
+        .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]), "{dot}");
+
+        // A dynamic slide's verses sit in its cluster, one after the other
+        assert!(dot.contains("    label=\"2. Psalm\";\n"));
+        assert!(dot.contains("    s1_v0 [label=\"1. First verse\"];\n"));
+        assert!(dot.contains("    s1_v1 [label=\"2. Second verse\"];\n"));
+        assert!(dot.contains("    s1_v0 -> s1_v1;\n"));
+        // A segment with no verses here still gets a placeholder
+        assert!(dot.contains("    s2_v0 [label=\"segment @meta.reading\", shape=note];\n"));
+        assert!(dot.contains(
+            "  s3 [label=\"4. Dismissal\", style=\"rounded,dashed\", fontcolor=gray];\n"
+        ));
+
+        for edge in [
+            "  s0 -> s1_v0;\n",
+            "  s1_v1 -> s2_v0;\n",
+            "  s2_v0 -> s3;\n",
+        ] {
+            assert!(dot.contains(edge), "{edge}\n{dot}");
+        }
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn rule_edges_end_at_the_neighbours_of_the_first_and_last_slide() {
+        let rules = [
+            rule("Skip \"intro\"", "first", false),
+            rule("Show close", "last", true),
+            rule("Skip close", "last", false),
+        ];
+        let dot = render_dot("Kidase", &fixture(), &rules);
+
+        let condition = r#"{\"meta.isFeast\":{\"$eq\":true}}"#;
+        // Hiding the first slide goes from it straight to the next
+        assert!(dot.contains(&format!(
+            "  s0 -> s1_v0 [style=dashed, color=firebrick, label=\"Skip \\\"intro\\\": hide when {condition}\"];\n"
+        )), "{dot}");
+        // Showing the last comes in from the slide before it
+        assert!(dot.contains(&format!(
+            "  s2_v0 -> s3 [style=dotted, color=darkgreen, label=\"Show close: show when {condition}\"];\n"
+        )), "{dot}");
+        // Hiding the last runs from the slide before it to its own exit
+        assert!(dot.contains(&format!(
+            "  s2_v0 -> s3 [style=dashed, color=firebrick, label=\"Skip close: hide when {condition}\"];\n"
+        )), "{dot}");
+    }
+}
@@ -0,0 +1,381 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tauri::path::BaseDirectory;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_sql::DbInstances;
+
+use crate::db;
+
+/// Families tried first for Ge'ez text, in order, before any face that has Ethiopic glyphs.
+const ETHIOPIC_FAMILIES: [&str; 5] = [
+    "Nyala",
+    "Abyssinica SIL",
+    "Noto Sans Ethiopic",
+    "Noto Serif Ethiopic",
+    "Ebrima",
+];
+const ETHIOPIC_PROBE: char = 'ሀ';
+
+/// Error prefix when an export is refused because fonts are missing.
+pub const MISSING_FONTS: &str = "MissingFonts";
+
+/// Ge'ez font shipped with the app, relative to the resource directory.
+const BUNDLED_FONT: &str = "fonts/AbyssinicaSIL-Regular.ttf";
+
+/// Where [`BUNDLED_FONT`] resolved to, set once by [`register_bundled_font`].
+static BUNDLED_FONT_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// CSS generic families always resolved by the webview and exporters.
+const GENERIC_FAMILIES: [&str; 12] = [
+    "serif",
+    "sans-serif",
+    "monospace",
+    "cursive",
+    "fantasy",
+    "system-ui",
+    "ui-serif",
+    "ui-sans-serif",
+    "ui-monospace",
+    "emoji",
+    "math",
+    "inherit",
+];
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontIssue {
+    pub family: String,
+    /// Template names (or `languageSettings`) that reference the family.
+    pub referenced_by: Vec<String>,
+    pub slide_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontInfo {
+    pub family: String,
+    /// Whether the family comes from the font shipped with the app.
+    pub bundled: bool,
+}
+
+#[derive(Default)]
+struct FontUsage {
+    family: String,
+    referenced_by: Vec<String>,
+    slide_ids: Vec<String>,
+}
+
+/// Resolve the bundled Ge'ez font through Tauri's resource resolver so
+/// [`font_database`] finds it wherever the app is installed. Called once at
+/// startup; a missing file only means exports fall back to installed fonts.
+pub fn register_bundled_font(app: &AppHandle) -> Result<(), String> {
+    let path = app
+        .path()
+        .resolve(BUNDLED_FONT, BaseDirectory::Resource)
+        .map_err(|e| e.to_string())?;
+    if !path.is_file() {
+        return Err(format!("Bundled font not found at {}", path.display()));
+    }
+    let _ = BUNDLED_FONT_PATH.set(path);
+    Ok(())
+}
+
+/// Report font families used by a presentation that are neither bundled nor installed.
+#[tauri::command]
+pub async fn verify_fonts(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<Vec<FontIssue>, String> {
+    let pool = db::pool(&db).await?;
+    missing_fonts(&pool, &presentation_id).await
+}
+
+/// Font families that can render Ge'ez text, bundled first, then installed
+/// ones by name. Backs the template editor's font picker.
+#[tauri::command]
+pub async fn list_available_fonts() -> Result<Vec<FontInfo>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let database = font_database();
+        let mut fonts: BTreeMap<String, FontInfo> = BTreeMap::new();
+        for face in database.faces() {
+            let covers_ethiopic = database
+                .with_face_data(face.id, |data, index| {
+                    ttf_parser::Face::parse(data, index)
+                        .is_ok_and(|f| f.glyph_index(ETHIOPIC_PROBE).is_some())
+                })
+                .unwrap_or(false);
+            if !covers_ethiopic {
+                continue;
+            }
+            let bundled = match &face.source {
+                fontdb::Source::File(path) | fontdb::Source::SharedFile(path, _) => {
+                    BUNDLED_FONT_PATH.get() == Some(path)
+                }
+                fontdb::Source::Binary(_) => false,
+            };
+            for (family, _) in &face.families {
+                let info = fonts
+                    .entry(family.to_lowercase())
+                    .or_insert_with(|| FontInfo {
+                        family: family.clone(),
+                        bundled: false,
+                    });
+                info.bundled |= bundled;
+            }
+        }
+        let mut fonts: Vec<FontInfo> = fonts.into_values().collect();
+        fonts.sort_by_key(|font| !font.bundled);
+        fonts
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Refuse an export while any font the presentation uses is neither bundled
+/// nor installed, so the file isn't written with substitutes. `allow_missing`
+/// is the operator choosing to export anyway.
+pub async fn check_export_fonts(
+    pool: &SqlitePool,
+    presentation_id: &str,
+    allow_missing: bool,
+) -> Result<(), String> {
+    if allow_missing {
+        return Ok(());
+    }
+    let missing = missing_fonts(pool, presentation_id).await?;
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let families: Vec<&str> = missing.iter().map(|issue| issue.family.as_str()).collect();
+    Err(format!(
+        "{MISSING_FONTS}: {} not installed; install them or export anyway with substitutes",
+        families.join(", ")
+    ))
+}
+
+/// Shared with the exporters so they can refuse or warn before writing a file.
+pub async fn missing_fonts(
+    pool: &SqlitePool,
+    presentation_id: &str,
+) -> Result<Vec<FontIssue>, String> {
+    let usage = collect_font_usage(pool, presentation_id).await?;
+    let available = tauri::async_runtime::spawn_blocking(available_families)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(usage
+        .into_values()
+        .filter(|u| !available.contains(&u.family.to_lowercase()))
+        .map(|u| FontIssue {
+            family: u.family,
+            referenced_by: u.referenced_by,
+            slide_ids: u.slide_ids,
+        })
+        .collect())
+}
+
+async fn collect_font_usage(
+    pool: &SqlitePool,
+    presentation_id: &str,
+) -> Result<BTreeMap<String, FontUsage>, String> {
+    let presentation = db::fetch_presentation(pool, presentation_id).await?;
+    let slides = db::fetch_slides(pool, presentation_id).await?;
+
+    let mut usage: BTreeMap<String, FontUsage> = BTreeMap::new();
+    let all_slide_ids: Vec<&str> = slides.iter().map(|s| s.id.as_str()).collect();
+
+    if let Some(settings) = presentation
+        .language_settings
+        .as_deref()
+        .and_then(|s| serde_json::from_str::<Value>(s).ok())
+    {
+        let stacks = settings
+            .as_object()
+            .into_iter()
+            .flat_map(|slots| slots.values())
+            .filter_map(|config| config.get("fontFamily").and_then(Value::as_str));
+        for stack in stacks {
+            record(&mut usage, stack, "languageSettings", &all_slide_ids);
+        }
+    }
+
+    let mut template_ids = vec![presentation.template_id.clone()];
+    for slide in &slides {
+        if let Some(id) = &slide.template_override_id {
+            if !template_ids.contains(id) {
+                template_ids.push(id.clone());
+            }
+        }
+    }
+
+    for template_id in template_ids {
+        let template = if template_id == presentation.template_id {
+            db::fetch_template(pool, &template_id).await?
+        } else {
+            // A dangling override falls back to the presentation template when rendering
+            match db::fetch_template(pool, &template_id).await {
+                Ok(template) => template,
+                Err(_) => continue,
+            }
+        };
+        let slide_ids: Vec<&str> = slides
+            .iter()
+            .filter(|s| {
+                s.template_override_id
+                    .as_deref()
+                    .unwrap_or(&presentation.template_id)
+                    == template_id
+            })
+            .map(|s| s.id.as_str())
+            .collect();
+        let definition: Value = serde_json::from_str(&template.definition_json)
+            .map_err(|e| format!("Invalid definition for template {}: {e}", template.name))?;
+        let stacks = definition
+            .get("languages")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|lang| lang.get("fontFamily").and_then(Value::as_str));
+        for stack in stacks {
+            record(&mut usage, stack, &template.name, &slide_ids);
+        }
+    }
+
+    Ok(usage)
+}
+
+fn record(usage: &mut BTreeMap<String, FontUsage>, stack: &str, source: &str, slide_ids: &[&str]) {
+    for family in parse_font_stack(stack) {
+        let entry = usage.entry(family.to_lowercase()).or_default();
+        if entry.family.is_empty() {
+            entry.family = family;
+        }
+        if !entry.referenced_by.iter().any(|s| s == source) {
+            entry.referenced_by.push(source.to_string());
+        }
+        for id in slide_ids {
+            if !entry.slide_ids.iter().any(|s| s == id) {
+                entry.slide_ids.push(id.to_string());
+            }
+        }
+    }
+}
+
+/// Split a CSS `font-family` value into named families, dropping generics.
+pub(crate) fn parse_font_stack(stack: &str) -> Vec<String> {
+    stack
+        .split(',')
+        .map(|f| f.trim().trim_matches(|c| c == '"' || c == '\'').trim())
+        .filter(|f| !f.is_empty())
+        .filter(|f| !GENERIC_FAMILIES.contains(&f.to_lowercase().as_str()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Lowercased family names from the system font list plus the bundled font.
+fn available_families() -> HashSet<String> {
+    font_database()
+        .faces()
+        .flat_map(|face| face.families.iter().map(|(name, _)| name.to_lowercase()))
+        .collect()
+}
+
+/// System fonts plus the registered bundled font. Scanning is slow, so call it
+/// from a blocking task.
+pub fn font_database() -> fontdb::Database {
+    let mut fonts = fontdb::Database::new();
+    fonts.load_system_fonts();
+    if let Some(path) = BUNDLED_FONT_PATH.get() {
+        // Checked when registered; a file that vanished since just isn't offered
+        let _ = fonts.load_font_file(path);
+    }
+    fonts
+}
+
+/// Font files for Ge'ez text and, when the Ge'ez font lacks them, Latin text.
+///
+/// Only TrueType outlines in the first face of a file are considered, since
+/// that is what the PDF writer can embed. Scans `database`, so call it from a
+/// blocking task.
+pub fn ethiopic_font_data(
+    database: fontdb::Database,
+) -> Result<(Vec<u8>, Option<Vec<u8>>), String> {
+    let embeddable = |id: fontdb::ID, probe: char| {
+        database
+            .with_face_data(id, |data, index| {
+                let face = ttf_parser::Face::parse(data, index).ok()?;
+                let usable =
+                    index == 0 && face.tables().glyf.is_some() && face.glyph_index(probe).is_some();
+                usable.then(|| data.to_vec())
+            })
+            .flatten()
+    };
+    let family_rank = |face: &fontdb::FaceInfo| {
+        face.families
+            .iter()
+            .filter_map(|(family, _)| {
+                ETHIOPIC_FAMILIES
+                    .iter()
+                    .position(|f| f.eq_ignore_ascii_case(family))
+            })
+            .min()
+            .unwrap_or(ETHIOPIC_FAMILIES.len())
+    };
+
+    let mut faces: Vec<&fontdb::FaceInfo> = database
+        .faces()
+        .filter(|face| face.weight == fontdb::Weight::NORMAL)
+        .filter(|face| face.style == fontdb::Style::Normal)
+        .collect();
+    faces.sort_by_key(|face| family_rank(face));
+    let ethiopic = faces
+        .iter()
+        .find_map(|face| embeddable(face.id, ETHIOPIC_PROBE))
+        .ok_or("No font with Ge'ez characters is installed; install Nyala or Abyssinica SIL")?;
+
+    let latin = if ttf_parser::Face::parse(&ethiopic, 0).is_ok_and(|f| f.glyph_index('A').is_some())
+    {
+        None
+    } else {
+        let query = fontdb::Query {
+            families: &[fontdb::Family::SansSerif],
+            ..Default::default()
+        };
+        database
+            .query(&query)
+            .into_iter()
+            .chain(faces.iter().map(|face| face.id))
+            .find_map(|id| embeddable(id, 'A'))
+    };
+    Ok((ethiopic, latin))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[tokio::test]
+    async fn exports_are_refused_until_missing_fonts_are_accepted() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        assert_eq!(check_export_fonts(&pool, "p1", false).await, Ok(()));
+
+        test_support::exec(
+            &pool,
+            r#"UPDATE templates SET definition_json =
+                 '{"languages":[{"fontFamily":"\"Kidase Missing Font\", serif"}]}'
+               WHERE id = 't1'"#,
+        )
+        .await;
+        let error = check_export_fonts(&pool, "p1", false).await.unwrap_err();
+        assert!(error.starts_with(MISSING_FONTS), "{error}");
+        assert!(error.contains("Kidase Missing Font"), "{error}");
+        assert_eq!(check_export_fonts(&pool, "p1", true).await, Ok(()));
+    }
+}
@@ -0,0 +1,707 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::audit;
+use crate::bahire_hasab;
+use crate::calendar::EthiopianDate;
+use crate::db::{self, Gitsawe, RuleDefinition};
+use crate::rules::{self, condition::Condition, Rule};
+
+/// Gitsawes with their parsed selection rules, ready to resolve any number of dates.
+pub struct GitsaweSelector {
+    candidates: Vec<(Gitsawe, Vec<Condition>)>,
+}
+
+impl GitsaweSelector {
+    pub async fn load(pool: &SqlitePool) -> Result<Self, String> {
+        let gitsawes =
+            sqlx::query_as::<_, Gitsawe>("SELECT * FROM gitsawes ORDER BY priority, line_id")
+                .fetch_all(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+        let definitions = sqlx::query_as::<_, RuleDefinition>(
+            "SELECT * FROM rule_definitions
+             WHERE scope = 'gitsawe' AND is_enabled = 1 AND gitsawe_id IS NOT NULL
+             ORDER BY created_at",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut conditions: HashMap<String, Vec<Condition>> = HashMap::new();
+        for definition in &definitions {
+            // Malformed rules are skipped, as the frontend engine does
+            let Ok(Rule::Gitsawe {
+                gitsawe_id: Some(gitsawe_id),
+                entry,
+                ..
+            }) = Rule::from_definition(definition)
+            else {
+                continue;
+            };
+            if let Ok(condition) = Condition::parse(&entry.when) {
+                conditions.entry(gitsawe_id).or_default().push(condition);
+            }
+        }
+
+        let candidates = gitsawes
+            .into_iter()
+            .filter_map(|g| conditions.remove(&g.id).map(|c| (g, c)))
+            .collect();
+        Ok(Self { candidates })
+    }
+
+    /// Highest-priority gitsawe (lowest `priority` number) whose selection rule
+    /// matches `date`.
+    pub fn select(&self, date: NaiveDate) -> Result<Option<&Gitsawe>, String> {
+        let context = rules::meta_context(rules::date_meta(date)?);
+        Ok(self
+            .candidates
+            .iter()
+            .find(|(_, conditions)| conditions.iter().any(|c| c.matches(&context)))
+            .map(|(gitsawe, _)| gitsawe))
+    }
+}
+
+/// Resolve the gitsawe whose selection rules match an Ethiopian calendar date.
+///
+/// Rules see the same `meta` date fields as in the editor. Of the holidays,
+/// only the feasts `buildContext` adds itself (`tsige`, `quskuam`, `filseta`,
+/// `kiremt`) and the movable feasts of [`crate::bahire_hasab`], under its
+/// names, are available here.
+#[tauri::command]
+pub async fn resolve_gitsawe_for_date(
+    db: State<'_, DbInstances>,
+    ethiopian_year: i32,
+    ethiopian_month: u32,
+    ethiopian_day: u32,
+) -> Result<Option<Gitsawe>, String> {
+    let date = EthiopianDate::new(ethiopian_year, ethiopian_month, ethiopian_day)?;
+    let pool = db::pool(&db).await?;
+    resolve(&pool, date).await
+}
+
+async fn resolve(pool: &SqlitePool, date: EthiopianDate) -> Result<Option<Gitsawe>, String> {
+    let date = date.to_gregorian()?;
+    let selector = GitsaweSelector::load(pool).await?;
+    Ok(selector.select(date)?.cloned())
+}
+
+/// Gitsawes matching every filter given, highest priority (lowest number)
+/// first. With no filters, every gitsawe.
+#[tauri::command]
+pub async fn find_gitsawes(
+    db: State<'_, DbInstances>,
+    kidase_type: Option<String>,
+    gitsawe_type: Option<String>,
+    evangelist: Option<String>,
+) -> Result<Vec<Gitsawe>, String> {
+    let pool = db::pool(&db).await?;
+    find(
+        &pool,
+        kidase_type.as_deref(),
+        gitsawe_type.as_deref(),
+        evangelist.as_deref(),
+    )
+    .await
+}
+
+async fn find(
+    pool: &SqlitePool,
+    kidase_type: Option<&str>,
+    gitsawe_type: Option<&str>,
+    evangelist: Option<&str>,
+) -> Result<Vec<Gitsawe>, String> {
+    sqlx::query_as::<_, Gitsawe>(
+        "SELECT * FROM gitsawes
+         WHERE (?1 IS NULL OR kidase_type = ?1)
+           AND (?2 IS NULL OR gitsawe_type = ?2)
+           AND (?3 IS NULL OR evangelist = ?3)
+         ORDER BY priority, line_id",
+    )
+    .bind(kidase_type)
+    .bind(gitsawe_type)
+    .bind(evangelist)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Rewrite every gitsawe's `priority` to a dense, unique 0..n sequence that
+/// keeps the current order, ties broken by `created_at` then `line_id`, so
+/// resolution never depends on how the database happens to order equal
+/// priorities. Returns how many rows changed; an already normalized table is
+/// left alone.
+#[tauri::command]
+pub async fn normalize_gitsawe_priorities(db: State<'_, DbInstances>) -> Result<u32, String> {
+    let pool = db::writable_pool(&db).await?;
+    normalize_priorities(&pool).await
+}
+
+async fn normalize_priorities(pool: &SqlitePool) -> Result<u32, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let changed = sqlx::query(
+        "UPDATE gitsawes SET priority = ranked.position
+         FROM (SELECT id, row_number() OVER (ORDER BY priority, created_at, line_id, id) - 1
+                   AS position
+               FROM gitsawes) AS ranked
+         WHERE gitsawes.id = ranked.id AND gitsawes.priority <> ranked.position",
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+    if changed > 0 {
+        let detail = serde_json::json!({ "rowsRenumbered": changed });
+        audit::record(&mut *tx, "normalize_priorities", "gitsawe", "*", detail).await;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(changed as u32)
+}
+
+/// Longest range `build_schedule` resolves in one call, in days.
+const MAX_SCHEDULE_DAYS: i64 = 400;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledReading {
+    pub date: EthiopianDate,
+    /// The same day as `YYYY-MM-DD`.
+    pub gregorian_date: String,
+    /// `None` when no gitsawe's selection rule matches the day.
+    pub line_id: Option<String>,
+    pub gitsawe_type: Option<String>,
+}
+
+/// The gitsawe selected for every day from `start` to `end` inclusive, resolved
+/// as [`resolve_gitsawe_for_date`] does. Days nothing matches are kept, with no
+/// selection, so gaps in the rules show up.
+#[tauri::command]
+pub async fn build_schedule(
+    db: State<'_, DbInstances>,
+    start: EthiopianDate,
+    end: EthiopianDate,
+) -> Result<Vec<ScheduledReading>, String> {
+    let pool = db::pool(&db).await?;
+    schedule(&pool, start, end).await
+}
+
+async fn schedule(
+    pool: &SqlitePool,
+    start: EthiopianDate,
+    end: EthiopianDate,
+) -> Result<Vec<ScheduledReading>, String> {
+    let first = EthiopianDate::new(start.year, start.month, start.day)?.to_gregorian()?;
+    let last = EthiopianDate::new(end.year, end.month, end.day)?.to_gregorian()?;
+    if last < first {
+        return Err(format!("Schedule ends ({end}) before it starts ({start})"));
+    }
+    if (last - first).num_days() >= MAX_SCHEDULE_DAYS {
+        return Err(format!(
+            "Schedules cover at most {MAX_SCHEDULE_DAYS} days at a time"
+        ));
+    }
+
+    let selector = GitsaweSelector::load(pool).await?;
+    let mut schedule = Vec::new();
+    for date in first.iter_days().take_while(|date| *date <= last) {
+        let selected = selector.select(date)?;
+        schedule.push(ScheduledReading {
+            date: EthiopianDate::from_gregorian(date),
+            gregorian_date: date.format("%Y-%m-%d").to_string(),
+            line_id: selected.map(|g| g.line_id.clone()),
+            gitsawe_type: selected.and_then(|g| g.gitsawe_type.clone()),
+        });
+    }
+    Ok(schedule)
+}
+
+/// Reading fields in the order they are read during the Kidase.
+fn readings(gitsawe: &Gitsawe) -> [(&'static str, Option<&str>); 5] {
+    [
+        ("St. Paul", gitsawe.message_st_paul.as_deref()),
+        ("Apostle", gitsawe.message_apostle.as_deref()),
+        ("Book of Acts", gitsawe.message_book_of_acts.as_deref()),
+        ("Misbak", gitsawe.misbak.as_deref()),
+        ("Wengel", gitsawe.wengel.as_deref()),
+    ]
+}
+
+/// Create a presentation with one dynamic slide per populated reading of a
+/// gitsawe, keyed to its `line_id`. Returns the new presentation's id.
+///
+/// The language map is borrowed from the primary presentation so the readings
+/// show in the same languages as the rest of the service.
+#[tauri::command]
+pub async fn generate_presentation_from_gitsawe(
+    db: State<'_, DbInstances>,
+    gitsawe_id: String,
+    template_id: String,
+    name: String,
+) -> Result<String, String> {
+    let pool = db::writable_pool(&db).await?;
+    generate_from(&pool, &gitsawe_id, &template_id, &name).await
+}
+
+async fn generate_from(
+    pool: &SqlitePool,
+    gitsawe_id: &str,
+    template_id: &str,
+    name: &str,
+) -> Result<String, String> {
+    let gitsawe = sqlx::query_as::<_, Gitsawe>("SELECT * FROM gitsawes WHERE id = ?")
+        .bind(gitsawe_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Gitsawe not found: {gitsawe_id}"))?;
+    db::fetch_template(pool, template_id).await?;
+    let primary: Option<(String, String)> = sqlx::query_as(
+        "SELECT id, language_map FROM presentations
+         WHERE is_primary = 1 AND deleted_at IS NULL LIMIT 1",
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let (parent_id, language_map) = match primary {
+        Some((id, language_map)) => (Some(id), language_map),
+        None => (None, r#"{"Lang1":"Amharic"}"#.to_string()),
+    };
+
+    let presentation_id = db::new_id();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query(
+        "INSERT INTO presentations
+         (id, name, type, template_id, language_map, is_primary, is_active, created_at, parent_id)
+         VALUES (?, ?, 'Kidase', ?, ?, 0, 0, ?, ?)",
+    )
+    .bind(&presentation_id)
+    .bind(name)
+    .bind(template_id)
+    .bind(&language_map)
+    .bind(db::now())
+    .bind(&parent_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let populated = readings(&gitsawe)
+        .into_iter()
+        .filter_map(|(label, text)| Some((label, text?.trim())))
+        .filter(|(_, text)| !text.is_empty());
+    for (index, (label, text)) in populated.enumerate() {
+        sqlx::query(
+            "INSERT INTO slides
+             (id, presentation_id, slide_order, line_id, title_json, blocks_json,
+              is_disabled, is_dynamic)
+             VALUES (?, ?, ?, ?, ?, ?, 0, 1)",
+        )
+        .bind(db::new_id())
+        .bind(&presentation_id)
+        .bind(index as i64 + 1)
+        .bind(&gitsawe.line_id)
+        .bind(serde_json::json!({ "Lang1": label }).to_string())
+        .bind(serde_json::json!([{ "Lang1": text }]).to_string())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    let detail = serde_json::json!({
+        "name": name,
+        "templateId": template_id,
+        "gitsaweId": gitsawe_id,
+    });
+    audit::record(&mut *tx, "create", "presentation", &presentation_id, detail).await;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(presentation_id)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitsaweForDate {
+    pub date: EthiopianDate,
+    /// The same day as `YYYY-MM-DD`.
+    pub gregorian_date: String,
+    /// Movable feasts falling on the day, by [`crate::bahire_hasab`] name.
+    pub feasts: Vec<&'static str>,
+    /// `None` when no gitsawe's selection rule matches the day.
+    pub gitsawe: Option<Gitsawe>,
+    /// The selected gitsawe's populated readings in the order they are read.
+    pub readings: Vec<GitsaweReading>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitsaweReading {
+    pub label: &'static str,
+    pub text: String,
+}
+
+/// The readings for a Gregorian `YYYY-MM-DD`, today when omitted, so the
+/// operator needn't pick the gitsawe by hand.
+///
+/// Selection is [`resolve_gitsawe_for_date`]'s: of the gitsawes whose rules
+/// match the day, the highest priority wins. A feast or Sunday gitsawe
+/// overrides the ordinary one by matching `meta.holidays` or `meta.dayOfWeek`
+/// with a lower `priority` number.
+#[tauri::command]
+pub async fn get_gitsawe_for_date(
+    db: State<'_, DbInstances>,
+    date: Option<String>,
+) -> Result<GitsaweForDate, String> {
+    let date = match date {
+        Some(date) => crate::calendar::parse_gregorian(&date)?,
+        None => chrono::Local::now().date_naive(),
+    };
+    let ethiopian = EthiopianDate::from_gregorian(date);
+    let gregorian_date = date.format("%Y-%m-%d").to_string();
+    let feasts = bahire_hasab::movable_feasts(ethiopian.year)?
+        .feasts
+        .into_iter()
+        .filter(|feast| feast.gregorian == gregorian_date)
+        .map(|feast| feast.name)
+        .collect();
+
+    let pool = db::pool(&db).await?;
+    let selector = GitsaweSelector::load(&pool).await?;
+    let gitsawe = selector.select(date)?.cloned();
+    let readings = gitsawe
+        .as_ref()
+        .map(|gitsawe| {
+            readings(gitsawe)
+                .into_iter()
+                .filter_map(|(label, text)| Some((label, text?.trim())))
+                .filter(|(_, text)| !text.is_empty())
+                .map(|(label, text)| GitsaweReading {
+                    label,
+                    text: text.to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(GitsaweForDate {
+        date: ethiopian,
+        gregorian_date,
+        feasts,
+        gitsawe,
+        readings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    async fn gitsawe(pool: &SqlitePool, st_paul: &str, acts: &str) {
+        sqlx::query(
+            "INSERT INTO gitsawes (id, line_id, message_st_paul, message_apostle,
+                 message_book_of_acts, misbak, wengel, priority, created_at)
+             VALUES ('g1', 'line-7', ?, 'Apostle text', ?, 'Misbak text', 'Wengel text', 1,
+                 '2026-01-01T00:00:00.000Z')",
+        )
+        .bind(st_paul)
+        .bind(acts)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn generated(pool: &SqlitePool, presentation_id: &str) -> Vec<(String, String, bool)> {
+        sqlx::query_as(
+            "SELECT title_json, line_id, is_dynamic FROM slides
+             WHERE presentation_id = ? ORDER BY slide_order",
+        )
+        .bind(presentation_id)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn every_reading_becomes_a_dynamic_slide_in_liturgical_order() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "primary").await;
+        gitsawe(&pool, "St. Paul text", "Acts text").await;
+
+        let id = generate_from(&pool, "g1", "t1", "Sunday").await.unwrap();
+        let slides = generated(&pool, &id).await;
+        let titles: Vec<serde_json::Value> = slides
+            .iter()
+            .map(|(title, _, _)| serde_json::from_str(title).unwrap())
+            .collect();
+        let labels = ["St. Paul", "Apostle", "Book of Acts", "Misbak", "Wengel"];
+        assert_eq!(titles.len(), labels.len());
+        for (title, label) in titles.iter().zip(labels) {
+            assert_eq!(title["Lang1"], label);
+        }
+        assert!(slides
+            .iter()
+            .all(|(_, line_id, is_dynamic)| line_id == "line-7" && *is_dynamic));
+
+        let parent: Option<String> =
+            sqlx::query_scalar("SELECT parent_id FROM presentations WHERE id = ?")
+                .bind(&id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(parent.as_deref(), Some("primary"));
+    }
+
+    #[tokio::test]
+    async fn empty_readings_are_skipped() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "primary").await;
+        gitsawe(&pool, "", "  ").await;
+
+        let id = generate_from(&pool, "g1", "t1", "Sunday").await.unwrap();
+        assert_eq!(generated(&pool, &id).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn an_unknown_gitsawe_or_template_is_refused() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "primary").await;
+        gitsawe(&pool, "St. Paul text", "Acts text").await;
+        assert!(generate_from(&pool, "missing", "t1", "Sunday")
+            .await
+            .is_err());
+        assert!(generate_from(&pool, "g1", "missing", "Sunday")
+            .await
+            .is_err());
+    }
+
+    async fn typed(pool: &SqlitePool, line_id: &str, types: (&str, &str, &str), priority: i64) {
+        let (kidase_type, gitsawe_type, evangelist) = types;
+        sqlx::query(
+            "INSERT INTO gitsawes
+                 (id, line_id, kidase_type, gitsawe_type, evangelist, priority, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, '2026-01-01T00:00:00.000Z')",
+        )
+        .bind(line_id)
+        .bind(line_id)
+        .bind(kidase_type)
+        .bind(gitsawe_type)
+        .bind(evangelist)
+        .bind(priority)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn find_gitsawes_matches_every_filter_given() {
+        let pool = test_support::pool().await;
+        typed(&pool, "a", ("Sunday", "Feast", "Matthew"), 3).await;
+        typed(&pool, "b", ("Sunday", "Fast", "Mark"), 1).await;
+        typed(&pool, "c", ("Weekday", "Feast", "Mark"), 2).await;
+        typed(&pool, "d", ("Weekday", "Fast", "Matthew"), 0).await;
+
+        type Filters = (
+            Option<&'static str>,
+            Option<&'static str>,
+            Option<&'static str>,
+        );
+        let cases: [(Filters, &[&str]); 8] = [
+            ((None, None, None), &["d", "b", "c", "a"]),
+            ((Some("Sunday"), None, None), &["b", "a"]),
+            ((None, Some("Feast"), None), &["c", "a"]),
+            ((None, None, Some("Mark")), &["b", "c"]),
+            ((Some("Sunday"), Some("Feast"), None), &["a"]),
+            ((Some("Weekday"), None, Some("Matthew")), &["d"]),
+            ((None, Some("Fast"), Some("Mark")), &["b"]),
+            ((Some("Sunday"), Some("Fast"), Some("Matthew")), &[]),
+        ];
+        for ((kidase_type, gitsawe_type, evangelist), expected) in cases {
+            let found: Vec<String> = find(&pool, kidase_type, gitsawe_type, evangelist)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|g| g.line_id)
+                .collect();
+            assert_eq!(
+                found, expected,
+                "{kidase_type:?} {gitsawe_type:?} {evangelist:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn find_gitsawes_treats_filters_as_values() {
+        let pool = test_support::pool().await;
+        typed(&pool, "a", ("Sunday", "Feast", "Matthew"), 0).await;
+
+        let found = find(&pool, Some("x' OR '1'='1"), None, None).await.unwrap();
+        assert!(found.is_empty());
+        assert_eq!(find(&pool, None, None, None).await.unwrap().len(), 1);
+    }
+
+    /// A gitsawe of `gitsawe_type` selected when `when` matches.
+    async fn selected_when(
+        pool: &SqlitePool,
+        line_id: &str,
+        gitsawe_type: &str,
+        when: serde_json::Value,
+    ) {
+        sqlx::query(
+            "INSERT INTO gitsawes (id, line_id, gitsawe_type, priority, created_at)
+             VALUES (?, ?, ?, 0, '2026-01-01T00:00:00.000Z')",
+        )
+        .bind(line_id)
+        .bind(line_id)
+        .bind(gitsawe_type)
+        .execute(pool)
+        .await
+        .unwrap();
+        let entry = serde_json::json!({ "id": line_id, "when": when, "then": {} });
+        sqlx::query(
+            "INSERT INTO rule_definitions (id, name, scope, gitsawe_id, rule_json, created_at)
+             VALUES (?, ?, 'gitsawe', ?, ?, '2026-01-01T00:00:00.000Z')",
+        )
+        .bind(line_id)
+        .bind(line_id)
+        .bind(line_id)
+        .bind(entry.to_string())
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> EthiopianDate {
+        EthiopianDate { year, month, day }
+    }
+
+    #[tokio::test]
+    async fn a_schedule_runs_through_pagume_into_the_new_year_keeping_gaps() {
+        let pool = test_support::pool().await;
+        selected_when(
+            &pool,
+            "pagume",
+            "Pagume",
+            serde_json::json!({ "meta.ethMonth": { "$eq": 13 } }),
+        )
+        .await;
+        selected_when(
+            &pool,
+            "new-year",
+            "Feast",
+            serde_json::json!({ "meta.ethMonthDay": { "$eq": "01-01" } }),
+        )
+        .await;
+
+        // 2017 isn't a leap year, so Pagume has five days
+        let schedule = schedule(&pool, date(2017, 12, 29), date(2018, 1, 2))
+            .await
+            .unwrap();
+        let days: Vec<(u32, u32, Option<&str>)> = schedule
+            .iter()
+            .map(|r| (r.date.month, r.date.day, r.line_id.as_deref()))
+            .collect();
+        assert_eq!(
+            days,
+            [
+                (12, 29, None),
+                (12, 30, None),
+                (13, 1, Some("pagume")),
+                (13, 2, Some("pagume")),
+                (13, 3, Some("pagume")),
+                (13, 4, Some("pagume")),
+                (13, 5, Some("pagume")),
+                (1, 1, Some("new-year")),
+                (1, 2, None),
+            ]
+        );
+        assert_eq!(schedule[7].date.year, 2018);
+        assert_eq!(schedule[7].gitsawe_type.as_deref(), Some("Feast"));
+        assert_eq!(schedule[7].gregorian_date, "2025-09-11");
+    }
+
+    #[tokio::test]
+    async fn a_date_resolves_to_the_highest_priority_match() {
+        let pool = test_support::pool().await;
+        let pagume = serde_json::json!({ "meta.ethMonth": { "$eq": 13 } });
+        selected_when(&pool, "any-pagume", "Pagume", pagume.clone()).await;
+        selected_when(&pool, "pagume-again", "Pagume", pagume).await;
+        selected_when(
+            &pool,
+            "sixth-day",
+            "Feast",
+            serde_json::json!({ "meta.ethMonthDay": { "$eq": "13-06" } }),
+        )
+        .await;
+        test_support::exec(
+            &pool,
+            "UPDATE gitsawes SET priority = CASE id
+                 WHEN 'any-pagume' THEN 2 WHEN 'pagume-again' THEN 3 ELSE 1 END",
+        )
+        .await;
+
+        async fn resolved(pool: &SqlitePool, year: i32, month: u32, day: u32) -> Option<String> {
+            let gitsawe = resolve(pool, date(year, month, day)).await.unwrap();
+            gitsawe.map(|g| g.line_id)
+        }
+        assert_eq!(
+            resolved(&pool, 2015, 13, 6).await.as_deref(),
+            Some("sixth-day")
+        );
+        assert_eq!(
+            resolved(&pool, 2015, 13, 5).await.as_deref(),
+            Some("any-pagume")
+        );
+        assert_eq!(resolved(&pool, 2016, 1, 1).await, None);
+    }
+
+    #[tokio::test]
+    async fn a_schedule_must_run_forwards() {
+        let pool = test_support::pool().await;
+        assert!(schedule(&pool, date(2018, 1, 2), date(2018, 1, 1))
+            .await
+            .is_err());
+        assert!(schedule(&pool, date(2017, 13, 6), date(2018, 1, 1))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn collided_priorities_become_dense_keeping_their_order() {
+        let pool = test_support::pool().await;
+        test_support::exec(
+            &pool,
+            "INSERT INTO gitsawes (id, line_id, priority, created_at) VALUES
+                 ('late', 'a', 5, '2026-03-01T00:00:00.000Z'),
+                 ('early', 'b', 5, '2026-01-01T00:00:00.000Z'),
+                 ('first', 'c', 2, '2026-05-01T00:00:00.000Z'),
+                 ('tied', 'd', 5, '2026-03-01T00:00:00.000Z'),
+                 ('last', 'e', 40, '2026-01-01T00:00:00.000Z')",
+        )
+        .await;
+
+        assert_eq!(normalize_priorities(&pool).await.unwrap(), 5);
+        let order: Vec<(String, i64)> =
+            sqlx::query_as("SELECT id, priority FROM gitsawes ORDER BY priority")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            order,
+            [
+                ("first".to_string(), 0),
+                ("early".to_string(), 1),
+                ("late".to_string(), 2),
+                ("tied".to_string(), 3),
+                ("last".to_string(), 4),
+            ]
+        );
+
+        assert_eq!(normalize_priorities(&pool).await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,314 @@
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::db;
+use crate::present_mode;
+
+const ORPHANED_SLIDES: &str =
+    "SELECT id FROM slides WHERE presentation_id NOT IN (SELECT id FROM presentations)";
+
+const ORPHANED_VARIABLES: &str =
+    "SELECT id FROM variables WHERE presentation_id NOT IN (SELECT id FROM presentations)";
+
+const ORPHANED_RULES: &str = "SELECT id FROM rule_definitions
+     WHERE (presentation_id IS NOT NULL AND presentation_id NOT IN (SELECT id FROM presentations))
+        OR (slide_id IS NOT NULL AND slide_id NOT IN (SELECT id FROM slides))
+        OR (gitsawe_id IS NOT NULL AND gitsawe_id NOT IN (SELECT id FROM gitsawes))";
+
+/// Segments are referenced by a slide's `line_id` or, through `@meta.gitsawe.*`,
+/// by a gitsawe's reading columns.
+const UNREFERENCED_VERSES: &str = "SELECT id FROM verses
+     WHERE segment_id NOT IN (SELECT line_id FROM slides WHERE line_id IS NOT NULL)
+       AND NOT EXISTS (
+           SELECT 1 FROM gitsawes g
+           WHERE verses.segment_id IN (g.line_id, g.message_st_paul, g.message_apostle,
+                                       g.message_book_of_acts, g.misbak, g.wengel,
+                                       g.message_apostle_evangelist))";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    /// Slides whose presentation no longer exists.
+    pub orphaned_slides: Vec<String>,
+    /// Variables whose presentation no longer exists.
+    pub orphaned_variables: Vec<String>,
+    /// Rules pointing at a missing presentation, slide or gitsawe.
+    pub orphaned_rules: Vec<String>,
+    /// Verses whose segment no slide or gitsawe refers to.
+    pub unreferenced_verses: Vec<String>,
+    pub duplicate_slide_orders: Vec<DuplicateSlideOrder>,
+    pub duplicate_gitsawe_lines: Vec<DuplicateGitsaweLine>,
+    /// What `fix` repaired; `None` when run as a check only.
+    pub repaired: Option<RepairCounts>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateSlideOrder {
+    pub presentation_id: String,
+    pub slide_order: i64,
+    pub slide_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGitsaweLine {
+    pub line_id: String,
+    pub gitsawe_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LineIdIssueKind {
+    /// A slide's `line_id` matches no gitsawe; usually a typo.
+    UnknownLine,
+    /// A gitsawe no slide refers to. Informational: not every reading is
+    /// used by every service.
+    UnreferencedGitsawe,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineIdIssue {
+    pub kind: LineIdIssueKind,
+    pub line_id: String,
+    /// Set for `unknownLine`.
+    pub slide_id: Option<String>,
+    pub slide_order: Option<i64>,
+    /// Set for `unreferencedGitsawe`.
+    pub gitsawe_id: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairCounts {
+    pub slides_deleted: u64,
+    pub variables_deleted: u64,
+    pub rules_deleted: u64,
+    pub slides_reordered: u64,
+}
+
+/// Report rows whose references don't resolve, plus duplicated orderings and line ids.
+///
+/// With `fix`, orphaned slides, variables and rules are deleted and every
+/// presentation's `slide_order` is compacted to 1..n in one transaction. The
+/// report still describes the state before the repair. Unreferenced verses and
+/// duplicate gitsawe lines are only reported: segments can also be named at
+/// present time, and which gitsawe to keep is the operator's call.
+#[tauri::command]
+pub async fn check_integrity(
+    db: State<'_, DbInstances>,
+    fix: Option<bool>,
+) -> Result<IntegrityReport, String> {
+    let pool = db::pool(&db).await?;
+    let mut report = IntegrityReport {
+        orphaned_slides: ids(&pool, ORPHANED_SLIDES).await?,
+        orphaned_variables: ids(&pool, ORPHANED_VARIABLES).await?,
+        orphaned_rules: ids(&pool, ORPHANED_RULES).await?,
+        unreferenced_verses: ids(&pool, UNREFERENCED_VERSES).await?,
+        duplicate_slide_orders: duplicate_slide_orders(&pool).await?,
+        duplicate_gitsawe_lines: duplicate_gitsawe_lines(&pool).await?,
+        repaired: None,
+    };
+
+    if fix.unwrap_or(false) {
+        present_mode::ensure_writable(&pool).await?;
+        report.repaired = Some(repair(&pool).await?);
+    }
+    Ok(report)
+}
+
+/// Check a presentation's slide `line_id`s against `gitsawes.line_id`.
+///
+/// Slides whose non-null `line_id` has no gitsawe come first, in slide order,
+/// followed by gitsawes that no slide in any presentation references.
+/// Read-only; nothing is repaired.
+#[tauri::command]
+pub async fn validate_line_ids(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<Vec<LineIdIssue>, String> {
+    let pool = db::pool(&db).await?;
+    line_id_issues(&pool, &presentation_id).await
+}
+
+async fn line_id_issues(
+    pool: &SqlitePool,
+    presentation_id: &str,
+) -> Result<Vec<LineIdIssue>, String> {
+    db::fetch_presentation(pool, presentation_id).await?;
+
+    let unknown: Vec<(String, i64, String)> = sqlx::query_as(
+        "SELECT id, slide_order, line_id FROM slides
+         WHERE presentation_id = ? AND line_id IS NOT NULL
+           AND line_id NOT IN (SELECT line_id FROM gitsawes)
+         ORDER BY slide_order",
+    )
+    .bind(presentation_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let unreferenced: Vec<(String, String)> = sqlx::query_as(
+        "SELECT id, line_id FROM gitsawes
+         WHERE line_id NOT IN (SELECT line_id FROM slides WHERE line_id IS NOT NULL)
+         ORDER BY priority, line_id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let unknown = unknown
+        .into_iter()
+        .map(|(slide_id, slide_order, line_id)| LineIdIssue {
+            kind: LineIdIssueKind::UnknownLine,
+            line_id,
+            slide_id: Some(slide_id),
+            slide_order: Some(slide_order),
+            gitsawe_id: None,
+        });
+    let unreferenced = unreferenced
+        .into_iter()
+        .map(|(gitsawe_id, line_id)| LineIdIssue {
+            kind: LineIdIssueKind::UnreferencedGitsawe,
+            line_id,
+            slide_id: None,
+            slide_order: None,
+            gitsawe_id: Some(gitsawe_id),
+        });
+    Ok(unknown.chain(unreferenced).collect())
+}
+
+async fn ids(pool: &SqlitePool, query: &str) -> Result<Vec<String>, String> {
+    sqlx::query_scalar(query)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn duplicate_slide_orders(pool: &SqlitePool) -> Result<Vec<DuplicateSlideOrder>, String> {
+    let rows: Vec<(String, i64, String)> = sqlx::query_as(
+        "SELECT presentation_id, slide_order, group_concat(id)
+         FROM slides
+         GROUP BY presentation_id, slide_order
+         HAVING count(*) > 1
+         ORDER BY presentation_id, slide_order",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(presentation_id, slide_order, ids)| DuplicateSlideOrder {
+            presentation_id,
+            slide_order,
+            slide_ids: ids.split(',').map(str::to_string).collect(),
+        })
+        .collect())
+}
+
+async fn duplicate_gitsawe_lines(pool: &SqlitePool) -> Result<Vec<DuplicateGitsaweLine>, String> {
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        "SELECT line_id, group_concat(id)
+         FROM gitsawes
+         GROUP BY line_id
+         HAVING count(*) > 1
+         ORDER BY line_id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(line_id, ids)| DuplicateGitsaweLine {
+            line_id,
+            gitsawe_ids: ids.split(',').map(str::to_string).collect(),
+        })
+        .collect())
+}
+
+async fn repair(pool: &SqlitePool) -> Result<RepairCounts, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut counts = RepairCounts::default();
+
+    // Slides first so rules left pointing at them are caught below
+    for (table, query, count) in [
+        ("slides", ORPHANED_SLIDES, &mut counts.slides_deleted),
+        (
+            "variables",
+            ORPHANED_VARIABLES,
+            &mut counts.variables_deleted,
+        ),
+        (
+            "rule_definitions",
+            ORPHANED_RULES,
+            &mut counts.rules_deleted,
+        ),
+    ] {
+        *count = sqlx::query(&format!("DELETE FROM {table} WHERE id IN ({query})"))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .rows_affected();
+    }
+
+    counts.slides_reordered = sqlx::query(
+        "UPDATE slides SET slide_order = ranked.position
+         FROM (SELECT id, row_number() OVER (
+                   PARTITION BY presentation_id ORDER BY slide_order, rowid
+               ) AS position
+               FROM slides) AS ranked
+         WHERE slides.id = ranked.id AND slides.slide_order <> ranked.position",
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[tokio::test]
+    async fn a_dangling_line_id_is_reported_and_a_valid_one_is_not() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        test_support::slide(&pool, "valid", "p1", 1).await;
+        test_support::slide(&pool, "dangling", "p1", 2).await;
+        test_support::exec(
+            &pool,
+            "UPDATE slides SET line_id = 'line-1' WHERE id = 'valid';
+             UPDATE slides SET line_id = 'line-l' WHERE id = 'dangling';
+             INSERT INTO gitsawes (id, line_id, priority, created_at) VALUES
+                 ('g1', 'line-1', 0, '2026-01-01T00:00:00.000Z'),
+                 ('g2', 'line-2', 1, '2026-01-01T00:00:00.000Z')",
+        )
+        .await;
+
+        let issues = line_id_issues(&pool, "p1").await.unwrap();
+        assert_eq!(issues.len(), 2, "{issues:?}");
+        assert!(matches!(issues[0].kind, LineIdIssueKind::UnknownLine));
+        assert_eq!(issues[0].slide_id.as_deref(), Some("dangling"));
+        assert_eq!(issues[0].slide_order, Some(2));
+        assert_eq!(issues[0].line_id, "line-l");
+        assert!(matches!(
+            issues[1].kind,
+            LineIdIssueKind::UnreferencedGitsawe
+        ));
+        assert_eq!(issues[1].gitsawe_id.as_deref(), Some("g2"));
+    }
+
+    #[tokio::test]
+    async fn an_unknown_presentation_is_refused() {
+        let pool = test_support::pool().await;
+        assert!(line_id_issues(&pool, "missing").await.is_err());
+    }
+}
@@ -0,0 +1,466 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::audit;
+use crate::db::{self, Slide, LANG_SLOTS};
+use crate::edits::{self, EditOp};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndeclaredLanguage {
+    pub slot: String,
+    pub slide_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageMapDrift {
+    /// Slots named in `language_map` that no slide has content for.
+    pub declared_unused: Vec<String>,
+    /// Slots with slide content but no entry in `language_map`.
+    pub undeclared_used: Vec<UndeclaredLanguage>,
+}
+
+/// Compare the declared `language_map` with the language slots slides actually fill.
+#[tauri::command]
+pub async fn language_map_drift(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<LanguageMapDrift, String> {
+    let pool = db::pool(&db).await?;
+    let presentation = db::fetch_presentation(&pool, &presentation_id).await?;
+    let slides = db::fetch_slides(&pool, &presentation_id).await?;
+
+    let language_map: Value = serde_json::from_str(&presentation.language_map)
+        .map_err(|e| format!("Invalid language map: {e}"))?;
+    let declared: Vec<&str> = LANG_SLOTS
+        .into_iter()
+        .filter(|slot| {
+            language_map
+                .get(slot)
+                .and_then(Value::as_str)
+                .is_some_and(|name| !name.trim().is_empty())
+        })
+        .collect();
+
+    let mut declared_unused = Vec::new();
+    let mut undeclared_used = Vec::new();
+    for slot in LANG_SLOTS {
+        let slide_ids: Vec<String> = slides
+            .iter()
+            .filter(|slide| used_slots(slide).contains(&slot))
+            .map(|slide| slide.id.clone())
+            .collect();
+        let is_declared = declared.contains(&slot);
+        if is_declared && slide_ids.is_empty() {
+            declared_unused.push(slot.to_string());
+        } else if !is_declared && !slide_ids.is_empty() {
+            undeclared_used.push(UndeclaredLanguage {
+                slot: slot.to_string(),
+                slide_ids,
+            });
+        }
+    }
+
+    Ok(LanguageMapDrift {
+        declared_unused,
+        undeclared_used,
+    })
+}
+
+/// Move a presentation's languages between slots, carrying everything stored
+/// per slot along with them, in one transaction.
+///
+/// `new_order` lists the 1-based indices of the slots `language_map` names, in
+/// their new order: the first goes to the lowest of those slots, and so on, so
+/// `[2, 1]` swaps Lang1 and Lang2. The language map, the per-language settings
+/// (each slot keeps its display position), every variable's values and each
+/// slide's title, blocks and footer are remapped. Templates are shared between
+/// presentations and keep their per-slot styles. Undo moves it all back.
+#[tauri::command]
+pub async fn reorder_languages(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    new_order: Vec<u8>,
+) -> Result<(), String> {
+    let pool = db::writable_pool(&db).await?;
+    reorder(&pool, &presentation_id, new_order).await
+}
+
+async fn reorder(
+    pool: &SqlitePool,
+    presentation_id: &str,
+    new_order: Vec<u8>,
+) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let presentation: db::Presentation = sqlx::query_as("SELECT * FROM presentations WHERE id = ?")
+        .bind(presentation_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Presentation not found: {presentation_id}"))?;
+
+    let language_map: Value = serde_json::from_str(&presentation.language_map)
+        .map_err(|e| format!("Invalid language map: {e}"))?;
+    let existing: Vec<u8> = (1..=LANG_SLOTS.len() as u8)
+        .filter(|index| {
+            language_map
+                .get(LANG_SLOTS[*index as usize - 1])
+                .and_then(Value::as_str)
+                .is_some_and(|name| !name.trim().is_empty())
+        })
+        .collect();
+    let mut requested = new_order.clone();
+    requested.sort_unstable();
+    if requested != existing {
+        return Err(format!(
+            "New order {new_order:?} must list each of the presentation's languages {existing:?} once"
+        ));
+    }
+
+    // (old index, new index), for the slots that actually move
+    let index_moves: Vec<(i64, i64)> = existing
+        .iter()
+        .zip(&new_order)
+        .filter(|(target, source)| target != source)
+        .map(|(target, source)| (i64::from(*source), i64::from(*target)))
+        .collect();
+    if index_moves.is_empty() {
+        return Ok(());
+    }
+    remap_languages(&mut tx, presentation_id, &index_moves).await?;
+
+    let moves = slot_moves(&index_moves);
+    let slides: Vec<Slide> =
+        sqlx::query_as("SELECT * FROM slides WHERE presentation_id = ? ORDER BY slide_order")
+            .bind(presentation_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    let mut undo = Vec::new();
+    let mut redo = Vec::new();
+    for slide in slides {
+        // Only JSON that actually had moved slots is rewritten
+        let remap = |json: &str| -> Option<String> {
+            let original: Value = serde_json::from_str(json).ok()?;
+            let mut value = original.clone();
+            remap_slots(&mut value, &moves, true);
+            (value != original).then(|| value.to_string())
+        };
+        let title_json = slide.title_json.as_deref().and_then(remap);
+        let blocks_json = remap(&slide.blocks_json);
+        let footer_json = slide.footer_json.as_deref().and_then(remap);
+        if title_json.is_none() && blocks_json.is_none() && footer_json.is_none() {
+            continue;
+        }
+        let remapped = Slide {
+            title_json: title_json.or_else(|| slide.title_json.clone()),
+            blocks_json: blocks_json.unwrap_or_else(|| slide.blocks_json.clone()),
+            footer_json: footer_json.or_else(|| slide.footer_json.clone()),
+            ..slide.clone()
+        };
+        sqlx::query(
+            "UPDATE slides SET title_json = ?, blocks_json = ?, footer_json = ? WHERE id = ?",
+        )
+        .bind(&remapped.title_json)
+        .bind(&remapped.blocks_json)
+        .bind(&remapped.footer_json)
+        .bind(&remapped.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        undo.push(EditOp::PutSlide { slide });
+        redo.push(EditOp::PutSlide { slide: remapped });
+    }
+    let slides_changed = redo.len();
+    undo.push(EditOp::RemapLanguages {
+        presentation_id: presentation_id.to_string(),
+        moves: index_moves
+            .iter()
+            .map(|&(source, target)| (target, source))
+            .collect(),
+    });
+    redo.push(EditOp::RemapLanguages {
+        presentation_id: presentation_id.to_string(),
+        moves: index_moves,
+    });
+
+    let detail = json!({ "newOrder": new_order, "slidesRemapped": slides_changed });
+    audit::record(
+        &mut *tx,
+        "reorder_languages",
+        "presentation",
+        presentation_id,
+        detail,
+    )
+    .await;
+    edits::record(&mut tx, presentation_id, "reorder_languages", &undo, &redo).await?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// Move the language map, the per-language settings and every variable's
+/// values of `presentation_id` between slots by `moves`, `(old index, new
+/// index)` pairs; a slot's display position stays with the slot. The slides
+/// are left to the caller.
+pub(crate) async fn remap_languages(
+    tx: &mut Transaction<'_, Sqlite>,
+    presentation_id: &str,
+    moves: &[(i64, i64)],
+) -> Result<(), String> {
+    let (language_map, language_settings): (String, Option<String>) =
+        sqlx::query_as("SELECT language_map, language_settings FROM presentations WHERE id = ?")
+            .bind(presentation_id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Presentation not found: {presentation_id}"))?;
+
+    let slots = slot_moves(moves);
+    let mut language_map: Value =
+        serde_json::from_str(&language_map).map_err(|e| format!("Invalid language map: {e}"))?;
+    remap_slots(&mut language_map, &slots, false);
+    let language_settings = match language_settings.as_deref() {
+        Some(json) => {
+            let mut settings: Value = serde_json::from_str(json)
+                .map_err(|e| format!("Invalid language settings: {e}"))?;
+            let before = settings.clone();
+            remap_slots(&mut settings, &slots, false);
+            for target in slots.values() {
+                if let (Some(order), Some(Value::Object(config))) = (
+                    before.get(target).and_then(|c| c.get("order")),
+                    settings.get_mut(target),
+                ) {
+                    config.insert("order".to_string(), order.clone());
+                }
+            }
+            Some(settings.to_string())
+        }
+        None => None,
+    };
+    sqlx::query("UPDATE presentations SET language_map = ?, language_settings = ? WHERE id = ?")
+        .bind(language_map.to_string())
+        .bind(&language_settings)
+        .bind(presentation_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // The sync trigger rewrites `variable_values` 1..4 from the legacy columns,
+    // turning NULLs into '', so set those rows aside and put them back remapped
+    let values: Vec<(String, i64, Option<String>)> = sqlx::query_as(
+        "SELECT vv.variable_id, vv.language_index, vv.value FROM variable_values vv
+         JOIN variables v ON v.id = vv.variable_id
+         WHERE v.presentation_id = ? AND vv.language_index <= ?",
+    )
+    .bind(presentation_id)
+    .bind(LANG_SLOTS.len() as i64)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let assignments: Vec<String> = moves
+        .iter()
+        .map(|(source, target)| format!("value_lang{target} = value_lang{source}"))
+        .collect();
+    sqlx::query(&format!(
+        "UPDATE variables SET {} WHERE presentation_id = ?",
+        assignments.join(", ")
+    ))
+    .bind(presentation_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let index_moves: HashMap<i64, i64> = moves.iter().copied().collect();
+    for (variable_id, language_index, value) in values {
+        sqlx::query(
+            "INSERT INTO variable_values (variable_id, language_index, value) VALUES (?, ?, ?)
+             ON CONFLICT(variable_id, language_index) DO UPDATE SET value = excluded.value",
+        )
+        .bind(&variable_id)
+        .bind(index_moves.get(&language_index).unwrap_or(&language_index))
+        .bind(&value)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// `moves` by slot name.
+fn slot_moves(moves: &[(i64, i64)]) -> HashMap<&'static str, &'static str> {
+    moves
+        .iter()
+        .filter_map(|&(source, target)| {
+            let slot = |index: i64| LANG_SLOTS.get(usize::try_from(index - 1).ok()?).copied();
+            Some((slot(source)?, slot(target)?))
+        })
+        .collect()
+}
+
+/// Rename the language slot keys of `value` by `moves`, descending into nested
+/// objects and arrays when `deep`.
+fn remap_slots(value: &mut Value, moves: &HashMap<&'static str, &'static str>, deep: bool) {
+    match value {
+        Value::Object(map) => {
+            let mut remapped = Map::new();
+            for (key, mut child) in std::mem::take(map) {
+                if deep {
+                    remap_slots(&mut child, moves, deep);
+                }
+                let key = moves.get(key.as_str()).map_or(key, |slot| slot.to_string());
+                remapped.insert(key, child);
+            }
+            *map = remapped;
+        }
+        Value::Array(items) if deep => {
+            for item in items {
+                remap_slots(item, moves, deep);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Language slots holding non-empty text anywhere in the slide's title, blocks or footer.
+pub fn used_slots(slide: &Slide) -> Vec<&'static str> {
+    let mut nodes: Vec<Value> = Vec::new();
+    let parse = |json: &str| serde_json::from_str::<Value>(json).ok();
+    if let Some(title) = slide.title_json.as_deref().and_then(parse) {
+        nodes.push(title);
+    }
+    if let Some(Value::Array(blocks)) = parse(&slide.blocks_json) {
+        nodes.extend(blocks);
+    }
+    if let Some(footer) = slide.footer_json.as_deref().and_then(parse) {
+        nodes.extend(
+            ["title", "text"]
+                .iter()
+                .filter_map(|k| footer.get(k).cloned()),
+        );
+    }
+
+    LANG_SLOTS
+        .into_iter()
+        .filter(|slot| {
+            nodes.iter().any(|node| {
+                node.get(slot)
+                    .and_then(Value::as_str)
+                    .is_some_and(|text| !text.trim().is_empty())
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edits::Direction;
+    use crate::test_support;
+
+    async fn scalar(pool: &SqlitePool, sql: &str) -> String {
+        sqlx::query_scalar(sql).fetch_one(pool).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn swapping_two_languages_moves_everything_stored_per_slot() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        test_support::exec(
+            &pool,
+            r#"UPDATE presentations SET
+                   language_map = '{"Lang1":"Geez","Lang2":"Amharic"}',
+                   language_settings = '{"Lang1":{"name":"Geez","enabled":true,"order":0},
+                                         "Lang2":{"name":"Amharic","enabled":false,"order":1}}'
+               WHERE id = 'p1';
+               INSERT INTO variables (id, presentation_id, name, value, value_lang1, value_lang2)
+               VALUES ('v1', 'p1', 'SAINT', 'ማርያም', 'ማርያም', 'ማሪያም');
+               INSERT INTO slides (id, presentation_id, slide_order, title_json, blocks_json)
+               VALUES ('s1', 'p1', 1, '{"Lang1":"ቅዳሴ","Lang2":"ቅዳሴ ማርያም"}',
+                       '[{"Lang1":"ግዕዝ","Lang2":"አማርኛ"}]'),
+                      ('s2', 'p1', 2, NULL, '[]');"#,
+        )
+        .await;
+
+        assert!(reorder(&pool, "p1", vec![1, 3]).await.is_err());
+        reorder(&pool, "p1", vec![2, 1]).await.unwrap();
+
+        let map: Value = serde_json::from_str(
+            &scalar(
+                &pool,
+                "SELECT language_map FROM presentations WHERE id = 'p1'",
+            )
+            .await,
+        )
+        .unwrap();
+        assert_eq!(map, json!({ "Lang1": "Amharic", "Lang2": "Geez" }));
+        let settings: Value = serde_json::from_str(
+            &scalar(
+                &pool,
+                "SELECT language_settings FROM presentations WHERE id = 'p1'",
+            )
+            .await,
+        )
+        .unwrap();
+        assert_eq!(
+            settings,
+            json!({
+                "Lang1": { "name": "Amharic", "enabled": false, "order": 0 },
+                "Lang2": { "name": "Geez", "enabled": true, "order": 1 },
+            })
+        );
+        let columns: (String, String) =
+            sqlx::query_as("SELECT value_lang1, value_lang2 FROM variables WHERE id = 'v1'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(columns, ("ማሪያም".to_string(), "ማርያም".to_string()));
+        let values: Vec<(i64, String)> = sqlx::query_as(
+            "SELECT language_index, value FROM variable_values
+             WHERE variable_id = 'v1' AND language_index <= 2 ORDER BY language_index",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(values, [(1, "ማሪያም".to_string()), (2, "ማርያም".to_string())]);
+        let slide = db::fetch_slide(&pool, "s1").await.unwrap();
+        let blocks: Value = serde_json::from_str(&slide.blocks_json).unwrap();
+        assert_eq!(blocks, json!([{ "Lang1": "አማርኛ", "Lang2": "ግዕዝ" }]));
+        assert_eq!(used_slots(&slide), ["Lang1", "Lang2"]);
+        let title: Value = serde_json::from_str(slide.title_json.as_deref().unwrap()).unwrap();
+        assert_eq!(title["Lang1"], "ቅዳሴ ማርያም");
+
+        // One undo puts everything back
+        let kind = edits::step(&pool, "p1", Direction::Undo).await.unwrap();
+        assert_eq!(kind.as_deref(), Some("reorder_languages"));
+        let map: Value = serde_json::from_str(
+            &scalar(
+                &pool,
+                "SELECT language_map FROM presentations WHERE id = 'p1'",
+            )
+            .await,
+        )
+        .unwrap();
+        assert_eq!(map, json!({ "Lang1": "Geez", "Lang2": "Amharic" }));
+        assert_eq!(
+            scalar(&pool, "SELECT value_lang1 FROM variables WHERE id = 'v1'").await,
+            "ማርያም"
+        );
+        assert_eq!(
+            scalar(
+                &pool,
+                "SELECT value FROM variable_values WHERE variable_id = 'v1' AND language_index = 1"
+            )
+            .await,
+            "ማርያም"
+        );
+        let blocks: Value =
+            serde_json::from_str(&db::fetch_slide(&pool, "s1").await.unwrap().blocks_json).unwrap();
+        assert_eq!(blocks, json!([{ "Lang1": "ግዕዝ", "Lang2": "አማርኛ" }]));
+    }
+}
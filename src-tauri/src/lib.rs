@@ -1,4 +1,50 @@
-use tauri_plugin_sql::{Migration, MigrationKind};
+mod audit;
+mod backup;
+mod bahire_hasab;
+mod blocks;
+mod bundle;
+mod calendar;
+mod contact_sheet;
+mod db;
+mod diff;
+mod dynamic;
+mod edits;
+mod export;
+mod flow;
+mod fonts;
+mod gitsawe;
+mod integrity;
+mod language_map;
+mod maintenance;
+mod markup;
+mod merge;
+mod migrations;
+mod notes;
+mod output_window;
+mod placeholders;
+mod present_mode;
+mod presentation_state;
+mod presentation_types;
+mod presentations;
+mod remote;
+mod render;
+mod replace;
+mod required_regions;
+mod rules;
+mod schedule;
+mod search;
+mod secrets;
+mod settings;
+mod slides;
+mod template;
+#[cfg(test)]
+mod test_support;
+mod theme;
+mod variables;
+mod verses;
+mod window_layout;
+
+use tauri::{Emitter, Manager};
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -8,225 +54,200 @@ fn greet(name: &str) -> String {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let migrations = vec![
-        Migration {
-            version: 1,
-            description: "create_initial_tables",
-            sql: r#"
-                CREATE TABLE IF NOT EXISTS templates (
-                    id TEXT PRIMARY KEY,
-                    name TEXT NOT NULL,
-                    max_lang_count INTEGER NOT NULL DEFAULT 4,
-                    definition_json TEXT NOT NULL,
-                    created_at TEXT NOT NULL
-                );
-
-                CREATE TABLE IF NOT EXISTS presentations (
-                    id TEXT PRIMARY KEY,
-                    name TEXT NOT NULL,
-                    type TEXT NOT NULL,
-                    template_id TEXT NOT NULL,
-                    language_map TEXT NOT NULL,
-                    is_active INTEGER NOT NULL DEFAULT 0,
-                    created_at TEXT NOT NULL,
-                    FOREIGN KEY (template_id) REFERENCES templates(id)
-                );
-
-                CREATE TABLE IF NOT EXISTS slides (
-                    id TEXT PRIMARY KEY,
-                    presentation_id TEXT NOT NULL,
-                    slide_order INTEGER NOT NULL,
-                    line_id TEXT,
-                    title_json TEXT,
-                    blocks_json TEXT NOT NULL,
-                    notes TEXT,
-                    is_disabled INTEGER NOT NULL DEFAULT 0,
-                    FOREIGN KEY (presentation_id) REFERENCES presentations(id)
-                );
-
-                CREATE TABLE IF NOT EXISTS variables (
-                    id TEXT PRIMARY KEY,
-                    presentation_id TEXT NOT NULL,
-                    name TEXT NOT NULL,
-                    value TEXT NOT NULL,
-                    FOREIGN KEY (presentation_id) REFERENCES presentations(id)
-                );
-
-                CREATE INDEX IF NOT EXISTS idx_slides_presentation
-                    ON slides(presentation_id, slide_order);
-
-                CREATE INDEX IF NOT EXISTS idx_variables_presentation
-                    ON variables(presentation_id);
-            "#,
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 2,
-            description: "add_footer_to_slides",
-            sql: r#"
-                ALTER TABLE slides ADD COLUMN footer_json TEXT;
-            "#,
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 3,
-            description: "add_language_settings_to_presentations",
-            sql: r#"
-                ALTER TABLE presentations ADD COLUMN language_settings TEXT;
-            "#,
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 4,
-            description: "create_app_settings_table",
-            sql: r#"
-                CREATE TABLE IF NOT EXISTS app_settings (
-                    key TEXT PRIMARY KEY,
-                    value TEXT NOT NULL
-                );
-            "#,
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 5,
-            description: "create_rule_definitions_table",
-            sql: r#"
-                CREATE TABLE IF NOT EXISTS rule_definitions (
-                    id TEXT PRIMARY KEY,
-                    name TEXT NOT NULL,
-                    scope TEXT NOT NULL,
-                    presentation_id TEXT,
-                    slide_id TEXT,
-                    rule_json TEXT NOT NULL,
-                    is_enabled INTEGER NOT NULL DEFAULT 1,
-                    created_at TEXT NOT NULL
-                );
-
-                CREATE INDEX IF NOT EXISTS idx_rules_presentation
-                    ON rule_definitions(presentation_id);
-
-                CREATE INDEX IF NOT EXISTS idx_rules_slide
-                    ON rule_definitions(slide_id);
-
-                CREATE INDEX IF NOT EXISTS idx_rules_scope
-                    ON rule_definitions(scope);
-            "#,
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 6,
-            description: "add_per_language_variable_values",
-            sql: r#"
-                ALTER TABLE variables ADD COLUMN value_lang1 TEXT NOT NULL DEFAULT '';
-                ALTER TABLE variables ADD COLUMN value_lang2 TEXT NOT NULL DEFAULT '';
-                ALTER TABLE variables ADD COLUMN value_lang3 TEXT NOT NULL DEFAULT '';
-                ALTER TABLE variables ADD COLUMN value_lang4 TEXT NOT NULL DEFAULT '';
-            "#,
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 7,
-            description: "create_gitsawes_table",
-            sql: r#"
-                CREATE TABLE IF NOT EXISTS gitsawes (
-                    id TEXT PRIMARY KEY,
-                    line_id TEXT NOT NULL UNIQUE,
-                    message_st_paul TEXT,
-                    message_apostle TEXT,
-                    message_book_of_acts TEXT,
-                    misbak TEXT,
-                    wengel TEXT,
-                    kidase_type TEXT,
-                    evangelist TEXT,
-                    message_apostle_evangelist TEXT,
-                    gitsawe_type TEXT,
-                    priority INTEGER NOT NULL,
-                    created_at TEXT NOT NULL
-                );
-
-                CREATE INDEX IF NOT EXISTS idx_gitsawes_priority
-                    ON gitsawes(priority);
-
-                CREATE INDEX IF NOT EXISTS idx_gitsawes_line_id
-                    ON gitsawes(line_id);
-
-                CREATE INDEX IF NOT EXISTS idx_gitsawes_gitsawe_type
-                    ON gitsawes(gitsawe_type);
-
-                ALTER TABLE rule_definitions ADD COLUMN gitsawe_id TEXT;
-
-                CREATE INDEX IF NOT EXISTS idx_rules_gitsawe
-                    ON rule_definitions(gitsawe_id);
-            "#,
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 8,
-            description: "add_is_dynamic_to_slides",
-            sql: r#"
-                ALTER TABLE slides ADD COLUMN is_dynamic INTEGER NOT NULL DEFAULT 0;
-            "#,
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 9,
-            description: "create_verses_table",
-            sql: r#"
-                CREATE TABLE IF NOT EXISTS verses (
-                    id TEXT PRIMARY KEY,
-                    segment_id TEXT NOT NULL,
-                    verse_order INTEGER NOT NULL,
-                    title_lang1 TEXT,
-                    title_lang2 TEXT,
-                    title_lang3 TEXT,
-                    title_lang4 TEXT,
-                    text_lang1 TEXT,
-                    text_lang2 TEXT,
-                    text_lang3 TEXT,
-                    text_lang4 TEXT,
-                    created_at TEXT NOT NULL
-                );
-
-                CREATE INDEX IF NOT EXISTS idx_verses_segment_id
-                    ON verses(segment_id);
-            "#,
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 10,
-            description: "add_template_override_id_to_slides",
-            sql: "ALTER TABLE slides ADD COLUMN template_override_id TEXT;",
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 11,
-            description: "add_is_primary_to_presentations",
-            sql: "ALTER TABLE presentations ADD COLUMN is_primary INTEGER NOT NULL DEFAULT 1;",
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 12,
-            description: "add_name_and_additional_info_to_gitsawes",
-            sql: r#"
-                ALTER TABLE gitsawes ADD COLUMN name TEXT;
-                ALTER TABLE gitsawes ADD COLUMN additional_info TEXT;
-            "#,
-            kind: MigrationKind::Up,
-        },
-    ];
-
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
-        .plugin(
-            tauri_plugin_sql::Builder::default()
-                .add_migrations("sqlite:kidase.db", migrations)
-                .build(),
-        )
-        .invoke_handler(tauri::generate_handler![greet])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .setup(|app| {
+            let compatibility = tauri::async_runtime::block_on(db::prepare(app.handle()))?;
+            if compatibility.compatible {
+                app.handle().plugin(
+                    tauri_plugin_sql::Builder::default()
+                        .add_migrations(db::DB_URL, migrations::migrations())
+                        .build(),
+                )?;
+                tauri::async_runtime::spawn(output_window::reattach(app.handle().clone()));
+            } else {
+                // Migrating a newer schema could undo changes this build doesn't
+                // know about, so the database stays closed.
+                eprintln!(
+                    "kidase.db is at schema version {} but this build only knows up to {}",
+                    compatibility.stored_version, compatibility.expected_version
+                );
+                let _ = app.emit(db::SCHEMA_TOO_NEW, &compatibility);
+            }
+            app.manage(compatibility);
+            // Exports and thumbnails still work from installed fonts without it
+            if let Err(e) = fonts::register_bundled_font(app.handle()) {
+                eprintln!("{e}");
+            }
+            if let Err(e) = output_window::OutputWatcher::start(app.handle()) {
+                eprintln!("{e}");
+            }
+            if let Err(e) = backup::scheduler::BackupScheduler::start(app.handle()) {
+                eprintln!("{e}");
+            }
+            Ok(())
+        })
+        .manage(presentation_state::PresentationState::default())
+        .manage(remote::RemoteControl::default())
+        .manage(output_window::OutputWatcher::default())
+        .manage(backup::scheduler::BackupScheduler::default())
+        .invoke_handler(tauri::generate_handler![
+            greet,
+            audit::get_audit_log,
+            backup::backup_database,
+            backup::restore_database,
+            backup::scheduler::configure_auto_backup,
+            backup::scheduler::list_backups,
+            backup::scheduler::restore_backup,
+            backup::scheduler::trigger_backup_now,
+            bahire_hasab::get_bahire_hasab,
+            bahire_hasab::get_movable_feasts,
+            blocks::migrate_presentation_blocks,
+            bundle::archive::export_presentations,
+            bundle::archive::import_archive,
+            bundle::archive::read_archive_manifest,
+            bundle::duplicate::duplicate_presentation,
+            bundle::export_presentation,
+            bundle::import::import_presentation,
+            bundle::import::legacy::detect_bundle_version,
+            bundle::year::export_year_archive,
+            bundle::year::import_year_archive,
+            bundle::zip::export_bundle_zip,
+            bundle::zip::import_bundle_zip,
+            calendar::ethiopian_to_gregorian,
+            calendar::gregorian_to_ethiopian,
+            contact_sheet::export_contact_sheet,
+            db::can_open_database,
+            db::new_id,
+            diff::diff_presentations,
+            dynamic::expand_dynamic_slides,
+            edits::redo,
+            edits::undo,
+            export::filename::suggest_export_filename,
+            export::pdf::export_handout_pdf,
+            export::pdf::export_pdf,
+            export::pptx::export_pptx,
+            flow::export_flow_dot,
+            fonts::list_available_fonts,
+            fonts::verify_fonts,
+            gitsawe::build_schedule,
+            gitsawe::find_gitsawes,
+            gitsawe::generate_presentation_from_gitsawe,
+            gitsawe::get_gitsawe_for_date,
+            gitsawe::normalize_gitsawe_priorities,
+            gitsawe::resolve_gitsawe_for_date,
+            integrity::check_integrity,
+            integrity::validate_line_ids,
+            language_map::language_map_drift,
+            language_map::reorder_languages,
+            maintenance::analyze_database,
+            maintenance::get_db_pragmas,
+            maintenance::get_expected_schema_version,
+            maintenance::get_schema_version,
+            maintenance::vacuum_database,
+            merge::merge_presentations,
+            merge::split_presentation,
+            notes::import_notes,
+            output_window::close_output_window,
+            output_window::list_displays,
+            output_window::open_output_window,
+            placeholders::find_unused_variables,
+            placeholders::render_slide,
+            placeholders::render_slide_blocks,
+            present_mode::get_present_mode,
+            present_mode::set_present_mode,
+            presentation_state::get_live_slide,
+            presentation_state::get_live_state,
+            presentation_state::live_blank,
+            presentation_state::live_freeze,
+            presentation_state::live_goto,
+            presentation_state::live_load,
+            presentation_state::live_next,
+            presentation_state::live_prev,
+            presentation_state::live_stop,
+            presentation_state::set_active_presentation,
+            presentation_state::set_live_slide,
+            presentation_types::audit_presentation_types,
+            presentation_types::normalize_presentation_types,
+            presentations::delete_presentation,
+            presentations::delete_presentation_cascade,
+            presentations::get_active_presentation,
+            presentations::get_display_scale,
+            presentations::get_presentation,
+            presentations::get_recent_presentations,
+            presentations::list_primary_presentations,
+            presentations::list_presentations,
+            presentations::list_recently_modified,
+            presentations::mark_presentation_opened,
+            presentations::purge_deleted,
+            presentations::repair_active_flags,
+            presentations::restore_presentation,
+            presentations::save_presentation,
+            presentations::set_display_scale,
+            presentations::set_presentation_primary,
+            remote::api::set_live_api_token,
+            remote::configure_remote_control,
+            remote::get_remote_control_status,
+            remote::resume_remote_control,
+            render::html::render_slide_html,
+            render::render_slide_thumbnail,
+            replace::replace_in_slides,
+            required_regions::find_empty_required_regions,
+            rules::apply_rules,
+            rules::evaluate_rules,
+            rules::preview_rule,
+            rules::set_rules_enabled,
+            rules::validate_rule,
+            schedule::get_scheduled_services,
+            schedule::schedule_service,
+            schedule::unschedule_service,
+            search::search_slides,
+            search::search_text,
+            secrets::get_secret,
+            secrets::set_secret,
+            settings::export_settings,
+            settings::import_settings,
+            slides::compact_slide_order,
+            slides::copy_slides,
+            slides::delete_slide,
+            slides::find_duplicate_slides,
+            slides::get_active_slide_count,
+            slides::normalize_presentation_json,
+            slides::reorder_slides,
+            slides::save_slide,
+            slides::set_slides_disabled,
+            slides::update_slide,
+            template::clone_template,
+            template::create_template,
+            template::delete_template,
+            template::get_template_usage,
+            template::update_template,
+            template::validate_template,
+            theme::generate_theme_variants,
+            variables::get_variable_values,
+            variables::set_variable_all_languages,
+            variables::set_variable_value,
+            verses::export_segment_verses,
+            verses::get_verse_of_day,
+            verses::import_verses_csv,
+            window_layout::restore_window_layout,
+            window_layout::save_window_layout
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| match event {
+            // Before the plugin closes the database on `Exit`
+            tauri::RunEvent::ExitRequested { .. } => {
+                app.state::<backup::scheduler::BackupScheduler>()
+                    .backup_on_exit(app);
+            }
+            tauri::RunEvent::Exit => {
+                app.state::<backup::scheduler::BackupScheduler>().shutdown();
+            }
+            _ => {}
+        });
 }
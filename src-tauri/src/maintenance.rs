@@ -0,0 +1,155 @@
+use serde::Serialize;
+use sqlx::pool::PoolConnection;
+use sqlx::{FromRow, Sqlite};
+use tauri::State;
+use tauri_plugin_sql::{DbInstances, MigrationKind};
+
+use crate::db;
+use crate::migrations;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbStats {
+    pub page_size: i64,
+    pub pages_before: i64,
+    pub pages_after: i64,
+    pub bytes_before: i64,
+    pub bytes_after: i64,
+}
+
+/// Settings as seen by one of the pool's connections.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbPragmas {
+    pub journal_mode: String,
+    /// 0 = OFF, 1 = NORMAL, 2 = FULL, 3 = EXTRA.
+    pub synchronous: i64,
+    pub foreign_keys: bool,
+    /// Milliseconds.
+    pub busy_timeout: i64,
+}
+
+/// Report the pragmas in effect on the database the frontend is using.
+#[tauri::command]
+pub async fn get_db_pragmas(db: State<'_, DbInstances>) -> Result<DbPragmas, String> {
+    let pool = db::pool(&db).await?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    let synchronous = pragma_int(&mut conn, "synchronous").await?;
+    let foreign_keys = pragma_int(&mut conn, "foreign_keys").await? == 1;
+    let busy_timeout = pragma_int(&mut conn, "busy_timeout").await?;
+
+    Ok(DbPragmas {
+        journal_mode,
+        synchronous,
+        foreign_keys,
+        busy_timeout,
+    })
+}
+
+/// Rebuild `kidase.db` to reclaim space left by deleted rows.
+///
+/// `VACUUM` can't run inside a transaction, so it is issued on its own and the
+/// WAL is checkpointed afterwards so the file actually shrinks. If another
+/// connection is mid-write the SQLite error is returned as is.
+#[tauri::command]
+pub async fn vacuum_database(db: State<'_, DbInstances>) -> Result<DbStats, String> {
+    let pool = db::writable_pool(&db).await?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let page_size = pragma_int(&mut conn, "page_size").await?;
+    let pages_before = pragma_int(&mut conn, "page_count").await?;
+    sqlx::query("VACUUM")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Vacuum failed: {e}"))?;
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Checkpoint failed: {e}"))?;
+    let pages_after = pragma_int(&mut conn, "page_count").await?;
+
+    Ok(DbStats {
+        page_size,
+        pages_before,
+        pages_after,
+        bytes_before: pages_before * page_size,
+        bytes_after: pages_after * page_size,
+    })
+}
+
+/// Refresh the query planner's statistics.
+#[tauri::command]
+pub async fn analyze_database(db: State<'_, DbInstances>) -> Result<(), String> {
+    let pool = db::writable_pool(&db).await?;
+    sqlx::query("ANALYZE")
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Analyze failed: {e}"))?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub description: String,
+    pub installed_on: String,
+    /// False when the migration failed part-way and left the schema dirty.
+    pub success: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaVersion {
+    /// Highest migration applied successfully; 0 on an unmigrated database.
+    pub version: i64,
+    pub migrations: Vec<AppliedMigration>,
+}
+
+/// The migration version `kidase.db` is at, with every migration recorded in
+/// the plugin's `_sqlx_migrations` table, oldest first.
+#[tauri::command]
+pub async fn get_schema_version(db: State<'_, DbInstances>) -> Result<SchemaVersion, String> {
+    let pool = db::pool(&db).await?;
+    let migrations = sqlx::query_as::<_, AppliedMigration>(
+        "SELECT version, description, CAST(installed_on AS TEXT) AS installed_on, success
+         FROM _sqlx_migrations ORDER BY version",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let version = migrations
+        .iter()
+        .filter(|m| m.success)
+        .map(|m| m.version)
+        .max()
+        .unwrap_or(0);
+    Ok(SchemaVersion {
+        version,
+        migrations,
+    })
+}
+
+/// The highest migration version this build applies. A database ahead of it
+/// was opened by a newer version of the app.
+#[tauri::command]
+pub fn get_expected_schema_version() -> i64 {
+    migrations::migrations()
+        .iter()
+        .filter(|m| matches!(m.kind, MigrationKind::Up))
+        .map(|m| m.version)
+        .max()
+        .unwrap_or(0)
+}
+
+async fn pragma_int(conn: &mut PoolConnection<Sqlite>, name: &str) -> Result<i64, String> {
+    sqlx::query_scalar(&format!("PRAGMA {name}"))
+        .fetch_one(&mut **conn)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,7 @@
+/// Escape text for an HTML, SVG or XML attribute value or element body.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,328 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::audit;
+use crate::db::{self, Slide, Variable};
+use crate::placeholders::PLACEHOLDER_PATTERN;
+
+/// Where the source's slides go in the target.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MergePosition {
+    Append,
+    Prepend,
+    /// Before the target slide at this 0-based index; past the end appends.
+    At(u32),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariableCollision {
+    pub name: String,
+    /// Value kept from the target.
+    pub target_value: String,
+    /// Value dropped from the source.
+    pub source_value: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeReport {
+    pub slides_moved: u32,
+    pub variable_collisions: Vec<VariableCollision>,
+}
+
+/// Move every slide, variable and rule of `source_id` into `target_id`, then
+/// delete the source, in one transaction.
+///
+/// Slides are renumbered 1..n across the merged order. Source slides that
+/// relied on the source's template get it as an override so they look the
+/// same. Variables merge by name; on a conflict the target's value wins and
+/// the differing source value is reported.
+#[tauri::command]
+pub async fn merge_presentations(
+    db: State<'_, DbInstances>,
+    target_id: String,
+    source_id: String,
+    position: MergePosition,
+) -> Result<MergeReport, String> {
+    if target_id == source_id {
+        return Err("Cannot merge a presentation into itself".to_string());
+    }
+
+    let pool = db::writable_pool(&db).await?;
+    let target = db::fetch_presentation(&pool, &target_id).await?;
+    let source = db::fetch_presentation(&pool, &source_id).await?;
+    let target_slides = db::fetch_slides(&pool, &target_id).await?;
+    let source_slides = db::fetch_slides(&pool, &source_id).await?;
+    let target_variables = db::fetch_variables(&pool, &target_id).await?;
+    let source_variables = db::fetch_variables(&pool, &source_id).await?;
+
+    let insert_at = match position {
+        MergePosition::Append => target_slides.len(),
+        MergePosition::Prepend => 0,
+        MergePosition::At(index) => (index as usize).min(target_slides.len()),
+    };
+    let (before, after) = target_slides.split_at(insert_at);
+    let order = before.iter().chain(&source_slides).chain(after);
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    for (index, slide) in order.enumerate() {
+        let template_override_id = match &slide.template_override_id {
+            None if slide.presentation_id == source_id
+                && source.template_id != target.template_id =>
+            {
+                Some(&source.template_id)
+            }
+            other => other.as_ref(),
+        };
+        sqlx::query(
+            "UPDATE slides SET presentation_id = ?, slide_order = ?, template_override_id = ?
+             WHERE id = ?",
+        )
+        .bind(&target_id)
+        .bind(index as i64 + 1)
+        .bind(template_override_id)
+        .bind(&slide.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    let mut variable_collisions = Vec::new();
+    for variable in &source_variables {
+        match target_variables.iter().find(|v| v.name == variable.name) {
+            Some(existing) => {
+                if !same_values(existing, variable) {
+                    variable_collisions.push(VariableCollision {
+                        name: variable.name.clone(),
+                        target_value: existing.value.clone(),
+                        source_value: variable.value.clone(),
+                    });
+                }
+                sqlx::query("DELETE FROM variables WHERE id = ?")
+                    .bind(&variable.id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+            None => {
+                sqlx::query("UPDATE variables SET presentation_id = ? WHERE id = ?")
+                    .bind(&target_id)
+                    .bind(&variable.id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    sqlx::query("UPDATE rule_definitions SET presentation_id = ? WHERE presentation_id = ?")
+        .bind(&target_id)
+        .bind(&source_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if source.is_active {
+        sqlx::query("UPDATE presentations SET is_active = 1 WHERE id = ?")
+            .bind(&target_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    sqlx::query("DELETE FROM presentations WHERE id = ?")
+        .bind(&source_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let detail = json!({
+        "sourceId": source_id,
+        "slidesMoved": source_slides.len(),
+        "variableCollisions": &variable_collisions,
+    });
+    audit::record(&mut *tx, "merge", "presentation", &target_id, detail).await;
+    let detail = json!({ "name": source.name, "mergedInto": target_id });
+    audit::record(&mut *tx, "delete", "presentation", &source_id, detail).await;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(MergeReport {
+        slides_moved: source_slides.len() as u32,
+        variable_collisions,
+    })
+}
+
+/// Move the slides of `source_id` from `split_at_order` on into a new
+/// presentation named `new_name`, returning its id. The inverse of
+/// [`merge_presentations`], in one transaction.
+///
+/// The new presentation shares the source's template, type, language map and
+/// display scale; it is neither primary nor active, and belongs to the
+/// source's primary. Both halves are renumbered 1..n. Variables only the moved
+/// slides use go with them, those both halves use are copied, and the rest
+/// stay put. Rules on moved slides follow their slide.
+#[tauri::command]
+pub async fn split_presentation(
+    db: State<'_, DbInstances>,
+    source_id: String,
+    split_at_order: u32,
+    new_name: String,
+) -> Result<String, String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err("Presentation name cannot be empty".to_string());
+    }
+
+    let pool = db::writable_pool(&db).await?;
+    let source = db::fetch_presentation(&pool, &source_id).await?;
+    let slides = db::fetch_slides(&pool, &source_id).await?;
+    let variables = db::fetch_variables(&pool, &source_id).await?;
+
+    let (moved, kept): (Vec<&Slide>, Vec<&Slide>) = slides
+        .iter()
+        .partition(|slide| slide.slide_order >= i64::from(split_at_order));
+    if moved.is_empty() || kept.is_empty() {
+        return Err(format!(
+            "Splitting at slide {split_at_order} would leave one half empty"
+        ));
+    }
+
+    let pattern = Regex::new(PLACEHOLDER_PATTERN).map_err(|e| e.to_string())?;
+    let moved_names = placeholder_names(&pattern, &moved);
+    let kept_names = placeholder_names(&pattern, &kept);
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let new_id = db::new_id();
+    sqlx::query(
+        "INSERT INTO presentations
+         (id, name, type, template_id, language_map, language_settings, is_primary, is_active,
+          created_at, display_scale, parent_id)
+         VALUES (?, ?, ?, ?, ?, ?, 0, 0, ?, ?,
+                 (SELECT CASE WHEN is_primary = 1 THEN id ELSE parent_id END
+                  FROM presentations WHERE id = ?))",
+    )
+    .bind(&new_id)
+    .bind(new_name)
+    .bind(&source.presentation_type)
+    .bind(&source.template_id)
+    .bind(&source.language_map)
+    .bind(&source.language_settings)
+    .bind(db::now())
+    .bind(source.display_scale)
+    .bind(&source.id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for (index, slide) in kept.iter().enumerate() {
+        sqlx::query("UPDATE slides SET slide_order = ? WHERE id = ?")
+            .bind(index as i64 + 1)
+            .bind(&slide.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    for (index, slide) in moved.iter().enumerate() {
+        sqlx::query("UPDATE slides SET presentation_id = ?, slide_order = ? WHERE id = ?")
+            .bind(&new_id)
+            .bind(index as i64 + 1)
+            .bind(&slide.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        sqlx::query("UPDATE rule_definitions SET presentation_id = ? WHERE slide_id = ?")
+            .bind(&new_id)
+            .bind(&slide.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    for variable in &variables {
+        if !moved_names.contains(variable.name.as_str()) {
+            continue;
+        }
+        if !kept_names.contains(variable.name.as_str()) {
+            sqlx::query("UPDATE variables SET presentation_id = ? WHERE id = ?")
+                .bind(&new_id)
+                .bind(&variable.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+            continue;
+        }
+
+        let copy_id = db::new_id();
+        sqlx::query(
+            "INSERT INTO variables
+             (id, presentation_id, name, value, value_lang1, value_lang2, value_lang3, value_lang4)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&copy_id)
+        .bind(&new_id)
+        .bind(&variable.name)
+        .bind(&variable.value)
+        .bind(&variable.value_lang1)
+        .bind(&variable.value_lang2)
+        .bind(&variable.value_lang3)
+        .bind(&variable.value_lang4)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        // Languages past Lang4 only live in `variable_values`
+        sqlx::query(
+            "INSERT INTO variable_values (variable_id, language_index, value)
+             SELECT ?, language_index, value FROM variable_values WHERE variable_id = ?
+             ON CONFLICT(variable_id, language_index) DO UPDATE SET value = excluded.value",
+        )
+        .bind(&copy_id)
+        .bind(&variable.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    let detail = json!({
+        "newId": new_id,
+        "splitAtOrder": split_at_order,
+        "slidesMoved": moved.len(),
+    });
+    audit::record(&mut *tx, "split", "presentation", &source_id, detail).await;
+    let detail = json!({ "name": new_name, "splitFrom": source_id });
+    audit::record(&mut *tx, "create", "presentation", &new_id, detail).await;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(new_id)
+}
+
+/// Placeholders used anywhere in the title, blocks or footer of `slides`.
+fn placeholder_names<'a>(pattern: &Regex, slides: &[&'a Slide]) -> HashSet<&'a str> {
+    slides
+        .iter()
+        .flat_map(|s| {
+            [
+                s.title_json.as_deref(),
+                Some(s.blocks_json.as_str()),
+                s.footer_json.as_deref(),
+            ]
+        })
+        .flatten()
+        .flat_map(|text| pattern.find_iter(text).map(|m| m.as_str()))
+        .collect()
+}
+
+fn same_values(a: &Variable, b: &Variable) -> bool {
+    a.value == b.value
+        && a.value_lang1 == b.value_lang1
+        && a.value_lang2 == b.value_lang2
+        && a.value_lang3 == b.value_lang3
+        && a.value_lang4 == b.value_lang4
+}
@@ -0,0 +1,1532 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Schema migrations for `kidase.db`.
+///
+/// Every `Up` from version 2 on is paired with a `Down` whose description names
+/// the version it restores. Column drops rebuild the table because older SQLite
+/// builds have no `DROP COLUMN`; rebuilt tables keep their column order,
+/// indexes and triggers so a later `Up` re-applies cleanly. Tables other tables
+/// point at (`templates`, `presentations`) can't be renamed into place, since
+/// `foreign_keys` can't be turned off inside the migration transaction: their
+/// rows go to a temp table and back into the recreated table under
+/// `defer_foreign_keys`, which lets the children's keys resolve again by commit.
+pub fn migrations() -> Vec<Migration> {
+    vec![
+        Migration {
+            version: 1,
+            description: "create_initial_tables",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS templates (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    max_lang_count INTEGER NOT NULL DEFAULT 4,
+                    definition_json TEXT NOT NULL,
+                    created_at TEXT NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS presentations (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    type TEXT NOT NULL,
+                    template_id TEXT NOT NULL,
+                    language_map TEXT NOT NULL,
+                    is_active INTEGER NOT NULL DEFAULT 0,
+                    created_at TEXT NOT NULL,
+                    FOREIGN KEY (template_id) REFERENCES templates(id)
+                );
+
+                CREATE TABLE IF NOT EXISTS slides (
+                    id TEXT PRIMARY KEY,
+                    presentation_id TEXT NOT NULL,
+                    slide_order INTEGER NOT NULL,
+                    line_id TEXT,
+                    title_json TEXT,
+                    blocks_json TEXT NOT NULL,
+                    notes TEXT,
+                    is_disabled INTEGER NOT NULL DEFAULT 0,
+                    FOREIGN KEY (presentation_id) REFERENCES presentations(id)
+                );
+
+                CREATE TABLE IF NOT EXISTS variables (
+                    id TEXT PRIMARY KEY,
+                    presentation_id TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    value TEXT NOT NULL,
+                    FOREIGN KEY (presentation_id) REFERENCES presentations(id)
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_slides_presentation
+                    ON slides(presentation_id, slide_order);
+
+                CREATE INDEX IF NOT EXISTS idx_variables_presentation
+                    ON variables(presentation_id);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 2,
+            description: "add_footer_to_slides",
+            sql: r#"
+                ALTER TABLE slides ADD COLUMN footer_json TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 2,
+            description: "revert_add_footer_to_slides_to_v1",
+            sql: r#"
+                CREATE TABLE slides_v1 (
+                    id TEXT PRIMARY KEY,
+                    presentation_id TEXT NOT NULL,
+                    slide_order INTEGER NOT NULL,
+                    line_id TEXT,
+                    title_json TEXT,
+                    blocks_json TEXT NOT NULL,
+                    notes TEXT,
+                    is_disabled INTEGER NOT NULL DEFAULT 0,
+                    FOREIGN KEY (presentation_id) REFERENCES presentations(id)
+                );
+
+                INSERT INTO slides_v1
+                    SELECT id, presentation_id, slide_order, line_id, title_json, blocks_json,
+                           notes, is_disabled
+                    FROM slides;
+
+                DROP TABLE slides;
+                ALTER TABLE slides_v1 RENAME TO slides;
+
+                CREATE INDEX IF NOT EXISTS idx_slides_presentation
+                    ON slides(presentation_id, slide_order);
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 3,
+            description: "add_language_settings_to_presentations",
+            sql: r#"
+                ALTER TABLE presentations ADD COLUMN language_settings TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 3,
+            description: "revert_add_language_settings_to_presentations_to_v2",
+            sql: r#"
+                PRAGMA defer_foreign_keys = ON;
+
+                CREATE TEMP TABLE presentations_v2 AS
+                    SELECT id, name, type, template_id, language_map, is_active, created_at
+                    FROM presentations;
+
+                DROP TABLE presentations;
+
+                CREATE TABLE presentations (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    type TEXT NOT NULL,
+                    template_id TEXT NOT NULL,
+                    language_map TEXT NOT NULL,
+                    is_active INTEGER NOT NULL DEFAULT 0,
+                    created_at TEXT NOT NULL,
+                    FOREIGN KEY (template_id) REFERENCES templates(id)
+                );
+
+                INSERT INTO presentations SELECT * FROM presentations_v2;
+                DROP TABLE presentations_v2;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 4,
+            description: "create_app_settings_table",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS app_settings (
+                    key TEXT PRIMARY KEY,
+                    value TEXT NOT NULL
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 4,
+            description: "revert_create_app_settings_table_to_v3",
+            sql: r#"
+                DROP TABLE IF EXISTS app_settings;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 5,
+            description: "create_rule_definitions_table",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS rule_definitions (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    scope TEXT NOT NULL,
+                    presentation_id TEXT,
+                    slide_id TEXT,
+                    rule_json TEXT NOT NULL,
+                    is_enabled INTEGER NOT NULL DEFAULT 1,
+                    created_at TEXT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_rules_presentation
+                    ON rule_definitions(presentation_id);
+
+                CREATE INDEX IF NOT EXISTS idx_rules_slide
+                    ON rule_definitions(slide_id);
+
+                CREATE INDEX IF NOT EXISTS idx_rules_scope
+                    ON rule_definitions(scope);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 5,
+            description: "revert_create_rule_definitions_table_to_v4",
+            sql: r#"
+                DROP INDEX IF EXISTS idx_rules_scope;
+                DROP INDEX IF EXISTS idx_rules_slide;
+                DROP INDEX IF EXISTS idx_rules_presentation;
+                DROP TABLE IF EXISTS rule_definitions;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 6,
+            description: "add_per_language_variable_values",
+            sql: r#"
+                ALTER TABLE variables ADD COLUMN value_lang1 TEXT NOT NULL DEFAULT '';
+                ALTER TABLE variables ADD COLUMN value_lang2 TEXT NOT NULL DEFAULT '';
+                ALTER TABLE variables ADD COLUMN value_lang3 TEXT NOT NULL DEFAULT '';
+                ALTER TABLE variables ADD COLUMN value_lang4 TEXT NOT NULL DEFAULT '';
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 6,
+            description: "revert_add_per_language_variable_values_to_v5",
+            sql: r#"
+                CREATE TABLE variables_v5 (
+                    id TEXT PRIMARY KEY,
+                    presentation_id TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    value TEXT NOT NULL,
+                    FOREIGN KEY (presentation_id) REFERENCES presentations(id)
+                );
+
+                INSERT INTO variables_v5
+                    SELECT id, presentation_id, name, value
+                    FROM variables;
+
+                DROP TABLE variables;
+                ALTER TABLE variables_v5 RENAME TO variables;
+
+                CREATE INDEX IF NOT EXISTS idx_variables_presentation
+                    ON variables(presentation_id);
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 7,
+            description: "create_gitsawes_table",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS gitsawes (
+                    id TEXT PRIMARY KEY,
+                    line_id TEXT NOT NULL UNIQUE,
+                    message_st_paul TEXT,
+                    message_apostle TEXT,
+                    message_book_of_acts TEXT,
+                    misbak TEXT,
+                    wengel TEXT,
+                    kidase_type TEXT,
+                    evangelist TEXT,
+                    message_apostle_evangelist TEXT,
+                    gitsawe_type TEXT,
+                    priority INTEGER NOT NULL,
+                    created_at TEXT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_gitsawes_priority
+                    ON gitsawes(priority);
+
+                CREATE INDEX IF NOT EXISTS idx_gitsawes_line_id
+                    ON gitsawes(line_id);
+
+                CREATE INDEX IF NOT EXISTS idx_gitsawes_gitsawe_type
+                    ON gitsawes(gitsawe_type);
+
+                ALTER TABLE rule_definitions ADD COLUMN gitsawe_id TEXT;
+
+                CREATE INDEX IF NOT EXISTS idx_rules_gitsawe
+                    ON rule_definitions(gitsawe_id);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 7,
+            description: "revert_create_gitsawes_table_to_v6",
+            sql: r#"
+                CREATE TABLE rule_definitions_v6 (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    scope TEXT NOT NULL,
+                    presentation_id TEXT,
+                    slide_id TEXT,
+                    rule_json TEXT NOT NULL,
+                    is_enabled INTEGER NOT NULL DEFAULT 1,
+                    created_at TEXT NOT NULL
+                );
+
+                INSERT INTO rule_definitions_v6
+                    SELECT id, name, scope, presentation_id, slide_id, rule_json, is_enabled, created_at
+                    FROM rule_definitions;
+
+                DROP TABLE rule_definitions;
+                ALTER TABLE rule_definitions_v6 RENAME TO rule_definitions;
+
+                CREATE INDEX IF NOT EXISTS idx_rules_presentation
+                    ON rule_definitions(presentation_id);
+
+                CREATE INDEX IF NOT EXISTS idx_rules_slide
+                    ON rule_definitions(slide_id);
+
+                CREATE INDEX IF NOT EXISTS idx_rules_scope
+                    ON rule_definitions(scope);
+
+                DROP INDEX IF EXISTS idx_gitsawes_gitsawe_type;
+                DROP INDEX IF EXISTS idx_gitsawes_line_id;
+                DROP INDEX IF EXISTS idx_gitsawes_priority;
+                DROP TABLE IF EXISTS gitsawes;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 8,
+            description: "add_is_dynamic_to_slides",
+            sql: r#"
+                ALTER TABLE slides ADD COLUMN is_dynamic INTEGER NOT NULL DEFAULT 0;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 8,
+            description: "revert_add_is_dynamic_to_slides_to_v7",
+            sql: r#"
+                CREATE TABLE slides_v7 (
+                    id TEXT PRIMARY KEY,
+                    presentation_id TEXT NOT NULL,
+                    slide_order INTEGER NOT NULL,
+                    line_id TEXT,
+                    title_json TEXT,
+                    blocks_json TEXT NOT NULL,
+                    notes TEXT,
+                    is_disabled INTEGER NOT NULL DEFAULT 0,
+                    footer_json TEXT,
+                    FOREIGN KEY (presentation_id) REFERENCES presentations(id)
+                );
+
+                INSERT INTO slides_v7
+                    SELECT id, presentation_id, slide_order, line_id, title_json, blocks_json,
+                           notes, is_disabled, footer_json
+                    FROM slides;
+
+                DROP TABLE slides;
+                ALTER TABLE slides_v7 RENAME TO slides;
+
+                CREATE INDEX IF NOT EXISTS idx_slides_presentation
+                    ON slides(presentation_id, slide_order);
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 9,
+            description: "create_verses_table",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS verses (
+                    id TEXT PRIMARY KEY,
+                    segment_id TEXT NOT NULL,
+                    verse_order INTEGER NOT NULL,
+                    title_lang1 TEXT,
+                    title_lang2 TEXT,
+                    title_lang3 TEXT,
+                    title_lang4 TEXT,
+                    text_lang1 TEXT,
+                    text_lang2 TEXT,
+                    text_lang3 TEXT,
+                    text_lang4 TEXT,
+                    created_at TEXT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_verses_segment_id
+                    ON verses(segment_id);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 9,
+            description: "revert_create_verses_table_to_v8",
+            sql: r#"
+                DROP INDEX IF EXISTS idx_verses_segment_id;
+                DROP TABLE IF EXISTS verses;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 10,
+            description: "add_template_override_id_to_slides",
+            sql: "ALTER TABLE slides ADD COLUMN template_override_id TEXT;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 10,
+            description: "revert_add_template_override_id_to_slides_to_v9",
+            sql: r#"
+                CREATE TABLE slides_v9 (
+                    id TEXT PRIMARY KEY,
+                    presentation_id TEXT NOT NULL,
+                    slide_order INTEGER NOT NULL,
+                    line_id TEXT,
+                    title_json TEXT,
+                    blocks_json TEXT NOT NULL,
+                    notes TEXT,
+                    is_disabled INTEGER NOT NULL DEFAULT 0,
+                    footer_json TEXT,
+                    is_dynamic INTEGER NOT NULL DEFAULT 0,
+                    FOREIGN KEY (presentation_id) REFERENCES presentations(id)
+                );
+
+                INSERT INTO slides_v9
+                    SELECT id, presentation_id, slide_order, line_id, title_json, blocks_json,
+                           notes, is_disabled, footer_json, is_dynamic
+                    FROM slides;
+
+                DROP TABLE slides;
+                ALTER TABLE slides_v9 RENAME TO slides;
+
+                CREATE INDEX IF NOT EXISTS idx_slides_presentation
+                    ON slides(presentation_id, slide_order);
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 11,
+            description: "add_is_primary_to_presentations",
+            sql: "ALTER TABLE presentations ADD COLUMN is_primary INTEGER NOT NULL DEFAULT 1;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 11,
+            description: "revert_add_is_primary_to_presentations_to_v10",
+            sql: r#"
+                PRAGMA defer_foreign_keys = ON;
+
+                CREATE TEMP TABLE presentations_v10 AS
+                    SELECT id, name, type, template_id, language_map, is_active, created_at,
+                           language_settings
+                    FROM presentations;
+
+                DROP TABLE presentations;
+
+                CREATE TABLE presentations (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    type TEXT NOT NULL,
+                    template_id TEXT NOT NULL,
+                    language_map TEXT NOT NULL,
+                    is_active INTEGER NOT NULL DEFAULT 0,
+                    created_at TEXT NOT NULL,
+                    language_settings TEXT,
+                    FOREIGN KEY (template_id) REFERENCES templates(id)
+                );
+
+                INSERT INTO presentations SELECT * FROM presentations_v10;
+                DROP TABLE presentations_v10;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 12,
+            description: "add_name_and_additional_info_to_gitsawes",
+            sql: r#"
+                ALTER TABLE gitsawes ADD COLUMN name TEXT;
+                ALTER TABLE gitsawes ADD COLUMN additional_info TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 12,
+            description: "revert_add_name_and_additional_info_to_gitsawes_to_v11",
+            sql: r#"
+                CREATE TABLE gitsawes_v11 (
+                    id TEXT PRIMARY KEY,
+                    line_id TEXT NOT NULL UNIQUE,
+                    message_st_paul TEXT,
+                    message_apostle TEXT,
+                    message_book_of_acts TEXT,
+                    misbak TEXT,
+                    wengel TEXT,
+                    kidase_type TEXT,
+                    evangelist TEXT,
+                    message_apostle_evangelist TEXT,
+                    gitsawe_type TEXT,
+                    priority INTEGER NOT NULL,
+                    created_at TEXT NOT NULL
+                );
+
+                INSERT INTO gitsawes_v11
+                    SELECT id, line_id, message_st_paul, message_apostle, message_book_of_acts,
+                           misbak, wengel, kidase_type, evangelist, message_apostle_evangelist,
+                           gitsawe_type, priority, created_at
+                    FROM gitsawes;
+
+                DROP TABLE gitsawes;
+                ALTER TABLE gitsawes_v11 RENAME TO gitsawes;
+
+                CREATE INDEX IF NOT EXISTS idx_gitsawes_priority
+                    ON gitsawes(priority);
+
+                CREATE INDEX IF NOT EXISTS idx_gitsawes_line_id
+                    ON gitsawes(line_id);
+
+                CREATE INDEX IF NOT EXISTS idx_gitsawes_gitsawe_type
+                    ON gitsawes(gitsawe_type);
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 13,
+            description: "create_text_search_index",
+            sql: r#"
+                -- Ethiopic syllables are letters to unicode61 and the wordspace (፡) is
+                -- punctuation, so Ge'ez splits into words; diacritic folding only touches Latin.
+                CREATE VIRTUAL TABLE IF NOT EXISTS text_search USING fts5(
+                    title,
+                    body,
+                    source UNINDEXED,
+                    source_id UNINDEXED,
+                    segment_id UNINDEXED,
+                    presentation_id UNINDEXED,
+                    lang UNINDEXED,
+                    tokenize = 'unicode61 remove_diacritics 2'
+                );
+
+                INSERT INTO text_search (title, body, source, source_id, segment_id, presentation_id, lang)
+                SELECT title, body, 'verse', id, segment_id, NULL, lang FROM (
+                    SELECT id, segment_id, 'Lang1' AS lang,
+                           title_lang1 AS title, text_lang1 AS body FROM verses
+                    UNION ALL SELECT id, segment_id, 'Lang2', title_lang2, text_lang2 FROM verses
+                    UNION ALL SELECT id, segment_id, 'Lang3', title_lang3, text_lang3 FROM verses
+                    UNION ALL SELECT id, segment_id, 'Lang4', title_lang4, text_lang4 FROM verses
+                )
+                WHERE coalesce(title, '') <> '' OR coalesce(body, '') <> '';
+
+                INSERT INTO text_search (title, body, source, source_id, segment_id, presentation_id, lang)
+                SELECT title, body, 'slide', id, NULL, presentation_id, lang FROM (
+                    SELECT s.id AS id, s.presentation_id AS presentation_id, l.lang AS lang,
+                           CASE WHEN json_valid(s.title_json)
+                                THEN json_extract(s.title_json, '$.' || l.lang) END AS title,
+                           (SELECT group_concat(json_extract(b.value, '$.' || l.lang), char(10))
+                            FROM json_each(CASE WHEN json_valid(s.blocks_json)
+                                                THEN s.blocks_json ELSE '[]' END) AS b
+                            WHERE b.type = 'object') AS body
+                    FROM slides AS s, (SELECT 'Lang1' AS lang UNION ALL SELECT 'Lang2'
+                                       UNION ALL SELECT 'Lang3' UNION ALL SELECT 'Lang4') AS l
+                )
+                WHERE coalesce(title, '') <> '' OR coalesce(body, '') <> '';
+
+                CREATE TRIGGER IF NOT EXISTS text_search_verses_insert AFTER INSERT ON verses BEGIN
+                    INSERT INTO text_search (title, body, source, source_id, segment_id, presentation_id, lang)
+                    SELECT title, body, 'verse', id, segment_id, NULL, lang FROM (
+                        SELECT NEW.id AS id, NEW.segment_id AS segment_id, 'Lang1' AS lang,
+                               NEW.title_lang1 AS title, NEW.text_lang1 AS body
+                        UNION ALL SELECT NEW.id, NEW.segment_id, 'Lang2', NEW.title_lang2, NEW.text_lang2
+                        UNION ALL SELECT NEW.id, NEW.segment_id, 'Lang3', NEW.title_lang3, NEW.text_lang3
+                        UNION ALL SELECT NEW.id, NEW.segment_id, 'Lang4', NEW.title_lang4, NEW.text_lang4
+                    )
+                    WHERE coalesce(title, '') <> '' OR coalesce(body, '') <> '';
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS text_search_verses_update AFTER UPDATE ON verses BEGIN
+                    DELETE FROM text_search WHERE source = 'verse' AND source_id = OLD.id;
+                    INSERT INTO text_search (title, body, source, source_id, segment_id, presentation_id, lang)
+                    SELECT title, body, 'verse', id, segment_id, NULL, lang FROM (
+                        SELECT NEW.id AS id, NEW.segment_id AS segment_id, 'Lang1' AS lang,
+                               NEW.title_lang1 AS title, NEW.text_lang1 AS body
+                        UNION ALL SELECT NEW.id, NEW.segment_id, 'Lang2', NEW.title_lang2, NEW.text_lang2
+                        UNION ALL SELECT NEW.id, NEW.segment_id, 'Lang3', NEW.title_lang3, NEW.text_lang3
+                        UNION ALL SELECT NEW.id, NEW.segment_id, 'Lang4', NEW.title_lang4, NEW.text_lang4
+                    )
+                    WHERE coalesce(title, '') <> '' OR coalesce(body, '') <> '';
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS text_search_verses_delete AFTER DELETE ON verses BEGIN
+                    DELETE FROM text_search WHERE source = 'verse' AND source_id = OLD.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS text_search_slides_insert AFTER INSERT ON slides BEGIN
+                    INSERT INTO text_search (title, body, source, source_id, segment_id, presentation_id, lang)
+                    SELECT title, body, 'slide', id, NULL, presentation_id, lang FROM (
+                        SELECT NEW.id AS id, NEW.presentation_id AS presentation_id, l.lang AS lang,
+                               CASE WHEN json_valid(NEW.title_json)
+                                    THEN json_extract(NEW.title_json, '$.' || l.lang) END AS title,
+                               (SELECT group_concat(json_extract(b.value, '$.' || l.lang), char(10))
+                                FROM json_each(CASE WHEN json_valid(NEW.blocks_json)
+                                                    THEN NEW.blocks_json ELSE '[]' END) AS b
+                                WHERE b.type = 'object') AS body
+                        FROM (SELECT 'Lang1' AS lang UNION ALL SELECT 'Lang2'
+                              UNION ALL SELECT 'Lang3' UNION ALL SELECT 'Lang4') AS l
+                    )
+                    WHERE coalesce(title, '') <> '' OR coalesce(body, '') <> '';
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS text_search_slides_update
+                AFTER UPDATE OF title_json, blocks_json, presentation_id ON slides BEGIN
+                    DELETE FROM text_search WHERE source = 'slide' AND source_id = OLD.id;
+                    INSERT INTO text_search (title, body, source, source_id, segment_id, presentation_id, lang)
+                    SELECT title, body, 'slide', id, NULL, presentation_id, lang FROM (
+                        SELECT NEW.id AS id, NEW.presentation_id AS presentation_id, l.lang AS lang,
+                               CASE WHEN json_valid(NEW.title_json)
+                                    THEN json_extract(NEW.title_json, '$.' || l.lang) END AS title,
+                               (SELECT group_concat(json_extract(b.value, '$.' || l.lang), char(10))
+                                FROM json_each(CASE WHEN json_valid(NEW.blocks_json)
+                                                    THEN NEW.blocks_json ELSE '[]' END) AS b
+                                WHERE b.type = 'object') AS body
+                        FROM (SELECT 'Lang1' AS lang UNION ALL SELECT 'Lang2'
+                              UNION ALL SELECT 'Lang3' UNION ALL SELECT 'Lang4') AS l
+                    )
+                    WHERE coalesce(title, '') <> '' OR coalesce(body, '') <> '';
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS text_search_slides_delete AFTER DELETE ON slides BEGIN
+                    DELETE FROM text_search WHERE source = 'slide' AND source_id = OLD.id;
+                END;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 13,
+            description: "revert_create_text_search_index_to_v12",
+            sql: r#"
+                DROP TRIGGER IF EXISTS text_search_slides_delete;
+                DROP TRIGGER IF EXISTS text_search_slides_update;
+                DROP TRIGGER IF EXISTS text_search_slides_insert;
+                DROP TRIGGER IF EXISTS text_search_verses_delete;
+                DROP TRIGGER IF EXISTS text_search_verses_update;
+                DROP TRIGGER IF EXISTS text_search_verses_insert;
+                DROP TABLE IF EXISTS text_search;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 14,
+            description: "add_updated_at_tracking",
+            sql: r#"
+                ALTER TABLE templates ADD COLUMN updated_at TEXT;
+                ALTER TABLE presentations ADD COLUMN updated_at TEXT;
+                ALTER TABLE slides ADD COLUMN updated_at TEXT;
+                ALTER TABLE variables ADD COLUMN updated_at TEXT;
+
+                UPDATE templates SET updated_at = created_at;
+                UPDATE presentations SET updated_at = created_at;
+                UPDATE slides SET updated_at = (SELECT created_at FROM presentations p WHERE p.id = slides.presentation_id);
+                UPDATE variables SET updated_at = (SELECT created_at FROM presentations p WHERE p.id = variables.presentation_id);
+
+                -- Rows written without updated_at get the insert time. The update
+                -- trigger only fires when a statement left updated_at unchanged,
+                -- so its own UPDATE (and any explicit timestamp) doesn't re-fire it.
+                CREATE TRIGGER IF NOT EXISTS templates_touch_insert AFTER INSERT ON templates
+                WHEN NEW.updated_at IS NULL BEGIN
+                    UPDATE templates SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS templates_touch_update AFTER UPDATE ON templates
+                WHEN NEW.updated_at IS OLD.updated_at BEGIN
+                    UPDATE templates SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS presentations_touch_insert AFTER INSERT ON presentations
+                WHEN NEW.updated_at IS NULL BEGIN
+                    UPDATE presentations SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS presentations_touch_update AFTER UPDATE ON presentations
+                WHEN NEW.updated_at IS OLD.updated_at BEGIN
+                    UPDATE presentations SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS slides_touch_insert AFTER INSERT ON slides
+                WHEN NEW.updated_at IS NULL BEGIN
+                    UPDATE slides SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS slides_touch_update AFTER UPDATE ON slides
+                WHEN NEW.updated_at IS OLD.updated_at BEGIN
+                    UPDATE slides SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS variables_touch_insert AFTER INSERT ON variables
+                WHEN NEW.updated_at IS NULL BEGIN
+                    UPDATE variables SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS variables_touch_update AFTER UPDATE ON variables
+                WHEN NEW.updated_at IS OLD.updated_at BEGIN
+                    UPDATE variables SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 14,
+            description: "revert_add_updated_at_tracking_to_v13",
+            sql: r#"
+                DROP TRIGGER IF EXISTS variables_touch_update;
+                DROP TRIGGER IF EXISTS variables_touch_insert;
+                DROP TRIGGER IF EXISTS slides_touch_update;
+                DROP TRIGGER IF EXISTS slides_touch_insert;
+                DROP TRIGGER IF EXISTS presentations_touch_update;
+                DROP TRIGGER IF EXISTS presentations_touch_insert;
+                DROP TRIGGER IF EXISTS templates_touch_update;
+                DROP TRIGGER IF EXISTS templates_touch_insert;
+
+                PRAGMA defer_foreign_keys = ON;
+
+                CREATE TABLE variables_v13 (
+                    id TEXT PRIMARY KEY,
+                    presentation_id TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    value TEXT NOT NULL,
+                    value_lang1 TEXT NOT NULL DEFAULT '',
+                    value_lang2 TEXT NOT NULL DEFAULT '',
+                    value_lang3 TEXT NOT NULL DEFAULT '',
+                    value_lang4 TEXT NOT NULL DEFAULT '',
+                    FOREIGN KEY (presentation_id) REFERENCES presentations(id)
+                );
+
+                INSERT INTO variables_v13
+                    SELECT id, presentation_id, name, value, value_lang1, value_lang2,
+                           value_lang3, value_lang4
+                    FROM variables;
+
+                DROP TABLE variables;
+                ALTER TABLE variables_v13 RENAME TO variables;
+
+                CREATE INDEX IF NOT EXISTS idx_variables_presentation
+                    ON variables(presentation_id);
+
+                CREATE TABLE slides_v13 (
+                    id TEXT PRIMARY KEY,
+                    presentation_id TEXT NOT NULL,
+                    slide_order INTEGER NOT NULL,
+                    line_id TEXT,
+                    title_json TEXT,
+                    blocks_json TEXT NOT NULL,
+                    notes TEXT,
+                    is_disabled INTEGER NOT NULL DEFAULT 0,
+                    footer_json TEXT,
+                    is_dynamic INTEGER NOT NULL DEFAULT 0,
+                    template_override_id TEXT,
+                    FOREIGN KEY (presentation_id) REFERENCES presentations(id)
+                );
+
+                INSERT INTO slides_v13
+                    SELECT id, presentation_id, slide_order, line_id, title_json, blocks_json,
+                           notes, is_disabled, footer_json, is_dynamic, template_override_id
+                    FROM slides;
+
+                DROP TABLE slides;
+                ALTER TABLE slides_v13 RENAME TO slides;
+
+                CREATE INDEX IF NOT EXISTS idx_slides_presentation
+                    ON slides(presentation_id, slide_order);
+
+                CREATE TRIGGER IF NOT EXISTS text_search_slides_insert AFTER INSERT ON slides BEGIN
+                    INSERT INTO text_search (title, body, source, source_id, segment_id, presentation_id, lang)
+                    SELECT title, body, 'slide', id, NULL, presentation_id, lang FROM (
+                        SELECT NEW.id AS id, NEW.presentation_id AS presentation_id, l.lang AS lang,
+                               CASE WHEN json_valid(NEW.title_json)
+                                    THEN json_extract(NEW.title_json, '$.' || l.lang) END AS title,
+                               (SELECT group_concat(json_extract(b.value, '$.' || l.lang), char(10))
+                                FROM json_each(CASE WHEN json_valid(NEW.blocks_json)
+                                                    THEN NEW.blocks_json ELSE '[]' END) AS b
+                                WHERE b.type = 'object') AS body
+                        FROM (SELECT 'Lang1' AS lang UNION ALL SELECT 'Lang2'
+                              UNION ALL SELECT 'Lang3' UNION ALL SELECT 'Lang4') AS l
+                    )
+                    WHERE coalesce(title, '') <> '' OR coalesce(body, '') <> '';
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS text_search_slides_update
+                AFTER UPDATE OF title_json, blocks_json, presentation_id ON slides BEGIN
+                    DELETE FROM text_search WHERE source = 'slide' AND source_id = OLD.id;
+                    INSERT INTO text_search (title, body, source, source_id, segment_id, presentation_id, lang)
+                    SELECT title, body, 'slide', id, NULL, presentation_id, lang FROM (
+                        SELECT NEW.id AS id, NEW.presentation_id AS presentation_id, l.lang AS lang,
+                               CASE WHEN json_valid(NEW.title_json)
+                                    THEN json_extract(NEW.title_json, '$.' || l.lang) END AS title,
+                               (SELECT group_concat(json_extract(b.value, '$.' || l.lang), char(10))
+                                FROM json_each(CASE WHEN json_valid(NEW.blocks_json)
+                                                    THEN NEW.blocks_json ELSE '[]' END) AS b
+                                WHERE b.type = 'object') AS body
+                        FROM (SELECT 'Lang1' AS lang UNION ALL SELECT 'Lang2'
+                              UNION ALL SELECT 'Lang3' UNION ALL SELECT 'Lang4') AS l
+                    )
+                    WHERE coalesce(title, '') <> '' OR coalesce(body, '') <> '';
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS text_search_slides_delete AFTER DELETE ON slides BEGIN
+                    DELETE FROM text_search WHERE source = 'slide' AND source_id = OLD.id;
+                END;
+
+                CREATE TEMP TABLE presentations_v13 AS
+                    SELECT id, name, type, template_id, language_map, is_active, created_at,
+                           language_settings, is_primary
+                    FROM presentations;
+
+                DROP TABLE presentations;
+
+                CREATE TABLE presentations (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    type TEXT NOT NULL,
+                    template_id TEXT NOT NULL,
+                    language_map TEXT NOT NULL,
+                    is_active INTEGER NOT NULL DEFAULT 0,
+                    created_at TEXT NOT NULL,
+                    language_settings TEXT,
+                    is_primary INTEGER NOT NULL DEFAULT 1,
+                    FOREIGN KEY (template_id) REFERENCES templates(id)
+                );
+
+                INSERT INTO presentations SELECT * FROM presentations_v13;
+                DROP TABLE presentations_v13;
+
+                CREATE TEMP TABLE templates_v13 AS
+                    SELECT id, name, max_lang_count, definition_json, created_at
+                    FROM templates;
+
+                DROP TABLE templates;
+
+                CREATE TABLE templates (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    max_lang_count INTEGER NOT NULL DEFAULT 4,
+                    definition_json TEXT NOT NULL,
+                    created_at TEXT NOT NULL
+                );
+
+                INSERT INTO templates SELECT * FROM templates_v13;
+                DROP TABLE templates_v13;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 15,
+            description: "create_edits",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS edits (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    presentation_id TEXT NOT NULL,
+                    kind TEXT NOT NULL,
+                    undo_json TEXT NOT NULL,
+                    redo_json TEXT NOT NULL,
+                    is_undone INTEGER NOT NULL DEFAULT 0,
+                    created_at TEXT NOT NULL,
+                    FOREIGN KEY (presentation_id) REFERENCES presentations(id) ON DELETE CASCADE
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_edits_presentation
+                    ON edits(presentation_id, is_undone);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 15,
+            description: "revert_create_edits_to_v14",
+            sql: r#"
+                DROP INDEX IF EXISTS idx_edits_presentation;
+                DROP TABLE IF EXISTS edits;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 16,
+            description: "create_variable_values",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS variable_values (
+                    variable_id TEXT NOT NULL,
+                    language_index INTEGER NOT NULL CHECK (language_index >= 1),
+                    value TEXT,
+                    PRIMARY KEY (variable_id, language_index),
+                    FOREIGN KEY (variable_id) REFERENCES variables(id) ON DELETE CASCADE
+                );
+
+                INSERT INTO variable_values (variable_id, language_index, value)
+                    SELECT id, 1, value_lang1 FROM variables
+                    UNION ALL SELECT id, 2, value_lang2 FROM variables
+                    UNION ALL SELECT id, 3, value_lang3 FROM variables
+                    UNION ALL SELECT id, 4, value_lang4 FROM variables;
+
+                CREATE TRIGGER IF NOT EXISTS variables_values_insert AFTER INSERT ON variables
+                BEGIN
+                    INSERT INTO variable_values (variable_id, language_index, value)
+                    VALUES (NEW.id, 1, NEW.value_lang1)
+                    ON CONFLICT(variable_id, language_index) DO UPDATE SET value = excluded.value;
+                    INSERT INTO variable_values (variable_id, language_index, value)
+                    VALUES (NEW.id, 2, NEW.value_lang2)
+                    ON CONFLICT(variable_id, language_index) DO UPDATE SET value = excluded.value;
+                    INSERT INTO variable_values (variable_id, language_index, value)
+                    VALUES (NEW.id, 3, NEW.value_lang3)
+                    ON CONFLICT(variable_id, language_index) DO UPDATE SET value = excluded.value;
+                    INSERT INTO variable_values (variable_id, language_index, value)
+                    VALUES (NEW.id, 4, NEW.value_lang4)
+                    ON CONFLICT(variable_id, language_index) DO UPDATE SET value = excluded.value;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS variables_values_update
+                AFTER UPDATE OF value_lang1, value_lang2, value_lang3, value_lang4 ON variables
+                BEGIN
+                    INSERT INTO variable_values (variable_id, language_index, value)
+                    SELECT NEW.id, 1, NEW.value_lang1 WHERE NEW.value_lang1 IS NOT OLD.value_lang1
+                    ON CONFLICT(variable_id, language_index) DO UPDATE SET value = excluded.value;
+                    INSERT INTO variable_values (variable_id, language_index, value)
+                    SELECT NEW.id, 2, NEW.value_lang2 WHERE NEW.value_lang2 IS NOT OLD.value_lang2
+                    ON CONFLICT(variable_id, language_index) DO UPDATE SET value = excluded.value;
+                    INSERT INTO variable_values (variable_id, language_index, value)
+                    SELECT NEW.id, 3, NEW.value_lang3 WHERE NEW.value_lang3 IS NOT OLD.value_lang3
+                    ON CONFLICT(variable_id, language_index) DO UPDATE SET value = excluded.value;
+                    INSERT INTO variable_values (variable_id, language_index, value)
+                    SELECT NEW.id, 4, NEW.value_lang4 WHERE NEW.value_lang4 IS NOT OLD.value_lang4
+                    ON CONFLICT(variable_id, language_index) DO UPDATE SET value = excluded.value;
+                END;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 16,
+            description: "revert_create_variable_values_to_v15",
+            sql: r#"
+                DROP TRIGGER IF EXISTS variables_values_update;
+                DROP TRIGGER IF EXISTS variables_values_insert;
+
+                UPDATE variables SET
+                    value_lang1 = COALESCE((SELECT value FROM variable_values
+                        WHERE variable_id = variables.id AND language_index = 1), value_lang1),
+                    value_lang2 = COALESCE((SELECT value FROM variable_values
+                        WHERE variable_id = variables.id AND language_index = 2), value_lang2),
+                    value_lang3 = COALESCE((SELECT value FROM variable_values
+                        WHERE variable_id = variables.id AND language_index = 3), value_lang3),
+                    value_lang4 = COALESCE((SELECT value FROM variable_values
+                        WHERE variable_id = variables.id AND language_index = 4), value_lang4);
+
+                DROP TABLE IF EXISTS variable_values;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 17,
+            description: "add_deleted_at_to_presentations",
+            sql: r#"
+                ALTER TABLE presentations ADD COLUMN deleted_at TEXT;
+
+                CREATE INDEX IF NOT EXISTS idx_presentations_deleted_at
+                    ON presentations(deleted_at);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 17,
+            description: "revert_add_deleted_at_to_presentations_to_v16",
+            sql: r#"
+                PRAGMA defer_foreign_keys = ON;
+
+                DROP INDEX IF EXISTS idx_presentations_deleted_at;
+
+                -- Dropping presentations cascades to edits, so they're set aside too
+
+                CREATE TEMP TABLE edits_v16 AS SELECT * FROM edits;
+
+                CREATE TEMP TABLE presentations_v16 AS
+                    SELECT id, name, type, template_id, language_map, is_active, created_at,
+                           language_settings, is_primary, updated_at
+                    FROM presentations;
+
+                DROP TABLE presentations;
+
+                CREATE TABLE presentations (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    type TEXT NOT NULL,
+                    template_id TEXT NOT NULL,
+                    language_map TEXT NOT NULL,
+                    is_active INTEGER NOT NULL DEFAULT 0,
+                    created_at TEXT NOT NULL,
+                    language_settings TEXT,
+                    is_primary INTEGER NOT NULL DEFAULT 1,
+                    updated_at TEXT,
+                    FOREIGN KEY (template_id) REFERENCES templates(id)
+                );
+
+                INSERT INTO presentations SELECT * FROM presentations_v16;
+                DROP TABLE presentations_v16;
+
+                INSERT INTO edits SELECT * FROM edits_v16;
+                DROP TABLE edits_v16;
+
+                CREATE TRIGGER IF NOT EXISTS presentations_touch_insert AFTER INSERT ON presentations
+                WHEN NEW.updated_at IS NULL BEGIN
+                    UPDATE presentations SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS presentations_touch_update AFTER UPDATE ON presentations
+                WHEN NEW.updated_at IS OLD.updated_at BEGIN
+                    UPDATE presentations SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 18,
+            description: "create_audit_log",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS audit_log (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    at TEXT NOT NULL,
+                    operation TEXT NOT NULL,
+                    entity_type TEXT NOT NULL,
+                    entity_id TEXT NOT NULL,
+                    detail_json TEXT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_audit_log_entity
+                    ON audit_log(entity_id);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 18,
+            description: "revert_create_audit_log_to_v17",
+            sql: r#"
+                DROP INDEX IF EXISTS idx_audit_log_entity;
+                DROP TABLE IF EXISTS audit_log;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 19,
+            description: "add_parent_id_to_presentations",
+            // No REFERENCES clause; set_presentation_primary checks it.
+            // Existing secondaries are left without a parent rather than guessed
+            // one; they're asked for it when next made secondary.
+            sql: r#"
+                ALTER TABLE presentations ADD COLUMN parent_id TEXT;
+
+                CREATE INDEX IF NOT EXISTS idx_presentations_parent_id
+                    ON presentations(parent_id);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 19,
+            description: "revert_add_parent_id_to_presentations_to_v18",
+            sql: r#"
+                PRAGMA defer_foreign_keys = ON;
+
+                DROP INDEX IF EXISTS idx_presentations_parent_id;
+
+                -- Dropping presentations cascades to edits, so they're set aside too
+
+                CREATE TEMP TABLE edits_v18 AS SELECT * FROM edits;
+
+                CREATE TEMP TABLE presentations_v18 AS
+                    SELECT id, name, type, template_id, language_map, is_active, created_at,
+                           language_settings, is_primary, updated_at, deleted_at
+                    FROM presentations;
+
+                DROP TABLE presentations;
+
+                CREATE TABLE presentations (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    type TEXT NOT NULL,
+                    template_id TEXT NOT NULL,
+                    language_map TEXT NOT NULL,
+                    is_active INTEGER NOT NULL DEFAULT 0,
+                    created_at TEXT NOT NULL,
+                    language_settings TEXT,
+                    is_primary INTEGER NOT NULL DEFAULT 1,
+                    updated_at TEXT,
+                    deleted_at TEXT,
+                    FOREIGN KEY (template_id) REFERENCES templates(id)
+                );
+
+                INSERT INTO presentations SELECT * FROM presentations_v18;
+                DROP TABLE presentations_v18;
+
+                INSERT INTO edits SELECT * FROM edits_v18;
+                DROP TABLE edits_v18;
+
+                CREATE INDEX IF NOT EXISTS idx_presentations_deleted_at
+                    ON presentations(deleted_at);
+
+                CREATE TRIGGER IF NOT EXISTS presentations_touch_insert AFTER INSERT ON presentations
+                WHEN NEW.updated_at IS NULL BEGIN
+                    UPDATE presentations SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS presentations_touch_update AFTER UPDATE ON presentations
+                WHEN NEW.updated_at IS OLD.updated_at BEGIN
+                    UPDATE presentations SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 20,
+            description: "add_display_scale_to_presentations",
+            sql: "ALTER TABLE presentations ADD COLUMN display_scale REAL;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 20,
+            description: "revert_add_display_scale_to_presentations_to_v19",
+            sql: r#"
+                PRAGMA defer_foreign_keys = ON;
+
+                -- Dropping presentations cascades to edits, so they're set aside too
+
+                CREATE TEMP TABLE edits_v19 AS SELECT * FROM edits;
+
+                CREATE TEMP TABLE presentations_v19 AS
+                    SELECT id, name, type, template_id, language_map, is_active, created_at,
+                           language_settings, is_primary, updated_at, deleted_at, parent_id
+                    FROM presentations;
+
+                DROP TABLE presentations;
+
+                CREATE TABLE presentations (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    type TEXT NOT NULL,
+                    template_id TEXT NOT NULL,
+                    language_map TEXT NOT NULL,
+                    is_active INTEGER NOT NULL DEFAULT 0,
+                    created_at TEXT NOT NULL,
+                    language_settings TEXT,
+                    is_primary INTEGER NOT NULL DEFAULT 1,
+                    updated_at TEXT,
+                    deleted_at TEXT,
+                    parent_id TEXT,
+                    FOREIGN KEY (template_id) REFERENCES templates(id)
+                );
+
+                INSERT INTO presentations SELECT * FROM presentations_v19;
+                DROP TABLE presentations_v19;
+
+                INSERT INTO edits SELECT * FROM edits_v19;
+                DROP TABLE edits_v19;
+
+                CREATE INDEX IF NOT EXISTS idx_presentations_deleted_at
+                    ON presentations(deleted_at);
+
+                CREATE INDEX IF NOT EXISTS idx_presentations_parent_id
+                    ON presentations(parent_id);
+
+                CREATE TRIGGER IF NOT EXISTS presentations_touch_insert AFTER INSERT ON presentations
+                WHEN NEW.updated_at IS NULL BEGIN
+                    UPDATE presentations SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS presentations_touch_update AFTER UPDATE ON presentations
+                WHEN NEW.updated_at IS OLD.updated_at BEGIN
+                    UPDATE presentations SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 21,
+            description: "add_version_to_slides",
+            sql: r#"
+                ALTER TABLE slides ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+
+                -- Any write to a slide's content moves it to the next version, so
+                -- an editor holding the old one can tell its copy is stale.
+                -- Renumbering doesn't count: reordering would otherwise make every
+                -- open copy stale. A statement that sets version itself
+                -- (update_slide) is left alone, and the nested UPDATE touches only
+                -- version, so neither this nor the updated_at trigger's own UPDATE
+                -- bumps it twice.
+                CREATE TRIGGER IF NOT EXISTS slides_bump_version
+                AFTER UPDATE OF line_id, title_json, blocks_json, footer_json, notes,
+                    is_disabled, is_dynamic, template_override_id ON slides
+                WHEN NEW.version IS OLD.version BEGIN
+                    UPDATE slides SET version = OLD.version + 1 WHERE id = NEW.id;
+                END;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 21,
+            description: "revert_add_version_to_slides_to_v20",
+            sql: r#"
+                DROP TRIGGER IF EXISTS slides_bump_version;
+
+                CREATE TABLE slides_v20 (
+                    id TEXT PRIMARY KEY,
+                    presentation_id TEXT NOT NULL,
+                    slide_order INTEGER NOT NULL,
+                    line_id TEXT,
+                    title_json TEXT,
+                    blocks_json TEXT NOT NULL,
+                    notes TEXT,
+                    is_disabled INTEGER NOT NULL DEFAULT 0,
+                    footer_json TEXT,
+                    is_dynamic INTEGER NOT NULL DEFAULT 0,
+                    template_override_id TEXT,
+                    updated_at TEXT,
+                    FOREIGN KEY (presentation_id) REFERENCES presentations(id)
+                );
+
+                INSERT INTO slides_v20
+                    SELECT id, presentation_id, slide_order, line_id, title_json, blocks_json,
+                           notes, is_disabled, footer_json, is_dynamic, template_override_id,
+                           updated_at
+                    FROM slides;
+
+                DROP TABLE slides;
+                ALTER TABLE slides_v20 RENAME TO slides;
+
+                CREATE INDEX IF NOT EXISTS idx_slides_presentation
+                    ON slides(presentation_id, slide_order);
+
+                CREATE TRIGGER IF NOT EXISTS text_search_slides_insert AFTER INSERT ON slides BEGIN
+                    INSERT INTO text_search (title, body, source, source_id, segment_id, presentation_id, lang)
+                    SELECT title, body, 'slide', id, NULL, presentation_id, lang FROM (
+                        SELECT NEW.id AS id, NEW.presentation_id AS presentation_id, l.lang AS lang,
+                               CASE WHEN json_valid(NEW.title_json)
+                                    THEN json_extract(NEW.title_json, '$.' || l.lang) END AS title,
+                               (SELECT group_concat(json_extract(b.value, '$.' || l.lang), char(10))
+                                FROM json_each(CASE WHEN json_valid(NEW.blocks_json)
+                                                    THEN NEW.blocks_json ELSE '[]' END) AS b
+                                WHERE b.type = 'object') AS body
+                        FROM (SELECT 'Lang1' AS lang UNION ALL SELECT 'Lang2'
+                              UNION ALL SELECT 'Lang3' UNION ALL SELECT 'Lang4') AS l
+                    )
+                    WHERE coalesce(title, '') <> '' OR coalesce(body, '') <> '';
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS text_search_slides_update
+                AFTER UPDATE OF title_json, blocks_json, presentation_id ON slides BEGIN
+                    DELETE FROM text_search WHERE source = 'slide' AND source_id = OLD.id;
+                    INSERT INTO text_search (title, body, source, source_id, segment_id, presentation_id, lang)
+                    SELECT title, body, 'slide', id, NULL, presentation_id, lang FROM (
+                        SELECT NEW.id AS id, NEW.presentation_id AS presentation_id, l.lang AS lang,
+                               CASE WHEN json_valid(NEW.title_json)
+                                    THEN json_extract(NEW.title_json, '$.' || l.lang) END AS title,
+                               (SELECT group_concat(json_extract(b.value, '$.' || l.lang), char(10))
+                                FROM json_each(CASE WHEN json_valid(NEW.blocks_json)
+                                                    THEN NEW.blocks_json ELSE '[]' END) AS b
+                                WHERE b.type = 'object') AS body
+                        FROM (SELECT 'Lang1' AS lang UNION ALL SELECT 'Lang2'
+                              UNION ALL SELECT 'Lang3' UNION ALL SELECT 'Lang4') AS l
+                    )
+                    WHERE coalesce(title, '') <> '' OR coalesce(body, '') <> '';
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS text_search_slides_delete AFTER DELETE ON slides BEGIN
+                    DELETE FROM text_search WHERE source = 'slide' AND source_id = OLD.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS slides_touch_insert AFTER INSERT ON slides
+                WHEN NEW.updated_at IS NULL BEGIN
+                    UPDATE slides SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS slides_touch_update AFTER UPDATE ON slides
+                WHEN NEW.updated_at IS OLD.updated_at BEGIN
+                    UPDATE slides SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = NEW.id;
+                END;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 22,
+            description: "add_present_mode_write_guard",
+            // Refuses writes to content from every connection while present mode
+            // is on, the frontend's through the SQL plugin included. Commands
+            // still check first (db::writable_pool) for a plain error message.
+            // Which presentation is active isn't content: the operator still
+            // opens the next one mid-service.
+            sql: r#"
+                CREATE TRIGGER IF NOT EXISTS templates_present_mode_insert
+                BEFORE INSERT ON templates WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS templates_present_mode_update
+                BEFORE UPDATE ON templates WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS templates_present_mode_delete
+                BEFORE DELETE ON templates WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS presentations_present_mode_insert
+                BEFORE INSERT ON presentations WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS presentations_present_mode_update
+                BEFORE UPDATE OF name, type, template_id, language_map, language_settings,
+                    is_primary, parent_id, display_scale, deleted_at
+                ON presentations WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS presentations_present_mode_delete
+                BEFORE DELETE ON presentations WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS slides_present_mode_insert
+                BEFORE INSERT ON slides WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS slides_present_mode_update
+                BEFORE UPDATE ON slides WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS slides_present_mode_delete
+                BEFORE DELETE ON slides WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS variables_present_mode_insert
+                BEFORE INSERT ON variables WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS variables_present_mode_update
+                BEFORE UPDATE ON variables WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS variables_present_mode_delete
+                BEFORE DELETE ON variables WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS variable_values_present_mode_insert
+                BEFORE INSERT ON variable_values WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS variable_values_present_mode_update
+                BEFORE UPDATE ON variable_values WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS variable_values_present_mode_delete
+                BEFORE DELETE ON variable_values WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS rule_definitions_present_mode_insert
+                BEFORE INSERT ON rule_definitions WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS rule_definitions_present_mode_update
+                BEFORE UPDATE ON rule_definitions WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS rule_definitions_present_mode_delete
+                BEFORE DELETE ON rule_definitions WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS gitsawes_present_mode_insert
+                BEFORE INSERT ON gitsawes WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS gitsawes_present_mode_update
+                BEFORE UPDATE ON gitsawes WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS gitsawes_present_mode_delete
+                BEFORE DELETE ON gitsawes WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS verses_present_mode_insert
+                BEFORE INSERT ON verses WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS verses_present_mode_update
+                BEFORE UPDATE ON verses WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS verses_present_mode_delete
+                BEFORE DELETE ON verses WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 22,
+            description: "revert_add_present_mode_write_guard_to_v21",
+            sql: r#"
+                DROP TRIGGER IF EXISTS verses_present_mode_delete;
+                DROP TRIGGER IF EXISTS verses_present_mode_update;
+                DROP TRIGGER IF EXISTS verses_present_mode_insert;
+                DROP TRIGGER IF EXISTS gitsawes_present_mode_delete;
+                DROP TRIGGER IF EXISTS gitsawes_present_mode_update;
+                DROP TRIGGER IF EXISTS gitsawes_present_mode_insert;
+                DROP TRIGGER IF EXISTS rule_definitions_present_mode_delete;
+                DROP TRIGGER IF EXISTS rule_definitions_present_mode_update;
+                DROP TRIGGER IF EXISTS rule_definitions_present_mode_insert;
+                DROP TRIGGER IF EXISTS variable_values_present_mode_delete;
+                DROP TRIGGER IF EXISTS variable_values_present_mode_update;
+                DROP TRIGGER IF EXISTS variable_values_present_mode_insert;
+                DROP TRIGGER IF EXISTS variables_present_mode_delete;
+                DROP TRIGGER IF EXISTS variables_present_mode_update;
+                DROP TRIGGER IF EXISTS variables_present_mode_insert;
+                DROP TRIGGER IF EXISTS slides_present_mode_delete;
+                DROP TRIGGER IF EXISTS slides_present_mode_update;
+                DROP TRIGGER IF EXISTS slides_present_mode_insert;
+                DROP TRIGGER IF EXISTS presentations_present_mode_delete;
+                DROP TRIGGER IF EXISTS presentations_present_mode_update;
+                DROP TRIGGER IF EXISTS presentations_present_mode_insert;
+                DROP TRIGGER IF EXISTS templates_present_mode_delete;
+                DROP TRIGGER IF EXISTS templates_present_mode_update;
+                DROP TRIGGER IF EXISTS templates_present_mode_insert;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 23,
+            description: "create_scheduled_services",
+            // Which presentations are prepared for which day, by Gregorian
+            // YYYY-MM-DD as `meta.date` has it
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS scheduled_services (
+                    service_date TEXT NOT NULL,
+                    presentation_id TEXT NOT NULL,
+                    created_at TEXT NOT NULL,
+                    PRIMARY KEY (service_date, presentation_id),
+                    FOREIGN KEY (presentation_id) REFERENCES presentations(id) ON DELETE CASCADE
+                );
+
+                CREATE TRIGGER IF NOT EXISTS scheduled_services_present_mode_insert
+                BEFORE INSERT ON scheduled_services WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS scheduled_services_present_mode_update
+                BEFORE UPDATE ON scheduled_services WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+                CREATE TRIGGER IF NOT EXISTS scheduled_services_present_mode_delete
+                BEFORE DELETE ON scheduled_services WHEN (SELECT value FROM app_settings WHERE key = 'presentMode') = 'true'
+                BEGIN SELECT RAISE(ABORT, 'PresentModeActive: changes are locked while presenting; turn off present mode first'); END;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 23,
+            description: "revert_create_scheduled_services_to_v22",
+            sql: r#"
+                DROP TRIGGER IF EXISTS scheduled_services_present_mode_delete;
+                DROP TRIGGER IF EXISTS scheduled_services_present_mode_update;
+                DROP TRIGGER IF EXISTS scheduled_services_present_mode_insert;
+                DROP TABLE IF EXISTS scheduled_services;
+            "#,
+            kind: MigrationKind::Down,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    /// Tables with their columns, foreign keys, indexes and triggers.
+    async fn schema(pool: &sqlx::SqlitePool) -> Vec<String> {
+        sqlx::query_scalar(
+            "SELECT m.type || ' ' || m.name || ' ' || m.tbl_name FROM sqlite_schema m
+             WHERE m.name NOT LIKE 'sqlite_%'
+             UNION ALL
+             SELECT m.name || '.' || c.name || ' ' || c.type || ' ' || c.\"notnull\" || ' '
+                    || coalesce(c.dflt_value, '') || ' ' || c.pk
+             FROM sqlite_schema m JOIN pragma_table_info(m.name) c ON 1
+             WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%'
+             UNION ALL
+             SELECT m.name || ' -> ' || f.\"table\" || '(' || f.\"to\" || ') ' || f.on_delete
+             FROM sqlite_schema m JOIN pragma_foreign_key_list(m.name) f ON 1
+             WHERE m.type = 'table'
+             ORDER BY 1",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn every_down_restores_the_previous_schema_and_keeps_the_rows() {
+        let latest = migrations().iter().map(|m| m.version).max().unwrap();
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        test_support::slide(&pool, "s1", "p1", 1).await;
+        test_support::exec(
+            &pool,
+            "INSERT INTO variables (id, presentation_id, name, value) VALUES ('v1', 'p1', 'SAINT', 'Mary');
+             INSERT INTO edits (presentation_id, kind, undo_json, redo_json, created_at)
+                 VALUES ('p1', 'slide', '{}', '{}', '2026-01-01T00:00:00.000Z');
+             INSERT INTO gitsawes (id, line_id, priority, created_at)
+                 VALUES ('g1', 'line', 1, '2026-01-01T00:00:00.000Z')",
+        )
+        .await;
+
+        for version in (2..=latest).rev() {
+            test_support::migrate(&pool, version..=version, MigrationKind::Down).await;
+            let expected = test_support::pool_at(version - 1).await;
+            assert_eq!(
+                schema(&pool).await,
+                schema(&expected).await,
+                "after reverting {version}"
+            );
+
+            let problems: Vec<(String,)> =
+                sqlx::query_as("SELECT \"table\" FROM pragma_foreign_key_check")
+                    .fetch_all(&pool)
+                    .await
+                    .unwrap();
+            assert!(
+                problems.is_empty(),
+                "after reverting {version}: {problems:?}"
+            );
+            for table in ["presentations", "slides", "variables"] {
+                let rows: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+                assert_eq!(rows, 1, "{table} after reverting {version}");
+            }
+            if version > 15 {
+                let edits: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM edits")
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+                assert_eq!(edits, 1, "edits after reverting {version}");
+            }
+        }
+
+        test_support::migrate(&pool, 2..=latest, MigrationKind::Up).await;
+        assert_eq!(
+            schema(&pool).await,
+            schema(&test_support::pool().await).await
+        );
+    }
+
+    #[tokio::test]
+    async fn existing_secondaries_are_left_without_a_parent() {
+        let pool = test_support::pool_at(18).await;
+        test_support::exec(
+            &pool,
+            "INSERT INTO templates (id, name, definition_json, created_at)
+                 VALUES ('t1', 't1', '{}', '2026-01-01T00:00:00.000Z');
+             INSERT INTO presentations
+                 (id, name, type, template_id, language_map, is_primary, created_at)
+             VALUES ('main', 'main', 'kidase', 't1', '{}', 1, '2026-01-01T00:00:00.000Z'),
+                    ('vigil', 'vigil', 'kidase', 't1', '{}', 0, '2026-01-02T00:00:00.000Z')",
+        )
+        .await;
+        test_support::migrate(&pool, 19..=19, MigrationKind::Up).await;
+
+        let parents: Vec<Option<String>> =
+            sqlx::query_scalar("SELECT parent_id FROM presentations ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(parents, [None, None]);
+    }
+}
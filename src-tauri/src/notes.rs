@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_sql::DbInstances;
+
+use crate::db::{self, Slide};
+
+/// How the first column of a notes file identifies its slide.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotesMatch {
+    /// The displayed 1-based `slide_order`.
+    Order,
+    /// The slide's first non-empty title text, compared case-insensitively.
+    Title,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmatchedNote {
+    pub line: usize,
+    pub key: String,
+}
+
+struct NoteRow {
+    line: usize,
+    key: String,
+    /// `None` when the line has no separator and cannot be applied.
+    text: Option<String>,
+}
+
+/// Merge presenter notes from a `key<TAB>note` (or `key,note`) text file.
+///
+/// Lines that match no slide are emitted as an `import-notes-unmatched` event.
+#[tauri::command]
+pub async fn import_notes(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    path: String,
+    match_by: NotesMatch,
+) -> Result<usize, String> {
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    let rows = parse_notes(&content);
+
+    let pool = db::writable_pool(&db).await?;
+    db::fetch_presentation(&pool, &presentation_id).await?;
+    let slides = db::fetch_slides(&pool, &presentation_id).await?;
+
+    let mut updates: Vec<(&str, &str)> = Vec::new();
+    let mut unmatched = Vec::new();
+    for row in &rows {
+        let slide = find_slide(&slides, &row.key, match_by);
+        match (slide, row.text.as_deref()) {
+            (Some(slide), Some(text)) => updates.push((slide.id.as_str(), text)),
+            _ => unmatched.push(UnmatchedNote {
+                line: row.line,
+                key: row.key.clone(),
+            }),
+        }
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for (slide_id, text) in &updates {
+        sqlx::query("UPDATE slides SET notes = ? WHERE id = ?")
+            .bind(text)
+            .bind(slide_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    if !unmatched.is_empty() {
+        let _ = app.emit("import-notes-unmatched", &unmatched);
+    }
+
+    Ok(updates.len())
+}
+
+fn parse_notes(content: &str) -> Vec<NoteRow> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(index, line)| {
+            let split = line.split_once('\t').or_else(|| line.split_once(','));
+            let (key, text) = match split {
+                Some((key, text)) => (key, Some(text)),
+                None => (line, None),
+            };
+            NoteRow {
+                line: index + 1,
+                key: key.trim().to_string(),
+                // Literal `\n` lets a single line carry a multi-line note
+                text: text.map(|t| t.trim().replace("\\n", "\n")),
+            }
+        })
+        .collect()
+}
+
+fn find_slide<'a>(slides: &'a [Slide], key: &str, match_by: NotesMatch) -> Option<&'a Slide> {
+    match match_by {
+        NotesMatch::Order => {
+            let order: i64 = key.parse().ok()?;
+            slides.iter().find(|s| s.slide_order == order)
+        }
+        NotesMatch::Title => {
+            let key = key.to_lowercase();
+            slides
+                .iter()
+                .find(|s| s.title_text().is_some_and(|t| t.to_lowercase() == key))
+        }
+    }
+}
@@ -0,0 +1,448 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Connection, SqliteConnection, SqliteExecutor};
+use tauri::{
+    AppHandle, Emitter, Manager, Monitor, PhysicalPosition, PhysicalSize, State, WebviewUrl,
+    WebviewWindowBuilder,
+};
+use tauri_plugin_sql::DbInstances;
+
+use crate::db;
+use crate::window_layout::{find_monitor, MonitorId};
+
+/// Event emitted whenever a display is connected, disconnected or rearranged,
+/// carrying the displays now connected as `MonitorId`s.
+pub const DISPLAYS_CHANGED: &str = "displays-changed";
+/// Event emitted when an output's display disconnects, carrying an
+/// [`OutputDisplay`]. The output is hidden until it's back.
+pub const OUTPUT_DISPLAY_LOST: &str = "output-display-lost";
+/// Event emitted when an output is shown again on its reconnected display,
+/// carrying an [`OutputDisplay`].
+pub const OUTPUT_DISPLAY_RESTORED: &str = "output-display-restored";
+
+/// A window the app shows fullscreen on a display of its own.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputKind {
+    /// The slides, for the congregation; rendered as `AudienceView`.
+    #[default]
+    Audience,
+    /// Notes, the next slide and a clock, for the chanters; rendered as
+    /// `StageView`.
+    Stage,
+}
+
+impl OutputKind {
+    const ALL: [OutputKind; 2] = [OutputKind::Audience, OutputKind::Stage];
+
+    /// Window label; the frontend picks its view from the same name.
+    pub fn label(self) -> &'static str {
+        match self {
+            OutputKind::Audience => "audience",
+            OutputKind::Stage => "stage",
+        }
+    }
+
+    fn url(self) -> &'static str {
+        match self {
+            OutputKind::Audience => "/?view=audience",
+            OutputKind::Stage => "/?view=stage",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            OutputKind::Audience => "Presentation",
+            OutputKind::Stage => "Stage Display",
+        }
+    }
+
+    /// `app_settings` keys: the display chosen for the output, and `"true"`
+    /// while its window is open so it comes back after a restart.
+    fn display_key(self) -> &'static str {
+        match self {
+            OutputKind::Audience => "outputDisplay",
+            OutputKind::Stage => "stageDisplay",
+        }
+    }
+
+    fn open_key(self) -> &'static str {
+        match self {
+            OutputKind::Audience => "outputWindowOpen",
+            OutputKind::Stage => "stageWindowOpen",
+        }
+    }
+}
+
+/// Payload of `output-display-lost` and `output-display-restored`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputDisplay {
+    pub output: OutputKind,
+    pub display: MonitorId,
+}
+
+/// How often the connected displays are compared; no platform event covers
+/// all three operating systems.
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Where each open output belongs, so it can follow its display being
+/// unplugged and plugged back in.
+#[derive(Default)]
+pub struct OutputWatcher(Mutex<HashMap<OutputKind, Target>>);
+
+struct Target {
+    display: MonitorId,
+    /// The display is disconnected and the output hidden.
+    lost: bool,
+}
+
+impl OutputWatcher {
+    /// Compare the connected displays every couple of seconds on a thread of
+    /// its own, for as long as the app runs.
+    pub fn start(app: &AppHandle) -> Result<(), String> {
+        let app = app.clone();
+        std::thread::Builder::new()
+            .name("display-watch".to_string())
+            .spawn(move || {
+                let mut known = connected(&app);
+                loop {
+                    std::thread::sleep(WATCH_INTERVAL);
+                    let Ok(monitors) = app.available_monitors() else {
+                        continue;
+                    };
+                    let current: Vec<MonitorId> = monitors.iter().map(MonitorId::from).collect();
+                    if current == known {
+                        continue;
+                    }
+                    let _ = app.emit(DISPLAYS_CHANGED, &current);
+                    known = current;
+                    app.state::<OutputWatcher>().follow(&app, &monitors);
+                }
+            })
+            .map_err(|e| format!("Failed to watch for display changes: {e}"))?;
+        Ok(())
+    }
+
+    fn set(&self, output: OutputKind, target: Option<Target>) {
+        if let Ok(mut targets) = self.0.lock() {
+            match target {
+                Some(target) => targets.insert(output, target),
+                None => targets.remove(&output),
+            };
+        }
+    }
+
+    /// Hide an output when its display is gone so it doesn't land on the
+    /// operator's screen, and put it back once the display returns.
+    fn follow(&self, app: &AppHandle, monitors: &[Monitor]) {
+        let Ok(mut targets) = self.0.lock() else {
+            return;
+        };
+        for (&output, target) in targets.iter_mut() {
+            match find_monitor(monitors, &target.display) {
+                None if !target.lost => {
+                    target.lost = true;
+                    if let Some(window) = app.get_webview_window(output.label()) {
+                        let _ = window.hide();
+                    }
+                    let _ = app.emit(OUTPUT_DISPLAY_LOST, target.event(output));
+                }
+                None => {}
+                Some(monitor) => {
+                    let moved = MonitorId::from(monitor) != target.display;
+                    if !target.lost && !moved {
+                        continue;
+                    }
+                    // Recreated if it was closed while the display was away
+                    if let Err(e) = show_on(app, output, monitor) {
+                        eprintln!("Couldn't restore the {} window: {e}", output.label());
+                        continue;
+                    }
+                    target.display = MonitorId::from(monitor);
+                    if target.lost {
+                        target.lost = false;
+                        let _ = app.emit(OUTPUT_DISPLAY_RESTORED, target.event(output));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Target {
+    fn event(&self, output: OutputKind) -> OutputDisplay {
+        OutputDisplay {
+            output,
+            display: self.display.clone(),
+        }
+    }
+}
+
+fn connected(app: &AppHandle) -> Vec<MonitorId> {
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(MonitorId::from)
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayInfo {
+    /// What `open_output_window` takes; valid until displays are rearranged.
+    pub id: String,
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub primary: bool,
+    /// The display last chosen for the output.
+    pub selected: bool,
+    /// Whether the output window is on it now.
+    pub output: bool,
+}
+
+/// Connected displays, in the order the platform lists them. `selected` and
+/// `output` describe `output`, the audience unless given.
+#[tauri::command]
+pub async fn list_displays(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    output: Option<OutputKind>,
+) -> Result<Vec<DisplayInfo>, String> {
+    let kind = output.unwrap_or_default();
+    let pool = db::pool(&db).await?;
+    let saved = load_display(&pool, kind).await?;
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+    let primary = app
+        .primary_monitor()
+        .map_err(|e| e.to_string())?
+        .map(|monitor| MonitorId::from(&monitor));
+    let selected = saved
+        .as_ref()
+        .and_then(|saved| find_monitor(&monitors, saved))
+        .map(MonitorId::from);
+    let output = match app.get_webview_window(kind.label()) {
+        Some(window) => window
+            .current_monitor()
+            .map_err(|e| e.to_string())?
+            .map(|monitor| MonitorId::from(&monitor)),
+        None => None,
+    };
+
+    Ok(monitors
+        .iter()
+        .map(|monitor| {
+            let id = MonitorId::from(monitor);
+            DisplayInfo {
+                id: display_id(&id),
+                name: id.name.clone(),
+                x: id.x,
+                y: id.y,
+                width: id.width,
+                height: id.height,
+                scale_factor: monitor.scale_factor(),
+                primary: primary.as_ref() == Some(&id),
+                selected: selected.as_ref() == Some(&id),
+                output: output.as_ref() == Some(&id),
+            }
+        })
+        .collect())
+}
+
+/// Show `output`, the audience unless given, fullscreen and borderless on
+/// `display_id`, moving it there if it's already open elsewhere, and remember
+/// the display so the output comes back on it after a restart.
+#[tauri::command]
+pub async fn open_output_window(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    display_id: String,
+    output: Option<OutputKind>,
+) -> Result<DisplayInfo, String> {
+    let kind = output.unwrap_or_default();
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+    let monitor = monitors
+        .iter()
+        .find(|monitor| display_id_of(monitor) == display_id)
+        .ok_or_else(|| format!("Display not found: {display_id}"))?;
+    show_on(&app, kind, monitor)?;
+    app.state::<OutputWatcher>().set(
+        kind,
+        Some(Target {
+            display: MonitorId::from(monitor),
+            lost: false,
+        }),
+    );
+
+    let pool = db::pool(&db).await?;
+    let saved = serde_json::to_string(&MonitorId::from(monitor)).map_err(|e| e.to_string())?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    save_setting(&mut *tx, kind.display_key(), &saved).await?;
+    save_setting(&mut *tx, kind.open_key(), "true").await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    list_displays(app, db, Some(kind))
+        .await?
+        .into_iter()
+        .find(|display| display.id == display_id)
+        .ok_or_else(|| format!("Display not found: {display_id}"))
+}
+
+/// Close `output`, the audience unless given. The display stays chosen, but
+/// the window isn't reopened at the next start.
+#[tauri::command]
+pub async fn close_output_window(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    output: Option<OutputKind>,
+) -> Result<(), String> {
+    let kind = output.unwrap_or_default();
+    app.state::<OutputWatcher>().set(kind, None);
+    if let Some(window) = app.get_webview_window(kind.label()) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    let pool = db::pool(&db).await?;
+    save_setting(&pool, kind.open_key(), "false").await
+}
+
+/// Reopen the outputs that were open when the app last closed, each on its
+/// display once that's connected. Runs from setup, before the frontend has
+/// opened the database, so it reads the settings on a connection of its own.
+pub async fn reattach(app: AppHandle) {
+    let saved = async {
+        let path = db::db_path(&app)?;
+        let mut connection = SqliteConnection::connect_with(&db::connect_options(&path))
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut saved = Vec::new();
+        for kind in OutputKind::ALL {
+            let open = load_setting(&mut connection, kind.open_key()).await?;
+            let display = load_display(&mut connection, kind).await?;
+            if let Some(display) = display.filter(|_| open.as_deref() == Some("true")) {
+                saved.push((kind, display));
+            }
+        }
+        connection.close().await.map_err(|e| e.to_string())?;
+        Ok::<_, String>(saved)
+    }
+    .await;
+
+    // A new database has no settings to read yet
+    let Ok(saved) = saved else {
+        return;
+    };
+    let monitors = app.available_monitors().unwrap_or_default();
+    for (kind, display) in saved {
+        // Unlike a window layout, an output never falls back to another
+        // display: that would put the service on the operator's screen. It's
+        // shown once the display is plugged in instead.
+        let Some(monitor) = find_monitor(&monitors, &display) else {
+            app.state::<OutputWatcher>().set(
+                kind,
+                Some(Target {
+                    display,
+                    lost: true,
+                }),
+            );
+            continue;
+        };
+        if let Err(e) = show_on(&app, kind, monitor) {
+            eprintln!("Couldn't restore the {} window: {e}", kind.label());
+            continue;
+        }
+        app.state::<OutputWatcher>().set(
+            kind,
+            Some(Target {
+                display: MonitorId::from(monitor),
+                lost: false,
+            }),
+        );
+    }
+}
+
+fn show_on(app: &AppHandle, output: OutputKind, monitor: &Monitor) -> Result<(), String> {
+    let window = match app.get_webview_window(output.label()) {
+        Some(window) => window,
+        None => {
+            WebviewWindowBuilder::new(app, output.label(), WebviewUrl::App(output.url().into()))
+                .title(output.title())
+                .decorations(false)
+                .always_on_top(true)
+                .skip_taskbar(true)
+                .focused(false)
+                .visible(false)
+                .build()
+                .map_err(|e| format!("Failed to open the {} window: {e}", output.label()))?
+        }
+    };
+
+    // A fullscreen window ignores moves, and goes fullscreen on whichever
+    // display it's on, so place it first
+    if window.is_fullscreen().map_err(|e| e.to_string())? {
+        window.set_fullscreen(false).map_err(|e| e.to_string())?;
+    }
+    let position = monitor.position();
+    let size = monitor.size();
+    window
+        .set_position(PhysicalPosition::new(position.x, position.y))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_size(PhysicalSize::new(size.width, size.height))
+        .map_err(|e| e.to_string())?;
+    window.set_fullscreen(true).map_err(|e| e.to_string())?;
+    window.show().map_err(|e| e.to_string())
+}
+
+fn display_id_of(monitor: &Monitor) -> String {
+    display_id(&MonitorId::from(monitor))
+}
+
+/// `<name>@<x>,<y>`, so two displays of the same model still differ.
+fn display_id(id: &MonitorId) -> String {
+    format!("{}@{},{}", id.name.as_deref().unwrap_or(""), id.x, id.y)
+}
+
+async fn load_display<'e>(
+    executor: impl SqliteExecutor<'e>,
+    output: OutputKind,
+) -> Result<Option<MonitorId>, String> {
+    // An unreadable selection is just forgotten; the next open replaces it
+    Ok(load_setting(executor, output.display_key())
+        .await?
+        .and_then(|json| serde_json::from_str(&json).ok()))
+}
+
+async fn load_setting<'e>(
+    executor: impl SqliteExecutor<'e>,
+    key: &str,
+) -> Result<Option<String>, String> {
+    sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(executor)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn save_setting<'e>(
+    executor: impl SqliteExecutor<'e>,
+    key: &str,
+    value: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO app_settings (key, value) VALUES (?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+    )
+    .bind(key)
+    .bind(value)
+    .execute(executor)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
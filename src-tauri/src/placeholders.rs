@@ -0,0 +1,303 @@
+use std::collections::{BTreeSet, HashSet};
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::audit;
+use crate::db::{self, Variable, LANG_SLOTS};
+use crate::export::language_slots;
+use crate::present_mode;
+
+/// Placeholder shapes the editor recognises, as in `findPlaceholders`.
+pub const PLACEHOLDER_PATTERN: &str = r"\{\{[A-Z_]+\}\}|@[A-Z_]+";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedBlocks {
+    pub blocks_json: String,
+    /// Placeholders with no matching variable, left in the text as written.
+    pub unknown_variables: Vec<String>,
+}
+
+/// Substitutes variables in one language's text in a single pass, so a value
+/// that itself contains a placeholder is inserted literally.
+pub struct Substituter<'a> {
+    variables: &'a [Variable],
+    pattern: Regex,
+}
+
+impl<'a> Substituter<'a> {
+    pub fn new(variables: &'a [Variable]) -> Result<Self, String> {
+        let mut names: Vec<&str> = variables.iter().map(|v| v.name.as_str()).collect();
+        // Longest first so `@SAINT_NAME` isn't consumed as `@SAINT`
+        names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+        let alternatives: Vec<String> = names
+            .iter()
+            .filter(|name| !name.is_empty())
+            .map(|name| regex::escape(name))
+            .chain([PLACEHOLDER_PATTERN.to_string()])
+            .collect();
+        let pattern = Regex::new(&alternatives.join("|")).map_err(|e| e.to_string())?;
+        Ok(Self { variables, pattern })
+    }
+
+    /// Expand `text` for the language `slot`, collecting unknown placeholders.
+    pub fn replace(&self, text: &str, slot: &str, unknown: &mut BTreeSet<String>) -> String {
+        self.pattern
+            .replace_all(text, |caps: &regex::Captures| {
+                let token = &caps[0];
+                match self.variables.iter().find(|v| v.name == token) {
+                    Some(variable) => value_for(variable, slot).to_string(),
+                    None => {
+                        unknown.insert(token.to_string());
+                        token.to_string()
+                    }
+                }
+            })
+            .into_owned()
+    }
+
+    /// Placeholders in `text` exactly as [`Substituter::replace`] would see them.
+    pub fn tokens<'t>(&self, text: &'t str) -> Vec<&'t str> {
+        self.pattern.find_iter(text).map(|m| m.as_str()).collect()
+    }
+}
+
+/// `@Var` uses the per-language value when set; `{{VAR}}` always the single value.
+fn value_for<'v>(variable: &'v Variable, slot: &str) -> &'v str {
+    if variable.name.starts_with('@') {
+        let lang_value = match slot {
+            "Lang1" => &variable.value_lang1,
+            "Lang2" => &variable.value_lang2,
+            "Lang3" => &variable.value_lang3,
+            "Lang4" => &variable.value_lang4,
+            _ => "",
+        };
+        if !lang_value.is_empty() {
+            return lang_value;
+        }
+    }
+    &variable.value
+}
+
+/// Expand the variables in a slide's blocks for one language (1–4).
+///
+/// Only that language's text in each block is expanded; the other slots are
+/// returned as stored.
+#[tauri::command]
+pub async fn render_slide_blocks(
+    db: State<'_, DbInstances>,
+    slide_id: String,
+    language_index: u8,
+) -> Result<RenderedBlocks, String> {
+    let slot = language_index
+        .checked_sub(1)
+        .and_then(|i| LANG_SLOTS.get(i as usize))
+        .ok_or_else(|| format!("Invalid language index: {language_index}"))?;
+
+    let pool = db::pool(&db).await?;
+    let slide = db::fetch_slide(&pool, &slide_id).await?;
+    let variables = db::fetch_variables(&pool, &slide.presentation_id).await?;
+    let substituter = Substituter::new(&variables)?;
+
+    let mut blocks: Vec<Value> = serde_json::from_str(&slide.blocks_json)
+        .map_err(|e| format!("Invalid blocks for slide {slide_id}: {e}"))?;
+    let mut unknown = BTreeSet::new();
+    for block in &mut blocks {
+        if let Some(Value::String(text)) = block.get_mut(*slot) {
+            *text = substituter.replace(text, slot, &mut unknown);
+        }
+    }
+
+    Ok(RenderedBlocks {
+        blocks_json: serde_json::to_string(&blocks).map_err(|e| e.to_string())?,
+        unknown_variables: unknown.into_iter().collect(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedContent {
+    pub slide_id: String,
+    pub title_json: Option<String>,
+    pub blocks_json: String,
+    pub footer_json: Option<String>,
+    /// Placeholders with no matching variable, left in the text as written.
+    pub unknown_variables: Vec<String>,
+}
+
+/// A slide's title, blocks and footer with the variables expanded in every
+/// language of `lang_map` (1-based indices, all four when omitted), as the
+/// operator and projector views both show it.
+///
+/// Other language slots are returned as stored, as is a title or footer that
+/// isn't valid JSON.
+#[tauri::command]
+pub async fn render_slide(
+    db: State<'_, DbInstances>,
+    slide_id: String,
+    lang_map: Option<Vec<u8>>,
+) -> Result<RenderedContent, String> {
+    let slots = match lang_map {
+        Some(indices) => language_slots(&indices)?,
+        None => LANG_SLOTS.to_vec(),
+    };
+
+    let pool = db::pool(&db).await?;
+    let slide = db::fetch_slide(&pool, &slide_id).await?;
+    let variables = db::fetch_variables(&pool, &slide.presentation_id).await?;
+    let substituter = Substituter::new(&variables)?;
+
+    let mut unknown = BTreeSet::new();
+    let mut expand = |json: &str| -> Option<String> {
+        let mut value: Value = serde_json::from_str(json).ok()?;
+        expand_slots(&mut value, &substituter, &slots, &mut unknown);
+        serde_json::to_string(&value).ok()
+    };
+    let blocks_json =
+        expand(&slide.blocks_json).ok_or_else(|| format!("Invalid blocks for slide {slide_id}"))?;
+    let title_json = slide
+        .title_json
+        .as_deref()
+        .map(|json| expand(json).unwrap_or_else(|| json.to_string()));
+    let footer_json = slide
+        .footer_json
+        .as_deref()
+        .map(|json| expand(json).unwrap_or_else(|| json.to_string()));
+
+    Ok(RenderedContent {
+        slide_id,
+        title_json,
+        blocks_json,
+        footer_json,
+        unknown_variables: unknown.into_iter().collect(),
+    })
+}
+
+/// Expand every string under one of `slots` anywhere in `node`.
+fn expand_slots(
+    node: &mut Value,
+    substituter: &Substituter,
+    slots: &[&str],
+    unknown: &mut BTreeSet<String>,
+) {
+    match node {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                match child {
+                    Value::String(text) if slots.contains(&key.as_str()) => {
+                        *text = substituter.replace(text, key, unknown);
+                    }
+                    _ => expand_slots(child, substituter, slots, unknown),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                expand_slots(item, substituter, slots, unknown);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariableRef {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnusedVariables {
+    /// Variables no slide refers to; these are the ones `delete` removes.
+    pub unused: Vec<VariableRef>,
+    /// Variables only disabled slides refer to, kept so re-enabling them works.
+    pub disabled_only: Vec<VariableRef>,
+    pub deleted: bool,
+}
+
+/// Variables of a presentation that no slide's title, blocks or footer refers
+/// to, by name. Those only disabled slides use are reported apart and never
+/// deleted. With `delete`, the unused ones are removed in one transaction.
+#[tauri::command]
+pub async fn find_unused_variables(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    delete: Option<bool>,
+) -> Result<UnusedVariables, String> {
+    let pool = db::pool(&db).await?;
+    db::fetch_presentation(&pool, &presentation_id).await?;
+    let slides = db::fetch_slides(&pool, &presentation_id).await?;
+    let variables = db::fetch_variables(&pool, &presentation_id).await?;
+    let substituter = Substituter::new(&variables)?;
+
+    let mut enabled_refs = HashSet::new();
+    let mut disabled_refs = HashSet::new();
+    for slide in &slides {
+        let refs = if slide.is_disabled {
+            &mut disabled_refs
+        } else {
+            &mut enabled_refs
+        };
+        for text in [
+            slide.title_json.as_deref(),
+            Some(slide.blocks_json.as_str()),
+            slide.footer_json.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            refs.extend(substituter.tokens(text));
+        }
+    }
+
+    let mut report = UnusedVariables {
+        unused: Vec::new(),
+        disabled_only: Vec::new(),
+        deleted: false,
+    };
+    for variable in &variables {
+        let name = variable.name.as_str();
+        if enabled_refs.contains(name) {
+            continue;
+        }
+        let found = VariableRef {
+            id: variable.id.clone(),
+            name: variable.name.clone(),
+        };
+        if disabled_refs.contains(name) {
+            report.disabled_only.push(found);
+        } else {
+            report.unused.push(found);
+        }
+    }
+
+    if delete.unwrap_or(false) && !report.unused.is_empty() {
+        present_mode::ensure_writable(&pool).await?;
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        for variable in &report.unused {
+            sqlx::query("DELETE FROM variables WHERE id = ?")
+                .bind(&variable.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        let detail = json!({ "unusedVariables": &report.unused });
+        audit::record(
+            &mut *tx,
+            "delete_variables",
+            "presentation",
+            &presentation_id,
+            detail,
+        )
+        .await;
+        tx.commit().await.map_err(|e| e.to_string())?;
+        report.deleted = true;
+    }
+    Ok(report)
+}
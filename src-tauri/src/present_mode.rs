@@ -0,0 +1,141 @@
+use serde_json::json;
+use sqlx::{SqliteExecutor, SqlitePool};
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::audit;
+use crate::db;
+
+/// Leads the error a command that writes returns while present mode is on.
+pub const PRESENT_MODE_ACTIVE: &str = "PresentModeActive";
+
+/// `app_settings` key holding `"true"` while present mode is on.
+const PRESENT_MODE_KEY: &str = "presentMode";
+
+/// Turn present mode on or off, returning the new state.
+///
+/// While it's on every command that writes to the database fails with
+/// [`PRESENT_MODE_ACTIVE`], and the database's own triggers refuse writes to
+/// content from anywhere else, the frontend's SQL plugin included; reading,
+/// rendering and settings carry on. The flag lives in `app_settings`, so a
+/// reload in the middle of a service keeps it.
+#[tauri::command]
+pub async fn set_present_mode(db: State<'_, DbInstances>, enabled: bool) -> Result<bool, String> {
+    let pool = db::pool(&db).await?;
+    set(&pool, enabled).await?;
+    Ok(enabled)
+}
+
+async fn set(pool: &SqlitePool, enabled: bool) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO app_settings (key, value) VALUES (?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+    )
+    .bind(PRESENT_MODE_KEY)
+    .bind(enabled.to_string())
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    audit::record(
+        pool,
+        "presentMode",
+        "setting",
+        PRESENT_MODE_KEY,
+        json!({ "enabled": enabled }),
+    )
+    .await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_present_mode(db: State<'_, DbInstances>) -> Result<bool, String> {
+    let pool = db::pool(&db).await?;
+    is_on(&pool).await
+}
+
+/// Fail with [`PRESENT_MODE_ACTIVE`] while present mode is on. Commands that
+/// only sometimes write call this once they know they will.
+pub async fn ensure_writable<'e>(executor: impl SqliteExecutor<'e>) -> Result<(), String> {
+    if is_on(executor).await? {
+        return Err(format!(
+            "{PRESENT_MODE_ACTIVE}: changes are locked while presenting; turn off present mode first"
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) async fn is_on<'e>(executor: impl SqliteExecutor<'e>) -> Result<bool, String> {
+    let value: Option<String> = sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+        .bind(PRESENT_MODE_KEY)
+        .fetch_optional(executor)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(value.as_deref() == Some("true"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use tauri_plugin_sql::DbPool;
+    use tokio::sync::RwLock;
+
+    use super::*;
+    use crate::test_support;
+
+    #[tokio::test]
+    async fn writing_commands_are_refused_while_on() {
+        let pool = test_support::pool().await;
+        let instances = DbInstances(RwLock::new(HashMap::from([(
+            db::DB_URL.to_string(),
+            DbPool::Sqlite(pool.clone()),
+        )])));
+        assert!(db::writable_pool(&instances).await.is_ok());
+
+        set(&pool, true).await.unwrap();
+        let error = db::writable_pool(&instances).await.unwrap_err();
+        assert!(error.starts_with(PRESENT_MODE_ACTIVE), "{error}");
+        // Reading carries on
+        assert!(db::pool(&instances).await.is_ok());
+
+        set(&pool, false).await.unwrap();
+        assert!(db::writable_pool(&instances).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn the_database_refuses_writes_to_content_while_on() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        set(&pool, true).await.unwrap();
+
+        // What the frontend's SQL plugin would send
+        for sql in [
+            "UPDATE presentations SET name = 'Renamed' WHERE id = 'p1'",
+            "INSERT INTO slides (id, presentation_id, slide_order, blocks_json)
+             VALUES ('s1', 'p1', 0, '[]')",
+            "DELETE FROM presentations WHERE id = 'p1'",
+        ] {
+            let error = sqlx::query(sql).execute(&pool).await.unwrap_err();
+            assert!(error.to_string().contains(PRESENT_MODE_ACTIVE), "{error}");
+        }
+        let name: String = sqlx::query_scalar("SELECT name FROM presentations WHERE id = 'p1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(name, "p1");
+
+        // Settings aren't content: the live position is saved while presenting
+        test_support::exec(
+            &pool,
+            "INSERT INTO app_settings (key, value) VALUES ('liveSlide', '{}')",
+        )
+        .await;
+
+        set(&pool, false).await.unwrap();
+        test_support::exec(
+            &pool,
+            "UPDATE presentations SET name = 'Renamed' WHERE id = 'p1'",
+        )
+        .await;
+    }
+}
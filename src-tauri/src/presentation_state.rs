@@ -0,0 +1,447 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_sql::DbInstances;
+use tokio::sync::Mutex;
+
+use crate::db;
+use crate::remote::RemoteControl;
+use crate::required_regions;
+
+/// `app_settings` key the live position is persisted under.
+const LIVE_SLIDE_KEY: &str = "liveSlide";
+/// Event carrying the [`LiveSlide`] the audience should see, sent when it
+/// changes.
+const LIVE_SLIDE_CHANGED: &str = "live-slide-changed";
+/// Event carrying the whole [`LiveState`], sent after every change to it.
+pub const LIVE_STATE_CHANGED: &str = "live-state-changed";
+
+/// A position in a presentation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveSlide {
+    pub presentation_id: Option<String>,
+    /// Index into the presentation's enabled, expanded slides.
+    pub slide_index: u32,
+    /// Id of the slide at `slide_index`, as `expand_dynamic_slides` names it.
+    #[serde(default)]
+    pub slide_id: Option<String>,
+    /// Language slots shown, in display order.
+    pub language_set: Vec<String>,
+}
+
+/// The live session every window and remote works from: where the presenter
+/// is, and what the audience sees.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveState {
+    /// Where the presenter is.
+    pub current: LiveSlide,
+    /// Ids of the slides being presented, in order, as the presenting window
+    /// loaded them; moves stay within them. Empty when nothing is presented.
+    pub slide_ids: Vec<String>,
+    /// The audience screen is black.
+    pub blank: bool,
+    /// While frozen, the slide the audience keeps seeing as the presenter
+    /// moves on.
+    pub frozen: Option<LiveSlide>,
+}
+
+impl LiveState {
+    /// What the audience sees, blank or not.
+    pub fn shown(&self) -> &LiveSlide {
+        self.frozen.as_ref().unwrap_or(&self.current)
+    }
+
+    pub fn next(&mut self) -> Result<(), String> {
+        let last = self.last_index()?;
+        self.move_to(self.current.slide_index.saturating_add(1).min(last));
+        Ok(())
+    }
+
+    pub fn prev(&mut self) -> Result<(), String> {
+        self.last_index()?;
+        self.move_to(self.current.slide_index.saturating_sub(1));
+        Ok(())
+    }
+
+    pub fn goto(&mut self, slide_index: u32) -> Result<(), String> {
+        let last = self.last_index()?;
+        if slide_index > last {
+            return Err(format!(
+                "Slide {} doesn't exist; the presentation has {}",
+                slide_index + 1,
+                last + 1
+            ));
+        }
+        self.move_to(slide_index);
+        Ok(())
+    }
+
+    /// Blank or unblank the audience screen, or toggle it for `None`.
+    pub fn set_blank(&mut self, blank: Option<bool>) {
+        self.blank = blank.unwrap_or(!self.blank);
+    }
+
+    /// Hold the audience on the slide they see now, or release them to the
+    /// presenter's, or toggle it for `None`.
+    pub fn set_frozen(&mut self, frozen: Option<bool>) {
+        let frozen = frozen.unwrap_or(self.frozen.is_none());
+        self.frozen = match (frozen, self.frozen.take()) {
+            (true, Some(held)) => Some(held),
+            (true, None) => Some(self.current.clone()),
+            (false, _) => None,
+        };
+    }
+
+    fn last_index(&self) -> Result<u32, String> {
+        match self.slide_ids.len() {
+            0 => Err("Nothing is being presented".to_string()),
+            len => Ok(u32::try_from(len - 1).unwrap_or(u32::MAX)),
+        }
+    }
+
+    fn move_to(&mut self, slide_index: u32) {
+        self.current.slide_index = slide_index;
+        self.current.slide_id = self.slide_ids.get(slide_index as usize).cloned();
+    }
+}
+
+/// The live session, shared by every window. `None` until the position is
+/// first read from `app_settings`, so a reloaded window picks up where the
+/// last one left off. An async lock, held while a change is persisted and
+/// broadcast, so the stored position and the events follow the changes in
+/// order.
+#[derive(Default)]
+pub struct PresentationState(Mutex<Option<LiveState>>);
+
+/// Change the live session with `change` and tell everyone: every window
+/// through `live-state-changed`, and `live-slide-changed` when the audience's
+/// slide moved; the remote control server's clients; and `app_settings` when
+/// the presenter's position moved. The session is left alone if `change`
+/// fails.
+pub async fn update_live(
+    app: &AppHandle,
+    change: impl FnOnce(&mut LiveState) -> Result<(), String>,
+) -> Result<LiveState, String> {
+    let pool = db::pool(&app.state::<DbInstances>()).await?;
+    let state = app.state::<PresentationState>();
+    let mut session = state.0.lock().await;
+    let (before, after) = apply(&pool, &mut session, change).await?;
+
+    app.state::<RemoteControl>().set_live(after.clone());
+    if after.shown() != before.shown() {
+        app.emit(LIVE_SLIDE_CHANGED, after.shown())
+            .map_err(|e| e.to_string())?;
+    }
+    app.emit(LIVE_STATE_CHANGED, &after)
+        .map_err(|e| e.to_string())?;
+    Ok(after)
+}
+
+/// Apply `change` to the locked session and persist the presenter's position
+/// if it moved, returning the session before and after.
+async fn apply(
+    pool: &SqlitePool,
+    session: &mut Option<LiveState>,
+    change: impl FnOnce(&mut LiveState) -> Result<(), String>,
+) -> Result<(LiveState, LiveState), String> {
+    let before = load(pool, session).await?.clone();
+    let mut after = before.clone();
+    change(&mut after)?;
+
+    if after.current != before.current {
+        let json = serde_json::to_string(&after.current).map_err(|e| e.to_string())?;
+        sqlx::query(
+            "INSERT INTO app_settings (key, value) VALUES (?, ?)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        )
+        .bind(LIVE_SLIDE_KEY)
+        .bind(json)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    *session = Some(after.clone());
+    Ok((before, after))
+}
+
+/// Restore the presenter's position from `app_settings` the first time the
+/// session is needed.
+async fn load<'a>(
+    pool: &SqlitePool,
+    session: &'a mut Option<LiveState>,
+) -> Result<&'a mut LiveState, String> {
+    if session.is_none() {
+        let stored: Option<String> =
+            sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+                .bind(LIVE_SLIDE_KEY)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+        let current: LiveSlide = stored
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        *session = Some(LiveState {
+            current,
+            ..LiveState::default()
+        });
+    }
+    Ok(session.get_or_insert_with(LiveState::default))
+}
+
+/// Start presenting `slide_ids`, the enabled, expanded slides of
+/// `presentation_id` in order, at `slide_index`. Also called again whenever
+/// the slide list changes mid-presentation; leaving out `slide_index` then
+/// keeps the current position, moved back if its slide is gone.
+///
+/// Starting runs the pre-flight first: a slide with an empty required region
+/// refuses it; see [`required_regions::check_go_live`].
+#[tauri::command]
+pub async fn live_load(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    slide_ids: Vec<String>,
+    language_set: Vec<String>,
+    slide_index: Option<u32>,
+) -> Result<LiveState, String> {
+    if slide_index.is_some() {
+        let pool = db::pool(&db).await?;
+        required_regions::check_go_live(&pool, &presentation_id).await?;
+    }
+    update_live(&app, |live| {
+        let same = live.current.presentation_id.as_deref() == Some(presentation_id.as_str());
+        let slide_index = match slide_index {
+            Some(slide_index) => slide_index,
+            None if same => live.current.slide_index,
+            None => 0,
+        };
+        if !same {
+            live.frozen = None;
+        }
+        live.current.presentation_id = Some(presentation_id);
+        live.current.language_set = language_set;
+        live.slide_ids = slide_ids;
+        let last = live.last_index().unwrap_or(0);
+        live.move_to(slide_index.min(last));
+        Ok(())
+    })
+    .await
+}
+
+/// Stop presenting. The position is kept for next time; blank and freeze are
+/// lifted.
+#[tauri::command]
+pub async fn live_stop(app: AppHandle) -> Result<LiveState, String> {
+    update_live(&app, |live| {
+        live.slide_ids.clear();
+        live.blank = false;
+        live.frozen = None;
+        Ok(())
+    })
+    .await
+}
+
+/// Move to the next slide, staying on the last.
+#[tauri::command]
+pub async fn live_next(app: AppHandle) -> Result<LiveState, String> {
+    update_live(&app, LiveState::next).await
+}
+
+/// Move to the previous slide, staying on the first.
+#[tauri::command]
+pub async fn live_prev(app: AppHandle) -> Result<LiveState, String> {
+    update_live(&app, LiveState::prev).await
+}
+
+#[tauri::command]
+pub async fn live_goto(app: AppHandle, slide_index: u32) -> Result<LiveState, String> {
+    update_live(&app, |live| live.goto(slide_index)).await
+}
+
+/// Blank or unblank the audience screen; toggles when `blank` is left out.
+#[tauri::command]
+pub async fn live_blank(app: AppHandle, blank: Option<bool>) -> Result<LiveState, String> {
+    update_live(&app, |live| {
+        live.set_blank(blank);
+        Ok(())
+    })
+    .await
+}
+
+/// Freeze the audience screen on its slide while the presenter moves on, or
+/// let it catch up; toggles when `freeze` is left out.
+#[tauri::command]
+pub async fn live_freeze(app: AppHandle, freeze: Option<bool>) -> Result<LiveState, String> {
+    update_live(&app, |live| {
+        live.set_frozen(freeze);
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_live_state(
+    db: State<'_, DbInstances>,
+    state: State<'_, PresentationState>,
+) -> Result<LiveState, String> {
+    let pool = db::pool(&db).await?;
+    let mut session = state.0.lock().await;
+    Ok(load(&pool, &mut session).await?.clone())
+}
+
+/// Move the presenter to a position directly, without the slide list being
+/// checked.
+#[tauri::command]
+pub async fn set_live_slide(
+    app: AppHandle,
+    presentation_id: Option<String>,
+    slide_index: u32,
+    slide_id: Option<String>,
+    language_set: Vec<String>,
+) -> Result<LiveSlide, String> {
+    let live = update_live(&app, |live| {
+        live.current = LiveSlide {
+            presentation_id,
+            slide_index,
+            slide_id,
+            language_set,
+        };
+        Ok(())
+    })
+    .await?;
+    Ok(live.current)
+}
+
+/// The slide the audience sees, restored from `app_settings` after a
+/// restart.
+#[tauri::command]
+pub async fn get_live_slide(
+    db: State<'_, DbInstances>,
+    state: State<'_, PresentationState>,
+) -> Result<LiveSlide, String> {
+    Ok(get_live_state(db, state).await?.shown().clone())
+}
+
+/// Make `presentation_id` the active presentation and restart the live slide at
+/// its first slide, keeping the language set. Allowed in present mode: opening
+/// the next presentation is part of presenting.
+#[tauri::command]
+pub async fn set_active_presentation(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<LiveSlide, String> {
+    let pool = db::pool(&db).await?;
+    activate(&pool, &presentation_id).await?;
+
+    let live = update_live(&app, |live| {
+        // The presenting window loads the new slide list
+        live.current.presentation_id = Some(presentation_id);
+        live.slide_ids.clear();
+        live.frozen = None;
+        live.move_to(0);
+        Ok(())
+    })
+    .await?;
+    Ok(live.current)
+}
+
+/// Flag `presentation_id` as the one active presentation.
+async fn activate(pool: &SqlitePool, presentation_id: &str) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let exists: Option<String> = sqlx::query_scalar("SELECT id FROM presentations WHERE id = ?")
+        .bind(presentation_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err(format!("Presentation not found: {presentation_id}"));
+    }
+    // One transaction, so no reader ever sees two active presentations
+    sqlx::query("UPDATE presentations SET is_active = (id = ?)")
+        .bind(presentation_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    async fn active(pool: &SqlitePool) -> Vec<String> {
+        sqlx::query_scalar("SELECT id FROM presentations WHERE is_active = 1")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn only_the_last_activated_presentation_is_active() {
+        let pool = test_support::pool().await;
+        for id in ["p1", "p2", "p3"] {
+            test_support::presentation(&pool, id).await;
+        }
+        for id in ["p1", "p2", "p3", "p2"] {
+            activate(&pool, id).await.unwrap();
+            assert_eq!(active(&pool).await, [id]);
+        }
+    }
+
+    #[tokio::test]
+    async fn activating_an_unknown_presentation_changes_nothing() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        activate(&pool, "p1").await.unwrap();
+
+        let error = activate(&pool, "missing").await.unwrap_err();
+        assert!(error.contains("not found"), "{error}");
+        assert_eq!(active(&pool).await, ["p1"]);
+    }
+
+    #[tokio::test]
+    async fn presentations_are_still_activated_in_present_mode() {
+        let pool = test_support::pool().await;
+        for id in ["p1", "p2"] {
+            test_support::presentation(&pool, id).await;
+        }
+        test_support::exec(
+            &pool,
+            "INSERT INTO app_settings (key, value) VALUES ('presentMode', 'true')",
+        )
+        .await;
+
+        for id in ["p1", "p2"] {
+            activate(&pool, id).await.unwrap();
+            assert_eq!(active(&pool).await, [id]);
+        }
+    }
+
+    #[tokio::test]
+    async fn the_stored_position_is_the_last_one_moved_to() {
+        let pool = test_support::pool().await;
+        let state = PresentationState::default();
+        let moves = (0..10u32).map(|slide_index| {
+            let (pool, state) = (&pool, &state);
+            async move {
+                let mut session = state.0.lock().await;
+                apply(pool, &mut session, |live| {
+                    live.slide_ids = (0..10).map(|i| format!("s{i}")).collect();
+                    live.goto(slide_index)
+                })
+                .await
+                .unwrap()
+            }
+        });
+        futures_util::future::join_all(moves).await;
+
+        let live = state.0.lock().await.clone().unwrap();
+        let restored = load(&pool, &mut None).await.unwrap().clone();
+        assert_eq!(restored.current, live.current);
+        assert_eq!(live.current.slide_id.as_deref(), Some("s9"));
+    }
+}
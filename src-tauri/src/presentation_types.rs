@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::db;
+
+/// Canonical values of the frontend's `PresentationType`.
+pub const PRESENTATION_TYPES: [&str; 6] =
+    ["Kidase", "Mahlet", "Seatat", "Tselot", "Mezmur", "Custom"];
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeAnomaly {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub presentation_type: String,
+    /// Canonical type that matches ignoring case and surrounding whitespace.
+    pub suggested: Option<String>,
+}
+
+/// Canonical type for a loosely-written one, e.g. `" kidase"` → `"Kidase"`.
+pub fn canonical_type(value: &str) -> Option<&'static str> {
+    let value = value.trim();
+    PRESENTATION_TYPES
+        .into_iter()
+        .find(|t| t.eq_ignore_ascii_case(value))
+}
+
+/// List presentations whose `type` is not exactly a canonical `PresentationType`.
+#[tauri::command]
+pub async fn audit_presentation_types(
+    db: State<'_, DbInstances>,
+) -> Result<Vec<TypeAnomaly>, String> {
+    let pool = db::pool(&db).await?;
+    let rows: Vec<(String, String, String)> =
+        sqlx::query_as("SELECT id, name, type FROM presentations ORDER BY name")
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .filter(|(_, _, t)| !PRESENTATION_TYPES.contains(&t.as_str()))
+        .map(|(id, name, presentation_type)| TypeAnomaly {
+            suggested: canonical_type(&presentation_type).map(str::to_string),
+            id,
+            name,
+            presentation_type,
+        })
+        .collect())
+}
+
+/// Rewrite `type` values per `mapping` (found → canonical), returning rows changed.
+#[tauri::command]
+pub async fn normalize_presentation_types(
+    db: State<'_, DbInstances>,
+    mapping: HashMap<String, String>,
+) -> Result<usize, String> {
+    if let Some(target) = mapping
+        .values()
+        .find(|t| !PRESENTATION_TYPES.contains(&t.as_str()))
+    {
+        return Err(format!("Unknown presentation type: {target}"));
+    }
+
+    let pool = db::writable_pool(&db).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut changed = 0;
+    for (from, to) in mapping.iter().filter(|(from, to)| from != to) {
+        let result = sqlx::query("UPDATE presentations SET type = ? WHERE type = ?")
+            .bind(to)
+            .bind(from)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        changed += result.rows_affected() as usize;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(changed)
+}
@@ -0,0 +1,901 @@
+use chrono::{Duration, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::{FromRow, Sqlite, SqliteConnection, SqlitePool, Transaction};
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::audit;
+use crate::db::{self, LANG_SLOTS};
+use crate::presentation_types::canonical_type;
+
+/// `app_settings` key holding the open history, newest first.
+const RECENT_KEY: &str = "recentPresentations";
+/// Entries kept in the history; older opens fall off the end.
+const RECENT_CAPACITY: usize = 20;
+
+/// Summary columns for `presentations p`, shared by the listings below.
+const SUMMARY_COLUMNS: &str = "p.id, p.name, p.type, p.deleted_at,
+    (SELECT count(*) FROM slides s WHERE s.presentation_id = p.id) AS slide_count,
+    nullif(max(coalesce(p.updated_at, ''),
+        coalesce((SELECT max(updated_at) FROM slides s
+                  WHERE s.presentation_id = p.id), ''),
+        coalesce((SELECT max(updated_at) FROM variables v
+                  WHERE v.presentation_id = p.id), '')), '') AS updated_at";
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PresentationSummary {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub presentation_type: String,
+    pub slide_count: i64,
+    /// Latest change to the presentation, its slides or its variables.
+    pub updated_at: Option<String>,
+    /// When the presentation was moved to the trash.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<String>,
+    /// When the presentation was last opened; only set by `get_recent_presentations`.
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opened_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecentEntry {
+    presentation_id: String,
+    opened_at: String,
+}
+
+/// Presentations ordered by their most recent change, newest first.
+///
+/// Editing a slide or variable counts as changing its presentation.
+#[tauri::command]
+pub async fn list_recently_modified(
+    db: State<'_, DbInstances>,
+    limit: u32,
+) -> Result<Vec<PresentationSummary>, String> {
+    let pool = db::pool(&db).await?;
+    sqlx::query_as::<_, PresentationSummary>(&format!(
+        "SELECT {SUMMARY_COLUMNS} FROM presentations p
+         WHERE p.deleted_at IS NULL
+         ORDER BY updated_at DESC, p.name
+         LIMIT ?"
+    ))
+    .bind(limit)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Order of a [`list_presentations`] page: names A–Z, the others newest first.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortKey {
+    Name,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PagedPresentations {
+    pub presentations: Vec<PresentationSummary>,
+    /// Matching presentations across all pages.
+    pub total: i64,
+}
+
+/// One page of presentations, optionally only those of `type_filter`, with the
+/// total number that match. With `include_deleted`, those in the trash are
+/// listed too, with `deletedAt` set.
+#[tauri::command]
+pub async fn list_presentations(
+    db: State<'_, DbInstances>,
+    include_deleted: bool,
+    type_filter: Option<String>,
+    offset: u32,
+    limit: u32,
+    sort: SortKey,
+) -> Result<PagedPresentations, String> {
+    let pool = db::pool(&db).await?;
+    let filter = "(?1 OR p.deleted_at IS NULL) AND (?2 IS NULL OR p.type = ?2)";
+
+    let total: i64 = sqlx::query_scalar(&format!(
+        "SELECT count(*) FROM presentations p WHERE {filter}"
+    ))
+    .bind(include_deleted)
+    .bind(&type_filter)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let order = match sort {
+        SortKey::Name => "p.name, p.id",
+        SortKey::CreatedAt => "p.created_at DESC, p.name, p.id",
+        SortKey::UpdatedAt => "updated_at DESC, p.name, p.id",
+    };
+    let presentations = sqlx::query_as::<_, PresentationSummary>(&format!(
+        "SELECT {SUMMARY_COLUMNS} FROM presentations p
+         WHERE {filter}
+         ORDER BY {order}
+         LIMIT ?3 OFFSET ?4"
+    ))
+    .bind(include_deleted)
+    .bind(&type_filter)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(PagedPresentations {
+        presentations,
+        total,
+    })
+}
+
+/// A presentation by id, whether or not it's in the trash.
+#[tauri::command]
+pub async fn get_presentation(
+    db: State<'_, DbInstances>,
+    id: String,
+) -> Result<Option<db::Presentation>, String> {
+    let pool = db::pool(&db).await?;
+    sqlx::query_as("SELECT * FROM presentations WHERE id = ?")
+        .bind(&id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Create `presentation`, or overwrite the stored one with the same id, after
+/// checking its type, template and language JSON. A blank `id` or
+/// `createdAt` is filled in. Returns the presentation as stored.
+///
+/// The active flag and display scale are left alone on an existing
+/// presentation: [`crate::presentation_state::set_active_presentation`] and
+/// [`set_display_scale`] own them. A new one created active takes the flag
+/// from every other. Changing `isPrimary` on an existing presentation is
+/// checked as [`set_presentation_primary`] checks it, keeping its parent.
+#[tauri::command]
+pub async fn save_presentation(
+    db: State<'_, DbInstances>,
+    presentation: db::Presentation,
+) -> Result<db::Presentation, String> {
+    let pool = db::writable_pool(&db).await?;
+    save(&pool, presentation).await
+}
+
+async fn save(
+    pool: &SqlitePool,
+    presentation: db::Presentation,
+) -> Result<db::Presentation, String> {
+    let mut presentation = presentation;
+    presentation.name = presentation.name.trim().to_string();
+    if presentation.name.is_empty() {
+        return Err("Presentation name must not be empty".to_string());
+    }
+    presentation.presentation_type = canonical_type(&presentation.presentation_type)
+        .ok_or_else(|| {
+            format!(
+                "Unknown presentation type: {}",
+                presentation.presentation_type
+            )
+        })?
+        .to_string();
+    validate_language_json(&presentation.language_map, false)
+        .map_err(|e| format!("Invalid language map: {e}"))?;
+    if let Some(settings) = &presentation.language_settings {
+        validate_language_json(settings, true)
+            .map_err(|e| format!("Invalid language settings: {e}"))?;
+    }
+    if presentation.id.is_empty() {
+        presentation.id = db::new_id();
+    }
+    if presentation.created_at.is_empty() {
+        presentation.created_at = db::now();
+    }
+
+    db::fetch_template(pool, &presentation.template_id).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let existing: Option<db::Presentation> =
+        sqlx::query_as("SELECT * FROM presentations WHERE id = ?")
+            .bind(&presentation.id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let operation = match &existing {
+        Some(existing) => {
+            presentation.is_active = existing.is_active;
+            presentation.display_scale = existing.display_scale;
+            presentation.created_at = existing.created_at.clone();
+            if presentation.is_primary != existing.is_primary {
+                apply_primary(&mut tx, &presentation.id, presentation.is_primary, None).await?;
+            }
+            sqlx::query(
+                "UPDATE presentations
+                 SET name = ?, type = ?, template_id = ?, language_map = ?,
+                     language_settings = ?
+                 WHERE id = ?",
+            )
+            .bind(&presentation.name)
+            .bind(&presentation.presentation_type)
+            .bind(&presentation.template_id)
+            .bind(&presentation.language_map)
+            .bind(&presentation.language_settings)
+            .bind(&presentation.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+            "update"
+        }
+        None => {
+            if presentation.is_active {
+                sqlx::query("UPDATE presentations SET is_active = 0 WHERE is_active = 1")
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+            sqlx::query(
+                "INSERT INTO presentations
+                 (id, name, type, template_id, language_map, language_settings,
+                  is_primary, is_active, created_at, display_scale)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&presentation.id)
+            .bind(&presentation.name)
+            .bind(&presentation.presentation_type)
+            .bind(&presentation.template_id)
+            .bind(&presentation.language_map)
+            .bind(&presentation.language_settings)
+            .bind(presentation.is_primary)
+            .bind(presentation.is_active)
+            .bind(&presentation.created_at)
+            .bind(presentation.display_scale)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+            "create"
+        }
+    };
+    let detail = json!({ "before": existing, "after": presentation });
+    audit::record(
+        &mut *tx,
+        operation,
+        "presentation",
+        &presentation.id,
+        detail,
+    )
+    .await;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(presentation)
+}
+
+/// Check a `language_map` (slot to name) or, with `settings`, a
+/// `language_settings` (slot to `{name, enabled, order}`) document.
+fn validate_language_json(json: &str, settings: bool) -> Result<(), String> {
+    let value: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let entries = value
+        .as_object()
+        .ok_or_else(|| "expected an object keyed by language slot".to_string())?;
+    for (slot, entry) in entries {
+        if !LANG_SLOTS.contains(&slot.as_str()) {
+            return Err(format!("unknown language slot {slot}"));
+        }
+        let valid = if settings {
+            entry.get("name").is_some_and(Value::is_string)
+                && entry.get("enabled").is_some_and(Value::is_boolean)
+                && entry.get("order").is_some_and(Value::is_number)
+        } else {
+            entry.is_string() || entry.is_null()
+        };
+        if !valid {
+            return Err(format!("malformed entry for {slot}"));
+        }
+    }
+    Ok(())
+}
+
+/// Move a presentation to the trash. Its slides, variables and rules stay in
+/// place so [`restore_presentation`] brings it back whole; a trashed
+/// presentation is no longer active.
+#[tauri::command]
+pub async fn delete_presentation(db: State<'_, DbInstances>, id: String) -> Result<(), String> {
+    let pool = db::writable_pool(&db).await?;
+    let deleted_at = trashed_at(&pool, &id).await?;
+    if deleted_at.is_some() {
+        return Err(format!("Presentation is already in the trash: {id}"));
+    }
+    let deleted_at = db::now();
+    sqlx::query("UPDATE presentations SET deleted_at = ?, is_active = 0 WHERE id = ?")
+        .bind(&deleted_at)
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let detail = json!({ "softDelete": true, "deletedAt": deleted_at });
+    audit::record(&pool, "delete", "presentation", &id, detail).await;
+    Ok(())
+}
+
+/// Take a presentation back out of the trash.
+#[tauri::command]
+pub async fn restore_presentation(db: State<'_, DbInstances>, id: String) -> Result<(), String> {
+    let pool = db::writable_pool(&db).await?;
+    if trashed_at(&pool, &id).await?.is_none() {
+        return Err(format!("Presentation is not in the trash: {id}"));
+    }
+    sqlx::query("UPDATE presentations SET deleted_at = NULL WHERE id = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    audit::record(&pool, "restore", "presentation", &id, json!({})).await;
+    Ok(())
+}
+
+/// Permanently remove presentations that have been in the trash for at least
+/// `older_than_days`, with their slides, variables and rules. Returns how many
+/// presentations were removed.
+#[tauri::command]
+pub async fn purge_deleted(
+    db: State<'_, DbInstances>,
+    older_than_days: u32,
+) -> Result<u32, String> {
+    let cutoff = (Utc::now() - Duration::days(i64::from(older_than_days)))
+        .to_rfc3339_opts(SecondsFormat::Millis, true);
+
+    let pool = db::writable_pool(&db).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let ids: Vec<String> = sqlx::query_scalar(
+        "SELECT id FROM presentations WHERE deleted_at IS NOT NULL AND deleted_at <= ?",
+    )
+    .bind(&cutoff)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for id in &ids {
+        delete_with_children(&mut tx, id).await?;
+        let detail = json!({ "purgedBefore": cutoff });
+        audit::record(&mut *tx, "purge", "presentation", id, detail).await;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(ids.len() as u32)
+}
+
+/// Mark a presentation primary, or secondary under `parent_id`.
+///
+/// A secondary presentation is a linked service and needs a primary parent:
+/// `parent_id`, or the one it already has. A primary that secondaries are
+/// linked to can't become secondary itself until they're moved.
+#[tauri::command]
+pub async fn set_presentation_primary(
+    db: State<'_, DbInstances>,
+    id: String,
+    primary: bool,
+    parent_id: Option<String>,
+) -> Result<(), String> {
+    let pool = db::writable_pool(&db).await?;
+    set_primary(&pool, &id, primary, parent_id).await
+}
+
+async fn set_primary(
+    pool: &SqlitePool,
+    id: &str,
+    primary: bool,
+    parent_id: Option<String>,
+) -> Result<(), String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    let parent_id = apply_primary(&mut conn, id, primary, parent_id).await?;
+    let detail = json!({ "isPrimary": primary, "parentId": parent_id });
+    audit::record(&mut *conn, "update", "presentation", id, detail).await;
+    Ok(())
+}
+
+/// Make `id` primary, or secondary under `parent_id` or the parent it already
+/// has, once the link is checked; returns the parent it's left with.
+async fn apply_primary(
+    conn: &mut SqliteConnection,
+    id: &str,
+    primary: bool,
+    parent_id: Option<String>,
+) -> Result<Option<String>, String> {
+    let current_parent: Option<(Option<String>,)> =
+        sqlx::query_as("SELECT parent_id FROM presentations WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    let Some((current_parent,)) = current_parent else {
+        return Err(format!("Presentation not found: {id}"));
+    };
+
+    let parent_id = if primary {
+        None
+    } else {
+        let children: i64 =
+            sqlx::query_scalar("SELECT count(*) FROM presentations WHERE parent_id = ?")
+                .bind(id)
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+        if children > 0 {
+            return Err(format!(
+                "{children} secondary presentation(s) belong to this one; move them first"
+            ));
+        }
+        let parent_id = parent_id
+            .or(current_parent)
+            .ok_or("A secondary presentation needs a primary presentation to belong to")?;
+        if parent_id == id {
+            return Err("A presentation can't be its own parent".to_string());
+        }
+        let parent: Option<(bool, Option<String>)> =
+            sqlx::query_as("SELECT is_primary, deleted_at FROM presentations WHERE id = ?")
+                .bind(&parent_id)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+        match parent {
+            None => return Err(format!("Presentation not found: {parent_id}")),
+            Some((false, _)) => return Err("The parent presentation isn't primary".to_string()),
+            Some((_, Some(_))) => return Err("The parent presentation is in the trash".to_string()),
+            Some((true, None)) => {}
+        }
+        Some(parent_id)
+    };
+
+    sqlx::query("UPDATE presentations SET is_primary = ?, parent_id = ? WHERE id = ?")
+        .bind(primary)
+        .bind(&parent_id)
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(parent_id)
+}
+
+/// Primary presentations outside the trash, by name.
+#[tauri::command]
+pub async fn list_primary_presentations(
+    db: State<'_, DbInstances>,
+) -> Result<Vec<PresentationSummary>, String> {
+    let pool = db::pool(&db).await?;
+    sqlx::query_as::<_, PresentationSummary>(&format!(
+        "SELECT {SUMMARY_COLUMNS} FROM presentations p
+         WHERE p.is_primary = 1 AND p.deleted_at IS NULL
+         ORDER BY p.name COLLATE NOCASE"
+    ))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletionSummary {
+    pub slides: u32,
+    pub variables: u32,
+    pub rules: u32,
+}
+
+/// Permanently delete a presentation with its slides, variables and rules in
+/// one transaction, returning how many of each went. Trashed or not makes no
+/// difference. The active presentation is refused unless `force` is set, as
+/// is a primary that secondaries still belong to.
+#[tauri::command]
+pub async fn delete_presentation_cascade(
+    db: State<'_, DbInstances>,
+    id: String,
+    force: Option<bool>,
+) -> Result<DeletionSummary, String> {
+    let pool = db::writable_pool(&db).await?;
+    let presentation = db::fetch_presentation(&pool, &id).await?;
+    if presentation.is_active && !force.unwrap_or(false) {
+        return Err(format!(
+            "\"{}\" is the active presentation; force the delete to remove it anyway",
+            presentation.name
+        ));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let children: i64 =
+        sqlx::query_scalar("SELECT count(*) FROM presentations WHERE parent_id = ?")
+            .bind(&id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    if children > 0 {
+        return Err(format!(
+            "{children} secondary presentation(s) belong to \"{}\"; move them first",
+            presentation.name
+        ));
+    }
+    let summary = delete_with_children(&mut tx, &id).await?;
+    let detail = json!({ "presentation": presentation, "cascade": &summary });
+    audit::record(&mut *tx, "delete", "presentation", &id, detail).await;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(summary)
+}
+
+/// Delete a presentation row and everything under it. Children go first:
+/// slides, variables and rules have no `ON DELETE CASCADE`, so with foreign
+/// keys enforced the parent can't go before them. Undo history and variable
+/// values do cascade.
+async fn delete_with_children(
+    tx: &mut Transaction<'_, Sqlite>,
+    id: &str,
+) -> Result<DeletionSummary, String> {
+    let mut summary = DeletionSummary::default();
+    for (query, count) in [
+        (
+            "DELETE FROM rule_definitions WHERE presentation_id = ?1
+                OR slide_id IN (SELECT id FROM slides WHERE presentation_id = ?1)",
+            Some(&mut summary.rules),
+        ),
+        (
+            "DELETE FROM slides WHERE presentation_id = ?1",
+            Some(&mut summary.slides),
+        ),
+        (
+            "DELETE FROM variables WHERE presentation_id = ?1",
+            Some(&mut summary.variables),
+        ),
+        ("DELETE FROM presentations WHERE id = ?1", None),
+    ] {
+        let deleted = sqlx::query(query)
+            .bind(id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .rows_affected();
+        if let Some(count) = count {
+            *count = deleted as u32;
+        }
+    }
+    Ok(summary)
+}
+
+/// Set the factor the template's font sizes are multiplied by when this
+/// presentation is rendered, e.g. larger on the projector than in the editor.
+/// Values outside 0.5–3.0 are clamped; NaN and infinities are refused.
+#[tauri::command]
+pub async fn set_display_scale(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    scale: f32,
+) -> Result<(), String> {
+    let pool = db::writable_pool(&db).await?;
+    set_scale(&pool, &presentation_id, scale).await
+}
+
+async fn set_scale(pool: &SqlitePool, presentation_id: &str, scale: f32) -> Result<(), String> {
+    if !scale.is_finite() {
+        return Err(format!("Display scale must be a number, got {scale}"));
+    }
+    let (min, max) = db::DISPLAY_SCALE_RANGE;
+    let scale = f64::from(scale).clamp(min, max);
+
+    let updated = sqlx::query("UPDATE presentations SET display_scale = ? WHERE id = ?")
+        .bind(scale)
+        .bind(presentation_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .rows_affected();
+    if updated == 0 {
+        return Err(format!("Presentation not found: {presentation_id}"));
+    }
+    let detail = json!({ "displayScale": scale });
+    audit::record(pool, "update", "presentation", presentation_id, detail).await;
+    Ok(())
+}
+
+/// The presentation's display scale; 1.0 until one is set.
+#[tauri::command]
+pub async fn get_display_scale(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<f32, String> {
+    let pool = db::pool(&db).await?;
+    let presentation = db::fetch_presentation(&pool, &presentation_id).await?;
+    Ok(presentation.font_scale() as f32)
+}
+
+/// `deleted_at` of a presentation, failing when it doesn't exist at all.
+async fn trashed_at(pool: &SqlitePool, id: &str) -> Result<Option<String>, String> {
+    let row: Option<(Option<String>,)> =
+        sqlx::query_as("SELECT deleted_at FROM presentations WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    row.map(|(deleted_at,)| deleted_at)
+        .ok_or_else(|| format!("Presentation not found: {id}"))
+}
+
+/// The presentation marked active, if any. Should older data have several
+/// flagged, the most recently modified wins.
+#[tauri::command]
+pub async fn get_active_presentation(
+    db: State<'_, DbInstances>,
+) -> Result<Option<PresentationSummary>, String> {
+    let pool = db::pool(&db).await?;
+    sqlx::query_as::<_, PresentationSummary>(&format!(
+        "SELECT {SUMMARY_COLUMNS} FROM presentations p
+         WHERE p.is_active = 1 AND p.deleted_at IS NULL
+         ORDER BY updated_at DESC
+         LIMIT 1"
+    ))
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Leave only one presentation active, returning how many were cleared.
+///
+/// Nothing stops two rows carrying `is_active`, so this keeps the one
+/// [`get_active_presentation`] would pick (the most recently modified, trash
+/// last) and clears the rest. It also runs once at startup.
+#[tauri::command]
+pub async fn repair_active_flags(db: State<'_, DbInstances>) -> Result<u32, String> {
+    let pool = db::writable_pool(&db).await?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    clear_extra_active(&mut conn).await
+}
+
+pub async fn clear_extra_active(conn: &mut SqliteConnection) -> Result<u32, String> {
+    let cleared: Vec<String> = sqlx::query_scalar(&format!(
+        "UPDATE presentations SET is_active = 0
+         WHERE is_active = 1 AND id <> (
+             SELECT id FROM (SELECT {SUMMARY_COLUMNS} FROM presentations p
+                             WHERE p.is_active = 1)
+             ORDER BY deleted_at IS NOT NULL, updated_at DESC, id DESC
+             LIMIT 1)
+         RETURNING id"
+    ))
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    for id in &cleared {
+        let detail = json!({ "isActive": false, "reason": "duplicate active flag" });
+        audit::record(&mut *conn, "update", "presentation", id, detail).await;
+    }
+    Ok(cleared.len() as u32)
+}
+
+/// Record that a presentation was just opened, moving it to the top of the
+/// recent list.
+#[tauri::command]
+pub async fn mark_presentation_opened(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<(), String> {
+    let pool = db::writable_pool(&db).await?;
+    db::fetch_presentation(&pool, &presentation_id).await?;
+
+    let mut recent = load_recent(&pool).await?;
+    recent.retain(|entry| entry.presentation_id != presentation_id);
+    recent.insert(
+        0,
+        RecentEntry {
+            presentation_id,
+            opened_at: db::now(),
+        },
+    );
+    recent.truncate(RECENT_CAPACITY);
+
+    let json = serde_json::to_string(&recent).map_err(|e| e.to_string())?;
+    sqlx::query(
+        "INSERT INTO app_settings (key, value) VALUES (?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+    )
+    .bind(RECENT_KEY)
+    .bind(json)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Presentations most recently opened, newest first. Deleted ones are skipped.
+#[tauri::command]
+pub async fn get_recent_presentations(
+    db: State<'_, DbInstances>,
+    limit: u32,
+) -> Result<Vec<PresentationSummary>, String> {
+    let pool = db::pool(&db).await?;
+    let query = format!(
+        "SELECT {SUMMARY_COLUMNS} FROM presentations p
+         WHERE p.id = ? AND p.deleted_at IS NULL"
+    );
+
+    let mut summaries = Vec::new();
+    for entry in load_recent(&pool).await? {
+        if summaries.len() >= limit as usize {
+            break;
+        }
+        let summary = sqlx::query_as::<_, PresentationSummary>(&query)
+            .bind(&entry.presentation_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(mut summary) = summary {
+            summary.opened_at = Some(entry.opened_at);
+            summaries.push(summary);
+        }
+    }
+    Ok(summaries)
+}
+
+async fn load_recent(pool: &SqlitePool) -> Result<Vec<RecentEntry>, String> {
+    let stored: Option<String> = sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+        .bind(RECENT_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(stored
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    async fn link(pool: &SqlitePool, id: &str) -> (bool, Option<String>) {
+        sqlx::query_as("SELECT is_primary, parent_id FROM presentations WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn a_secondary_presentation_belongs_to_a_primary() {
+        let pool = test_support::pool().await;
+        for id in ["main", "vigil"] {
+            test_support::presentation(&pool, id).await;
+        }
+
+        let error = set_primary(&pool, "vigil", false, None).await.unwrap_err();
+        assert!(error.contains("needs a primary"), "{error}");
+        assert!(
+            set_primary(&pool, "vigil", false, Some("vigil".to_string()))
+                .await
+                .is_err()
+        );
+        assert!(
+            set_primary(&pool, "vigil", false, Some("missing".to_string()))
+                .await
+                .is_err()
+        );
+
+        set_primary(&pool, "vigil", false, Some("main".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(
+            link(&pool, "vigil").await,
+            (false, Some("main".to_string()))
+        );
+
+        // The parent can't become secondary while it has secondaries
+        test_support::presentation(&pool, "other").await;
+        let error = set_primary(&pool, "main", false, Some("other".to_string()))
+            .await
+            .unwrap_err();
+        assert!(error.contains("move them first"), "{error}");
+        // Nor can a secondary be a parent
+        test_support::presentation(&pool, "third").await;
+        assert!(
+            set_primary(&pool, "third", false, Some("vigil".to_string()))
+                .await
+                .is_err()
+        );
+
+        // Back to primary drops the link
+        set_primary(&pool, "vigil", true, None).await.unwrap();
+        assert_eq!(link(&pool, "vigil").await, (true, None));
+    }
+
+    #[tokio::test]
+    async fn saving_checks_a_change_to_primary() {
+        let pool = test_support::pool().await;
+        for id in ["main", "vigil"] {
+            test_support::presentation(&pool, id).await;
+        }
+
+        // Demoting with no parent to belong to
+        let mut vigil = db::fetch_presentation(&pool, "vigil").await.unwrap();
+        vigil.is_primary = false;
+        let error = save(&pool, vigil).await.unwrap_err();
+        assert!(error.contains("needs a primary"), "{error}");
+        assert_eq!(link(&pool, "vigil").await, (true, None));
+
+        // Demoting a parent whose secondaries would be left without one
+        set_primary(&pool, "vigil", false, Some("main".to_string()))
+            .await
+            .unwrap();
+        let mut main = db::fetch_presentation(&pool, "main").await.unwrap();
+        main.is_primary = false;
+        let error = save(&pool, main).await.unwrap_err();
+        assert!(error.contains("move them first"), "{error}");
+        assert_eq!(link(&pool, "main").await, (true, None));
+
+        // Other edits keep the link
+        let mut vigil = db::fetch_presentation(&pool, "vigil").await.unwrap();
+        vigil.name = "Vigil".to_string();
+        save(&pool, vigil).await.unwrap();
+        assert_eq!(
+            link(&pool, "vigil").await,
+            (false, Some("main".to_string()))
+        );
+
+        let mut vigil = db::fetch_presentation(&pool, "vigil").await.unwrap();
+        vigil.is_primary = true;
+        save(&pool, vigil).await.unwrap();
+        assert_eq!(link(&pool, "vigil").await, (true, None));
+    }
+
+    #[tokio::test]
+    async fn display_scale_is_clamped_and_read_back() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        async fn scale(pool: &SqlitePool) -> f64 {
+            db::fetch_presentation(pool, "p1")
+                .await
+                .unwrap()
+                .font_scale()
+        }
+        assert_eq!(scale(&pool).await, 1.0);
+
+        set_scale(&pool, "p1", 1.25).await.unwrap();
+        assert_eq!(scale(&pool).await, 1.25);
+        set_scale(&pool, "p1", 10.0).await.unwrap();
+        assert_eq!(scale(&pool).await, 3.0);
+        set_scale(&pool, "p1", 0.1).await.unwrap();
+        assert_eq!(scale(&pool).await, 0.5);
+
+        assert!(set_scale(&pool, "p1", f32::NAN).await.is_err());
+        assert!(set_scale(&pool, "p1", f32::INFINITY).await.is_err());
+        assert_eq!(scale(&pool).await, 0.5);
+        assert!(set_scale(&pool, "missing", 1.0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn extra_active_flags_are_cleared_leaving_the_newest() {
+        let pool = test_support::pool().await;
+        for id in ["older", "newer", "trashed"] {
+            test_support::presentation(&pool, id).await;
+        }
+        test_support::exec(
+            &pool,
+            "UPDATE presentations SET is_active = 1;
+             UPDATE presentations SET updated_at = '2026-01-01T00:00:00.000Z' WHERE id = 'older';
+             UPDATE presentations SET updated_at = '2026-02-01T00:00:00.000Z' WHERE id = 'newer';
+             UPDATE presentations SET updated_at = '2026-03-01T00:00:00.000Z',
+                                      deleted_at = '2026-03-01T00:00:00.000Z'
+              WHERE id = 'trashed'",
+        )
+        .await;
+
+        let mut conn = pool.acquire().await.unwrap();
+        assert_eq!(clear_extra_active(&mut conn).await, Ok(2));
+        assert_eq!(clear_extra_active(&mut conn).await, Ok(0));
+        drop(conn);
+
+        let active: Vec<String> =
+            sqlx::query_scalar("SELECT id FROM presentations WHERE is_active = 1")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(active, ["newer"]);
+    }
+}
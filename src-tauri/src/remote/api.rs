@@ -0,0 +1,247 @@
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use subtle::ConstantTimeEq;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_sql::DbInstances;
+
+use super::{Remote, RemoteCommand, RemoteControl};
+use crate::db;
+use crate::dynamic;
+use crate::export::slide_text;
+use crate::placeholders::Substituter;
+use crate::presentation_state::LiveState;
+use crate::secrets;
+
+/// `app_settings` key of the bearer token the HTTP API requires, kept
+/// encrypted (see [`secrets`]). Empty or absent leaves it open to the network,
+/// like the remote control page.
+const TOKEN_KEY: &str = "liveApiToken";
+
+/// What `GET /live` returns: the slide the audience sees and its text.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveOutput {
+    pub presentation_id: Option<String>,
+    pub presentation_name: Option<String>,
+    pub slide_index: u32,
+    pub slide_id: Option<String>,
+    pub blank: bool,
+    /// The audience is held on this slide while the presenter moves on.
+    pub frozen: bool,
+    /// The live slide's text in each language shown, in display order, with
+    /// variables expanded.
+    pub languages: Vec<LanguageText>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageText {
+    pub slot: String,
+    pub title: Option<String>,
+    pub body: Vec<String>,
+    pub footer: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Goto {
+    slide_index: u32,
+}
+
+/// An error answered with a JSON `{ "error": ... }` body.
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+/// `GET /live` and `POST /goto`, for streaming overlays and other tools on the
+/// network. Both need `Authorization: Bearer <token>` once a token is set.
+pub(super) fn routes() -> Router<Remote> {
+    Router::new()
+        .route("/live", get(live))
+        .route("/goto", post(goto))
+}
+
+async fn live(
+    State(remote): State<Remote>,
+    headers: HeaderMap,
+) -> Result<Json<LiveOutput>, ApiError> {
+    let pool = authorize(&remote.app, &headers).await?;
+    let state = remote.app.state::<RemoteControl>().state.borrow().clone();
+    output(&pool, state)
+        .await
+        .map(Json)
+        .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+/// Move the presentation to `slideIndex`, as a remote's `goto` does, and
+/// return the new live slide. Fails with 409 when nothing is being presented
+/// or the slide doesn't exist.
+async fn goto(
+    State(remote): State<Remote>,
+    headers: HeaderMap,
+    Json(goto): Json<Goto>,
+) -> Result<Json<LiveOutput>, ApiError> {
+    let pool = authorize(&remote.app, &headers).await?;
+    let state = RemoteCommand::Goto {
+        slide_index: goto.slide_index,
+    }
+    .run(&remote.app)
+    .await
+    .map_err(|e| ApiError(StatusCode::CONFLICT, e))?;
+    output(&pool, state)
+        .await
+        .map(Json)
+        .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+async fn authorize(app: &AppHandle, headers: &HeaderMap) -> Result<SqlitePool, ApiError> {
+    let pool = db::pool(&app.state::<DbInstances>())
+        .await
+        .map_err(|e| ApiError(StatusCode::SERVICE_UNAVAILABLE, e))?;
+    check_token(&pool, headers).await?;
+    Ok(pool)
+}
+
+async fn check_token(pool: &SqlitePool, headers: &HeaderMap) -> Result<(), ApiError> {
+    let token = secrets::read(pool, TOKEN_KEY)
+        .await
+        .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let Some(token) = token.filter(|token| !token.is_empty()) else {
+        return Ok(());
+    };
+    let given = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if !given.is_some_and(|given| tokens_match(given, &token)) {
+        return Err(ApiError(
+            StatusCode::UNAUTHORIZED,
+            "Missing or wrong API token".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Compare digests in constant time, so neither how much of the token matched
+/// nor its length shows in the response time.
+fn tokens_match(given: &str, token: &str) -> bool {
+    Sha256::digest(given.as_bytes())
+        .ct_eq(&Sha256::digest(token.as_bytes()))
+        .into()
+}
+
+async fn output(pool: &SqlitePool, state: LiveState) -> Result<LiveOutput, String> {
+    let live = state.shown();
+    let mut output = LiveOutput {
+        presentation_id: live.presentation_id.clone(),
+        presentation_name: None,
+        slide_index: live.slide_index,
+        slide_id: live.slide_id.clone(),
+        blank: state.blank,
+        frozen: state.frozen.is_some(),
+        languages: Vec::new(),
+    };
+    let Some(presentation_id) = &live.presentation_id else {
+        return Ok(output);
+    };
+    let presentation = db::fetch_presentation(pool, presentation_id).await?;
+    output.presentation_name = Some(presentation.name.clone());
+    let Some(slide_id) = &live.slide_id else {
+        return Ok(output);
+    };
+
+    let slide = dynamic::fetch_presented(pool, slide_id).await?;
+    let variables = db::fetch_variables(pool, &presentation.id).await?;
+    let substituter = Substituter::new(&variables)?;
+    let slots: Vec<&str> = if live.language_set.is_empty() {
+        presentation.ordered_languages()
+    } else {
+        live.language_set.iter().map(String::as_str).collect()
+    };
+    for slot in slots {
+        let text = slide_text(&slide, &[slot], &substituter);
+        output.languages.push(LanguageText {
+            slot: slot.to_string(),
+            title: text.title.into_iter().next(),
+            body: text.bodies.concat(),
+            footer: text.footer,
+        });
+    }
+    Ok(output)
+}
+
+/// Set the token `GET /live` and `POST /goto` require, stored encrypted, or
+/// clear it with an empty or missing one. Returns whether a token is now
+/// required.
+#[tauri::command]
+pub async fn set_live_api_token(
+    db: tauri::State<'_, DbInstances>,
+    token: Option<String>,
+) -> Result<bool, String> {
+    let token = token
+        .map(|token| token.trim().to_string())
+        .unwrap_or_default();
+    let pool = db::writable_pool(&db).await?;
+    if token.is_empty() {
+        sqlx::query("DELETE FROM app_settings WHERE key = ?")
+            .bind(TOKEN_KEY)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(false);
+    }
+    secrets::store(&pool, TOKEN_KEY, &token).await?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+    use crate::test_support;
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let value = HeaderValue::from_str(&format!("Bearer {token}")).unwrap();
+        headers.insert(header::AUTHORIZATION, value);
+        headers
+    }
+
+    #[test]
+    fn only_the_exact_token_matches() {
+        assert!(tokens_match("s3cret", "s3cret"));
+        for given in ["", "s3cre", "s3cret!", "S3CRET"] {
+            assert!(!tokens_match(given, "s3cret"), "{given}");
+        }
+    }
+
+    #[tokio::test]
+    async fn the_api_is_open_until_a_token_is_set() {
+        let pool = test_support::pool().await;
+        assert!(check_token(&pool, &HeaderMap::new()).await.is_ok());
+
+        // A token saved before it was kept encrypted still works
+        test_support::exec(
+            &pool,
+            "INSERT INTO app_settings (key, value) VALUES ('liveApiToken', 's3cret')",
+        )
+        .await;
+        assert!(check_token(&pool, &bearer("s3cret")).await.is_ok());
+        for headers in [HeaderMap::new(), bearer("wrong"), bearer("s3cre")] {
+            let ApiError(status, _) = check_token(&pool, &headers).await.unwrap_err();
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+        }
+    }
+}
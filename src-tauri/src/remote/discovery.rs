@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+
+/// mDNS service type the remote control server is advertised under.
+const SERVICE_TYPE: &str = "_kidase._tcp.local.";
+/// How long withdrawing the advertisement waits for its goodbye to go out.
+const GOODBYE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The server's mDNS advertisement, withdrawn when dropped.
+pub(super) struct Advertisement {
+    daemon: ServiceDaemon,
+    fullname: String,
+}
+
+impl Advertisement {
+    /// Announce the server on `port` under this machine's name. TXT records
+    /// give the paths of the control page, its socket and the HTTP API, so a
+    /// companion app needs nothing else.
+    pub(super) fn publish(port: u16) -> Result<Self, String> {
+        let machine = gethostname::gethostname().to_string_lossy().into_owned();
+        let host = format!("{}.local.", host_label(&machine));
+        let properties = [
+            ("page", "/"),
+            ("ws", "/ws"),
+            ("api", "/live"),
+            ("version", env!("CARGO_PKG_VERSION")),
+        ];
+        let info = ServiceInfo::new(SERVICE_TYPE, &machine, &host, "", port, &properties[..])
+            .map_err(|e| format!("Invalid mDNS service: {e}"))?
+            .enable_addr_auto();
+        let fullname = info.get_fullname().to_string();
+
+        let daemon = ServiceDaemon::new().map_err(|e| format!("Failed to start mDNS: {e}"))?;
+        daemon
+            .register(info)
+            .map_err(|e| format!("Failed to advertise over mDNS: {e}"))?;
+        Ok(Self { daemon, fullname })
+    }
+
+    /// The full service name, e.g. `CHURCH-PC._kidase._tcp.local.`.
+    pub(super) fn name(&self) -> &str {
+        &self.fullname
+    }
+}
+
+impl Drop for Advertisement {
+    fn drop(&mut self) {
+        // The goodbye lets phones drop the entry now rather than when it expires
+        if let Ok(status) = self.daemon.unregister(&self.fullname) {
+            let _ = status.recv_timeout(GOODBYE_TIMEOUT);
+        }
+        let _ = self.daemon.shutdown();
+    }
+}
+
+/// `machine` as a DNS label: letters, digits and hyphens only.
+fn host_label(machine: &str) -> String {
+    let label: String = machine
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let label = label.trim_matches('-');
+    if label.is_empty() {
+        "kidase".to_string()
+    } else {
+        label.to_string()
+    }
+}
@@ -0,0 +1,360 @@
+pub mod api;
+mod discovery;
+
+use std::net::UdpSocket;
+use std::sync::Mutex;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::http::header;
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_sql::DbInstances;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+
+use self::discovery::Advertisement;
+use crate::db;
+use crate::presentation_state::{self, LiveState};
+
+/// `app_settings` keys configuring the remote control server.
+const ENABLED_KEY: &str = "remoteControlEnabled";
+const PORT_KEY: &str = "remoteControlPort";
+const DEFAULT_PORT: u16 = 8765;
+
+/// Served at `/` for a phone to drive the presentation from; it talks to
+/// `/ws`.
+const CONTROL_PAGE: &str = include_str!("page.html");
+
+/// A message from a remote, e.g. `{"action":"goto","slideIndex":4}`, carried
+/// out on the live session like the app's own `live_*` commands.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(
+    tag = "action",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum RemoteCommand {
+    Next,
+    Prev,
+    Goto {
+        slide_index: u32,
+    },
+    /// Toggles when `blank` is left out.
+    Blank {
+        blank: Option<bool>,
+    },
+    /// Toggles when `freeze` is left out.
+    Freeze {
+        freeze: Option<bool>,
+    },
+}
+
+impl RemoteCommand {
+    async fn run(self, app: &AppHandle) -> Result<LiveState, String> {
+        match self {
+            Self::Next => presentation_state::update_live(app, LiveState::next).await,
+            Self::Prev => presentation_state::update_live(app, LiveState::prev).await,
+            Self::Goto { slide_index } => {
+                presentation_state::update_live(app, |live| live.goto(slide_index)).await
+            }
+            Self::Blank { blank } => {
+                presentation_state::update_live(app, |live| {
+                    live.set_blank(blank);
+                    Ok(())
+                })
+                .await
+            }
+            Self::Freeze { freeze } => {
+                presentation_state::update_live(app, |live| {
+                    live.set_frozen(freeze);
+                    Ok(())
+                })
+                .await
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteControlStatus {
+    pub enabled: bool,
+    pub port: u16,
+    pub running: bool,
+    /// Remotes connected right now.
+    pub clients: usize,
+    /// Address to open on a phone on the same network, when it can be found.
+    pub url: Option<String>,
+    /// The mDNS name the server is advertised under, while it is.
+    pub service_name: Option<String>,
+}
+
+/// The opt-in server, started by `configure_remote_control` or, after a
+/// restart, `resume_remote_control`.
+pub struct RemoteControl {
+    /// The live session, sent to each remote when it connects and after every
+    /// change.
+    state: watch::Sender<LiveState>,
+    server: Mutex<Option<Server>>,
+}
+
+/// A listening server; dropping it closes the listener and every connection.
+struct Server {
+    port: u16,
+    _stop: watch::Sender<()>,
+    /// `None` when the network wouldn't take it; the server works regardless.
+    advertisement: Option<Advertisement>,
+}
+
+/// What every route of the server can reach.
+#[derive(Clone)]
+struct Remote {
+    app: AppHandle,
+    /// Closes when the server stops.
+    stopped: watch::Receiver<()>,
+}
+
+impl Default for RemoteControl {
+    fn default() -> Self {
+        Self {
+            state: watch::Sender::new(LiveState::default()),
+            server: Mutex::new(None),
+        }
+    }
+}
+
+impl RemoteControl {
+    /// Tell connected remotes about the live session as it is now.
+    pub fn set_live(&self, live: LiveState) {
+        self.state.send_replace(live);
+    }
+
+    /// Listen on `port`, replacing a server on another port.
+    async fn start(&self, app: &AppHandle, port: u16) -> Result<(), String> {
+        if self.port() == Some(port) {
+            return Ok(());
+        }
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .await
+            .map_err(|e| format!("Couldn't listen on port {port}: {e}"))?;
+        let (stop, stopped) = watch::channel(());
+        let router = Router::new()
+            .route("/", get(page))
+            .route("/ws", get(socket))
+            .merge(api::routes())
+            .with_state(Remote {
+                app: app.clone(),
+                stopped: stopped.clone(),
+            });
+        let mut shutdown = stopped;
+        tauri::async_runtime::spawn(async move {
+            let served = axum::serve(listener, router)
+                .with_graceful_shutdown(async move {
+                    let _ = shutdown.changed().await;
+                })
+                .await;
+            if let Err(e) = served {
+                eprintln!("Remote control server failed: {e}");
+            }
+        });
+        let advertisement = Advertisement::publish(port)
+            .inspect_err(|e| eprintln!("Remote control won't be discoverable: {e}"))
+            .ok();
+        *self.server.lock().map_err(|e| e.to_string())? = Some(Server {
+            port,
+            _stop: stop,
+            advertisement,
+        });
+        Ok(())
+    }
+
+    fn stop(&self) {
+        if let Ok(mut server) = self.server.lock() {
+            server.take();
+        }
+    }
+
+    fn port(&self) -> Option<u16> {
+        self.server.lock().ok()?.as_ref().map(|server| server.port)
+    }
+
+    fn service_name(&self) -> Option<String> {
+        let server = self.server.lock().ok()?;
+        let advertisement = server.as_ref()?.advertisement.as_ref()?;
+        Some(advertisement.name().to_string())
+    }
+
+    fn status(&self, enabled: bool, port: u16) -> RemoteControlStatus {
+        let running = self.port();
+        RemoteControlStatus {
+            enabled,
+            port: running.unwrap_or(port),
+            running: running.is_some(),
+            clients: self.state.receiver_count(),
+            url: running.and_then(|port| Some(format!("http://{}:{port}/", local_ip()?))),
+            service_name: self.service_name(),
+        }
+    }
+}
+
+async fn page() -> impl IntoResponse {
+    ([(header::CACHE_CONTROL, "no-store")], Html(CONTROL_PAGE))
+}
+
+async fn socket(
+    axum::extract::State(remote): axum::extract::State<Remote>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    upgrade.on_upgrade(move |socket| async move {
+        if let Err(e) = connection(remote, socket).await {
+            eprintln!("Remote control connection failed: {e}");
+        }
+    })
+}
+
+async fn connection(remote: Remote, socket: WebSocket) -> Result<(), String> {
+    let Remote { app, mut stopped } = remote;
+    let (mut sink, mut source) = socket.split();
+    let control = app.state::<RemoteControl>();
+    let mut updates = control.state.subscribe();
+    updates.mark_changed();
+
+    loop {
+        tokio::select! {
+            _ = stopped.changed() => break,
+            changed = updates.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                let json = serde_json::to_string(&*updates.borrow_and_update())
+                    .map_err(|e| e.to_string())?;
+                sink.send(Message::Text(json.into())).await.map_err(|e| e.to_string())?;
+            }
+            message = source.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    // Success reaches every remote as the new state
+                    let ran = match serde_json::from_str::<RemoteCommand>(&text) {
+                        Ok(command) => command.run(&app).await.map(|_| ()),
+                        Err(e) => Err(format!("Unknown command: {e}")),
+                    };
+                    if let Err(e) = ran {
+                        let error = json!({ "error": e });
+                        sink.send(Message::Text(error.to_string().into()))
+                            .await
+                            .map_err(|e| e.to_string())?;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e.to_string()),
+            },
+        }
+    }
+    let _ = sink.close().await;
+    Ok(())
+}
+
+/// This machine's address on the network its default route goes through.
+/// Connecting a UDP socket sends nothing.
+fn local_ip() -> Option<String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:80").ok()?;
+    Some(socket.local_addr().ok()?.ip().to_string())
+}
+
+async fn load_config(pool: &SqlitePool) -> Result<(bool, u16), String> {
+    let rows: Vec<(String, String)> =
+        sqlx::query_as("SELECT key, value FROM app_settings WHERE key IN (?, ?)")
+            .bind(ENABLED_KEY)
+            .bind(PORT_KEY)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let mut enabled = false;
+    let mut port = DEFAULT_PORT;
+    for (key, value) in rows {
+        match key.as_str() {
+            ENABLED_KEY => enabled = value.trim() == "true",
+            PORT_KEY => port = value.trim().parse().unwrap_or(DEFAULT_PORT),
+            _ => {}
+        }
+    }
+    Ok((enabled, port))
+}
+
+/// Turn the remote control server on or off and save the choice.
+///
+/// While it's on, a phone on the same network can open the returned `url` to
+/// move through the slides and blank the screen, or find it advertised over
+/// mDNS as `_kidase._tcp` under the machine's name. `port` defaults to 8765; a
+/// port already in use is an error and nothing is saved.
+#[tauri::command]
+pub async fn configure_remote_control(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    remote: State<'_, RemoteControl>,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<RemoteControlStatus, String> {
+    let port = port.unwrap_or(DEFAULT_PORT);
+    if port == 0 {
+        return Err("Choose a port between 1 and 65535".to_string());
+    }
+    let pool = db::writable_pool(&db).await?;
+    if enabled {
+        remote.start(&app, port).await?;
+    } else {
+        remote.stop();
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for (key, value) in [
+        (ENABLED_KEY, enabled.to_string()),
+        (PORT_KEY, port.to_string()),
+    ] {
+        sqlx::query(
+            "INSERT INTO app_settings (key, value) VALUES (?, ?)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        )
+        .bind(key)
+        .bind(value)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(remote.status(enabled, port))
+}
+
+#[tauri::command]
+pub async fn get_remote_control_status(
+    db: State<'_, DbInstances>,
+    remote: State<'_, RemoteControl>,
+) -> Result<RemoteControlStatus, String> {
+    let pool = db::pool(&db).await?;
+    let (enabled, port) = load_config(&pool).await?;
+    Ok(remote.status(enabled, port))
+}
+
+/// Start the server if it was on when the app last closed. Called by the
+/// frontend once the database is open.
+#[tauri::command]
+pub async fn resume_remote_control(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    remote: State<'_, RemoteControl>,
+) -> Result<RemoteControlStatus, String> {
+    let pool = db::pool(&db).await?;
+    let (enabled, port) = load_config(&pool).await?;
+    if enabled {
+        remote.start(&app, port).await?;
+    }
+    Ok(remote.status(enabled, port))
+}
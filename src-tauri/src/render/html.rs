@@ -0,0 +1,109 @@
+use std::fmt::Write;
+
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use super::{resolve, Content, TITLE_GAP, TITLE_LINE_HEIGHT};
+use crate::db;
+use crate::export::language_slots;
+use crate::markup::escape;
+use crate::template::{TemplateDefinition, TextAlignment, TitleAlignment, VerticalAlign};
+
+/// Render one slide as a self-contained HTML fragment for previews and sharing.
+///
+/// Only the requested languages (1–4) are included, in the order given, laid
+/// out with the slide's effective template: its margins, columns, colours and
+/// font sizes in the presenter's 1920×1080 design units, all as inline styles.
+/// Slide text is escaped, so it can't break out of the markup.
+#[tauri::command]
+pub async fn render_slide_html(
+    db: State<'_, DbInstances>,
+    slide_id: String,
+    language_indices: Vec<u8>,
+) -> Result<String, String> {
+    let slots = language_slots(&language_indices)?;
+    if slots.is_empty() {
+        return Err("Choose at least one language to render".to_string());
+    }
+
+    let pool = db::pool(&db).await?;
+    let slide = db::fetch_slide(&pool, &slide_id).await?;
+    let presentation = db::fetch_presentation(&pool, &slide.presentation_id).await?;
+    let (definition, content) = resolve(&pool, &slide, &presentation, &slots).await?;
+    Ok(to_html(&definition, &content))
+}
+
+fn to_html(definition: &TemplateDefinition, content: &Content) -> String {
+    let margins = &definition.margins;
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        r#"<div style="box-sizing:border-box;display:flex;flex-direction:column;gap:{TITLE_GAP}px;aspect-ratio:16/9;padding:{}px {}px {}px {}px;background:{}">"#,
+        margins.top,
+        margins.right,
+        margins.bottom,
+        margins.left,
+        escape(&definition.background.color)
+    );
+
+    let title = &definition.title;
+    let heading_style = format!(
+        "font-size:{}px;line-height:{TITLE_LINE_HEIGHT};color:{};text-align:{}",
+        title.font_size,
+        escape(&title.color),
+        match title.alignment {
+            TitleAlignment::Left => "left",
+            TitleAlignment::Center => "center",
+            TitleAlignment::Right => "right",
+        }
+    );
+    if let Some(text) = &content.title {
+        let _ = write!(
+            html,
+            r#"<div style="{heading_style}">{}</div>"#,
+            escape(text)
+        );
+    }
+
+    let columns = (definition.layout.columns as usize)
+        .min(content.bodies.len())
+        .max(1);
+    let _ = write!(
+        html,
+        r#"<div style="flex:1;display:grid;grid-template-columns:repeat({columns},1fr);gap:{}px;align-content:{}">"#,
+        definition.layout.gap.max(0.0),
+        match definition.layout.vertical_align {
+            Some(VerticalAlign::Top) => "start",
+            Some(VerticalAlign::Bottom) => "end",
+            Some(VerticalAlign::Center) | None => "center",
+        }
+    );
+    for (style, text) in &content.bodies {
+        let _ = write!(
+            html,
+            r#"<div style="white-space:pre-wrap;font-family:{};font-size:{}px;line-height:{};color:{};text-align:{}">{}</div>"#,
+            escape(&style.font_family),
+            style.font_size,
+            style.line_height,
+            escape(&style.color),
+            match style.alignment {
+                TextAlignment::Left => "left",
+                TextAlignment::Center => "center",
+                TextAlignment::Right => "right",
+                TextAlignment::Justify => "justify",
+            },
+            escape(text)
+        );
+    }
+    html.push_str("</div>");
+
+    for text in &content.footer {
+        let _ = write!(
+            html,
+            r#"<div style="{heading_style}">{}</div>"#,
+            escape(text)
+        );
+    }
+    html.push_str("</div>");
+    html
+}
@@ -0,0 +1,461 @@
+pub mod html;
+
+use std::sync::OnceLock;
+
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, Rect, Transform};
+use ttf_parser::{Face, OutlineBuilder};
+
+use crate::db::{self, Presentation, Slide};
+use crate::export::{slide_text, wrap_lines};
+use crate::fonts;
+use crate::placeholders::Substituter;
+use crate::template::{
+    LanguageStyle, TemplateDefinition, TextAlignment, TitleAlignment, VerticalAlign,
+};
+use crate::theme::Rgb;
+
+/// Layout happens in the presenter's design space, then scales to the thumbnail.
+const DESIGN_WIDTH: f32 = 1920.0;
+const DESIGN_HEIGHT: f32 = 1080.0;
+/// Thumbnails are clamped to this so a bad request can't allocate gigabytes.
+const MAX_WIDTH: u32 = 1920;
+const MAX_HEIGHT: u32 = 1080;
+
+pub(crate) const TITLE_LINE_HEIGHT: f32 = 1.2;
+pub(crate) const TITLE_GAP: f32 = 24.0;
+/// Smallest fraction of the template font sizes tried when shrinking text to fit.
+const MIN_FIT: f32 = 0.3;
+const WATERMARK_SIZE: f32 = 220.0;
+
+/// Ge'ez and Latin font files, found once per run since scanning is slow.
+static FONTS: OnceLock<(Vec<u8>, Option<Vec<u8>>)> = OnceLock::new();
+
+/// Rasterize one slide to PNG bytes for the editor's filmstrip.
+///
+/// The slide is laid out with its effective template (the override when it
+/// exists) in the presentation's display languages, with variables expanded and
+/// text shrunk to fit as the presenter does. Disabled slides are dimmed and
+/// stamped "DISABLED". Sizes above 1920×1080 are clamped.
+#[tauri::command]
+pub async fn render_slide_thumbnail(
+    db: State<'_, DbInstances>,
+    slide_id: String,
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>, String> {
+    if width == 0 || height == 0 {
+        return Err("Thumbnail width and height must be at least 1".to_string());
+    }
+
+    let pool = db::pool(&db).await?;
+    let slide = db::fetch_slide(&pool, &slide_id).await?;
+    let presentation = db::fetch_presentation(&pool, &slide.presentation_id).await?;
+    slide_pixmap(&pool, &slide, &presentation, width, height)
+        .await?
+        .encode_png()
+        .map_err(|e| e.to_string())
+}
+
+/// Rasterize a slide as [`render_slide_thumbnail`] does, before PNG encoding.
+/// Every pixel is opaque.
+pub(crate) async fn slide_pixmap(
+    pool: &SqlitePool,
+    slide: &Slide,
+    presentation: &Presentation,
+    width: u32,
+    height: u32,
+) -> Result<Pixmap, String> {
+    let (width, height) = (width.min(MAX_WIDTH), height.min(MAX_HEIGHT));
+    let (definition, content) =
+        resolve(pool, slide, presentation, &presentation.ordered_languages()).await?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let (ethiopic, latin) = font_data()?;
+        let faces = Faces {
+            ethiopic: Face::parse(ethiopic, 0).map_err(|e| e.to_string())?,
+            latin: latin
+                .as_deref()
+                .map(|data| Face::parse(data, 0))
+                .transpose()
+                .map_err(|e| e.to_string())?,
+        };
+        rasterize(&definition, &content, &faces, width, height)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// The slide's effective template (the override when it exists), its font
+/// sizes scaled by the presentation's display scale, and its text in `slots`,
+/// in that order, with variables expanded.
+pub(crate) async fn resolve(
+    pool: &SqlitePool,
+    slide: &Slide,
+    presentation: &Presentation,
+    slots: &[&str],
+) -> Result<(TemplateDefinition, Content), String> {
+    let template =
+        match db::fetch_template(pool, slide.template_id(&presentation.template_id)).await {
+            Ok(template) => template,
+            // A dangling override falls back to the presentation template when rendering
+            Err(_) => db::fetch_template(pool, &presentation.template_id).await?,
+        };
+    let mut definition: TemplateDefinition = serde_json::from_str(&template.definition_json)
+        .map_err(|e| format!("Invalid definition for template {}: {e}", template.name))?;
+    let scale = presentation.font_scale();
+    definition.title.font_size *= scale;
+    for language in &mut definition.languages {
+        language.font_size *= scale;
+    }
+    let variables = db::fetch_variables(pool, &presentation.id).await?;
+    let substituter = Substituter::new(&variables)?;
+
+    let mut content = Content {
+        title: None,
+        bodies: Vec::new(),
+        footer: Vec::new(),
+        disabled: slide.is_disabled,
+    };
+    for &slot in slots {
+        let Some(style) = definition.languages.iter().find(|l| l.slot == slot) else {
+            continue;
+        };
+        let text = slide_text(slide, &[slot], &substituter);
+        if content.title.is_none() {
+            content.title = text.title.into_iter().next();
+        }
+        let body = text.bodies.concat();
+        if !body.is_empty() {
+            content.bodies.push((style.clone(), body.join("\n")));
+        }
+        content.footer.extend(text.footer);
+    }
+    if !definition.title.show {
+        content.title = None;
+    }
+    Ok((definition, content))
+}
+
+fn font_data() -> Result<&'static (Vec<u8>, Option<Vec<u8>>), String> {
+    if let Some(data) = FONTS.get() {
+        return Ok(data);
+    }
+    // Only success is cached, so installing a font fixes thumbnails without a restart
+    let data = fonts::ethiopic_font_data(fonts::font_database())?;
+    Ok(FONTS.get_or_init(|| data))
+}
+
+/// Slide text resolved for drawing.
+pub(crate) struct Content {
+    pub(crate) title: Option<String>,
+    /// Each language's block text with the template style for its slot.
+    pub(crate) bodies: Vec<(LanguageStyle, String)>,
+    pub(crate) footer: Vec<String>,
+    pub(crate) disabled: bool,
+}
+
+struct Faces<'a> {
+    ethiopic: Face<'a>,
+    latin: Option<Face<'a>>,
+}
+
+impl Faces<'_> {
+    /// The Ge'ez face when it has every glyph of `text`, else the Latin one.
+    fn pick(&self, text: &str) -> &Face<'_> {
+        let covers = text
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .all(|c| self.ethiopic.glyph_index(c).is_some());
+        match &self.latin {
+            Some(latin) if !covers => latin,
+            _ => &self.ethiopic,
+        }
+    }
+
+    fn width(&self, text: &str, size: f32) -> f32 {
+        let face = self.pick(text);
+        let units: f32 = text
+            .chars()
+            .filter_map(|c| face.glyph_index(c))
+            .filter_map(|glyph| face.glyph_hor_advance(glyph))
+            .map(f32::from)
+            .sum();
+        units * size / f32::from(face.units_per_em())
+    }
+
+    fn wrap(&self, text: &str, size: f32, max_width: f32) -> Vec<String> {
+        wrap_lines(text, max_width, |s| self.width(s, size))
+    }
+}
+
+/// A block of wrapped lines sharing one style, measured in design units.
+struct Paragraph {
+    lines: Vec<String>,
+    size: f32,
+    line_height: f32,
+    color: Rgb,
+    align: TextAlignment,
+}
+
+impl Paragraph {
+    fn height(&self) -> f32 {
+        self.lines.len() as f32 * self.size * self.line_height
+    }
+}
+
+fn rasterize(
+    definition: &TemplateDefinition,
+    content: &Content,
+    faces: &Faces,
+    width: u32,
+    height: u32,
+) -> Result<Pixmap, String> {
+    let mut pixmap = Pixmap::new(width, height).ok_or("Thumbnail size is too large")?;
+    let background = Rgb::parse(&definition.background.color).unwrap_or(Rgb::BLACK);
+    pixmap.fill(tiny_skia::Color::from_rgba8(
+        background.r,
+        background.g,
+        background.b,
+        255,
+    ));
+
+    // Uniform scale, centred, so text keeps its proportions on odd aspect ratios
+    let scale = (width as f32 / DESIGN_WIDTH).min(height as f32 / DESIGN_HEIGHT);
+    let base = Transform::from_translate(
+        (width as f32 - DESIGN_WIDTH * scale) / 2.0,
+        (height as f32 - DESIGN_HEIGHT * scale) / 2.0,
+    )
+    .pre_scale(scale, scale);
+
+    let margins = &definition.margins;
+    let left = margins.left as f32;
+    let content_width = DESIGN_WIDTH - left - margins.right as f32;
+    let title_size = definition.title.font_size as f32;
+    let title_color = Rgb::parse(&definition.title.color).unwrap_or(Rgb::WHITE);
+    let title_align = match definition.title.alignment {
+        TitleAlignment::Left => TextAlignment::Left,
+        TitleAlignment::Center => TextAlignment::Center,
+        TitleAlignment::Right => TextAlignment::Right,
+    };
+    let heading = |text: &str| Paragraph {
+        lines: faces.wrap(text, title_size, content_width),
+        size: title_size,
+        line_height: TITLE_LINE_HEIGHT,
+        color: title_color,
+        align: title_align,
+    };
+
+    let mut top = margins.top as f32;
+    if let Some(title) = &content.title {
+        let title = heading(title);
+        draw_paragraph(&mut pixmap, faces, &title, left, content_width, top, base);
+        top += title.height() + TITLE_GAP;
+    }
+
+    let mut bottom = DESIGN_HEIGHT - margins.bottom as f32;
+    let footer: Vec<Paragraph> = content.footer.iter().map(|text| heading(text)).collect();
+    let footer_height: f32 = footer.iter().map(Paragraph::height).sum();
+    if !footer.is_empty() {
+        let mut footer_top = bottom - footer_height;
+        for paragraph in &footer {
+            draw_paragraph(
+                &mut pixmap,
+                faces,
+                paragraph,
+                left,
+                content_width,
+                footer_top,
+                base,
+            );
+            footer_top += paragraph.height();
+        }
+        bottom -= footer_height + TITLE_GAP;
+    }
+
+    let columns = (definition.layout.columns as usize)
+        .min(content.bodies.len())
+        .max(1);
+    let gap = (definition.layout.gap as f32).max(16.0);
+    let column_width = (content_width - gap * (columns - 1) as f32) / columns as f32;
+    let available = (bottom - top).max(0.0);
+
+    let layout = |fit: f32| -> Vec<Vec<Paragraph>> {
+        let mut laid_out: Vec<Vec<Paragraph>> = (0..columns).map(|_| Vec::new()).collect();
+        for (index, (style, text)) in content.bodies.iter().enumerate() {
+            let size = style.font_size as f32 * fit;
+            laid_out[index % columns].push(Paragraph {
+                lines: faces.wrap(text, size, column_width),
+                size,
+                line_height: style.line_height as f32,
+                color: Rgb::parse(&style.color).unwrap_or(Rgb::WHITE),
+                align: style.alignment,
+            });
+        }
+        laid_out
+    };
+    let column_height = |column: &[Paragraph]| {
+        column.iter().map(Paragraph::height).sum::<f32>()
+            + gap * column.len().saturating_sub(1) as f32
+    };
+
+    let mut fit = 1.0;
+    let mut laid_out = layout(fit);
+    while fit > MIN_FIT
+        && laid_out
+            .iter()
+            .any(|column| column_height(column) > available)
+    {
+        fit = (fit - 0.05_f32).max(MIN_FIT);
+        laid_out = layout(fit);
+    }
+
+    for (index, column) in laid_out.iter().enumerate() {
+        let spare = (available - column_height(column)).max(0.0);
+        let mut y = top
+            + match definition.layout.vertical_align {
+                Some(VerticalAlign::Top) => 0.0,
+                Some(VerticalAlign::Bottom) => spare,
+                Some(VerticalAlign::Center) | None => spare / 2.0,
+            };
+        let x = left + index as f32 * (column_width + gap);
+        for paragraph in column {
+            draw_paragraph(&mut pixmap, faces, paragraph, x, column_width, y, base);
+            y += paragraph.height() + gap;
+        }
+    }
+
+    if content.disabled {
+        draw_watermark(&mut pixmap, faces, base);
+    }
+
+    Ok(pixmap)
+}
+
+fn draw_paragraph(
+    pixmap: &mut Pixmap,
+    faces: &Faces,
+    paragraph: &Paragraph,
+    x: f32,
+    width: f32,
+    top: f32,
+    base: Transform,
+) {
+    let mut y = top;
+    for line in &paragraph.lines {
+        let line_width = faces.width(line, paragraph.size);
+        let offset = match paragraph.align {
+            TextAlignment::Left | TextAlignment::Justify => 0.0,
+            TextAlignment::Center => (width - line_width) / 2.0,
+            TextAlignment::Right => width - line_width,
+        };
+        let face = faces.pick(line);
+        let ascent = f32::from(face.ascender()) * paragraph.size / f32::from(face.units_per_em());
+        draw_text(
+            pixmap,
+            face,
+            line,
+            paragraph.size,
+            (x + offset.max(0.0), y + ascent),
+            (paragraph.color, 255),
+            base,
+        );
+        y += paragraph.size * paragraph.line_height;
+    }
+}
+
+/// Dim the slide and stamp a diagonal "DISABLED" across it.
+fn draw_watermark(pixmap: &mut Pixmap, faces: &Faces, base: Transform) {
+    let mut shade = Paint::default();
+    shade.set_color_rgba8(0, 0, 0, 150);
+    if let Some(rect) = Rect::from_xywh(0.0, 0.0, pixmap.width() as f32, pixmap.height() as f32) {
+        pixmap.fill_rect(rect, &shade, Transform::identity(), None);
+    }
+
+    let label = "DISABLED";
+    let face = faces.pick(label);
+    let width = faces.width(label, WATERMARK_SIZE);
+    let ascent = f32::from(face.ascender()) * WATERMARK_SIZE / f32::from(face.units_per_em());
+    let (cx, cy) = (DESIGN_WIDTH / 2.0, DESIGN_HEIGHT / 2.0);
+    draw_text(
+        pixmap,
+        face,
+        label,
+        WATERMARK_SIZE,
+        (cx - width / 2.0, cy - WATERMARK_SIZE / 2.0 + ascent),
+        (
+            Rgb {
+                r: 255,
+                g: 80,
+                b: 80,
+            },
+            210,
+        ),
+        base.pre_concat(Transform::from_rotate_at(-20.0, cx, cy)),
+    );
+}
+
+/// Fill `text` as glyph outlines with its baseline starting at `origin`.
+fn draw_text(
+    pixmap: &mut Pixmap,
+    face: &Face,
+    text: &str,
+    size: f32,
+    origin: (f32, f32),
+    (color, alpha): (Rgb, u8),
+    base: Transform,
+) {
+    let mut outline = GlyphOutline {
+        builder: PathBuilder::new(),
+        pen: 0.0,
+    };
+    for c in text.chars() {
+        let Some(glyph) = face.glyph_index(c) else {
+            continue;
+        };
+        face.outline_glyph(glyph, &mut outline);
+        outline.pen += face.glyph_hor_advance(glyph).map(f32::from).unwrap_or(0.0);
+    }
+    let Some(path) = outline.builder.finish() else {
+        return;
+    };
+
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(color.r, color.g, color.b, alpha);
+    paint.anti_alias = true;
+    // Font units are y-up; flip them onto the baseline
+    let em = size / f32::from(face.units_per_em());
+    let transform = base.pre_concat(Transform::from_row(em, 0.0, 0.0, -em, origin.0, origin.1));
+    pixmap.fill_path(&path, &paint, FillRule::Winding, transform, None);
+}
+
+/// Collects glyph outlines into one path, offsetting each by the pen position.
+struct GlyphOutline {
+    builder: PathBuilder,
+    pen: f32,
+}
+
+impl OutlineBuilder for GlyphOutline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.builder.move_to(x + self.pen, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.builder.line_to(x + self.pen, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.builder.quad_to(x1 + self.pen, y1, x + self.pen, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.builder
+            .cubic_to(x1 + self.pen, y1, x2 + self.pen, y2, x + self.pen, y);
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
@@ -0,0 +1,200 @@
+use regex::{NoExpand, Regex};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::audit;
+use crate::db::{self, Slide, LANG_SLOTS};
+use crate::edits::{self, EditOp};
+use crate::export::language_slots;
+use crate::present_mode;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceHit {
+    pub slide_id: String,
+    pub slide_order: i64,
+    /// Matches replaced in the slide's title and blocks.
+    pub replacements: u32,
+}
+
+/// Replace text across a presentation's slide titles and blocks, returning the
+/// slides that matched in order. With `dry_run` nothing is written; otherwise
+/// every change lands in one transaction and undoes as a single edit.
+///
+/// Only language text is touched: string values under a `Lang1`..`Lang4` key,
+/// or just `language_index`'s slot when given. `find` is literal text unless
+/// `regex` is set, in which case `replace` may refer to groups as `$1`.
+#[tauri::command]
+pub async fn replace_in_slides(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    find: String,
+    replace: String,
+    language_index: Option<u8>,
+    dry_run: bool,
+    regex: Option<bool>,
+) -> Result<Vec<ReplaceHit>, String> {
+    if find.is_empty() {
+        return Err("Enter the text to find".to_string());
+    }
+    let regex = regex.unwrap_or(false);
+    let pattern = if regex {
+        Regex::new(&find).map_err(|e| format!("Invalid pattern: {e}"))?
+    } else {
+        Regex::new(&regex::escape(&find)).map_err(|e| e.to_string())?
+    };
+    if pattern.is_match("") {
+        return Err("The pattern must not match empty text".to_string());
+    }
+    let slots = match language_index {
+        Some(index) => language_slots(&[index])?,
+        None => LANG_SLOTS.to_vec(),
+    };
+    let replacer = Replacer {
+        pattern,
+        replace: &replace,
+        expand: regex,
+        slots: &slots,
+    };
+
+    let pool = db::pool(&db).await?;
+    db::fetch_presentation(&pool, &presentation_id).await?;
+    if !dry_run {
+        present_mode::ensure_writable(&pool).await?;
+    }
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let slides: Vec<Slide> =
+        sqlx::query_as("SELECT * FROM slides WHERE presentation_id = ? ORDER BY slide_order")
+            .bind(&presentation_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let mut hits = Vec::new();
+    let mut undo = Vec::new();
+    let mut redo = Vec::new();
+    for slide in slides {
+        let mut replacements = 0;
+        let title_json = match slide.title_json.as_deref() {
+            Some(json) => replacer.apply(json, &mut replacements)?,
+            None => None,
+        };
+        let blocks_json = replacer.apply(&slide.blocks_json, &mut replacements)?;
+        if replacements == 0 {
+            continue;
+        }
+        hits.push(ReplaceHit {
+            slide_id: slide.id.clone(),
+            slide_order: slide.slide_order,
+            replacements,
+        });
+        if dry_run {
+            continue;
+        }
+
+        let mut updated = slide.clone();
+        if title_json.is_some() {
+            updated.title_json = title_json;
+        }
+        if let Some(blocks_json) = blocks_json {
+            updated.blocks_json = blocks_json;
+        }
+        sqlx::query("UPDATE slides SET title_json = ?, blocks_json = ? WHERE id = ?")
+            .bind(&updated.title_json)
+            .bind(&updated.blocks_json)
+            .bind(&updated.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        undo.push(EditOp::PutSlide { slide });
+        redo.push(EditOp::PutSlide { slide: updated });
+    }
+
+    if dry_run || hits.is_empty() {
+        return Ok(hits);
+    }
+    let total: u32 = hits.iter().map(|hit| hit.replacements).sum();
+    let detail = json!({
+        "find": find,
+        "replace": replace,
+        "regex": regex,
+        "languageIndex": language_index,
+        "slidesChanged": hits.len(),
+        "replacements": total,
+    });
+    audit::record(
+        &mut *tx,
+        "replace",
+        "presentation",
+        &presentation_id,
+        detail,
+    )
+    .await;
+    edits::record(&mut tx, &presentation_id, "replace", &undo, &redo).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(hits)
+}
+
+struct Replacer<'a> {
+    pattern: Regex,
+    replace: &'a str,
+    /// Whether `replace` may refer to capture groups.
+    expand: bool,
+    slots: &'a [&'static str],
+}
+
+impl Replacer<'_> {
+    /// The document with every match replaced, adding the matches to `count`.
+    /// `None` when nothing matched, so the stored JSON is left byte-for-byte.
+    fn apply(&self, json: &str, count: &mut u32) -> Result<Option<String>, String> {
+        let Ok(mut document) = serde_json::from_str::<Value>(json) else {
+            // Malformed slide JSON has no text nodes to speak of
+            return Ok(None);
+        };
+        let before = *count;
+        self.visit(&mut document, count);
+        if *count == before {
+            return Ok(None);
+        }
+        serde_json::to_string(&document)
+            .map(Some)
+            .map_err(|e| e.to_string())
+    }
+
+    fn visit(&self, node: &mut Value, count: &mut u32) {
+        match node {
+            Value::Object(map) => {
+                for (key, child) in map.iter_mut() {
+                    match child {
+                        Value::String(text) if self.slots.contains(&key.as_str()) => {
+                            *count += self.replace_text(text);
+                        }
+                        _ => self.visit(child, count),
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.visit(item, count);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn replace_text(&self, text: &mut String) -> u32 {
+        let matches = self.pattern.find_iter(text).count() as u32;
+        if matches > 0 {
+            *text = if self.expand {
+                self.pattern.replace_all(text, self.replace).into_owned()
+            } else {
+                self.pattern
+                    .replace_all(text, NoExpand(self.replace))
+                    .into_owned()
+            };
+        }
+        matches
+    }
+}
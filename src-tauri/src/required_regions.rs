@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::db::{self, Slide};
+
+/// Error prefix when going live is refused for empty required regions.
+pub const EMPTY_REQUIRED_REGIONS: &str = "EmptyRequiredRegions";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequiredRegionGap {
+    pub slide_id: String,
+    pub slide_order: i64,
+    /// `title` or the language slot (`Lang1`..`Lang4`) of the empty region.
+    pub region_id: String,
+}
+
+/// Regions a template definition marks with `"required": true`.
+pub fn required_regions(definition: &Value) -> Vec<String> {
+    let mut regions = Vec::new();
+    let title = definition.get("title");
+    let title_shown = title
+        .and_then(|t| t.get("show"))
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+    if title_shown && is_required(title) {
+        regions.push("title".to_string());
+    }
+    let languages = definition
+        .get("languages")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten();
+    for language in languages.filter(|l| is_required(Some(l))) {
+        if let Some(slot) = language.get("slot").and_then(Value::as_str) {
+            regions.push(slot.to_string());
+        }
+    }
+    regions
+}
+
+fn is_required(region: Option<&Value>) -> bool {
+    region
+        .and_then(|r| r.get("required"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Report enabled slides that leave a required region of their effective template empty.
+#[tauri::command]
+pub async fn find_empty_required_regions(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<Vec<RequiredRegionGap>, String> {
+    let pool = db::pool(&db).await?;
+    empty_required_regions(&pool, &presentation_id).await
+}
+
+/// The go-live pre-flight: refuse to present while any slide leaves a required
+/// region empty, listing them after [`EMPTY_REQUIRED_REGIONS`].
+pub async fn check_go_live(pool: &SqlitePool, presentation_id: &str) -> Result<(), String> {
+    let gaps = empty_required_regions(pool, presentation_id).await?;
+    if gaps.is_empty() {
+        return Ok(());
+    }
+    let listed: Vec<String> = gaps
+        .iter()
+        .map(|gap| format!("slide {} {}", gap.slide_order, gap.region_id))
+        .collect();
+    Err(format!(
+        "{EMPTY_REQUIRED_REGIONS}: {} left empty; fill them in before going live",
+        listed.join(", ")
+    ))
+}
+
+async fn empty_required_regions(
+    pool: &SqlitePool,
+    presentation_id: &str,
+) -> Result<Vec<RequiredRegionGap>, String> {
+    let presentation = db::fetch_presentation(pool, presentation_id).await?;
+    let slides = db::fetch_slides(pool, presentation_id).await?;
+
+    let mut regions_by_template: HashMap<String, Vec<String>> = HashMap::new();
+    let mut gaps = Vec::new();
+    // Disabled slides never go live; dynamic ones take their text from verses
+    for slide in slides.iter().filter(|s| !s.is_disabled && !s.is_dynamic) {
+        let template_id = slide.template_id(&presentation.template_id);
+        if !regions_by_template.contains_key(template_id) {
+            let template = match db::fetch_template(pool, template_id).await {
+                Ok(template) => template,
+                Err(_) => db::fetch_template(pool, &presentation.template_id).await?,
+            };
+            let definition: Value = serde_json::from_str(&template.definition_json)
+                .map_err(|e| format!("Invalid definition for template {}: {e}", template.name))?;
+            regions_by_template.insert(template_id.to_string(), required_regions(&definition));
+        }
+
+        for region in &regions_by_template[template_id] {
+            if is_region_empty(slide, region) {
+                gaps.push(RequiredRegionGap {
+                    slide_id: slide.id.clone(),
+                    slide_order: slide.slide_order,
+                    region_id: region.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(gaps)
+}
+
+fn is_region_empty(slide: &Slide, region: &str) -> bool {
+    if region == "title" {
+        return slide.title_text().is_none();
+    }
+    let blocks: Vec<Value> = serde_json::from_str(&slide.blocks_json).unwrap_or_default();
+    !blocks.iter().any(|block| {
+        block
+            .get(region)
+            .and_then(Value::as_str)
+            .is_some_and(|text| !text.trim().is_empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[tokio::test]
+    async fn going_live_is_refused_while_a_required_region_is_empty() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        for (id, order) in [("s1", 1), ("s2", 2), ("s3", 3)] {
+            test_support::slide(&pool, id, "p1", order).await;
+        }
+        test_support::exec(
+            &pool,
+            r#"UPDATE templates SET definition_json = '{"title":{"required":true}}';
+               UPDATE slides SET title_json = '{"Lang1":"ቅዳሴ"}' WHERE id = 's1';
+               UPDATE slides SET is_disabled = 1 WHERE id = 's3'"#,
+        )
+        .await;
+
+        let error = check_go_live(&pool, "p1").await.unwrap_err();
+        assert!(error.starts_with(EMPTY_REQUIRED_REGIONS), "{error}");
+        assert!(error.contains("slide 2 title"), "{error}");
+        assert!(!error.contains("slide 3"), "{error}");
+
+        test_support::exec(
+            &pool,
+            r#"UPDATE slides SET title_json = '{"Lang2":"Kidase"}' WHERE id = 's2'"#,
+        )
+        .await;
+        assert_eq!(check_go_live(&pool, "p1").await, Ok(()));
+    }
+}
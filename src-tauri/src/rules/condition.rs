@@ -0,0 +1,476 @@
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveDateTime};
+use regex::Regex;
+use serde_json::{Map, Value};
+
+/// Parsed `when` clause of a rule, mirroring the frontend engine's AST.
+#[derive(Debug, Clone)]
+pub enum Condition {
+    Compare {
+        path: String,
+        operator: Operator,
+        value: Operand,
+    },
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    Not(Box<Condition>),
+    Diff {
+        from: Operand,
+        to: Operand,
+        unit: DiffUnit,
+        operator: Operator,
+        value: Operand,
+    },
+    NthDayAfter {
+        from: Operand,
+        /// 0 = Sunday … 6 = Saturday, as in JavaScript's `getDay`.
+        weekday: u32,
+        nth: u32,
+        operator: Operator,
+        value: Operand,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    In,
+    Nin,
+    Exists,
+    Regex,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Between,
+    All,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffUnit {
+    Days,
+    Weeks,
+    Months,
+    Years,
+}
+
+/// A rule value that may still need `$ref:` resolution.
+#[derive(Debug, Clone)]
+pub enum Operand {
+    Literal(Value),
+    Ref(String),
+    Array(Vec<Operand>),
+}
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+impl Operator {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "$eq" => Self::Eq,
+            "$ne" => Self::Ne,
+            "$gt" => Self::Gt,
+            "$gte" => Self::Gte,
+            "$lt" => Self::Lt,
+            "$lte" => Self::Lte,
+            "$in" => Self::In,
+            "$nin" => Self::Nin,
+            "$exists" => Self::Exists,
+            "$regex" => Self::Regex,
+            "$contains" => Self::Contains,
+            "$startsWith" => Self::StartsWith,
+            "$endsWith" => Self::EndsWith,
+            "$between" => Self::Between,
+            "$all" => Self::All,
+            _ => return None,
+        })
+    }
+
+    fn compare(self, a: &Value, b: &Value) -> bool {
+        match self {
+            Self::Eq => coerce(a) == coerce(b),
+            Self::Ne => coerce(a) != coerce(b),
+            Self::Gt => to_num(a) > to_num(b),
+            Self::Gte => to_num(a) >= to_num(b),
+            Self::Lt => to_num(a) < to_num(b),
+            Self::Lte => to_num(a) <= to_num(b),
+            Self::In => b
+                .as_array()
+                .is_some_and(|items| items.iter().any(|item| coerce(item) == coerce(a))),
+            Self::Nin => b
+                .as_array()
+                .is_none_or(|items| !items.iter().any(|item| coerce(item) == coerce(a))),
+            Self::Exists => a.is_null() != is_truthy(b),
+            Self::Regex => match (a.as_str(), b.as_str()) {
+                (Some(text), Some(pattern)) => {
+                    Regex::new(pattern).is_ok_and(|regex| regex.is_match(text))
+                }
+                _ => false,
+            },
+            Self::Contains => match (a, b) {
+                (Value::String(text), Value::String(needle)) => text.contains(needle.as_str()),
+                (Value::Array(items), _) => items.iter().any(|item| coerce(item) == coerce(b)),
+                _ => false,
+            },
+            Self::StartsWith => match (a.as_str(), b.as_str()) {
+                (Some(text), Some(prefix)) => text.starts_with(prefix),
+                _ => false,
+            },
+            Self::EndsWith => match (a.as_str(), b.as_str()) {
+                (Some(text), Some(suffix)) => text.ends_with(suffix),
+                _ => false,
+            },
+            Self::Between => match b.as_array().map(Vec::as_slice) {
+                // Date strings like "YYYY-MM-DD" compare as text
+                Some([Value::String(lo), Value::String(hi)]) if a.is_string() => {
+                    let a = a.as_str().unwrap_or_default();
+                    a >= lo.as_str() && a <= hi.as_str()
+                }
+                Some([lo, hi]) => {
+                    let a = to_num(a);
+                    a >= to_num(lo) && a <= to_num(hi)
+                }
+                _ => false,
+            },
+            Self::All => match (a.as_array(), b.as_array()) {
+                (Some(have), Some(required)) => required
+                    .iter()
+                    .all(|r| have.iter().any(|item| coerce(item) == coerce(r))),
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Operand {
+    fn parse(value: &Value) -> Self {
+        match value {
+            Value::String(s) => match s.strip_prefix("$ref:") {
+                Some(path) => Self::Ref(path.to_string()),
+                None => Self::Literal(value.clone()),
+            },
+            Value::Null | Value::Bool(_) | Value::Number(_) => Self::Literal(value.clone()),
+            // Expressions are evaluated elsewhere; as a comparison value they are null
+            Value::Array(_) | Value::Object(_) => Self::Literal(Value::Null),
+        }
+    }
+
+    fn parse_list(value: &Value) -> Self {
+        match value {
+            Value::Array(items) => Self::Array(items.iter().map(Self::parse).collect()),
+            _ => Self::parse(value),
+        }
+    }
+
+    fn resolve(&self, context: &Value) -> Value {
+        match self {
+            Self::Literal(value) => value.clone(),
+            Self::Ref(path) => resolve_path(path, context).clone(),
+            Self::Array(items) => Value::Array(items.iter().map(|i| i.resolve(context)).collect()),
+        }
+    }
+}
+
+impl Condition {
+    pub fn parse(when: &Value) -> Result<Self, String> {
+        let clause = when
+            .as_object()
+            .ok_or_else(|| "When clause must be an object".to_string())?;
+        if let Some(diff) = clause.get("$diff") {
+            return parse_diff(diff);
+        }
+        if let Some(nth) = clause.get("$nthDayAfter") {
+            return parse_nth_day_after(nth);
+        }
+        if clause.is_empty() {
+            return Err("Empty when clause".to_string());
+        }
+
+        let mut nodes = Vec::new();
+        for (key, value) in clause {
+            match key.as_str() {
+                "$not" => nodes.push(Self::Not(Box::new(Self::parse(value)?))),
+                "$and" | "$or" => {
+                    let children = value
+                        .as_array()
+                        .ok_or_else(|| format!("{key} requires an array of conditions"))?
+                        .iter()
+                        .map(Self::parse)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    nodes.push(if key == "$and" {
+                        Self::And(children)
+                    } else {
+                        Self::Or(children)
+                    });
+                }
+                path => nodes.extend(parse_field(path, value)?),
+            }
+        }
+
+        Ok(if nodes.len() == 1 {
+            nodes.remove(0)
+        } else {
+            Self::And(nodes)
+        })
+    }
+
+    /// Whether the condition holds for `context` (`{presentation, slide, vars, settings, meta}`).
+    pub fn matches(&self, context: &Value) -> bool {
+        match self {
+            Self::Compare {
+                path,
+                operator,
+                value,
+            } => operator.compare(resolve_path(path, context), &value.resolve(context)),
+            Self::And(children) => children.iter().all(|c| c.matches(context)),
+            Self::Or(children) => children.iter().any(|c| c.matches(context)),
+            Self::Not(child) => !child.matches(context),
+            Self::Diff {
+                from,
+                to,
+                unit,
+                operator,
+                value,
+            } => {
+                let (Some(from), Some(to)) = (
+                    to_date(&from.resolve(context)),
+                    to_date(&to.resolve(context)),
+                ) else {
+                    return false;
+                };
+                let diff = date_diff(from, to, *unit);
+                operator.compare(&Value::from(diff), &value.resolve(context))
+            }
+            Self::NthDayAfter {
+                from,
+                weekday,
+                nth,
+                operator,
+                value,
+            } => {
+                let Some(from) = to_date(&from.resolve(context)) else {
+                    return false;
+                };
+                let day = nth_weekday_after(from.date(), *weekday, *nth);
+                let day = Value::from(day.format("%Y-%m-%d").to_string());
+                operator.compare(&day, &value.resolve(context))
+            }
+        }
+    }
+}
+
+fn parse_field(path: &str, condition: &Value) -> Result<Vec<Condition>, String> {
+    let ops = match condition {
+        Value::Object(ops) => ops,
+        Value::Array(_) => return Err("Unknown comparison operator: 0".to_string()),
+        // Shorthand equality: { "path": "value" }
+        _ => {
+            return Ok(vec![Condition::Compare {
+                path: path.to_string(),
+                operator: Operator::Eq,
+                value: Operand::parse(condition),
+            }])
+        }
+    };
+
+    ops.iter()
+        .map(|(op, value)| {
+            let operator =
+                Operator::parse(op).ok_or_else(|| format!("Unknown comparison operator: {op}"))?;
+            Ok(Condition::Compare {
+                path: path.to_string(),
+                operator,
+                value: Operand::parse_list(value),
+            })
+        })
+        .collect()
+}
+
+/// The comparison in a `$diff`/`$nthDayAfter` clause: the last operator key that isn't a parameter.
+fn clause_comparison(clause: &Map<String, Value>, params: &[&str]) -> Option<(Operator, Value)> {
+    clause
+        .iter()
+        .filter(|(key, _)| !params.contains(&key.as_str()))
+        .filter_map(|(key, value)| Operator::parse(key).map(|op| (op, value.clone())))
+        .next_back()
+}
+
+fn parse_diff(diff: &Value) -> Result<Condition, String> {
+    let clause = diff
+        .as_object()
+        .ok_or_else(|| "$diff requires from, to, and unit".to_string())?;
+    let (Some(from), Some(to), Some(unit)) = (
+        clause.get("from").filter(|v| is_truthy(v)),
+        clause.get("to").filter(|v| is_truthy(v)),
+        clause.get("unit").and_then(Value::as_str),
+    ) else {
+        return Err("$diff requires from, to, and unit".to_string());
+    };
+    let unit = match unit {
+        "days" => DiffUnit::Days,
+        "weeks" => DiffUnit::Weeks,
+        "months" => DiffUnit::Months,
+        "years" => DiffUnit::Years,
+        other => return Err(format!("Unknown $diff unit: {other}")),
+    };
+    let (operator, value) = clause_comparison(clause, &["from", "to", "unit"])
+        .ok_or_else(|| "$diff requires a comparison operator (e.g. $lte: 7)".to_string())?;
+
+    Ok(Condition::Diff {
+        from: Operand::parse(from),
+        to: Operand::parse(to),
+        unit,
+        operator,
+        value: Operand::parse(&value),
+    })
+}
+
+fn parse_nth_day_after(nth_day: &Value) -> Result<Condition, String> {
+    let clause = nth_day
+        .as_object()
+        .ok_or_else(|| "$nthDayAfter requires a \"from\" value".to_string())?;
+    let from = clause
+        .get("from")
+        .filter(|v| is_truthy(v))
+        .ok_or_else(|| "$nthDayAfter requires a \"from\" value".to_string())?;
+    let weekday = match clause.get("day") {
+        None | Some(Value::Null) => return Err("$nthDayAfter requires a \"day\" value".to_string()),
+        Some(Value::Number(n)) => {
+            n.as_u64()
+                .filter(|d| *d < 7)
+                .ok_or_else(|| format!("Invalid day number: {n}"))? as u32
+        }
+        Some(day) => {
+            let name = day.as_str().unwrap_or_default();
+            WEEKDAYS.iter().position(|d| *d == name).ok_or_else(|| {
+                format!("Invalid day name: \"{name}\". Use Sun, Mon, Tue, Wed, Thu, Fri, or Sat")
+            })? as u32
+        }
+    };
+    let nth = clause
+        .get("nth")
+        .and_then(Value::as_u64)
+        .filter(|n| *n >= 1)
+        .ok_or_else(|| "$nthDayAfter requires \"nth\" as a positive integer".to_string())?
+        as u32;
+    let (operator, value) =
+        clause_comparison(clause, &["from", "day", "nth"]).ok_or_else(|| {
+            "$nthDayAfter requires a comparison operator (e.g. $eq: \"2026-04-12\")".to_string()
+        })?;
+
+    Ok(Condition::NthDayAfter {
+        from: Operand::parse(from),
+        weekday,
+        nth,
+        operator,
+        value: Operand::parse_list(&value),
+    })
+}
+
+/// Walk a dotted path through the context; missing segments resolve to null.
+pub fn resolve_path<'a>(path: &str, context: &'a Value) -> &'a Value {
+    path.split('.')
+        .try_fold(context, |current, segment| current.get(segment))
+        .unwrap_or(&Value::Null)
+}
+
+/// Comparable primitive, following the engine's `coerce`.
+#[derive(Debug, PartialEq)]
+enum Primitive {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+fn coerce(value: &Value) -> Primitive {
+    match value {
+        Value::Null => Primitive::Null,
+        Value::Bool(b) => Primitive::Bool(*b),
+        Value::Number(n) => Primitive::Number(n.as_f64().unwrap_or_default()),
+        _ => Primitive::Text(js_string(value)),
+    }
+}
+
+/// `String(value)` as JavaScript would render it inside an array.
+pub fn js_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Number(n) => match n.as_f64() {
+            Some(f) if n.is_f64() && f.fract() == 0.0 && f.abs() < i64::MAX as f64 => {
+                (f as i64).to_string()
+            }
+            _ => n.to_string(),
+        },
+        Value::Array(items) => items.iter().map(js_string).collect::<Vec<_>>().join(","),
+        Value::Object(_) => "[object Object]".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Numeric view used by the ordering operators; unparseable values count as 0.
+pub fn to_num(value: &Value) -> f64 {
+    match value {
+        Value::Number(n) => n.as_f64().unwrap_or_default(),
+        Value::String(s) if s.trim().is_empty() => 0.0,
+        Value::String(s) => s
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|n| !n.is_nan())
+            .unwrap_or_default(),
+        Value::Bool(b) => f64::from(u8::from(*b)),
+        _ => 0.0,
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0 && !n.is_nan()),
+        Value::String(s) => !s.is_empty(),
+        _ => true,
+    }
+}
+
+/// Dates arrive as `YYYY-MM-DD`, ISO timestamps or epoch milliseconds.
+fn to_date(value: &Value) -> Option<NaiveDateTime> {
+    match value {
+        Value::String(s) => {
+            let s = s.trim();
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .or_else(|| DateTime::parse_from_rfc3339(s).ok().map(|d| d.naive_utc()))
+                .or_else(|| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f").ok())
+        }
+        Value::Number(n) => {
+            DateTime::from_timestamp_millis(n.as_f64()? as i64).map(|d| d.naive_utc())
+        }
+        _ => None,
+    }
+}
+
+fn date_diff(from: NaiveDateTime, to: NaiveDateTime, unit: DiffUnit) -> i64 {
+    let millis = (to - from).num_milliseconds();
+    match unit {
+        DiffUnit::Days => millis.div_euclid(86_400_000),
+        DiffUnit::Weeks => millis.div_euclid(7 * 86_400_000),
+        DiffUnit::Months => {
+            (to.year() - from.year()) as i64 * 12 + to.month() as i64 - from.month() as i64
+        }
+        DiffUnit::Years => (to.year() - from.year()) as i64,
+    }
+}
+
+/// The `nth` occurrence of `weekday` strictly after `from`.
+fn nth_weekday_after(from: NaiveDate, weekday: u32, nth: u32) -> NaiveDate {
+    let first = from + Days::new(1);
+    let offset = (weekday + 7 - first.weekday().num_days_from_sunday()) % 7;
+    first + Days::new(u64::from(offset) + 7 * u64::from(nth - 1))
+}
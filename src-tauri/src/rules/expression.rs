@@ -0,0 +1,94 @@
+use serde_json::Value;
+
+use super::condition::{js_string, resolve_path, to_num, Condition};
+
+/// Whether an outcome value is a `{ "$op": ... }` expression rather than a literal.
+pub fn is_expression(value: &Value) -> bool {
+    value
+        .as_object()
+        .is_some_and(|obj| obj.keys().any(|k| k.starts_with('$')))
+}
+
+/// Compute an outcome expression, mirroring the frontend's `ExpressionEvaluator`.
+pub fn evaluate(expr: &Value, context: &Value) -> Result<Value, String> {
+    let Some((op, arg)) = expr
+        .as_object()
+        .and_then(|obj| obj.iter().find(|(k, _)| k.starts_with('$')))
+    else {
+        return Ok(Value::Null);
+    };
+
+    Ok(match op.as_str() {
+        "$concat" => Value::from(
+            arg.as_array()
+                .into_iter()
+                .flatten()
+                .map(|v| match resolve_arg(v, context) {
+                    Value::Null => String::new(),
+                    v => display(&v),
+                })
+                .collect::<String>(),
+        ),
+        "$add" => math(arg, context, |a, b| a + b),
+        "$subtract" => math(arg, context, |a, b| a - b),
+        "$multiply" => math(arg, context, |a, b| a * b),
+        "$divide" => math(arg, context, |a, b| if b == 0.0 { 0.0 } else { a / b }),
+        "$toUpper" => Value::from(display(&resolve_arg(arg, context)).to_uppercase()),
+        "$toLower" => Value::from(display(&resolve_arg(arg, context)).to_lowercase()),
+        "$trim" => Value::from(display(&resolve_arg(arg, context)).trim()),
+        "$coalesce" => arg
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|v| resolve_arg(v, context))
+            .find(|v| !v.is_null())
+            .unwrap_or(Value::Null),
+        "$now" => Value::from(crate::db::now()),
+        "$cond" => {
+            let condition = Condition::parse(arg.get("if").unwrap_or(&Value::Null))?;
+            let branch = if condition.matches(context) {
+                arg.get("then")
+            } else {
+                arg.get("else")
+            };
+            match branch {
+                Some(branch) if is_expression(branch) => evaluate(branch, context)?,
+                Some(branch) => resolve_arg(branch, context),
+                None => Value::Null,
+            }
+        }
+        other => return Err(format!("Unknown expression operator: {other}")),
+    })
+}
+
+fn resolve_arg(value: &Value, context: &Value) -> Value {
+    match value.as_str().and_then(|s| s.strip_prefix("$ref:")) {
+        Some(path) => resolve_path(path, context).clone(),
+        None => value.clone(),
+    }
+}
+
+fn math(arg: &Value, context: &Value, op: impl Fn(f64, f64) -> f64) -> Value {
+    let values: Vec<f64> = match arg.as_array() {
+        Some(items) if items.len() >= 2 => items
+            .iter()
+            .map(|v| to_num(&resolve_arg(v, context)))
+            .collect(),
+        _ => return Value::from(0),
+    };
+    let result = values.into_iter().reduce(op).unwrap_or_default();
+    // Whole results serialize as integers, as JavaScript numbers would print
+    if result.fract() == 0.0 && result.abs() < i64::MAX as f64 {
+        Value::from(result as i64)
+    } else {
+        Value::from(result)
+    }
+}
+
+/// `String(value)` for a top-level value, where null prints as `"null"`.
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        other => js_string(other),
+    }
+}
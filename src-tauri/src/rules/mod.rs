@@ -0,0 +1,781 @@
+pub mod condition;
+pub mod expression;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::audit;
+use crate::bahire_hasab;
+use crate::calendar::EthiopianDate;
+use crate::db::{self, Gitsawe, Presentation, RuleDefinition, Slide, Variable};
+use crate::gitsawe::GitsaweSelector;
+use condition::Condition;
+
+const DAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Values of `rule_definitions.scope`, as in the frontend's `RuleScope`.
+pub const RULE_SCOPES: [&str; 4] = ["presentation", "slide", "gitsawe", "global"];
+
+/// Rule id the transient rule of [`preview_rule`] reports in effects.
+pub const PREVIEW_RULE_ID: &str = "preview";
+
+/// Feasts added on top of the calendar's holidays, as (key, Ethiopian month, day).
+const EXTRA_HOLIDAYS: [(&str, u32, u32); 4] = [
+    ("tsige", 1, 26),
+    ("quskuam", 3, 6),
+    ("filseta", 12, 1),
+    ("kiremt", 10, 26),
+];
+
+/// The `rule_json` DSL: a `when` clause and the outcomes for either branch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleEntry {
+    pub id: String,
+    pub when: Value,
+    pub then: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub otherwise: Option<Map<String, Value>>,
+}
+
+/// A stored rule with its parsed entry, keyed by what it targets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "scope",
+    rename_all = "lowercase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Rule {
+    Presentation {
+        id: String,
+        entry: RuleEntry,
+    },
+    Global {
+        id: String,
+        entry: RuleEntry,
+    },
+    /// Applies to one slide, or to every slide when `slide_id` is unset.
+    Slide {
+        id: String,
+        slide_id: Option<String>,
+        entry: RuleEntry,
+    },
+    Gitsawe {
+        id: String,
+        gitsawe_id: Option<String>,
+        entry: RuleEntry,
+    },
+}
+
+impl Rule {
+    pub fn from_definition(definition: &RuleDefinition) -> Result<Self, String> {
+        let entry: RuleEntry = serde_json::from_str(&definition.rule_json)
+            .map_err(|e| format!("Invalid rule {}: {e}", definition.name))?;
+        let id = definition.id.clone();
+        Ok(match definition.scope.as_str() {
+            "presentation" => Self::Presentation { id, entry },
+            "global" => Self::Global { id, entry },
+            "slide" => Self::Slide {
+                id,
+                slide_id: definition.slide_id.clone(),
+                entry,
+            },
+            "gitsawe" => Self::Gitsawe {
+                id,
+                gitsawe_id: definition.gitsawe_id.clone(),
+                entry,
+            },
+            other => return Err(format!("Unknown rule scope: {other}")),
+        })
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Presentation { id, .. }
+            | Self::Global { id, .. }
+            | Self::Slide { id, .. }
+            | Self::Gitsawe { id, .. } => id,
+        }
+    }
+
+    pub fn entry(&self) -> &RuleEntry {
+        match self {
+            Self::Presentation { entry, .. }
+            | Self::Global { entry, .. }
+            | Self::Slide { entry, .. }
+            | Self::Gitsawe { entry, .. } => entry,
+        }
+    }
+}
+
+impl RuleEntry {
+    /// The outcome for `context`, with expression values computed.
+    pub fn outcome(&self, context: &Value) -> Result<Map<String, Value>, String> {
+        let condition = Condition::parse(&self.when)?;
+        let mut outcome = if condition.matches(context) {
+            self.then.clone()
+        } else {
+            self.otherwise.clone().unwrap_or_default()
+        };
+        for value in outcome.values_mut() {
+            if expression::is_expression(value) {
+                *value = expression::evaluate(value, context)?;
+            }
+        }
+        Ok(outcome)
+    }
+}
+
+/// Merged outcome of the rules that apply to a presentation or one of its slides.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleEffect {
+    /// Slide the effect applies to; `None` for the presentation as a whole.
+    pub slide_id: Option<String>,
+    pub visible: Option<bool>,
+    pub style: Option<Value>,
+    pub value: Option<Value>,
+    /// Rules that contributed, in the order their outcomes were applied.
+    pub rule_ids: Vec<String>,
+}
+
+impl RuleEffect {
+    /// Layer `outcome` over the effect; later rules win key by key.
+    fn apply(&mut self, rule_id: &str, outcome: &Map<String, Value>) {
+        if let Some(visible) = outcome.get("visible").and_then(Value::as_bool) {
+            self.visible = Some(visible);
+        }
+        if let Some(style) = outcome.get("style") {
+            self.style = Some(style.clone());
+        }
+        if let Some(value) = outcome.get("value") {
+            self.value = Some(value.clone());
+        }
+        self.rule_ids.push(rule_id.to_string());
+    }
+}
+
+/// Evaluate a presentation's enabled rules for today without touching the database.
+///
+/// Presentation and global rules yield one presentation-wide effect; each slide
+/// with slide rules gets an effect layered on top of it, so slide rules override
+/// presentation rules. Within a scope rules apply oldest first, so the newest
+/// `created_at` wins a conflict. Malformed rules are skipped, as in the editor.
+#[tauri::command]
+pub async fn apply_rules(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<Vec<RuleEffect>, String> {
+    let pool = db::pool(&db).await?;
+    evaluate_enabled(&pool, &presentation_id, &RuleContext::default()).await
+}
+
+/// What `rule_json` would do if it were saved and enabled, layered on top of
+/// the presentation's enabled rules as the newest rule of `scope` (default
+/// `presentation`). Nothing is written to the database.
+///
+/// The transient rule shows up as [`PREVIEW_RULE_ID`] in `ruleIds`. A slide
+/// rule applies to `slide_id`, or to every slide when unset. Errors in
+/// `rule_json` name the field at fault.
+#[tauri::command]
+pub async fn preview_rule(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    rule_json: String,
+    scope: Option<String>,
+    slide_id: Option<String>,
+) -> Result<Vec<RuleEffect>, String> {
+    let entry = parse_entry(&rule_json, false)?;
+    let id = PREVIEW_RULE_ID.to_string();
+    let preview = match scope.as_deref().unwrap_or("presentation") {
+        "presentation" => Rule::Presentation { id, entry },
+        "global" => Rule::Global { id, entry },
+        "slide" => Rule::Slide {
+            id,
+            slide_id: slide_id.clone(),
+            entry,
+        },
+        "gitsawe" => {
+            return Err("Gitsawe rules pick readings and have no effect on slides".to_string())
+        }
+        other => return Err(format!("Unknown rule scope: {other}")),
+    };
+
+    let pool = db::pool(&db).await?;
+    if let Some(slide_id) = &slide_id {
+        let slide = db::fetch_slide(&pool, slide_id).await?;
+        if slide.presentation_id != presentation_id {
+            return Err(format!(
+                "Slide {slide_id} does not belong to presentation {presentation_id}"
+            ));
+        }
+    }
+    let definitions = db::fetch_enabled_rules(&pool, &presentation_id).await?;
+    let mut rules: Vec<Rule> = definitions
+        .iter()
+        .filter_map(|d| Rule::from_definition(d).ok())
+        .collect();
+    rules.push(preview);
+    evaluate(&pool, &presentation_id, &rules, &RuleContext::default()).await
+}
+
+/// Check `rule_json` before it's saved, so a rule the engine would skip is
+/// refused instead. The error names the field at fault.
+///
+/// Beyond what [`preview_rule`] checks, the rule must carry its `id`.
+#[tauri::command]
+pub fn validate_rule(rule_json: String) -> Result<(), String> {
+    parse_entry(&rule_json, true).map(|_| ())
+}
+
+/// Parse `rule_json` as a [`RuleEntry`], naming the field at fault when it
+/// isn't one. Unless `require_id`, a missing `id` is filled in, since a
+/// preview has none yet.
+///
+/// Outcome expressions are computed against an empty context, which catches
+/// unknown operators and malformed `$cond` clauses.
+fn parse_entry(rule_json: &str, require_id: bool) -> Result<RuleEntry, String> {
+    let field_error =
+        |field: &str, message: &str| format!("Invalid rule field `{field}`: {message}");
+
+    let value: Value =
+        serde_json::from_str(rule_json).map_err(|e| format!("Rule is not valid JSON: {e}"))?;
+    let Value::Object(mut fields) = value else {
+        return Err("Rule must be a JSON object".to_string());
+    };
+    match fields.get("id") {
+        None if require_id => return Err(field_error("id", "is required")),
+        None => {
+            fields.insert("id".to_string(), PREVIEW_RULE_ID.into());
+        }
+        Some(Value::String(_)) => {}
+        Some(_) => return Err(field_error("id", "must be a string")),
+    }
+    let when = fields
+        .get("when")
+        .ok_or_else(|| field_error("when", "is required"))?;
+    Condition::parse(when).map_err(|e| field_error("when", &e))?;
+    match fields.get("then") {
+        Some(Value::Object(_)) => {}
+        Some(_) => return Err(field_error("then", "must be an object")),
+        None => return Err(field_error("then", "is required")),
+    }
+    match fields.get("otherwise") {
+        None | Some(Value::Null | Value::Object(_)) => {}
+        Some(_) => return Err(field_error("otherwise", "must be an object")),
+    }
+    let empty = meta_context(json!({}));
+    for branch in ["then", "otherwise"] {
+        let Some(Value::Object(outcome)) = fields.get(branch) else {
+            continue;
+        };
+        for (key, value) in outcome {
+            if expression::is_expression(value) {
+                expression::evaluate(value, &empty)
+                    .map_err(|e| field_error(&format!("{branch}.{key}"), &e))?;
+            }
+        }
+    }
+
+    serde_json::from_value(Value::Object(fields)).map_err(|e| format!("Invalid rule: {e}"))
+}
+
+/// Enable or disable every rule of `scope` in one statement, returning how
+/// many rules changed.
+///
+/// `presentation_id` is required for presentation and slide rules; for global
+/// and gitsawe rules it narrows the change to one presentation when given.
+#[tauri::command]
+pub async fn set_rules_enabled(
+    db: State<'_, DbInstances>,
+    scope: String,
+    presentation_id: Option<String>,
+    enabled: bool,
+) -> Result<u32, String> {
+    let pool = db::writable_pool(&db).await?;
+    set_enabled(&pool, &scope, presentation_id.as_deref(), enabled).await
+}
+
+async fn set_enabled(
+    pool: &SqlitePool,
+    scope: &str,
+    presentation_id: Option<&str>,
+    enabled: bool,
+) -> Result<u32, String> {
+    if !RULE_SCOPES.contains(&scope) {
+        return Err(format!("Unknown rule scope: {scope}"));
+    }
+    if matches!(scope, "presentation" | "slide") && presentation_id.is_none() {
+        return Err(format!("A presentation is required for {scope} rules"));
+    }
+
+    let query = match presentation_id {
+        Some(presentation_id) => sqlx::query(
+            "UPDATE rule_definitions SET is_enabled = ?
+             WHERE presentation_id = ? AND scope = ? AND is_enabled <> ?",
+        )
+        .bind(enabled)
+        .bind(presentation_id),
+        None => sqlx::query(
+            "UPDATE rule_definitions SET is_enabled = ? WHERE scope = ? AND is_enabled <> ?",
+        )
+        .bind(enabled),
+    };
+    let changed = query
+        .bind(scope)
+        .bind(enabled)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .rows_affected();
+
+    if changed > 0 {
+        let (entity_type, entity_id) = match presentation_id {
+            Some(id) => ("presentation", id),
+            None => ("rule_scope", scope),
+        };
+        let detail = json!({ "scope": scope, "enabled": enabled, "changed": changed });
+        audit::record(pool, "set_rules_enabled", entity_type, entity_id, detail).await;
+    }
+    Ok(changed as u32)
+}
+
+/// What [`evaluate_rules`] evaluates against, in place of today's context.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleContext {
+    /// Gregorian `YYYY-MM-DD` to evaluate for instead of today.
+    pub date: Option<String>,
+    /// Gitsawe to expose as `meta.gitsawe` instead of the one selected for
+    /// the date.
+    pub gitsawe_id: Option<String>,
+    /// Extra `meta` fields, merged over the computed ones like
+    /// `buildContext`'s `extra`.
+    pub meta: Option<Map<String, Value>>,
+}
+
+/// Evaluate a presentation's enabled rules as [`apply_rules`] does, for the
+/// date, gitsawe and extra `meta` fields `context` gives. Nothing is written,
+/// so the editor and the live output can share one engine.
+#[tauri::command]
+pub async fn evaluate_rules(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    context: Option<RuleContext>,
+) -> Result<Vec<RuleEffect>, String> {
+    let pool = db::pool(&db).await?;
+    evaluate_enabled(&pool, &presentation_id, &context.unwrap_or_default()).await
+}
+
+/// [`evaluate`] with the presentation's enabled rules, oldest first.
+async fn evaluate_enabled(
+    pool: &SqlitePool,
+    presentation_id: &str,
+    context: &RuleContext,
+) -> Result<Vec<RuleEffect>, String> {
+    let definitions = db::fetch_enabled_rules(pool, presentation_id).await?;
+    let rules: Vec<Rule> = definitions
+        .iter()
+        .filter_map(|d| Rule::from_definition(d).ok())
+        .collect();
+    evaluate(pool, presentation_id, &rules, context).await
+}
+
+pub async fn evaluate(
+    pool: &SqlitePool,
+    presentation_id: &str,
+    rules: &[Rule],
+    overrides: &RuleContext,
+) -> Result<Vec<RuleEffect>, String> {
+    let presentation = db::fetch_presentation(pool, presentation_id).await?;
+    let slides = db::fetch_slides(pool, presentation_id).await?;
+    let variables = db::fetch_variables(pool, presentation_id).await?;
+    let settings = fetch_settings(pool).await?;
+
+    let date = match &overrides.date {
+        Some(date) => crate::calendar::parse_gregorian(date)?,
+        None => chrono::Local::now().date_naive(),
+    };
+    let mut meta = date_meta(date)?;
+    let gitsawe = match &overrides.gitsawe_id {
+        Some(id) => Some(
+            sqlx::query_as::<_, Gitsawe>("SELECT * FROM gitsawes WHERE id = ?")
+                .bind(id)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Gitsawe not found: {id}"))?,
+        ),
+        None => GitsaweSelector::load(pool).await?.select(date)?.cloned(),
+    };
+    if let Some(gitsawe) = gitsawe {
+        meta["gitsawe"] = serde_json::to_value(gitsawe).map_err(|e| e.to_string())?;
+    }
+    if let (Some(extra), Some(fields)) = (&overrides.meta, meta.as_object_mut()) {
+        fields.extend(extra.clone());
+    }
+    let mut context = json!({
+        "presentation": presentation_record(&presentation),
+        "slide": {},
+        "vars": vars_record(&variables),
+        "settings": settings,
+        "meta": meta,
+    });
+
+    let mut base = RuleEffect::default();
+    for rule in rules {
+        if let Rule::Presentation { .. } | Rule::Global { .. } = rule {
+            if let Ok(outcome) = rule.entry().outcome(&context) {
+                base.apply(rule.id(), &outcome);
+            }
+        }
+    }
+
+    let mut effects = Vec::new();
+    for slide in &slides {
+        context["slide"] = slide_record(slide);
+        let mut effect: Option<RuleEffect> = None;
+        for rule in rules {
+            let Rule::Slide {
+                slide_id, entry, ..
+            } = rule
+            else {
+                continue;
+            };
+            if slide_id.as_ref().is_some_and(|id| *id != slide.id) {
+                continue;
+            }
+            if let Ok(outcome) = entry.outcome(&context) {
+                effect
+                    .get_or_insert_with(|| RuleEffect {
+                        slide_id: Some(slide.id.clone()),
+                        ..base.clone()
+                    })
+                    .apply(rule.id(), &outcome);
+            }
+        }
+        effects.extend(effect);
+    }
+
+    if !base.rule_ids.is_empty() {
+        effects.insert(0, base);
+    }
+    Ok(effects)
+}
+
+/// The date part of `meta`, as `buildContext` fills it for an override date.
+pub fn date_meta(date: NaiveDate) -> Result<Value, String> {
+    let eth = EthiopianDate::from_gregorian(date);
+
+    let mut holidays = Map::new();
+    for (key, month, day) in EXTRA_HOLIDAYS {
+        let gc = EthiopianDate::new(eth.year, month, day)?.to_gregorian()?;
+        holidays.insert(key.to_string(), json!(gc.format("%Y-%m-%d").to_string()));
+    }
+    // The calendar's holidays only exist on the frontend, so the year's
+    // movable feasts stand in for them here
+    for feast in bahire_hasab::movable_feasts(eth.year)?.feasts {
+        holidays
+            .entry(feast.name)
+            .or_insert_with(|| json!(feast.gregorian));
+    }
+
+    Ok(json!({
+        "now": format!("{}T00:00:00.000Z", date.format("%Y-%m-%d")),
+        "date": date.format("%Y-%m-%d").to_string(),
+        "year": date.year(),
+        "month": date.month(),
+        "monthName": MONTH_NAMES[date.month0() as usize],
+        "day": date.day(),
+        "dayOfWeek": DAY_NAMES[date.weekday().num_days_from_sunday() as usize],
+        "ethDate": eth.to_string(),
+        "ethYear": eth.year,
+        "ethMonth": eth.month,
+        "ethDay": eth.day,
+        "ethMonthDay": format!("{:02}-{:02}", eth.month, eth.day),
+        "holidays": holidays,
+    }))
+}
+
+/// Rule context with only `meta` filled, as used for gitsawe selection rules.
+pub fn meta_context(meta: Value) -> Value {
+    json!({
+        "presentation": {},
+        "slide": {},
+        "vars": {},
+        "settings": {},
+        "meta": meta,
+    })
+}
+
+/// The presentation as the frontend entity, with its JSON columns parsed.
+pub fn presentation_record(presentation: &Presentation) -> Value {
+    let mut record = serde_json::to_value(presentation).unwrap_or_default();
+    parse_json_field(&mut record, "languageMap");
+    parse_json_field(&mut record, "languageSettings");
+    record
+}
+
+/// The slide as the frontend entity, with its JSON columns parsed.
+pub fn slide_record(slide: &Slide) -> Value {
+    let mut record = serde_json::to_value(slide).unwrap_or_default();
+    parse_json_field(&mut record, "titleJson");
+    parse_json_field(&mut record, "blocksJson");
+    parse_json_field(&mut record, "footerJson");
+    record
+}
+
+fn parse_json_field(record: &mut Value, key: &str) {
+    if let Some(field) = record.get_mut(key) {
+        if let Some(parsed) = field.as_str().and_then(|s| serde_json::from_str(s).ok()) {
+            *field = parsed;
+        }
+    }
+}
+
+/// `vars` keyed by the raw name and, for `{{VAR}}`, the bare name too.
+pub fn vars_record(variables: &[Variable]) -> Value {
+    let mut vars = Map::new();
+    for variable in variables {
+        let value = Value::from(variable.value.clone());
+        let clean = variable
+            .name
+            .trim_start_matches("{{")
+            .trim_end_matches("}}");
+        vars.insert(clean.to_string(), value.clone());
+        vars.insert(variable.name.clone(), value);
+    }
+    Value::Object(vars)
+}
+
+/// `app_settings` with the frontend's defaults and value parsing.
+pub async fn fetch_settings(pool: &SqlitePool) -> Result<Value, String> {
+    let rows: Vec<(String, String)> = sqlx::query_as("SELECT key, value FROM app_settings")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut settings = json!({
+        "theme": "dark",
+        "showSlideNumbers": true,
+        "showSidebarLabels": true,
+        "presentationDisplay": "currentWindow",
+        "locale": "en",
+    });
+    for (key, value) in rows {
+        match key.as_str() {
+            "theme" => settings[key] = json!(value),
+            "showSlideNumbers" | "showSidebarLabels" => settings[key] = json!(value == "true"),
+            "presentationDisplay" => {
+                // Older builds stored primary/secondary/auto
+                settings[key] = json!(match value.as_str() {
+                    "currentWindow" | "presenterView" => value.as_str(),
+                    "auto" | "secondary" => "presenterView",
+                    _ => "currentWindow",
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    /// An enabled rule that always matches and sets `visible`.
+    async fn rule(
+        pool: &SqlitePool,
+        id: &str,
+        scope: &str,
+        slide_id: Option<&str>,
+        visible: bool,
+        created_at: &str,
+    ) {
+        let entry = json!({
+            "id": id,
+            "when": { "presentation.id": { "$eq": "p1" } },
+            "then": { "visible": visible },
+        });
+        sqlx::query(
+            "INSERT INTO rule_definitions
+                 (id, name, scope, presentation_id, slide_id, rule_json, created_at)
+             VALUES (?, ?, ?, 'p1', ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(id)
+        .bind(scope)
+        .bind(slide_id)
+        .bind(entry.to_string())
+        .bind(created_at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn is_enabled(pool: &SqlitePool, id: &str) -> bool {
+        sqlx::query_scalar("SELECT is_enabled FROM rule_definitions WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn effects(pool: &SqlitePool) -> Vec<RuleEffect> {
+        let context = RuleContext {
+            date: Some("2026-01-07".to_string()),
+            ..RuleContext::default()
+        };
+        evaluate_enabled(pool, "p1", &context).await.unwrap()
+    }
+
+    async fn presentation_with_slide() -> SqlitePool {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        test_support::slide(&pool, "s1", "p1", 1).await;
+        pool
+    }
+
+    #[tokio::test]
+    async fn the_newest_rule_wins_whatever_order_rules_were_stored_in() {
+        let pool = presentation_with_slide().await;
+        rule(
+            &pool,
+            "newer",
+            "presentation",
+            None,
+            false,
+            "2026-03-01T00:00:00.000Z",
+        )
+        .await;
+        rule(
+            &pool,
+            "older",
+            "presentation",
+            None,
+            true,
+            "2026-02-01T00:00:00.000Z",
+        )
+        .await;
+
+        let effects = effects(&pool).await;
+        assert_eq!(effects.len(), 1);
+        assert_eq!(effects[0].slide_id, None);
+        assert_eq!(effects[0].visible, Some(false));
+        assert_eq!(effects[0].rule_ids, ["older", "newer"]);
+    }
+
+    #[tokio::test]
+    async fn the_newest_slide_rule_wins_among_slide_rules() {
+        let pool = presentation_with_slide().await;
+        rule(
+            &pool,
+            "newer",
+            "slide",
+            Some("s1"),
+            true,
+            "2026-03-01T00:00:00.000Z",
+        )
+        .await;
+        rule(
+            &pool,
+            "older",
+            "slide",
+            None,
+            false,
+            "2026-02-01T00:00:00.000Z",
+        )
+        .await;
+
+        let effects = effects(&pool).await;
+        assert_eq!(effects.len(), 1);
+        assert_eq!(effects[0].slide_id.as_deref(), Some("s1"));
+        assert_eq!(effects[0].visible, Some(true));
+        assert_eq!(effects[0].rule_ids, ["older", "newer"]);
+    }
+
+    #[tokio::test]
+    async fn slide_rules_override_newer_presentation_rules() {
+        let pool = presentation_with_slide().await;
+        rule(
+            &pool,
+            "slide",
+            "slide",
+            Some("s1"),
+            true,
+            "2026-02-01T00:00:00.000Z",
+        )
+        .await;
+        rule(
+            &pool,
+            "whole",
+            "presentation",
+            None,
+            false,
+            "2026-03-01T00:00:00.000Z",
+        )
+        .await;
+
+        let effects = effects(&pool).await;
+        assert_eq!(effects.len(), 2);
+        assert_eq!(effects[0].visible, Some(false));
+        assert_eq!(effects[1].slide_id.as_deref(), Some("s1"));
+        assert_eq!(effects[1].visible, Some(true));
+        assert_eq!(effects[1].rule_ids, ["whole", "slide"]);
+    }
+
+    #[tokio::test]
+    async fn set_rules_enabled_leaves_unrelated_rules_alone() {
+        let pool = presentation_with_slide().await;
+        test_support::presentation(&pool, "p2").await;
+        let at = "2026-02-01T00:00:00.000Z";
+        rule(&pool, "slide", "slide", Some("s1"), true, at).await;
+        rule(&pool, "whole", "presentation", None, true, at).await;
+        rule(&pool, "global", "global", None, true, at).await;
+        test_support::exec(
+            &pool,
+            "INSERT INTO rule_definitions (id, name, scope, presentation_id, rule_json, created_at)
+             VALUES ('elsewhere', 'elsewhere', 'slide', 'p2', '{}', '2026-02-01T00:00:00.000Z')",
+        )
+        .await;
+
+        assert_eq!(
+            set_enabled(&pool, "slide", Some("p1"), false)
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(!is_enabled(&pool, "slide").await);
+        for untouched in ["whole", "global", "elsewhere"] {
+            assert!(is_enabled(&pool, untouched).await, "{untouched}");
+        }
+
+        // Already disabled: nothing changes
+        assert_eq!(
+            set_enabled(&pool, "slide", Some("p1"), false)
+                .await
+                .unwrap(),
+            0
+        );
+        // Global rules may span presentations
+        assert_eq!(set_enabled(&pool, "global", None, false).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn set_rules_enabled_checks_its_arguments() {
+        let pool = test_support::pool().await;
+        assert!(set_enabled(&pool, "everything", None, false).await.is_err());
+        assert!(set_enabled(&pool, "slide", None, false).await.is_err());
+        assert!(set_enabled(&pool, "presentation", None, false)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use serde_json::json;
+use sqlx::{FromRow, SqlitePool};
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::audit;
+use crate::calendar::{self, EthiopianDate, PAGUME};
+use crate::db;
+
+/// A presentation prepared for a day's service.
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledService {
+    /// Gregorian `YYYY-MM-DD`.
+    pub service_date: String,
+    pub presentation_id: String,
+    pub created_at: String,
+}
+
+/// Schedule a presentation for the service on a Gregorian `YYYY-MM-DD`. A day
+/// can hold several; scheduling one twice is a no-op.
+#[tauri::command]
+pub async fn schedule_service(
+    db: State<'_, DbInstances>,
+    date: String,
+    presentation_id: String,
+) -> Result<(), String> {
+    let pool = db::writable_pool(&db).await?;
+    add(&pool, &date, &presentation_id).await
+}
+
+async fn add(pool: &SqlitePool, date: &str, presentation_id: &str) -> Result<(), String> {
+    let date = calendar::parse_gregorian(date)?
+        .format("%Y-%m-%d")
+        .to_string();
+    db::fetch_presentation(pool, presentation_id).await?;
+    let inserted = sqlx::query(
+        "INSERT OR IGNORE INTO scheduled_services (service_date, presentation_id, created_at)
+         VALUES (?, ?, ?)",
+    )
+    .bind(&date)
+    .bind(presentation_id)
+    .bind(db::now())
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+    if inserted > 0 {
+        let detail = json!({ "scheduledFor": date });
+        audit::record(pool, "update", "presentation", presentation_id, detail).await;
+    }
+    Ok(())
+}
+
+/// Take a presentation off a day's schedule; the presentation itself is kept.
+#[tauri::command]
+pub async fn unschedule_service(
+    db: State<'_, DbInstances>,
+    date: String,
+    presentation_id: String,
+) -> Result<(), String> {
+    let date = calendar::parse_gregorian(&date)?
+        .format("%Y-%m-%d")
+        .to_string();
+    let pool = db::writable_pool(&db).await?;
+    let removed = sqlx::query(
+        "DELETE FROM scheduled_services WHERE service_date = ? AND presentation_id = ?",
+    )
+    .bind(&date)
+    .bind(&presentation_id)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+    if removed > 0 {
+        let detail = json!({ "unscheduledFrom": date });
+        audit::record(&pool, "update", "presentation", &presentation_id, detail).await;
+    }
+    Ok(())
+}
+
+/// Every service scheduled in an Ethiopian year, from 1 Meskerem to the last
+/// day of Pagumē, by date. Presentations in the trash are left out.
+#[tauri::command]
+pub async fn get_scheduled_services(
+    db: State<'_, DbInstances>,
+    year: i32,
+) -> Result<Vec<ScheduledService>, String> {
+    let pool = db::pool(&db).await?;
+    for_year(&pool, year).await
+}
+
+pub async fn for_year(pool: &SqlitePool, year: i32) -> Result<Vec<ScheduledService>, String> {
+    let (first, last) = year_bounds(year)?;
+    sqlx::query_as::<_, ScheduledService>(
+        "SELECT s.* FROM scheduled_services s
+         JOIN presentations p ON p.id = s.presentation_id
+         WHERE s.service_date BETWEEN ? AND ? AND p.deleted_at IS NULL
+         ORDER BY s.service_date, s.created_at, s.presentation_id",
+    )
+    .bind(first.format("%Y-%m-%d").to_string())
+    .bind(last.format("%Y-%m-%d").to_string())
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Gregorian dates of the first and last day of an Ethiopian year.
+pub fn year_bounds(year: i32) -> Result<(NaiveDate, NaiveDate), String> {
+    let first = EthiopianDate::new(year, 1, 1)?.to_gregorian()?;
+    let last =
+        EthiopianDate::new(year, PAGUME, calendar::days_in_month(year, PAGUME))?.to_gregorian()?;
+    Ok((first, last))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[tokio::test]
+    async fn a_year_runs_from_meskerem_to_pagume() {
+        let pool = test_support::pool().await;
+        for id in ["p1", "p2", "trashed"] {
+            test_support::presentation(&pool, id).await;
+        }
+        // 2015 closes with a sixth day of Pagumē, so 2016 opens on 2023-09-12
+        for (date, id) in [
+            ("2023-09-11", "p1"),
+            ("2023-09-12", "p1"),
+            ("2023-09-13", "trashed"),
+            ("2024-09-10", "p2"),
+            ("2024-09-11", "p1"),
+        ] {
+            add(&pool, date, id).await.unwrap();
+        }
+        add(&pool, "2023-09-12", "p1").await.unwrap();
+        test_support::exec(
+            &pool,
+            "UPDATE presentations SET deleted_at = '2026-01-01T00:00:00.000Z' WHERE id = 'trashed'",
+        )
+        .await;
+
+        let scheduled: Vec<(String, String)> = for_year(&pool, 2016)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.service_date, s.presentation_id))
+            .collect();
+        assert_eq!(
+            scheduled,
+            [
+                ("2023-09-12".to_string(), "p1".to_string()),
+                ("2024-09-10".to_string(), "p2".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn scheduling_checks_the_date_and_presentation() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        assert!(add(&pool, "2024-02-30", "p1").await.is_err());
+        assert!(add(&pool, "2024-02-03", "missing").await.is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+use sqlx::FromRow;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::db::{self, LANG_SLOTS};
+
+const MAX_HITS: i64 = 100;
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    /// `verse` or `slide`.
+    pub source: String,
+    /// Verse id or slide id, depending on `source`.
+    pub source_id: String,
+    /// Segment the verse belongs to; `None` for slides.
+    pub segment_id: Option<String>,
+    /// Language slot (`Lang1`..`Lang4`) the match was found in.
+    pub language: String,
+    /// Matched text with hits wrapped in `[` `]`.
+    pub snippet: String,
+}
+
+/// Full-text search over verse text and slide titles and blocks, best matches first.
+///
+/// The query is matched as a phrase. With `presentation_id`, only that
+/// presentation's slides and the verses of its dynamic slides are searched.
+#[tauri::command]
+pub async fn search_text(
+    db: State<'_, DbInstances>,
+    query: String,
+    presentation_id: Option<String>,
+) -> Result<Vec<SearchHit>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pool = db::pool(&db).await?;
+    sqlx::query_as::<_, SearchHit>(
+        "SELECT source, source_id, segment_id, lang AS language,
+                snippet(text_search, -1, '[', ']', '…', 12) AS snippet
+         FROM text_search
+         WHERE text_search MATCH ?1
+           AND (?2 IS NULL
+                OR presentation_id = ?2
+                OR segment_id IN (SELECT line_id FROM slides
+                                  WHERE presentation_id = ?2 AND is_dynamic = 1))
+         ORDER BY bm25(text_search, 2.0, 1.0)
+         LIMIT ?3",
+    )
+    .bind(phrase(query))
+    .bind(presentation_id)
+    .bind(MAX_HITS)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SlideHit {
+    pub slide_id: String,
+    pub presentation_id: String,
+    pub slide_order: i64,
+    /// Language slot of the best match in the slide.
+    pub language: String,
+    /// Matched text with hits wrapped in `[` `]`.
+    pub snippet: String,
+}
+
+/// Slides whose title or blocks contain `query`, best matches first, each
+/// listed once with its best-matching language. Uses the same index and
+/// phrase matching as [`search_text`]; with `presentation_id`, only that
+/// presentation's slides are searched.
+#[tauri::command]
+pub async fn search_slides(
+    db: State<'_, DbInstances>,
+    query: String,
+    presentation_id: Option<String>,
+) -> Result<Vec<SlideHit>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let pool = db::pool(&db).await?;
+    // One row per matching language; the first of each slide is its best
+    let rows = sqlx::query_as::<_, SlideHit>(
+        "SELECT t.source_id AS slide_id, s.presentation_id, s.slide_order, t.lang AS language,
+                snippet(text_search, -1, '[', ']', '…', 12) AS snippet
+         FROM text_search t
+         JOIN slides s ON s.id = t.source_id
+         WHERE text_search MATCH ?1
+           AND t.source = 'slide'
+           AND (?2 IS NULL OR t.presentation_id = ?2)
+         ORDER BY bm25(text_search, 2.0, 1.0), s.slide_order
+         LIMIT ?3",
+    )
+    .bind(phrase(query))
+    .bind(presentation_id)
+    .bind(MAX_HITS * LANG_SLOTS.len() as i64)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut seen = HashSet::new();
+    Ok(rows
+        .into_iter()
+        .filter(|hit| seen.insert(hit.slide_id.clone()))
+        .take(MAX_HITS as usize)
+        .collect())
+}
+
+/// Quote as a single FTS5 string so user input never parses as query syntax.
+fn phrase(query: &str) -> String {
+    format!("\"{}\"", query.replace('"', "\"\""))
+}
@@ -0,0 +1,189 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::db;
+
+/// Keychain entry holding the random secret the settings key is derived from.
+const KEYCHAIN_SERVICE: &str = "com.yared.kidase-presentation";
+const KEYCHAIN_USER: &str = "app-settings-key";
+
+/// Marks an encrypted `app_settings` value; the version names the scheme
+/// (AES-256-GCM, 12-byte nonce prefix, setting key as associated data).
+pub const SECRET_PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+
+/// Encrypt `value` and store it in `app_settings` under `key`.
+///
+/// The encryption key is derived from a random secret kept in the OS keychain,
+/// created on first use. Ordinary settings are untouched and still read and
+/// written as plaintext.
+#[tauri::command]
+pub async fn set_secret(
+    db: State<'_, DbInstances>,
+    key: String,
+    value: String,
+) -> Result<(), String> {
+    let pool = db::pool(&db).await?;
+    store(&pool, &key, &value).await
+}
+
+/// [`set_secret`] for commands that keep a secret of their own.
+pub(crate) async fn store(pool: &SqlitePool, key: &str, value: &str) -> Result<(), String> {
+    let cipher_key = settings_key(true)?;
+    let stored = encrypt(&cipher_key, key, value)?;
+    sqlx::query(
+        "INSERT INTO app_settings (key, value) VALUES (?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+    )
+    .bind(key)
+    .bind(stored)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Read and decrypt a value written by [`set_secret`].
+///
+/// A value without the encrypted prefix was stored before encryption existed
+/// and is returned as is.
+#[tauri::command]
+pub async fn get_secret(db: State<'_, DbInstances>, key: String) -> Result<Option<String>, String> {
+    let pool = db::pool(&db).await?;
+    read(&pool, &key).await
+}
+
+/// [`get_secret`] for commands that keep a secret of their own.
+pub(crate) async fn read(pool: &SqlitePool, key: &str) -> Result<Option<String>, String> {
+    let stored: Option<String> = sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    match stored {
+        Some(stored) if stored.starts_with("enc:") => {
+            let cipher_key = settings_key(false)?;
+            decrypt(&cipher_key, key, &stored).map(Some)
+        }
+        other => Ok(other),
+    }
+}
+
+/// The AES key for settings, read from the keychain and optionally created.
+fn settings_key(create: bool) -> Result<[u8; 32], String> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_USER)
+        .map_err(|e| format!("Keychain unavailable: {e}"))?;
+    let secret = match entry.get_password() {
+        Ok(secret) => secret,
+        Err(keyring::Error::NoEntry) if create => {
+            let secret = STANDARD.encode(Aes256Gcm::generate_key(OsRng));
+            entry
+                .set_password(&secret)
+                .map_err(|e| format!("Failed to store key in keychain: {e}"))?;
+            secret
+        }
+        Err(keyring::Error::NoEntry) => {
+            return Err("No settings key in the keychain; secrets must be set again".to_string())
+        }
+        Err(e) => return Err(format!("Failed to read key from keychain: {e}")),
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"kidase app_settings v1");
+    hasher.update(secret.as_bytes());
+    Ok(hasher.finalize().into())
+}
+
+fn encrypt(cipher_key: &[u8; 32], key: &str, value: &str) -> Result<String, String> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(cipher_key));
+    let nonce = Aes256Gcm::generate_nonce(OsRng);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: value.as_bytes(),
+                aad: key.as_bytes(),
+            },
+        )
+        .map_err(|_| format!("Failed to encrypt {key}"))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    Ok(format!("{SECRET_PREFIX}{}", STANDARD.encode(sealed)))
+}
+
+fn decrypt(cipher_key: &[u8; 32], key: &str, stored: &str) -> Result<String, String> {
+    let encoded = stored
+        .strip_prefix(SECRET_PREFIX)
+        .ok_or_else(|| format!("Secret {key} uses an unsupported format"))?;
+    let sealed = STANDARD
+        .decode(encoded)
+        .map_err(|_| format!("Secret {key} is corrupted"))?;
+    if sealed.len() < NONCE_LEN {
+        return Err(format!("Secret {key} is corrupted"));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(cipher_key));
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: key.as_bytes(),
+            },
+        )
+        .map_err(|_| {
+            format!("Secret {key} could not be decrypted with the keychain key; set it again")
+        })?;
+    String::from_utf8(plaintext).map_err(|_| format!("Secret {key} is corrupted"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    #[test]
+    fn encrypted_values_round_trip() {
+        let stored = encrypt(&KEY, "remoteToken", "ሰላም secret").unwrap();
+        assert!(stored.starts_with(SECRET_PREFIX));
+        assert!(!stored.contains("secret"));
+        assert_eq!(decrypt(&KEY, "remoteToken", &stored).unwrap(), "ሰላም secret");
+    }
+
+    #[test]
+    fn each_encryption_uses_a_fresh_nonce() {
+        let first = encrypt(&KEY, "remoteToken", "same").unwrap();
+        let second = encrypt(&KEY, "remoteToken", "same").unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn the_wrong_key_is_refused() {
+        let stored = encrypt(&KEY, "remoteToken", "secret").unwrap();
+        let error = decrypt(&[8; 32], "remoteToken", &stored).unwrap_err();
+        assert!(error.contains("could not be decrypted"), "{error}");
+    }
+
+    #[test]
+    fn a_value_moved_to_another_setting_is_refused() {
+        let stored = encrypt(&KEY, "remoteToken", "secret").unwrap();
+        assert!(decrypt(&KEY, "backupToken", &stored).is_err());
+    }
+
+    #[test]
+    fn corrupted_values_are_refused() {
+        for stored in ["enc:v1:!!!", "enc:v1:AAAA", "enc:v2:AAAA"] {
+            assert!(decrypt(&KEY, "remoteToken", stored).is_err(), "{stored}");
+        }
+    }
+}
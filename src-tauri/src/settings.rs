@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::db;
+use crate::secrets::SECRET_PREFIX;
+
+/// Every `app_settings` entry as a JSON object of key to value, for carrying
+/// preferences across a reinstall.
+///
+/// Encrypted values are left out: their key lives in this machine's keychain,
+/// so they can't be read anywhere else and must be set again.
+#[tauri::command]
+pub async fn export_settings(db: State<'_, DbInstances>) -> Result<String, String> {
+    let pool = db::pool(&db).await?;
+    let rows: Vec<(String, String)> =
+        sqlx::query_as("SELECT key, value FROM app_settings ORDER BY key")
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let settings: BTreeMap<String, String> = rows
+        .into_iter()
+        .filter(|(_, value)| !value.starts_with(SECRET_PREFIX))
+        .collect();
+    serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())
+}
+
+/// Restore settings written by [`export_settings`]. Existing keys are replaced
+/// only when `overwrite` is set. Returns how many keys were written.
+///
+/// The JSON is parsed before anything is written, so a malformed file leaves
+/// the current settings alone.
+#[tauri::command]
+pub async fn import_settings(
+    db: State<'_, DbInstances>,
+    json: String,
+    overwrite: bool,
+) -> Result<u32, String> {
+    let settings: BTreeMap<String, String> =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid settings file: {e}"))?;
+
+    let pool = db::writable_pool(&db).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let query = if overwrite {
+        "INSERT INTO app_settings (key, value) VALUES (?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value"
+    } else {
+        "INSERT INTO app_settings (key, value) VALUES (?, ?)
+         ON CONFLICT(key) DO NOTHING"
+    };
+
+    let mut imported = 0;
+    for (key, value) in settings {
+        // Sealed with another machine's key, so it would never decrypt here
+        if value.starts_with(SECRET_PREFIX) {
+            continue;
+        }
+        imported += sqlx::query(query)
+            .bind(key)
+            .bind(value)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .rows_affected() as u32;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(imported)
+}
@@ -0,0 +1,955 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use regex::Regex;
+use serde_json::{json, Value};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::audit;
+use crate::blocks::BlocksDocument;
+use crate::db::{self, Slide};
+use crate::edits::{self, EditOp};
+use crate::placeholders::PLACEHOLDER_PATTERN;
+
+/// Rewrite a presentation's `slide_order` to follow `ordered_slide_ids`.
+///
+/// The list must name every slide of the presentation exactly once. Orders are
+/// written 1..n, as the editor numbers them, in a single transaction so a
+/// failure part-way leaves the previous order intact.
+#[tauri::command]
+pub async fn reorder_slides(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    ordered_slide_ids: Vec<String>,
+) -> Result<(), String> {
+    let pool = db::writable_pool(&db).await?;
+    reorder(&pool, &presentation_id, &ordered_slide_ids).await
+}
+
+async fn reorder(
+    pool: &SqlitePool,
+    presentation_id: &str,
+    ordered_slide_ids: &[String],
+) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let existing: HashSet<String> =
+        sqlx::query_scalar("SELECT id FROM slides WHERE presentation_id = ?")
+            .bind(presentation_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .collect();
+
+    let mut seen = HashSet::new();
+    for id in ordered_slide_ids {
+        if !existing.contains(id) {
+            return Err(format!(
+                "Slide {id} does not belong to presentation {presentation_id}"
+            ));
+        }
+        if !seen.insert(id) {
+            return Err(format!("Slide {id} is listed more than once"));
+        }
+    }
+    if let Some(missing) = existing.iter().find(|id| !seen.contains(id)) {
+        return Err(format!("Slide {missing} is missing from the new order"));
+    }
+
+    let before = edits::slide_orders(&mut tx, presentation_id).await?;
+    for (index, id) in ordered_slide_ids.iter().enumerate() {
+        sqlx::query("UPDATE slides SET slide_order = ? WHERE id = ? AND presentation_id = ?")
+            .bind(index as i64 + 1)
+            .bind(id)
+            .bind(presentation_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    let after = edits::slide_orders(&mut tx, presentation_id).await?;
+    if after != before {
+        let detail = json!({ "before": before, "after": after });
+        audit::record(&mut *tx, "reorder", "presentation", presentation_id, detail).await;
+        edits::record(
+            &mut tx,
+            presentation_id,
+            "reorder",
+            &[EditOp::SetOrder { orders: before }],
+            &[EditOp::SetOrder { orders: after }],
+        )
+        .await?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// Start of the error [`update_slide`] returns when the slide changed since
+/// the caller read it.
+pub const CONFLICT: &str = "Conflict";
+
+/// Overwrite a slide's row with `slide`, recording the previous row for undo,
+/// and return its new version.
+///
+/// The write only happens while the stored version is still
+/// `expected_version`, the one the caller loaded. Otherwise another window
+/// saved in between, and this fails with an error starting [`CONFLICT`] so the
+/// caller can reload rather than overwrite that edit.
+///
+/// `blocks_json`, `title_json` and `footer_json` are checked as [`save_slide`]
+/// checks them before anything is read.
+#[tauri::command]
+pub async fn update_slide(
+    db: State<'_, DbInstances>,
+    slide: Slide,
+    expected_version: i64,
+) -> Result<i64, String> {
+    validate_slide(&slide)?;
+    let pool = db::writable_pool(&db).await?;
+    overwrite_slide(&pool, slide, expected_version).await
+}
+
+/// Create `slide`, or update the stored one with the same id as
+/// [`update_slide`] would with the slide's own `version` as the expected one.
+/// A blank `id` is filled in. Returns the slide as stored.
+///
+/// `blocks_json` must be a blocks document [`BlocksDocument`] can read, and
+/// `title_json` and `footer_json` JSON objects, so a malformed edit is refused
+/// here rather than breaking every renderer later. A new slide goes on the
+/// presentation's undo stack like any other edit.
+#[tauri::command]
+pub async fn save_slide(db: State<'_, DbInstances>, slide: Slide) -> Result<Slide, String> {
+    validate_slide(&slide)?;
+    let mut slide = slide;
+    if slide.id.is_empty() {
+        slide.id = db::new_id();
+    }
+    let pool = db::writable_pool(&db).await?;
+    db::fetch_presentation(&pool, &slide.presentation_id).await?;
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM slides WHERE id = ?)")
+        .bind(&slide.id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if exists {
+        let version = overwrite_slide(&pool, slide.clone(), slide.version).await?;
+        return Ok(Slide { version, ..slide });
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query(
+        "INSERT INTO slides
+         (id, presentation_id, slide_order, line_id, title_json, blocks_json, footer_json,
+          notes, is_disabled, is_dynamic, template_override_id)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&slide.id)
+    .bind(&slide.presentation_id)
+    .bind(slide.slide_order)
+    .bind(&slide.line_id)
+    .bind(&slide.title_json)
+    .bind(&slide.blocks_json)
+    .bind(&slide.footer_json)
+    .bind(&slide.notes)
+    .bind(slide.is_disabled)
+    .bind(slide.is_dynamic)
+    .bind(&slide.template_override_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let slide = sqlx::query_as::<_, Slide>("SELECT * FROM slides WHERE id = ?")
+        .bind(&slide.id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    audit::record(&mut *tx, "create", "slide", &slide.id, json!(slide)).await;
+    edits::record(
+        &mut tx,
+        &slide.presentation_id,
+        "create",
+        &[EditOp::DeleteSlide {
+            id: slide.id.clone(),
+        }],
+        &[EditOp::PutSlide {
+            slide: slide.clone(),
+        }],
+    )
+    .await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(slide)
+}
+
+/// Refuse slide JSON the frontend and renderers couldn't read back.
+pub fn validate_slide(slide: &Slide) -> Result<(), String> {
+    BlocksDocument::parse(&slide.blocks_json)?;
+    for (column, json) in [("title", &slide.title_json), ("footer", &slide.footer_json)] {
+        let Some(json) = json else {
+            continue;
+        };
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| format!("Invalid {column} JSON: {e}"))?;
+        if !value.is_object() {
+            return Err(format!("Slide {column} must be a JSON object"));
+        }
+    }
+    Ok(())
+}
+
+async fn overwrite_slide(
+    pool: &SqlitePool,
+    slide: Slide,
+    expected_version: i64,
+) -> Result<i64, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let previous = sqlx::query_as::<_, Slide>("SELECT * FROM slides WHERE id = ?")
+        .bind(&slide.id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Slide not found: {}", slide.id))?;
+    if previous.presentation_id != slide.presentation_id {
+        return Err(format!(
+            "Slide {} does not belong to presentation {}",
+            slide.id, slide.presentation_id
+        ));
+    }
+    if previous.version != expected_version {
+        return Err(format!(
+            "{CONFLICT}: slide {} was changed elsewhere (version {} saved, {expected_version} expected); reload it",
+            slide.id, previous.version
+        ));
+    }
+
+    let version = expected_version + 1;
+    let updated = sqlx::query(
+        "UPDATE slides
+         SET slide_order = ?, line_id = ?, title_json = ?, blocks_json = ?, footer_json = ?,
+             notes = ?, is_disabled = ?, is_dynamic = ?, template_override_id = ?, version = ?
+         WHERE id = ? AND version = ?",
+    )
+    .bind(slide.slide_order)
+    .bind(&slide.line_id)
+    .bind(&slide.title_json)
+    .bind(&slide.blocks_json)
+    .bind(&slide.footer_json)
+    .bind(&slide.notes)
+    .bind(slide.is_disabled)
+    .bind(slide.is_dynamic)
+    .bind(&slide.template_override_id)
+    .bind(version)
+    .bind(&slide.id)
+    .bind(expected_version)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+    // Checked again in the write, in case another save landed since the read
+    if updated == 0 {
+        return Err(format!(
+            "{CONFLICT}: slide {} was changed elsewhere; reload it",
+            slide.id
+        ));
+    }
+
+    let presentation_id = slide.presentation_id.clone();
+    let slide = Slide { version, ..slide };
+    let detail = json!({ "before": previous, "after": slide });
+    audit::record(&mut *tx, "update", "slide", &slide.id, detail).await;
+    edits::record(
+        &mut tx,
+        &presentation_id,
+        "update",
+        &[EditOp::PutSlide { slide: previous }],
+        &[EditOp::PutSlide { slide }],
+    )
+    .await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(version)
+}
+
+/// Delete a slide and renumber the rest 1..n. Undo restores the full row at
+/// its original `slide_order` along with the order of every other slide.
+#[tauri::command]
+pub async fn delete_slide(db: State<'_, DbInstances>, id: String) -> Result<(), String> {
+    let pool = db::writable_pool(&db).await?;
+    delete(&pool, id).await
+}
+
+pub(crate) async fn delete(pool: &SqlitePool, id: String) -> Result<(), String> {
+    let slide = db::fetch_slide(pool, &id).await?;
+    let presentation_id = slide.presentation_id.clone();
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let before = edits::slide_orders(&mut tx, &presentation_id).await?;
+    sqlx::query("DELETE FROM slides WHERE id = ?")
+        .bind(&id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    for (index, (other_id, _)) in before.iter().filter(|(other, _)| *other != id).enumerate() {
+        set_slide_order(&mut tx, other_id, index + 1).await?;
+    }
+    let after = edits::slide_orders(&mut tx, &presentation_id).await?;
+
+    audit::record(&mut *tx, "delete", "slide", &id, json!({ "slide": slide })).await;
+    edits::record(
+        &mut tx,
+        &presentation_id,
+        "delete",
+        &[
+            EditOp::PutSlide { slide },
+            EditOp::SetOrder { orders: before },
+        ],
+        &[
+            EditOp::DeleteSlide { id },
+            EditOp::SetOrder { orders: after },
+        ],
+    )
+    .await?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// Disable or re-enable `slide_ids` in one statement, returning how many
+/// changed; slides already in that state don't count. Any id not in the
+/// presentation aborts the whole update. Undo restores the previous flags.
+#[tauri::command]
+pub async fn set_slides_disabled(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    slide_ids: Vec<String>,
+    disabled: bool,
+) -> Result<u32, String> {
+    let pool = db::writable_pool(&db).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let slides: HashMap<String, Slide> =
+        sqlx::query_as::<_, Slide>("SELECT * FROM slides WHERE presentation_id = ?")
+            .bind(&presentation_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|slide| (slide.id.clone(), slide))
+            .collect();
+    if let Some(unknown) = slide_ids.iter().find(|id| !slides.contains_key(*id)) {
+        return Err(format!(
+            "Slide {unknown} does not belong to presentation {presentation_id}"
+        ));
+    }
+    let ids: BTreeSet<&String> = slide_ids.iter().collect();
+    let changing: Vec<&Slide> = ids
+        .iter()
+        .map(|id| &slides[*id])
+        .filter(|slide| slide.is_disabled != disabled)
+        .collect();
+    if changing.is_empty() {
+        return Ok(0);
+    }
+
+    let ids_json = serde_json::to_string(&ids).map_err(|e| e.to_string())?;
+    let changed = sqlx::query(
+        "UPDATE slides SET is_disabled = ?1
+         WHERE presentation_id = ?2 AND is_disabled <> ?1
+           AND id IN (SELECT value FROM json_each(?3))",
+    )
+    .bind(disabled)
+    .bind(&presentation_id)
+    .bind(&ids_json)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+
+    let undo: Vec<EditOp> = changing
+        .iter()
+        .map(|slide| EditOp::PutSlide {
+            slide: (*slide).clone(),
+        })
+        .collect();
+    let redo: Vec<EditOp> = changing
+        .iter()
+        .map(|slide| EditOp::PutSlide {
+            slide: Slide {
+                is_disabled: disabled,
+                ..(*slide).clone()
+            },
+        })
+        .collect();
+    let detail = json!({
+        "disabled": disabled,
+        "slideIds": changing.iter().map(|slide| &slide.id).collect::<Vec<_>>(),
+    });
+    let label = if disabled { "disable" } else { "enable" };
+    audit::record(&mut *tx, label, "presentation", &presentation_id, detail).await;
+    edits::record(&mut tx, &presentation_id, label, &undo, &redo).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(changed as u32)
+}
+
+/// How many of a presentation's slides are not disabled.
+#[tauri::command]
+pub async fn get_active_slide_count(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<u32, String> {
+    let pool = db::pool(&db).await?;
+    db::fetch_presentation(&pool, &presentation_id).await?;
+    let count: i64 = sqlx::query_scalar(
+        "SELECT count(*) FROM slides WHERE presentation_id = ? AND is_disabled = 0",
+    )
+    .bind(&presentation_id)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(count as u32)
+}
+
+/// Renumber a presentation's slides to 1..n, closing gaps and splitting ties.
+///
+/// Disabled slides are renumbered along with the rest so they keep their place.
+/// Ties keep insertion order. Returns how many slides changed; a second call
+/// returns 0. A renumbering is recorded as an edit, so it can be undone.
+#[tauri::command]
+pub async fn compact_slide_order(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<u32, String> {
+    let pool = db::writable_pool(&db).await?;
+    compact(&pool, &presentation_id).await
+}
+
+async fn compact(pool: &SqlitePool, presentation_id: &str) -> Result<u32, String> {
+    db::fetch_presentation(pool, presentation_id).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let before = edits::slide_orders(&mut tx, presentation_id).await?;
+    let changed = sqlx::query(
+        "UPDATE slides SET slide_order = ranked.position
+         FROM (SELECT id, row_number() OVER (ORDER BY slide_order, rowid) AS position
+               FROM slides WHERE presentation_id = ?) AS ranked
+         WHERE slides.id = ranked.id AND slides.slide_order <> ranked.position",
+    )
+    .bind(presentation_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+    if changed > 0 {
+        let detail = json!({ "slidesRenumbered": changed });
+        audit::record(&mut *tx, "compact", "presentation", presentation_id, detail).await;
+        let after = edits::slide_orders(&mut tx, presentation_id).await?;
+        edits::record(
+            &mut tx,
+            presentation_id,
+            "compact",
+            &[EditOp::SetOrder { orders: before }],
+            &[EditOp::SetOrder { orders: after }],
+        )
+        .await?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(changed as u32)
+}
+
+/// Rewrite a presentation's slide `title_json`, `blocks_json` and
+/// `footer_json` in the form [`normalize_json`] gives, in one transaction.
+/// Returns how many slides changed; a second call returns 0.
+///
+/// Only formatting changes, so diffs, duplicate detection and checksums see
+/// the same text for the same content. Malformed JSON is left as it is. A
+/// normalization is recorded as an edit, so it can be undone.
+#[tauri::command]
+pub async fn normalize_presentation_json(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+) -> Result<u32, String> {
+    let pool = db::writable_pool(&db).await?;
+    normalize_slides(&pool, &presentation_id).await
+}
+
+async fn normalize_slides(pool: &SqlitePool, presentation_id: &str) -> Result<u32, String> {
+    db::fetch_presentation(pool, presentation_id).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let slides: Vec<Slide> =
+        sqlx::query_as("SELECT * FROM slides WHERE presentation_id = ? ORDER BY slide_order")
+            .bind(presentation_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let normalize = |json: &Option<String>| {
+        json.as_deref()
+            .map(|json| normalize_json(json).unwrap_or_else(|| json.to_string()))
+    };
+    let mut undo = Vec::new();
+    let mut redo = Vec::new();
+    for slide in slides {
+        let normalized = Slide {
+            title_json: normalize(&slide.title_json),
+            blocks_json: normalize_json(&slide.blocks_json)
+                .unwrap_or_else(|| slide.blocks_json.clone()),
+            footer_json: normalize(&slide.footer_json),
+            ..slide.clone()
+        };
+        if normalized.title_json == slide.title_json
+            && normalized.blocks_json == slide.blocks_json
+            && normalized.footer_json == slide.footer_json
+        {
+            continue;
+        }
+        sqlx::query(
+            "UPDATE slides SET title_json = ?, blocks_json = ?, footer_json = ? WHERE id = ?",
+        )
+        .bind(&normalized.title_json)
+        .bind(&normalized.blocks_json)
+        .bind(&normalized.footer_json)
+        .bind(&normalized.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        undo.push(EditOp::PutSlide { slide });
+        redo.push(EditOp::PutSlide { slide: normalized });
+    }
+    let changed = redo.len() as u32;
+    if changed > 0 {
+        let detail = json!({ "slidesNormalized": changed });
+        audit::record(
+            &mut *tx,
+            "normalize",
+            "presentation",
+            presentation_id,
+            detail,
+        )
+        .await;
+        edits::record(&mut tx, presentation_id, "normalize", &undo, &redo).await?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(changed)
+}
+
+/// Clone `slide_ids` from `source_id` into `target_id` with fresh ids.
+///
+/// The copies keep their source order and start at the 1-based `at_order`;
+/// target slides from there on shift down and the target is renumbered 1..n.
+/// Placeholders the copies use that the target lacks are created with empty
+/// values. Returns the new slide ids in order.
+#[tauri::command]
+pub async fn copy_slides(
+    db: State<'_, DbInstances>,
+    source_id: String,
+    slide_ids: Vec<String>,
+    target_id: String,
+    at_order: u32,
+) -> Result<Vec<String>, String> {
+    let pool = db::writable_pool(&db).await?;
+    let source = db::fetch_presentation(&pool, &source_id).await?;
+    let target = db::fetch_presentation(&pool, &target_id).await?;
+    let source_slides = db::fetch_slides(&pool, &source_id).await?;
+    let target_slides = db::fetch_slides(&pool, &target_id).await?;
+    let target_variables = db::fetch_variables(&pool, &target_id).await?;
+
+    let wanted: HashSet<&String> = slide_ids.iter().collect();
+    if let Some(stray) = wanted
+        .iter()
+        .find(|id| !source_slides.iter().any(|s| &&s.id == *id))
+    {
+        return Err(format!(
+            "Slide {stray} does not belong to presentation {source_id}"
+        ));
+    }
+    let copies: Vec<_> = source_slides
+        .iter()
+        .filter(|s| wanted.contains(&s.id))
+        .collect();
+
+    let pattern = Regex::new(PLACEHOLDER_PATTERN).map_err(|e| e.to_string())?;
+    let missing_variables: BTreeSet<&str> = copies
+        .iter()
+        .flat_map(|s| {
+            [
+                s.title_json.as_deref(),
+                Some(s.blocks_json.as_str()),
+                s.footer_json.as_deref(),
+            ]
+        })
+        .flatten()
+        .flat_map(|text| pattern.find_iter(text).map(|m| m.as_str()))
+        .filter(|name| !target_variables.iter().any(|v| v.name == *name))
+        .collect();
+
+    let insert_at = (at_order.max(1) as usize - 1).min(target_slides.len());
+    let (before, after) = target_slides.split_at(insert_at);
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    for (index, slide) in before.iter().enumerate() {
+        set_slide_order(&mut tx, &slide.id, index + 1).await?;
+    }
+    for (index, slide) in after.iter().enumerate() {
+        set_slide_order(&mut tx, &slide.id, before.len() + copies.len() + index + 1).await?;
+    }
+
+    let mut new_ids = Vec::with_capacity(copies.len());
+    for (index, slide) in copies.iter().enumerate() {
+        let new_id = db::new_id();
+        let template_override_id = match &slide.template_override_id {
+            None if source.template_id != target.template_id => Some(&source.template_id),
+            other => other.as_ref(),
+        };
+        sqlx::query(
+            "INSERT INTO slides
+             (id, presentation_id, slide_order, line_id, title_json, blocks_json, footer_json,
+              notes, is_disabled, is_dynamic, template_override_id)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&new_id)
+        .bind(&target_id)
+        .bind((before.len() + index + 1) as i64)
+        .bind(&slide.line_id)
+        .bind(&slide.title_json)
+        .bind(&slide.blocks_json)
+        .bind(&slide.footer_json)
+        .bind(&slide.notes)
+        .bind(slide.is_disabled)
+        .bind(slide.is_dynamic)
+        .bind(template_override_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        new_ids.push(new_id);
+    }
+
+    for name in missing_variables {
+        sqlx::query(
+            "INSERT INTO variables
+             (id, presentation_id, name, value, value_lang1, value_lang2, value_lang3, value_lang4)
+             VALUES (?, ?, ?, '', '', '', '', '')",
+        )
+        .bind(db::new_id())
+        .bind(&target_id)
+        .bind(name)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    let detail = json!({
+        "sourceId": source_id,
+        "slideIds": slide_ids,
+        "newIds": new_ids,
+        "atOrder": at_order,
+    });
+    audit::record(&mut *tx, "copy_slides", "presentation", &target_id, detail).await;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(new_ids)
+}
+
+/// Group slides whose title, blocks and footer are the same once the JSON is
+/// canonicalized, so key order and formatting don't matter. Returns clusters of
+/// two or more slide ids, each and all in slide order.
+///
+/// With `fuzzy`, text that differs only in whitespace (runs of spaces, line
+/// breaks, leading or trailing blanks) also counts as the same.
+#[tauri::command]
+pub async fn find_duplicate_slides(
+    db: State<'_, DbInstances>,
+    presentation_id: String,
+    fuzzy: Option<bool>,
+) -> Result<Vec<Vec<String>>, String> {
+    let fuzzy = fuzzy.unwrap_or(false);
+    let pool = db::pool(&db).await?;
+    db::fetch_presentation(&pool, &presentation_id).await?;
+    let slides = db::fetch_slides(&pool, &presentation_id).await?;
+
+    let mut clusters: Vec<Vec<String>> = Vec::new();
+    let mut by_content: HashMap<String, usize> = HashMap::new();
+    for slide in slides {
+        let mut key = String::new();
+        for json in [
+            slide.title_json.as_deref(),
+            Some(slide.blocks_json.as_str()),
+            slide.footer_json.as_deref(),
+        ] {
+            let value = match json {
+                // Unparseable JSON only matches itself byte for byte
+                Some(json) => serde_json::from_str(json).unwrap_or(Value::String(json.into())),
+                None => Value::Null,
+            };
+            canonicalize(&value, fuzzy, &mut key);
+            key.push('\n');
+        }
+        match by_content.get(&key) {
+            Some(&index) => clusters[index].push(slide.id),
+            None => {
+                by_content.insert(key, clusters.len());
+                clusters.push(vec![slide.id]);
+            }
+        }
+    }
+    clusters.retain(|ids| ids.len() > 1);
+    Ok(clusters)
+}
+
+/// Write `value` as JSON with object keys sorted, collapsing whitespace in
+/// strings when `fuzzy`.
+pub fn canonicalize(value: &Value, fuzzy: bool, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                canonicalize(value, fuzzy, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                canonicalize(item, fuzzy, out);
+            }
+            out.push(']');
+        }
+        Value::String(text) if fuzzy => {
+            let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            out.push_str(&Value::String(collapsed).to_string());
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// `json` with sorted keys and no whitespace between tokens, or `None` when it
+/// doesn't parse. Values, string contents included, come through unchanged.
+pub fn normalize_json(json: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(json).ok()?;
+    let mut out = String::new();
+    canonicalize(&value, false, &mut out);
+    Some(out)
+}
+
+async fn set_slide_order(
+    tx: &mut Transaction<'_, Sqlite>,
+    id: &str,
+    order: usize,
+) -> Result<(), String> {
+    sqlx::query("UPDATE slides SET slide_order = ? WHERE id = ?")
+        .bind(order as i64)
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    async fn three_slides() -> SqlitePool {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        for (id, order) in [("s1", 1), ("s2", 2), ("s3", 3)] {
+            test_support::slide(&pool, id, "p1", order).await;
+        }
+        pool
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    async fn edit_count(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM edits")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn compact_closes_gaps_and_ties_once() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        for (id, order) in [("s1", 3), ("s2", 10), ("s3", 10), ("s4", 40)] {
+            test_support::slide(&pool, id, "p1", order).await;
+        }
+
+        assert_eq!(compact(&pool, "p1").await.unwrap(), 4);
+        assert_eq!(
+            test_support::slide_orders(&pool, "p1").await,
+            [
+                ("s1".to_string(), 1),
+                ("s2".to_string(), 2),
+                ("s3".to_string(), 3),
+                ("s4".to_string(), 4)
+            ]
+        );
+        assert_eq!(edit_count(&pool).await, 1);
+
+        // Already compact: nothing changes and nothing is recorded
+        assert_eq!(compact(&pool, "p1").await.unwrap(), 0);
+        assert_eq!(edit_count(&pool).await, 1);
+    }
+
+    #[tokio::test]
+    async fn compact_is_undoable() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        for (id, order) in [("s1", 5), ("s2", 9)] {
+            test_support::slide(&pool, id, "p1", order).await;
+        }
+        compact(&pool, "p1").await.unwrap();
+
+        let undo: String = sqlx::query_scalar("SELECT undo_json FROM edits WHERE kind = 'compact'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let undo: Vec<EditOp> = serde_json::from_str(&undo).unwrap();
+        assert!(matches!(
+            undo.as_slice(),
+            [EditOp::SetOrder { orders }] if *orders == [("s1".to_string(), 5), ("s2".to_string(), 9)]
+        ));
+    }
+
+    #[tokio::test]
+    async fn compact_refuses_an_unknown_presentation() {
+        let pool = test_support::pool().await;
+        let error = compact(&pool, "missing").await.unwrap_err();
+        assert!(error.contains("not found"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn reorder_numbers_from_one_and_records_an_edit() {
+        let pool = three_slides().await;
+        reorder(&pool, "p1", &ids(&["s3", "s1", "s2"]))
+            .await
+            .unwrap();
+        assert_eq!(
+            test_support::slide_orders(&pool, "p1").await,
+            [
+                ("s3".to_string(), 1),
+                ("s1".to_string(), 2),
+                ("s2".to_string(), 3)
+            ]
+        );
+        assert_eq!(edit_count(&pool).await, 1);
+    }
+
+    #[tokio::test]
+    async fn reorder_refuses_a_list_that_isnt_every_slide_once() {
+        let pool = three_slides().await;
+        for list in [
+            ids(&["s1", "s2"]),
+            ids(&["s1", "s2", "s3", "s3"]),
+            ids(&["s1", "s2", "s3", "elsewhere"]),
+        ] {
+            assert!(reorder(&pool, "p1", &list).await.is_err(), "{list:?}");
+        }
+        assert_eq!(
+            test_support::slide_orders(&pool, "p1").await,
+            [
+                ("s1".to_string(), 1),
+                ("s2".to_string(), 2),
+                ("s3".to_string(), 3)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reorder_rolls_back_when_a_write_fails_part_way() {
+        let pool = three_slides().await;
+        // Fails the last of the three updates, after the first two went through
+        test_support::exec(
+            &pool,
+            "CREATE TEMP TRIGGER fail_s2 BEFORE UPDATE OF slide_order ON slides
+             WHEN NEW.id = 's2' BEGIN SELECT RAISE(ABORT, 'simulated failure'); END",
+        )
+        .await;
+
+        let error = reorder(&pool, "p1", &ids(&["s3", "s1", "s2"]))
+            .await
+            .unwrap_err();
+        assert!(error.contains("simulated failure"), "{error}");
+        assert_eq!(
+            test_support::slide_orders(&pool, "p1").await,
+            [
+                ("s1".to_string(), 1),
+                ("s2".to_string(), 2),
+                ("s3".to_string(), 3)
+            ]
+        );
+        assert_eq!(edit_count(&pool).await, 0);
+    }
+
+    #[tokio::test]
+    async fn the_second_of_two_edits_to_the_same_version_conflicts() {
+        let pool = three_slides().await;
+        let loaded = db::fetch_slide(&pool, "s1").await.unwrap();
+        let edit = |notes: &str| Slide {
+            notes: Some(notes.to_string()),
+            ..loaded.clone()
+        };
+
+        let (first, second) = tokio::join!(
+            overwrite_slide(&pool, edit("first window"), loaded.version),
+            overwrite_slide(&pool, edit("second window"), loaded.version),
+        );
+        assert_eq!(first, Ok(loaded.version + 1));
+        let error = second.unwrap_err();
+        assert!(error.starts_with(CONFLICT), "{error}");
+
+        let saved = db::fetch_slide(&pool, "s1").await.unwrap();
+        assert_eq!(saved.notes.as_deref(), Some("first window"));
+        assert_eq!(saved.version, loaded.version + 1);
+        assert_eq!(edit_count(&pool).await, 1);
+
+        // Reloading picks up the new version and the edit goes through
+        let retried = overwrite_slide(&pool, edit("second window"), saved.version).await;
+        assert_eq!(retried, Ok(saved.version + 1));
+    }
+
+    #[tokio::test]
+    async fn a_normalization_can_be_undone_and_redone() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        test_support::slide(&pool, "s1", "p1", 1).await;
+        test_support::slide(&pool, "s2", "p1", 2).await;
+        let messy = r#"[ { "Lang2" : "ሰላም", "Lang1" : "Peace" } ]"#;
+        sqlx::query("UPDATE slides SET blocks_json = ? WHERE id = 's1'")
+            .bind(messy)
+            .execute(&pool)
+            .await
+            .unwrap();
+        async fn blocks(pool: &SqlitePool) -> String {
+            db::fetch_slide(pool, "s1").await.unwrap().blocks_json
+        }
+
+        assert_eq!(normalize_slides(&pool, "p1").await.unwrap(), 1);
+        let normalized = blocks(&pool).await;
+        assert_eq!(Some(normalized.clone()), normalize_json(messy));
+        assert_eq!(normalize_slides(&pool, "p1").await.unwrap(), 0);
+
+        let undone = edits::step(&pool, "p1", edits::Direction::Undo)
+            .await
+            .unwrap();
+        assert_eq!(undone.as_deref(), Some("normalize"));
+        assert_eq!(blocks(&pool).await, messy);
+        edits::step(&pool, "p1", edits::Direction::Redo)
+            .await
+            .unwrap();
+        assert_eq!(blocks(&pool).await, normalized);
+    }
+}
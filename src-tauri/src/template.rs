@@ -0,0 +1,591 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{FromRow, SqliteConnection, SqlitePool};
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::audit;
+use crate::db::{self, Template, LANG_SLOTS};
+
+/// Typed mirror of the frontend's `TemplateDefinition`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TemplateDefinition {
+    pub layout: Layout,
+    pub title: TitleStyle,
+    pub languages: Vec<LanguageStyle>,
+    pub background: Background,
+    pub margins: Margins,
+    pub safe_area: SafeArea,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Layout {
+    pub columns: u32,
+    pub rows: u32,
+    pub gap: f64,
+    /// The renderer centres when this is absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vertical_align: Option<VerticalAlign>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TitleStyle {
+    pub show: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
+    pub font_size: f64,
+    pub color: String,
+    pub alignment: TitleAlignment,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LanguageStyle {
+    pub slot: String,
+    pub font_size: f64,
+    pub font_family: String,
+    pub color: String,
+    pub alignment: TextAlignment,
+    pub line_height: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Background {
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Margins {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SafeArea {
+    pub horizontal: f64,
+    pub vertical: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VerticalAlign {
+    Top,
+    Center,
+    Bottom,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TitleAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TextAlignment {
+    Left,
+    Center,
+    Right,
+    Justify,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateValidation {
+    pub valid: bool,
+    pub errors: Vec<TemplateIssue>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateIssue {
+    /// Dotted path to the offending value, e.g. `languages.1.fontSize`. Empty
+    /// when the JSON itself doesn't fit the schema.
+    pub path: String,
+    pub message: String,
+}
+
+impl TemplateIssue {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        TemplateIssue {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Check `definition_json` against [`TemplateDefinition`] and the limits the
+/// template editor enforces. `max_lang_count` defaults to every slot.
+pub fn validate(definition_json: &str, max_lang_count: Option<u32>) -> TemplateValidation {
+    let definition: TemplateDefinition = match serde_json::from_str(definition_json) {
+        Ok(definition) => definition,
+        Err(e) => {
+            return TemplateValidation {
+                valid: false,
+                errors: vec![TemplateIssue::new("", e.to_string())],
+            }
+        }
+    };
+
+    let mut errors = Vec::new();
+    let max_lang_count = max_lang_count.unwrap_or(LANG_SLOTS.len() as u32);
+    if max_lang_count == 0 || max_lang_count as usize > LANG_SLOTS.len() {
+        errors.push(TemplateIssue::new(
+            "maxLangCount",
+            format!("must be between 1 and {}", LANG_SLOTS.len()),
+        ));
+    }
+
+    if !(1..=4).contains(&definition.layout.columns) {
+        errors.push(TemplateIssue::new(
+            "layout.columns",
+            "must be between 1 and 4",
+        ));
+    }
+    if !(1..=2).contains(&definition.layout.rows) {
+        errors.push(TemplateIssue::new("layout.rows", "must be 1 or 2"));
+    }
+    if definition.layout.gap < 0.0 {
+        errors.push(TemplateIssue::new("layout.gap", "must not be negative"));
+    }
+    if definition.title.font_size <= 0.0 {
+        errors.push(TemplateIssue::new("title.fontSize", "must be positive"));
+    }
+
+    if definition.languages.is_empty() {
+        errors.push(TemplateIssue::new(
+            "languages",
+            "must define at least one language region",
+        ));
+    }
+    if definition.languages.len() > max_lang_count as usize {
+        errors.push(TemplateIssue::new(
+            "languages",
+            format!(
+                "defines {} language regions but maxLangCount is {max_lang_count}",
+                definition.languages.len()
+            ),
+        ));
+    }
+    let mut slots = HashSet::new();
+    for (index, language) in definition.languages.iter().enumerate() {
+        let path = |field: &str| format!("languages.{index}.{field}");
+        if !LANG_SLOTS.contains(&language.slot.as_str()) {
+            errors.push(TemplateIssue::new(
+                path("slot"),
+                format!("unknown slot `{}`", language.slot),
+            ));
+        } else if !slots.insert(language.slot.as_str()) {
+            errors.push(TemplateIssue::new(
+                path("slot"),
+                format!("slot `{}` is defined more than once", language.slot),
+            ));
+        }
+        if language.font_size <= 0.0 {
+            errors.push(TemplateIssue::new(path("fontSize"), "must be positive"));
+        }
+        if language.line_height <= 0.0 {
+            errors.push(TemplateIssue::new(path("lineHeight"), "must be positive"));
+        }
+    }
+
+    let margins = [
+        ("margins.top", definition.margins.top),
+        ("margins.right", definition.margins.right),
+        ("margins.bottom", definition.margins.bottom),
+        ("margins.left", definition.margins.left),
+    ];
+    for (path, value) in margins {
+        if value < 0.0 {
+            errors.push(TemplateIssue::new(path, "must not be negative"));
+        }
+    }
+
+    TemplateValidation {
+        valid: errors.is_empty(),
+        errors,
+    }
+}
+
+#[tauri::command]
+pub fn validate_template(
+    definition_json: String,
+    max_lang_count: Option<u32>,
+) -> Result<TemplateValidation, String> {
+    Ok(validate(&definition_json, max_lang_count))
+}
+
+/// Insert a template after validating its definition.
+#[tauri::command]
+pub async fn create_template(
+    db: State<'_, DbInstances>,
+    name: String,
+    max_lang_count: u32,
+    definition_json: String,
+) -> Result<Template, String> {
+    ensure_valid(&definition_json, max_lang_count)?;
+    let pool = db::writable_pool(&db).await?;
+    let id = db::new_id();
+    sqlx::query(
+        "INSERT INTO templates (id, name, max_lang_count, definition_json, created_at)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(&name)
+    .bind(max_lang_count)
+    .bind(&definition_json)
+    .bind(db::now())
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let detail = json!({ "name": name, "maxLangCount": max_lang_count });
+    audit::record(&pool, "create", "template", &id, detail).await;
+    db::fetch_template(&pool, &id).await
+}
+
+/// Replace a template's name, slot count and definition after validating it.
+#[tauri::command]
+pub async fn update_template(
+    db: State<'_, DbInstances>,
+    id: String,
+    name: String,
+    max_lang_count: u32,
+    definition_json: String,
+) -> Result<Template, String> {
+    ensure_valid(&definition_json, max_lang_count)?;
+    let pool = db::writable_pool(&db).await?;
+    let updated = sqlx::query(
+        "UPDATE templates SET name = ?, max_lang_count = ?, definition_json = ? WHERE id = ?",
+    )
+    .bind(&name)
+    .bind(max_lang_count)
+    .bind(&definition_json)
+    .bind(&id)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+    if updated == 0 {
+        return Err(format!("Template not found: {id}"));
+    }
+    let detail = json!({
+        "name": name,
+        "maxLangCount": max_lang_count,
+        "definitionJson": definition_json,
+    });
+    audit::record(&pool, "update", "template", &id, detail).await;
+    db::fetch_template(&pool, &id).await
+}
+
+/// Copy a template under a new name, returning the copy's id. The copy is
+/// independent: presentations keep pointing at the original.
+#[tauri::command]
+pub async fn clone_template(
+    db: State<'_, DbInstances>,
+    source_id: String,
+    new_name: String,
+) -> Result<String, String> {
+    let pool = db::writable_pool(&db).await?;
+    clone(&pool, &source_id, &new_name).await
+}
+
+async fn clone(pool: &SqlitePool, source_id: &str, new_name: &str) -> Result<String, String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err("Template name cannot be empty".to_string());
+    }
+
+    let source = db::fetch_template(pool, source_id).await?;
+    let taken: Option<String> =
+        sqlx::query_scalar("SELECT name FROM templates WHERE name = ? COLLATE NOCASE")
+            .bind(new_name)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if let Some(existing) = taken {
+        return Err(format!("A template named \"{existing}\" already exists"));
+    }
+
+    let id = db::new_id();
+    sqlx::query(
+        "INSERT INTO templates (id, name, max_lang_count, definition_json, created_at)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(new_name)
+    .bind(source.max_lang_count)
+    .bind(&source.definition_json)
+    .bind(db::now())
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let detail = json!({ "name": new_name, "clonedFrom": source_id });
+    audit::record(pool, "create", "template", &id, detail).await;
+    Ok(id)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateUsage {
+    /// Presentations laid out with the template, trashed ones included since
+    /// they can be restored.
+    pub presentations: Vec<TemplatePresentation>,
+    /// Slides overriding their presentation's template with this one.
+    pub slides: Vec<TemplateSlide>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplatePresentation {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateSlide {
+    pub id: String,
+    pub slide_order: i64,
+    pub presentation_id: String,
+    pub presentation_name: String,
+}
+
+impl TemplateUsage {
+    pub fn is_empty(&self) -> bool {
+        self.presentations.is_empty() && self.slides.is_empty()
+    }
+}
+
+/// What still points at a template, to show before deleting it.
+#[tauri::command]
+pub async fn get_template_usage(
+    db: State<'_, DbInstances>,
+    template_id: String,
+) -> Result<TemplateUsage, String> {
+    let pool = db::pool(&db).await?;
+    db::fetch_template(&pool, &template_id).await?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    template_usage(&mut conn, &template_id).await
+}
+
+async fn template_usage(
+    conn: &mut SqliteConnection,
+    template_id: &str,
+) -> Result<TemplateUsage, String> {
+    let presentations = sqlx::query_as(
+        "SELECT id, name FROM presentations WHERE template_id = ? ORDER BY name COLLATE NOCASE",
+    )
+    .bind(template_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let slides = sqlx::query_as(
+        "SELECT s.id, s.slide_order, p.id AS presentation_id, p.name AS presentation_name
+         FROM slides s JOIN presentations p ON p.id = s.presentation_id
+         WHERE s.template_override_id = ?
+         ORDER BY p.name COLLATE NOCASE, s.slide_order",
+    )
+    .bind(template_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(TemplateUsage {
+        presentations,
+        slides,
+    })
+}
+
+/// Delete a template. One still in use is refused unless `force` is set, in
+/// which case `fallback_id` takes its place on every presentation and slide
+/// override, in the same transaction as the delete.
+#[tauri::command]
+pub async fn delete_template(
+    db: State<'_, DbInstances>,
+    id: String,
+    force: Option<bool>,
+    fallback_id: Option<String>,
+) -> Result<(), String> {
+    let pool = db::writable_pool(&db).await?;
+    delete(&pool, &id, force.unwrap_or(false), fallback_id.as_deref()).await
+}
+
+async fn delete(
+    pool: &SqlitePool,
+    id: &str,
+    force: bool,
+    fallback_id: Option<&str>,
+) -> Result<(), String> {
+    let template = db::fetch_template(pool, id).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let usage = template_usage(&mut tx, id).await?;
+    let mut detail = json!({ "template": template });
+    if !usage.is_empty() {
+        if !force {
+            return Err(format!(
+                "Template \"{}\" is used by {} presentation(s) and {} slide override(s)",
+                template.name,
+                usage.presentations.len(),
+                usage.slides.len()
+            ));
+        }
+        let fallback_id = fallback_id.ok_or("Choose a template to use in its place")?;
+        if fallback_id == id {
+            return Err("The replacement template is the one being deleted".to_string());
+        }
+        let fallback: Option<String> = sqlx::query_scalar("SELECT id FROM templates WHERE id = ?")
+            .bind(fallback_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        if fallback.is_none() {
+            return Err(format!("Template not found: {fallback_id}"));
+        }
+
+        sqlx::query("UPDATE presentations SET template_id = ? WHERE template_id = ?")
+            .bind(fallback_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        sqlx::query("UPDATE slides SET template_override_id = ? WHERE template_override_id = ?")
+            .bind(fallback_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        detail["fallbackId"] = json!(fallback_id);
+        detail["presentationsReassigned"] = json!(usage.presentations.len());
+        detail["slidesReassigned"] = json!(usage.slides.len());
+    }
+
+    sqlx::query("DELETE FROM templates WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    audit::record(&mut *tx, "delete", "template", id, detail).await;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+pub(crate) fn ensure_valid(definition_json: &str, max_lang_count: u32) -> Result<(), String> {
+    let validation = validate(definition_json, Some(max_lang_count));
+    if validation.valid {
+        return Ok(());
+    }
+    let details: Vec<String> = validation
+        .errors
+        .iter()
+        .map(|issue| match issue.path.as_str() {
+            "" => issue.message.clone(),
+            path => format!("{path}: {}", issue.message),
+        })
+        .collect();
+    Err(format!(
+        "Invalid template definition: {}",
+        details.join("; ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[tokio::test]
+    async fn a_clone_has_the_same_definition_under_a_new_id() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        test_support::exec(
+            &pool,
+            "UPDATE templates SET definition_json = '{\"background\":\"#000\"}', max_lang_count = 2
+             WHERE id = 't1'",
+        )
+        .await;
+
+        let id = clone(&pool, "t1", " Dark copy ").await.unwrap();
+        assert_ne!(id, "t1");
+        let source = db::fetch_template(&pool, "t1").await.unwrap();
+        let copy = db::fetch_template(&pool, &id).await.unwrap();
+        assert_eq!(copy.name, "Dark copy");
+        assert_eq!(copy.definition_json, source.definition_json);
+        assert_eq!(copy.max_lang_count, 2);
+
+        // The presentation keeps the original
+        let presentation = db::fetch_presentation(&pool, "p1").await.unwrap();
+        assert_eq!(presentation.template_id, "t1");
+    }
+
+    #[tokio::test]
+    async fn a_taken_name_is_refused_whatever_its_case() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        let error = clone(&pool, "t1", "T1").await.unwrap_err();
+        assert!(error.contains("already exists"), "{error}");
+        assert!(clone(&pool, "t1", " ").await.is_err());
+        assert!(clone(&pool, "missing", "Copy").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn a_template_in_use_is_not_deleted() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        test_support::template(&pool, "spare").await;
+        test_support::slide(&pool, "s1", "p1", 1).await;
+        test_support::exec(
+            &pool,
+            "UPDATE slides SET template_override_id = 'spare' WHERE id = 's1'",
+        )
+        .await;
+
+        for id in ["t1", "spare"] {
+            let error = delete(&pool, id, false, None).await.unwrap_err();
+            assert!(error.contains("is used by"), "{error}");
+            assert!(db::fetch_template(&pool, id).await.is_ok());
+        }
+        // Forced, but with nothing to put in its place
+        assert!(delete(&pool, "t1", true, None).await.is_err());
+        assert!(delete(&pool, "t1", true, Some("t1")).await.is_err());
+        assert!(delete(&pool, "t1", true, Some("missing")).await.is_err());
+        assert!(db::fetch_template(&pool, "t1").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn a_forced_delete_moves_its_users_to_the_fallback() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        test_support::template(&pool, "spare").await;
+        test_support::slide(&pool, "s1", "p1", 1).await;
+        test_support::exec(
+            &pool,
+            "UPDATE slides SET template_override_id = 't1' WHERE id = 's1'",
+        )
+        .await;
+
+        delete(&pool, "t1", true, Some("spare")).await.unwrap();
+        assert!(db::fetch_template(&pool, "t1").await.is_err());
+        let presentation = db::fetch_presentation(&pool, "p1").await.unwrap();
+        assert_eq!(presentation.template_id, "spare");
+        let slide = db::fetch_slide(&pool, "s1").await.unwrap();
+        assert_eq!(slide.template_override_id.as_deref(), Some("spare"));
+
+        // The fallback is in use now; an unused template needs no force
+        assert!(delete(&pool, "spare", false, None).await.is_err());
+        test_support::template(&pool, "unused").await;
+        delete(&pool, "unused", false, None).await.unwrap();
+    }
+}
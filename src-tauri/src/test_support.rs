@@ -0,0 +1,131 @@
+//! Fixtures shared by the unit tests.
+
+use std::ops::RangeInclusive;
+
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::SqlitePool;
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+use crate::migrations;
+
+/// A fresh in-memory `kidase.db`, migrated to the current schema.
+pub async fn pool() -> SqlitePool {
+    pool_at(i64::MAX).await
+}
+
+/// A fresh in-memory `kidase.db`, migrated up to `version`. One connection
+/// that never closes, since each in-memory connection is its own database.
+pub async fn pool_at(version: i64) -> SqlitePool {
+    // Not sqlx's `sqlite::memory:`, whose memdb VFS would keep the files
+    // `VACUUM INTO` writes in memory too
+    let options = SqliteConnectOptions::new()
+        .filename(":memory:")
+        .foreign_keys(true);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect_with(options)
+        .await
+        .expect("open in-memory database");
+    migrate(&pool, 1..=version, MigrationKind::Up).await;
+    pool
+}
+
+/// Run the migrations of `kind` in `versions`, ups oldest first and downs
+/// newest first, each in its own transaction as the plugin runs them.
+pub async fn migrate(pool: &SqlitePool, versions: RangeInclusive<i64>, kind: MigrationKind) {
+    let down = matches!(kind, MigrationKind::Down);
+    let mut selected: Vec<Migration> = migrations::migrations()
+        .into_iter()
+        .filter(|m| versions.contains(&m.version) && matches!(m.kind, MigrationKind::Down) == down)
+        .collect();
+    if down {
+        selected.reverse();
+    }
+    for migration in selected {
+        let mut tx = pool.begin().await.expect("begin migration");
+        sqlx::raw_sql(migration.sql)
+            .execute(&mut *tx)
+            .await
+            .unwrap_or_else(|e| {
+                panic!(
+                    "migration {} ({}): {e}",
+                    migration.version, migration.description
+                )
+            });
+        tx.commit().await.expect("commit migration");
+    }
+}
+
+/// Run `sql` on `pool`, panicking on failure.
+pub async fn exec(pool: &SqlitePool, sql: &str) {
+    sqlx::raw_sql(sql)
+        .execute(pool)
+        .await
+        .unwrap_or_else(|e| panic!("{sql}: {e}"));
+}
+
+/// A template named after `id`, with an empty definition.
+pub async fn template(pool: &SqlitePool, id: &str) {
+    sqlx::query(
+        "INSERT INTO templates (id, name, definition_json, created_at)
+         VALUES (?, ?, '{}', '2026-01-01T00:00:00.000Z')",
+    )
+    .bind(id)
+    .bind(id)
+    .execute(pool)
+    .await
+    .expect("insert template");
+}
+
+/// A presentation named after `id` on template `t1`, which is made if missing.
+pub async fn presentation(pool: &SqlitePool, id: &str) {
+    exec(
+        pool,
+        "INSERT OR IGNORE INTO templates (id, name, definition_json, created_at)
+         VALUES ('t1', 't1', '{}', '2026-01-01T00:00:00.000Z')",
+    )
+    .await;
+    sqlx::query(
+        "INSERT INTO presentations (id, name, type, template_id, language_map, created_at)
+         VALUES (?, ?, 'kidase', 't1', '{\"Lang1\":\"Geez\"}', '2026-01-01T00:00:00.000Z')",
+    )
+    .bind(id)
+    .bind(id)
+    .execute(pool)
+    .await
+    .expect("insert presentation");
+}
+
+/// A slide of `presentation_id` at `slide_order`, with no blocks.
+pub async fn slide(pool: &SqlitePool, id: &str, presentation_id: &str, slide_order: i64) {
+    sqlx::query(
+        "INSERT INTO slides (id, presentation_id, slide_order, blocks_json)
+         VALUES (?, ?, ?, '[]')",
+    )
+    .bind(id)
+    .bind(presentation_id)
+    .bind(slide_order)
+    .execute(pool)
+    .await
+    .expect("insert slide");
+}
+
+/// Ids of `presentation_id`'s slides and their order, in order.
+pub async fn slide_orders(pool: &SqlitePool, presentation_id: &str) -> Vec<(String, i64)> {
+    sqlx::query_as(
+        "SELECT id, slide_order FROM slides WHERE presentation_id = ? ORDER BY slide_order",
+    )
+    .bind(presentation_id)
+    .fetch_all(pool)
+    .await
+    .expect("read slides")
+}
+
+/// A path in a fresh, empty temporary directory, named `name`.
+pub fn temp_path(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("kidase-test-{}", uuid::Uuid::now_v7()));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    dir.join(name)
+}
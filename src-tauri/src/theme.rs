@@ -0,0 +1,345 @@
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::db::{self, Template};
+use crate::template;
+
+/// WCAG AA minimum contrast for normal-size text.
+pub const WCAG_AA_CONTRAST: f64 = 4.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb { r: 0, g: 0, b: 0 };
+    pub const WHITE: Rgb = Rgb {
+        r: 255,
+        g: 255,
+        b: 255,
+    };
+
+    /// Parse `#rgb` or `#rrggbb`.
+    pub fn parse(hex: &str) -> Option<Rgb> {
+        let hex = hex.trim().strip_prefix('#')?;
+        // Byte offsets below would split a multi-byte character
+        if !hex.is_ascii() {
+            return None;
+        }
+        let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+        match hex.len() {
+            3 => {
+                let expand = |i: usize| channel(&hex[i..i + 1].repeat(2));
+                Some(Rgb {
+                    r: expand(0)?,
+                    g: expand(1)?,
+                    b: expand(2)?,
+                })
+            }
+            6 => Some(Rgb {
+                r: channel(&hex[0..2])?,
+                g: channel(&hex[2..4])?,
+                b: channel(&hex[4..6])?,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn to_hex(self) -> String {
+        format!("#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+
+    /// Relative luminance as defined by WCAG 2.x.
+    pub fn luminance(self) -> f64 {
+        let linear = |c: u8| {
+            let c = f64::from(c) / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    fn to_hsl(self) -> (f64, f64, f64) {
+        let (r, g, b) = (
+            f64::from(self.r) / 255.0,
+            f64::from(self.g) / 255.0,
+            f64::from(self.b) / 255.0,
+        );
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+        if max == min {
+            return (0.0, 0.0, l);
+        }
+        let d = max - min;
+        let s = if l > 0.5 {
+            d / (2.0 - max - min)
+        } else {
+            d / (max + min)
+        };
+        let h = if max == r {
+            (g - b) / d + if g < b { 6.0 } else { 0.0 }
+        } else if max == g {
+            (b - r) / d + 2.0
+        } else {
+            (r - g) / d + 4.0
+        };
+        (h / 6.0, s, l)
+    }
+
+    fn from_hsl(h: f64, s: f64, l: f64) -> Rgb {
+        let to_u8 = |v: f64| (v * 255.0).round().clamp(0.0, 255.0) as u8;
+        if s == 0.0 {
+            return Rgb {
+                r: to_u8(l),
+                g: to_u8(l),
+                b: to_u8(l),
+            };
+        }
+        let q = if l < 0.5 {
+            l * (1.0 + s)
+        } else {
+            l + s - l * s
+        };
+        let p = 2.0 * l - q;
+        let hue = |mut t: f64| {
+            if t < 0.0 {
+                t += 1.0;
+            }
+            if t > 1.0 {
+                t -= 1.0;
+            }
+            if t < 1.0 / 6.0 {
+                p + (q - p) * 6.0 * t
+            } else if t < 0.5 {
+                q
+            } else if t < 2.0 / 3.0 {
+                p + (q - p) * (2.0 / 3.0 - t) * 6.0
+            } else {
+                p
+            }
+        };
+        Rgb {
+            r: to_u8(hue(h + 1.0 / 3.0)),
+            g: to_u8(hue(h)),
+            b: to_u8(hue(h - 1.0 / 3.0)),
+        }
+    }
+
+    fn with_lightness(self, lightness: f64) -> Rgb {
+        let (h, s, _) = self.to_hsl();
+        Rgb::from_hsl(h, s, lightness.clamp(0.0, 1.0))
+    }
+}
+
+pub fn contrast_ratio(a: Rgb, b: Rgb) -> f64 {
+    let (la, lb) = (a.luminance(), b.luminance());
+    let (hi, lo) = if la > lb { (la, lb) } else { (lb, la) };
+    (hi + 0.05) / (lo + 0.05)
+}
+
+/// Smallest hue-preserving lightness shift that makes `text` readable on `background`.
+pub fn ensure_contrast(text: Rgb, background: Rgb) -> Rgb {
+    if contrast_ratio(text, background) >= WCAG_AA_CONTRAST {
+        return text;
+    }
+    let (_, _, lightness) = text.to_hsl();
+    let toward_light = background.luminance() < 0.5;
+    let mut l = lightness;
+    while (toward_light && l < 1.0) || (!toward_light && l > 0.0) {
+        l += if toward_light { 0.02 } else { -0.02 };
+        let candidate = text.with_lightness(l);
+        if contrast_ratio(candidate, background) >= WCAG_AA_CONTRAST {
+            return candidate;
+        }
+    }
+    if toward_light {
+        Rgb::WHITE
+    } else {
+        Rgb::BLACK
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Variant {
+    Dark,
+    Light,
+}
+
+impl Variant {
+    fn label(self) -> &'static str {
+        match self {
+            Variant::Dark => "Dark",
+            Variant::Light => "Light",
+        }
+    }
+
+    /// Keep backgrounds already on the right side, otherwise mirror their lightness.
+    fn background(self, current: Rgb) -> Rgb {
+        let (_, _, l) = current.to_hsl();
+        match self {
+            Variant::Dark if current.luminance() <= 0.05 => current,
+            Variant::Dark => current.with_lightness((1.0 - l).min(0.12)),
+            Variant::Light if current.luminance() >= 0.85 => current,
+            Variant::Light => current.with_lightness((1.0 - l).max(0.94)),
+        }
+    }
+}
+
+/// Derive dark and light copies of a template with AA-compliant text colors.
+/// Each copy must pass the same checks as a template saved in the editor.
+#[tauri::command]
+pub async fn generate_theme_variants(
+    db: State<'_, DbInstances>,
+    template_id: String,
+) -> Result<Vec<Template>, String> {
+    let pool = db::writable_pool(&db).await?;
+    generate(&pool, &template_id).await
+}
+
+async fn generate(pool: &SqlitePool, template_id: &str) -> Result<Vec<Template>, String> {
+    let source = db::fetch_template(pool, template_id).await?;
+    let definition: Value = serde_json::from_str(&source.definition_json)
+        .map_err(|e| format!("Invalid template definition: {e}"))?;
+
+    let mut variants = Vec::with_capacity(2);
+    for variant in [Variant::Dark, Variant::Light] {
+        let derived = derive_variant(&definition, variant)?.to_string();
+        let max_lang_count = u32::try_from(source.max_lang_count).unwrap_or(0);
+        template::ensure_valid(&derived, max_lang_count)
+            .map_err(|e| format!("{} variant: {e}", variant.label()))?;
+        variants.push(Template {
+            id: db::new_id(),
+            name: format!("{} ({})", source.name, variant.label()),
+            max_lang_count: source.max_lang_count,
+            definition_json: derived,
+            created_at: db::now(),
+        });
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for template in &variants {
+        sqlx::query(
+            "INSERT INTO templates (id, name, max_lang_count, definition_json, created_at)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&template.id)
+        .bind(&template.name)
+        .bind(template.max_lang_count)
+        .bind(&template.definition_json)
+        .bind(&template.created_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(variants)
+}
+
+fn derive_variant(definition: &Value, variant: Variant) -> Result<Value, String> {
+    let mut derived = definition.clone();
+    let current_bg = definition
+        .pointer("/background/color")
+        .and_then(Value::as_str)
+        .and_then(Rgb::parse)
+        .ok_or("Template background color must be a hex color")?;
+    let background = variant.background(current_bg);
+    derived["background"]["color"] = Value::String(background.to_hex());
+
+    let mut text_colors: Vec<&mut Value> = Vec::new();
+    let Value::Object(root) = &mut derived else {
+        return Err("Template definition must be an object".to_string());
+    };
+    for (key, section) in root.iter_mut() {
+        match key.as_str() {
+            "title" => text_colors.extend(section.get_mut("color")),
+            "languages" => {
+                if let Value::Array(languages) = section {
+                    text_colors.extend(languages.iter_mut().filter_map(|l| l.get_mut("color")));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for color in text_colors {
+        let text = color
+            .as_str()
+            .and_then(Rgb::parse)
+            .ok_or_else(|| format!("Unsupported text color: {color}"))?;
+        let adjusted = ensure_contrast(text, background);
+        if contrast_ratio(adjusted, background) < WCAG_AA_CONTRAST {
+            return Err(format!(
+                "Could not reach AA contrast for {} on {}",
+                text.to_hex(),
+                background.to_hex()
+            ));
+        }
+        *color = Value::String(adjusted.to_hex());
+    }
+
+    Ok(derived)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_support;
+
+    fn definition() -> Value {
+        json!({
+            "layout": { "columns": 1, "rows": 1, "gap": 0 },
+            "title": { "show": true, "fontSize": 48, "color": "#333333", "alignment": "center" },
+            "languages": [{
+                "slot": "Lang1", "fontSize": 40, "fontFamily": "Abyssinica SIL",
+                "color": "#444444", "alignment": "center", "lineHeight": 1.4
+            }],
+            "background": { "color": "#FFFFFF" },
+            "margins": { "top": 0, "right": 0, "bottom": 0, "left": 0 },
+            "safeArea": { "horizontal": 0, "vertical": 0 }
+        })
+    }
+
+    #[test]
+    fn a_color_with_a_multibyte_character_is_refused() {
+        assert_eq!(Rgb::parse("#fff"), Some(Rgb::WHITE));
+        assert_eq!(Rgb::parse("#ሀ"), None);
+        assert_eq!(Rgb::parse("#12ሀ4"), None);
+    }
+
+    #[tokio::test]
+    async fn variants_are_saved_only_when_valid() {
+        let pool = test_support::pool().await;
+        test_support::template(&pool, "plain").await;
+        let set_definition = |definition: Value| {
+            format!(
+                "UPDATE templates SET definition_json = '{definition}', max_lang_count = 1 WHERE id = 'plain'"
+            )
+        };
+        test_support::exec(&pool, &set_definition(definition())).await;
+        let variants = generate(&pool, "plain").await.unwrap();
+        assert_eq!(variants.len(), 2);
+
+        let mut invalid = definition();
+        invalid["layout"]["columns"] = json!(9);
+        test_support::exec(&pool, &set_definition(invalid)).await;
+        let error = generate(&pool, "plain").await.unwrap_err();
+        assert!(error.contains("layout.columns"), "{error}");
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM templates")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+    }
+}
@@ -0,0 +1,249 @@
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::db::{self, LANG_SLOTS};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariableValue {
+    /// 1-based, so indexes 1..4 line up with `value_lang1..4`.
+    pub language_index: u32,
+    /// `None` is distinct from an empty string: the value was never set.
+    pub value: Option<String>,
+}
+
+/// Set one language's value of a variable; any index from 1 up is allowed.
+///
+/// Indexes 1..4 are mirrored into `value_lang1..4` so readers of the old
+/// columns stay current. Those columns can't hold NULL, so a `None` shows
+/// there as an empty string while `variable_values` keeps the NULL.
+#[tauri::command]
+pub async fn set_variable_value(
+    db: State<'_, DbInstances>,
+    variable_id: String,
+    language_index: u32,
+    value: Option<String>,
+) -> Result<(), String> {
+    if language_index == 0 {
+        return Err("Language index starts at 1".to_string());
+    }
+
+    let pool = db::writable_pool(&db).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let exists: Option<String> = sqlx::query_scalar("SELECT id FROM variables WHERE id = ?")
+        .bind(&variable_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err(format!("Variable not found: {variable_id}"));
+    }
+
+    // The sync trigger on `variables` writes '' for a NULL, so mirror first
+    // and let the upsert below have the last word.
+    let legacy_column = match language_index {
+        1 => Some("value_lang1"),
+        2 => Some("value_lang2"),
+        3 => Some("value_lang3"),
+        4 => Some("value_lang4"),
+        _ => None,
+    };
+    if let Some(column) = legacy_column {
+        sqlx::query(&format!("UPDATE variables SET {column} = ? WHERE id = ?"))
+            .bind(value.as_deref().unwrap_or_default())
+            .bind(&variable_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    sqlx::query(
+        "INSERT INTO variable_values (variable_id, language_index, value) VALUES (?, ?, ?)
+         ON CONFLICT(variable_id, language_index) DO UPDATE SET value = excluded.value",
+    )
+    .bind(&variable_id)
+    .bind(language_index)
+    .bind(&value)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// Replace every language value of a variable, and optionally its name, in
+/// one transaction so no language is left stale by a partial write.
+///
+/// `values` run Lang1 onwards and are written to `variable_values` at every
+/// index, those past Lang4 included; stored languages past its end are
+/// cleared. More values than the presentation's template has language slots is
+/// an error.
+#[tauri::command]
+pub async fn set_variable_all_languages(
+    db: State<'_, DbInstances>,
+    variable_id: String,
+    values: Vec<String>,
+    name: Option<String>,
+) -> Result<(), String> {
+    let pool = db::writable_pool(&db).await?;
+    set_all(&pool, &variable_id, values, name).await
+}
+
+async fn set_all(
+    pool: &SqlitePool,
+    variable_id: &str,
+    values: Vec<String>,
+    name: Option<String>,
+) -> Result<(), String> {
+    let name = name.map(|name| name.trim().to_string());
+    if name.as_deref() == Some("") {
+        return Err("Variable name cannot be empty".to_string());
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let max_lang_count: Option<i64> = sqlx::query_scalar(
+        "SELECT t.max_lang_count FROM variables v
+         JOIN presentations p ON p.id = v.presentation_id
+         JOIN templates t ON t.id = p.template_id
+         WHERE v.id = ?",
+    )
+    .bind(variable_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let Some(max_lang_count) = max_lang_count else {
+        return Err(format!("Variable not found: {variable_id}"));
+    };
+    let max_lang_count = max_lang_count.max(0) as usize;
+    if values.len() > max_lang_count {
+        return Err(format!(
+            "Got {} values but the template has {max_lang_count} language slots",
+            values.len()
+        ));
+    }
+
+    let mut values = values;
+    values.resize(values.len().max(LANG_SLOTS.len()), String::new());
+    sqlx::query(
+        "UPDATE variables SET name = COALESCE(?, name),
+            value_lang1 = ?, value_lang2 = ?, value_lang3 = ?, value_lang4 = ?
+         WHERE id = ?",
+    )
+    .bind(&name)
+    .bind(&values[0])
+    .bind(&values[1])
+    .bind(&values[2])
+    .bind(&values[3])
+    .bind(variable_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // The sync trigger has mirrored 1..4; the rest only live here
+    for (index, value) in values.iter().enumerate().skip(LANG_SLOTS.len()) {
+        sqlx::query(
+            "INSERT INTO variable_values (variable_id, language_index, value) VALUES (?, ?, ?)
+             ON CONFLICT(variable_id, language_index) DO UPDATE SET value = excluded.value",
+        )
+        .bind(variable_id)
+        .bind(index as i64 + 1)
+        .bind(value)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    sqlx::query(
+        "UPDATE variable_values SET value = '' WHERE variable_id = ? AND language_index > ?",
+    )
+    .bind(variable_id)
+    .bind(values.len() as i64)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// Every stored language value of a variable, by `language_index`.
+#[tauri::command]
+pub async fn get_variable_values(
+    db: State<'_, DbInstances>,
+    variable_id: String,
+) -> Result<Vec<VariableValue>, String> {
+    let pool = db::pool(&db).await?;
+    let rows: Vec<(u32, Option<String>)> = sqlx::query_as(
+        "SELECT language_index, value FROM variable_values
+         WHERE variable_id = ? ORDER BY language_index",
+    )
+    .bind(&variable_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .map(|(language_index, value)| VariableValue {
+            language_index,
+            value,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    async fn stored(pool: &SqlitePool) -> Vec<(i64, Option<String>)> {
+        sqlx::query_as(
+            "SELECT language_index, value FROM variable_values
+             WHERE variable_id = 'v1' ORDER BY language_index",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn every_language_is_written_and_the_rest_cleared() {
+        let pool = test_support::pool().await;
+        test_support::presentation(&pool, "p1").await;
+        test_support::exec(
+            &pool,
+            "UPDATE templates SET max_lang_count = 6 WHERE id = 't1';
+             INSERT INTO variables (id, presentation_id, name, value) VALUES ('v1', 'p1', 'SAINT', '');
+             INSERT INTO variable_values (variable_id, language_index, value) VALUES ('v1', 6, 'old')",
+        )
+        .await;
+
+        let values = ["a", "b", "c", "d", "e"].map(String::from).to_vec();
+        set_all(&pool, "v1", values, None).await.unwrap();
+        let expected: Vec<(i64, Option<String>)> = ["a", "b", "c", "d", "e", ""]
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| (i as i64 + 1, Some(v.to_string())))
+            .collect();
+        assert_eq!(stored(&pool).await, expected);
+
+        set_all(
+            &pool,
+            "v1",
+            vec!["x".to_string()],
+            Some(" SAINTS ".to_string()),
+        )
+        .await
+        .unwrap();
+        let values: Vec<String> = stored(&pool)
+            .await
+            .into_iter()
+            .filter_map(|(_, value)| value)
+            .collect();
+        assert_eq!(values, ["x", "", "", "", "", ""]);
+
+        let too_many = vec![String::new(); 7];
+        assert!(set_all(&pool, "v1", too_many, None).await.is_err());
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashSet;
+
+use chrono::Datelike;
+use serde::Deserialize;
+use tauri::State;
+use tauri_plugin_sql::DbInstances;
+
+use crate::calendar::EthiopianDate;
+use crate::db::{self, Verse};
+use crate::markup::escape;
+
+/// `app_settings` key naming the segment the verse of the day is drawn from.
+const VERSE_OF_DAY_SEGMENT_KEY: &str = "verseOfDaySegmentId";
+
+/// Optional columns of a verses CSV, in `verses` column order.
+const TEXT_COLUMNS: [&str; 8] = [
+    "title_lang1",
+    "title_lang2",
+    "title_lang3",
+    "title_lang4",
+    "text_lang1",
+    "text_lang2",
+    "text_lang3",
+    "text_lang4",
+];
+
+/// Insert the verses in a CSV into `segment_id`, all or nothing.
+///
+/// The header row names the columns: `verse_order` is required, the
+/// `title_langN`/`text_langN` columns are optional and empty or missing cells
+/// are stored as NULL. Orders must be unique within the file and must not
+/// already exist in the segment. Returns the number of verses inserted.
+#[tauri::command]
+pub async fn import_verses_csv(
+    db: State<'_, DbInstances>,
+    segment_id: String,
+    csv_text: String,
+) -> Result<u32, String> {
+    let segment_id = segment_id.trim().to_string();
+    if segment_id.is_empty() {
+        return Err("Segment id is required".to_string());
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(csv_text.as_bytes());
+    let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(name))
+    };
+    let order_column = column("verse_order").ok_or("Missing verse_order column")?;
+    let text_columns: Vec<Option<usize>> = TEXT_COLUMNS.iter().map(|name| column(name)).collect();
+
+    let mut verses: Vec<(i64, Vec<Option<String>>)> = Vec::new();
+    let mut orders = HashSet::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| e.to_string())?;
+        let line = record.position().map_or(0, |p| p.line());
+        let order: i64 = record
+            .get(order_column)
+            .unwrap_or_default()
+            .parse()
+            .map_err(|_| format!("Line {line}: verse_order must be a whole number"))?;
+        if !orders.insert(order) {
+            return Err(format!(
+                "Line {line}: verse_order {order} appears more than once"
+            ));
+        }
+        let texts = text_columns
+            .iter()
+            .map(|index| {
+                index
+                    .and_then(|i| record.get(i))
+                    .filter(|text| !text.is_empty())
+                    .map(str::to_string)
+            })
+            .collect();
+        verses.push((order, texts));
+    }
+
+    let pool = db::writable_pool(&db).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let existing: Vec<i64> =
+        sqlx::query_scalar("SELECT verse_order FROM verses WHERE segment_id = ?")
+            .bind(&segment_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    if let Some(order) = existing.iter().find(|order| orders.contains(order)) {
+        return Err(format!(
+            "Segment {segment_id} already has a verse with verse_order {order}"
+        ));
+    }
+
+    let created_at = db::now();
+    for (order, texts) in &verses {
+        let mut query = sqlx::query(
+            "INSERT INTO verses
+             (id, segment_id, verse_order, title_lang1, title_lang2, title_lang3, title_lang4,
+              text_lang1, text_lang2, text_lang3, text_lang4, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(db::new_id())
+        .bind(&segment_id)
+        .bind(order);
+        for text in texts {
+            query = query.bind(text);
+        }
+        query
+            .bind(&created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(verses.len() as u32)
+}
+
+/// The verse of the day for `ethiopian_date`, from the segment named by the
+/// `verseOfDaySegmentId` setting.
+///
+/// Verses rotate one per day in `verse_order`, so a date always gets the same
+/// verse while the segment is unchanged. `None` when no segment is configured
+/// or it has no verses.
+#[tauri::command]
+pub async fn get_verse_of_day(
+    db: State<'_, DbInstances>,
+    ethiopian_date: EthiopianDate,
+) -> Result<Option<Verse>, String> {
+    let date = EthiopianDate::new(
+        ethiopian_date.year,
+        ethiopian_date.month,
+        ethiopian_date.day,
+    )?;
+    let day = date.to_gregorian()?.num_days_from_ce();
+
+    let pool = db::pool(&db).await?;
+    let segment_id: Option<String> =
+        sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+            .bind(VERSE_OF_DAY_SEGMENT_KEY)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let Some(segment_id) = segment_id.filter(|id| !id.trim().is_empty()) else {
+        return Ok(None);
+    };
+
+    let mut verses = db::fetch_segment_verses(&pool, segment_id.trim()).await?;
+    if verses.is_empty() {
+        return Ok(None);
+    }
+    let index = day.rem_euclid(verses.len() as i32) as usize;
+    Ok(Some(verses.swap_remove(index)))
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VerseExportFormat {
+    /// Same columns as [`import_verses_csv`] reads, so a translated file imports back.
+    Csv,
+    Json,
+    /// XLIFF 1.2, one `<file>` per target language.
+    Xliff,
+}
+
+/// A segment's verses in `verse_order`, with all four languages, for sending
+/// to a translator.
+///
+/// For XLIFF, `source_language` (1–4, default 1) is the `<source>` of every
+/// unit and each other language gets a `<file>` of its own. Untranslated text
+/// comes out as an empty `<target/>` rather than being left out. Languages
+/// have no codes in the database, so they're named `x-lang1` to `x-lang4`.
+#[tauri::command]
+pub async fn export_segment_verses(
+    db: State<'_, DbInstances>,
+    segment_id: String,
+    format: VerseExportFormat,
+    source_language: Option<u8>,
+) -> Result<String, String> {
+    let source_language = source_language.unwrap_or(1);
+    if !(1..=4).contains(&source_language) {
+        return Err(format!("Invalid language index: {source_language}"));
+    }
+
+    let pool = db::pool(&db).await?;
+    let verses = db::fetch_segment_verses(&pool, &segment_id).await?;
+    if verses.is_empty() {
+        return Err(format!("Segment {segment_id} has no verses"));
+    }
+
+    match format {
+        VerseExportFormat::Csv => to_csv(&verses),
+        VerseExportFormat::Json => serde_json::to_string_pretty(&verses).map_err(|e| e.to_string()),
+        VerseExportFormat::Xliff => {
+            Ok(to_xliff(&segment_id, &verses, source_language as usize - 1))
+        }
+    }
+}
+
+/// Title and text of `verse` by language, Lang1 first.
+fn verse_texts(verse: &Verse) -> [[Option<&str>; 4]; 2] {
+    [
+        [
+            verse.title_lang1.as_deref(),
+            verse.title_lang2.as_deref(),
+            verse.title_lang3.as_deref(),
+            verse.title_lang4.as_deref(),
+        ],
+        [
+            verse.text_lang1.as_deref(),
+            verse.text_lang2.as_deref(),
+            verse.text_lang3.as_deref(),
+            verse.text_lang4.as_deref(),
+        ],
+    ]
+}
+
+fn to_csv(verses: &[Verse]) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(std::iter::once("verse_order").chain(TEXT_COLUMNS))
+        .map_err(|e| e.to_string())?;
+    for verse in verses {
+        let order = verse.verse_order.to_string();
+        let texts = verse_texts(verse).into_iter().flatten();
+        writer
+            .write_record(
+                std::iter::once(order.as_str()).chain(texts.map(Option::unwrap_or_default)),
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+fn to_xliff(segment_id: &str, verses: &[Verse], source: usize) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <xliff version=\"1.2\" xmlns=\"urn:oasis:names:tc:xliff:document:1.2\">\n",
+    );
+    for target in (0..4).filter(|target| *target != source) {
+        xml.push_str(&format!(
+            "  <file original=\"{}\" source-language=\"x-lang{}\" target-language=\"x-lang{}\" datatype=\"plaintext\">\n    <body>\n",
+            escape(segment_id),
+            source + 1,
+            target + 1
+        ));
+        for verse in verses {
+            for (part, texts) in ["title", "text"].into_iter().zip(verse_texts(verse)) {
+                // Most verses have no title in any language
+                if texts.iter().all(|text| text.unwrap_or_default().is_empty()) {
+                    continue;
+                }
+                let source_text = texts[source].unwrap_or_default();
+                let target_text = texts[target].unwrap_or_default();
+                xml.push_str(&format!(
+                    "      <trans-unit id=\"{}.{part}\">\n        <source>{}</source>\n",
+                    verse.verse_order,
+                    escape(source_text)
+                ));
+                if target_text.is_empty() {
+                    xml.push_str("        <target/>\n");
+                } else {
+                    xml.push_str(&format!(
+                        "        <target>{}</target>\n",
+                        escape(target_text)
+                    ));
+                }
+                xml.push_str("      </trans-unit>\n");
+            }
+        }
+        xml.push_str("    </body>\n  </file>\n");
+    }
+    xml.push_str("</xliff>\n");
+    xml
+}
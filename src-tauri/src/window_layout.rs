@@ -0,0 +1,192 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, State, WebviewWindow};
+use tauri_plugin_sql::DbInstances;
+
+use crate::db;
+
+/// `app_settings` key holding every saved layout as a JSON object by window
+/// label.
+const LAYOUTS_KEY: &str = "windowLayouts";
+
+/// Where a window sat, in physical pixels.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowLayout {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub fullscreen: bool,
+    /// The monitor it was on; `None` when the platform couldn't say.
+    pub monitor: Option<MonitorId>,
+}
+
+/// A monitor as identified across runs: by name where the platform gives
+/// one, and by its place in the desktop.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorId {
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<&Monitor> for MonitorId {
+    fn from(monitor: &Monitor) -> Self {
+        Self {
+            name: monitor.name().cloned(),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        }
+    }
+}
+
+/// Remember where the window `label` is now, replacing what was saved for it.
+#[tauri::command]
+pub async fn save_window_layout(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    label: String,
+) -> Result<WindowLayout, String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window not found: {label}"))?;
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let layout = WindowLayout {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        fullscreen: window.is_fullscreen().map_err(|e| e.to_string())?,
+        monitor: window
+            .current_monitor()
+            .map_err(|e| e.to_string())?
+            .as_ref()
+            .map(MonitorId::from),
+    };
+
+    let pool = db::writable_pool(&db).await?;
+    let mut layouts = load_layouts(&pool).await?;
+    layouts.insert(label, layout.clone());
+    let json = serde_json::to_string(&layouts).map_err(|e| e.to_string())?;
+    sqlx::query(
+        "INSERT INTO app_settings (key, value) VALUES (?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+    )
+    .bind(LAYOUTS_KEY)
+    .bind(json)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(layout)
+}
+
+/// Put open windows back where they were saved, returning the labels moved.
+/// `label` limits this to one window; otherwise every open window with a
+/// saved layout is restored.
+///
+/// A window whose monitor is no longer connected goes to the same spot on the
+/// primary monitor, and is kept inside whichever monitor it lands on, so it
+/// never ends up off-screen.
+#[tauri::command]
+pub async fn restore_window_layout(
+    app: AppHandle,
+    db: State<'_, DbInstances>,
+    label: Option<String>,
+) -> Result<Vec<String>, String> {
+    let pool = db::pool(&db).await?;
+    let layouts = load_layouts(&pool).await?;
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+    let primary = app.primary_monitor().map_err(|e| e.to_string())?;
+
+    let mut restored = Vec::new();
+    for (window_label, layout) in layouts {
+        if label.as_ref().is_some_and(|label| *label != window_label) {
+            continue;
+        }
+        let Some(window) = app.get_webview_window(&window_label) else {
+            continue;
+        };
+        let target = layout
+            .monitor
+            .as_ref()
+            .and_then(|saved| find_monitor(&monitors, saved))
+            .or(primary.as_ref())
+            .or(monitors.first());
+        let Some(target) = target else {
+            continue;
+        };
+        apply(&window, &layout, target)?;
+        restored.push(window_label);
+    }
+    Ok(restored)
+}
+
+/// The connected monitor matching `saved`: by name and place, then by name
+/// alone (it was moved in the desktop arrangement), then by place alone (its
+/// name isn't stable on this platform).
+pub fn find_monitor<'a>(monitors: &'a [Monitor], saved: &MonitorId) -> Option<&'a Monitor> {
+    let ids: Vec<MonitorId> = monitors.iter().map(MonitorId::from).collect();
+    let same_geometry = |id: &MonitorId| {
+        id.x == saved.x && id.y == saved.y && id.width == saved.width && id.height == saved.height
+    };
+    let same_name = |id: &MonitorId| saved.name.is_some() && id.name == saved.name;
+
+    ids.iter()
+        .position(|id| id == saved)
+        .or_else(|| ids.iter().position(same_name))
+        .or_else(|| ids.iter().position(same_geometry))
+        .map(|index| &monitors[index])
+}
+
+fn apply(window: &WebviewWindow, layout: &WindowLayout, target: &Monitor) -> Result<(), String> {
+    // Offset within the monitor it was saved on, so the window keeps its spot
+    // if that monitor has moved or been replaced
+    let (origin_x, origin_y) = layout
+        .monitor
+        .as_ref()
+        .map_or((layout.x, layout.y), |monitor| (monitor.x, monitor.y));
+    let bounds = target.size();
+    let width = layout.width.min(bounds.width);
+    let height = layout.height.min(bounds.height);
+    let max_x = i32::try_from(bounds.width - width).unwrap_or(i32::MAX);
+    let max_y = i32::try_from(bounds.height - height).unwrap_or(i32::MAX);
+    let x = target.position().x + (layout.x - origin_x).clamp(0, max_x);
+    let y = target.position().y + (layout.y - origin_y).clamp(0, max_y);
+
+    // A fullscreen window ignores moves, so leave fullscreen to reposition
+    if window.is_fullscreen().map_err(|e| e.to_string())? {
+        window.set_fullscreen(false).map_err(|e| e.to_string())?;
+    }
+    window
+        .set_size(PhysicalSize::new(width, height))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_position(PhysicalPosition::new(x, y))
+        .map_err(|e| e.to_string())?;
+    if layout.fullscreen {
+        window.set_fullscreen(true).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+async fn load_layouts(pool: &SqlitePool) -> Result<BTreeMap<String, WindowLayout>, String> {
+    let json: Option<String> = sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+        .bind(LAYOUTS_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    match json {
+        // Unreadable layouts are just forgotten; the next save replaces them
+        Some(json) => Ok(serde_json::from_str(&json).unwrap_or_default()),
+        None => Ok(BTreeMap::new()),
+    }
+}
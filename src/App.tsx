@@ -15,10 +15,13 @@ import { SettingsPage } from './components/settings/SettingsPage';
 import { PresentationView } from './components/presentation/PresentationView';
 import { PresenterView } from './components/presentation/PresenterView';
 import { AudienceView } from './components/presentation/AudienceView';
+import { StageView } from './components/presentation/StageView';
 import { appBootstrapService } from './services/AppBootstrapService';
 import { ToastContainer } from './components/common/Toast';
 import { UpdateBanner } from './components/common/UpdateBanner';
 import { useUpdater } from './hooks/useUpdater';
+import { useLiveSession } from './hooks/useLiveSession';
+import { useOutputDisplay } from './hooks/useOutputDisplay';
 import './styles/global.css';
 import './styles/app.css';
 
@@ -70,7 +73,9 @@ class ErrorBoundary extends React.Component<
 }
 
 // Check if this window is the audience view
-const isAudienceWindow = new URLSearchParams(window.location.search).get('view') === 'audience';
+const outputView = new URLSearchParams(window.location.search).get('view');
+const isAudienceWindow = outputView === 'audience';
+const isStageWindow = outputView === 'stage';
 
 function App() {
   const { t } = useTranslation();
@@ -81,6 +86,8 @@ function App() {
   const [isLoading, setIsLoading] = useState(true);
   const [bootstrapError, setBootstrapError] = useState<string | null>(null);
   const { updateAvailable, updateVersion, installing, installUpdate, dismissUpdate } = useUpdater();
+  useLiveSession();
+  useOutputDisplay();
 
   useEffect(() => {
     const loadData = async () => {
@@ -139,6 +146,11 @@ function App() {
     return <AudienceView />;
   }
 
+  // Stage window renders only the chanters' confidence monitor
+  if (isStageWindow) {
+    return <StageView />;
+  }
+
   return (
     <div className="app-layout">
       {updateAvailable && (
@@ -1,10 +1,13 @@
 import { useState, useEffect } from 'react';
 import { listen } from '@tauri-apps/api/event';
+import { invoke } from '@tauri-apps/api/core';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 import { usePresentationDataStore } from '../../store/presentationDataStore';
 import { usePresentationModeStore } from '../../store/presentationModeStore';
 import { useRuleStore } from '../../store/ruleStore';
 import { SlideRenderer } from './SlideRenderer';
+import { useScreenBlank } from '../../hooks/useLiveSession';
+import type { LiveSlide } from '../../services/AudienceWindowService';
 import '../../styles/presentation.css';
 
 export const AudienceView: React.FC = () => {
@@ -21,6 +24,7 @@ export const AudienceView: React.FC = () => {
 
   const { getMergedEnabledSlides } = usePresentationModeStore();
   const ruleContextMeta = useRuleStore(s => s.ruleContextMeta);
+  const isBlank = useScreenBlank();
 
   const [scale, setScale] = useState(() =>
     Math.min(window.innerWidth / 1920, window.innerHeight / 1080)
@@ -34,10 +38,15 @@ export const AudienceView: React.FC = () => {
     return () => window.removeEventListener('resize', updateScale);
   }, []);
 
-  // Listen for slide-change events from the presenter window
+  // Follow the live slide set by the presenter window, starting from the last
+  // known position so a reloaded audience window doesn't jump back to the start
   useEffect(() => {
-    const unlistenSlide = listen<{ index: number }>('slide-change', (event) => {
-      setSlideIndex(event.payload.index);
+    invoke<LiveSlide>('get_live_slide')
+      .then(live => setSlideIndex(live.slideIndex))
+      .catch(() => {});
+
+    const unlistenSlide = listen<LiveSlide>('live-slide-changed', (event) => {
+      setSlideIndex(event.payload.slideIndex);
     });
 
     const unlistenStop = listen('presentation-stop', () => {
@@ -53,7 +62,7 @@ export const AudienceView: React.FC = () => {
   const enabledSlides = getMergedEnabledSlides();
   const currentSlide = enabledSlides[slideIndex];
 
-  if (!currentSlide || !currentTemplate || !currentPresentation) {
+  if (isBlank || !currentSlide || !currentTemplate || !currentPresentation) {
     return <div className="presentation-view" />;
   }
 
@@ -2,6 +2,7 @@ import { useState, useEffect, useCallback } from 'react';
 import { usePresentationModeStore } from '../../store/presentationModeStore';
 import { usePresentationDataStore } from '../../store/presentationDataStore';
 import { useRuleStore } from '../../store/ruleStore';
+import { useScreenBlank } from '../../hooks/useLiveSession';
 import { SlideRenderer } from './SlideRenderer';
 import '../../styles/presentation.css';
 
@@ -27,6 +28,7 @@ export const PresentationView: React.FC = () => {
   } = usePresentationDataStore();
 
   const ruleContextMeta = useRuleStore(s => s.ruleContextMeta);
+  const isBlank = useScreenBlank();
 
   // Scale fonts/margins proportionally to viewport vs 1920×1080 design size
   const [scale, setScale] = useState(() =>
@@ -156,18 +158,20 @@ export const PresentationView: React.FC = () => {
       onClick={handleClick}
       onContextMenu={handleContextMenu}
     >
-      <SlideRenderer
-        slide={currentSlide}
-        template={resolvedTemplate!}
-        variables={resolvedVariables}
-        languageMap={resolvedLanguageMap}
-        languageSettings={resolvedLanguageSettings}
-        scale={scale}
-        meta={ruleContextMeta}
-      />
+      {!isBlank && (
+        <SlideRenderer
+          slide={currentSlide}
+          template={resolvedTemplate!}
+          variables={resolvedVariables}
+          languageMap={resolvedLanguageMap}
+          languageSettings={resolvedLanguageSettings}
+          scale={scale}
+          meta={ruleContextMeta}
+        />
+      )}
 
       {/* Slide counter */}
-      {appSettings.showSlideNumbers && (
+      {!isBlank && appSettings.showSlideNumbers && (
         <div className="presentation-counter">
           {currentSlideIndex + 1} / {enabledSlides.length}
         </div>
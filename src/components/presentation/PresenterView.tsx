@@ -2,7 +2,6 @@ import { useState, useEffect, useCallback, useRef } from 'react';
 import { usePresentationModeStore } from '../../store/presentationModeStore';
 import { usePresentationDataStore } from '../../store/presentationDataStore';
 import { useRuleStore } from '../../store/ruleStore';
-import { audienceWindowService } from '../../services/AudienceWindowService';
 import { SlideRenderer } from './SlideRenderer';
 import '../../styles/presenter.css';
 
@@ -42,13 +41,6 @@ export const PresenterView: React.FC = () => {
     return () => clearInterval(interval);
   }, [isPresenting]);
 
-  // Sync slide changes to audience window
-  useEffect(() => {
-    if (isPresenting) {
-      audienceWindowService.emitSlideChange(currentSlideIndex);
-    }
-  }, [currentSlideIndex, isPresenting]);
-
   const handleNext = useCallback(() => { nextSlide(); }, [nextSlide]);
   const handlePrev = useCallback(() => { previousSlide(); }, [previousSlide]);
   const handleStop = useCallback(() => { stopPresentation(); }, [stopPresentation]);
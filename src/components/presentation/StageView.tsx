@@ -0,0 +1,128 @@
+import { useState, useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { getCurrentWindow } from '@tauri-apps/api/window';
+import { useTranslation } from 'react-i18next';
+import { usePresentationDataStore } from '../../store/presentationDataStore';
+import { usePresentationModeStore } from '../../store/presentationModeStore';
+import { useRuleStore } from '../../store/ruleStore';
+import { SlideRenderer } from './SlideRenderer';
+import { liveSessionService, LiveState } from '../../services/LiveSessionService';
+import { Slide } from '../../domain/entities/Slide';
+import '../../styles/presenter.css';
+import '../../styles/stage.css';
+
+/**
+ * Confidence monitor for the chanters: the current slide's notes, the next
+ * slide and a clock, following the presenter through the backend's live
+ * session rather than the projector, so a frozen output doesn't hold it back.
+ */
+export const StageView: React.FC = () => {
+  const { t } = useTranslation('presentation');
+  const [live, setLive] = useState<LiveState | null>(null);
+  const [now, setNow] = useState(() => new Date());
+
+  const {
+    currentTemplate,
+    getTemplateForSlide,
+    getVariablesForSlide,
+    getLanguageMapForSlide,
+    getLanguageSettingsForSlide,
+  } = usePresentationDataStore();
+
+  const { getMergedEnabledSlides } = usePresentationModeStore();
+  const ruleContextMeta = useRuleStore(s => s.ruleContextMeta);
+
+  useEffect(() => {
+    liveSessionService.getState().then(setLive).catch(() => {});
+
+    const unlistenState = listen<LiveState>('live-state-changed', (event) => {
+      setLive(event.payload);
+    });
+
+    const unlistenStop = listen('presentation-stop', () => {
+      getCurrentWindow().close().catch(() => {});
+    });
+
+    return () => {
+      unlistenState.then(fn => fn());
+      unlistenStop.then(fn => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    const interval = setInterval(() => setNow(new Date()), 1000);
+    return () => clearInterval(interval);
+  }, []);
+
+  // Looked up by id: this window's slide list is only as fresh as its stores
+  const slides = getMergedEnabledSlides();
+  const slideAt = (index: number): Slide | null => {
+    const id = live?.slideIds[index];
+    return id ? slides.find(s => s.id === id) ?? null : null;
+  };
+  const index = live?.current.slideIndex ?? 0;
+  const currentSlide = slideAt(index);
+  const nextSlide = slideAt(index + 1);
+  const clock = now.toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' });
+
+  if (!live || live.slideIds.length === 0 || !currentTemplate) {
+    return (
+      <div className="presenter-view stage-view">
+        <div className="stage-waiting">{t('stageWaiting')}</div>
+        <div className="presenter-bottom-bar">
+          <span />
+          <span className="stage-clock">{clock}</span>
+        </div>
+      </div>
+    );
+  }
+
+  return (
+    <div className="presenter-view stage-view" style={{ cursor: 'none' }}>
+      <div className="presenter-top">
+        {/* Notes, as large as the display allows */}
+        <div className="presenter-notes stage-notes">
+          <div className="presenter-section-label">{t('stageNotes')}</div>
+          <div className="presenter-notes-content stage-notes-content">
+            {currentSlide?.notes || ''}
+          </div>
+        </div>
+
+        {/* Next slide preview */}
+        <div className="presenter-right-panel">
+          <div className="presenter-next-slide">
+            <div className="presenter-section-label">{t('stageNext')}</div>
+            {nextSlide ? (
+              <div className="presenter-slide-container presenter-slide-container-small">
+                <SlideRenderer
+                  slide={nextSlide}
+                  template={getTemplateForSlide(nextSlide) || currentTemplate}
+                  variables={getVariablesForSlide(nextSlide)}
+                  languageMap={getLanguageMapForSlide(nextSlide)}
+                  languageSettings={getLanguageSettingsForSlide(nextSlide)}
+                  scale={0.3}
+                  meta={ruleContextMeta}
+                />
+              </div>
+            ) : (
+              <div className="presenter-slide-container presenter-slide-container-small presenter-end-marker">
+                {t('stageEnd')}
+              </div>
+            )}
+          </div>
+        </div>
+      </div>
+
+      <div className="presenter-bottom-bar">
+        <div className="presenter-info">
+          <span className="presenter-slide-counter">
+            {index + 1} / {live.slideIds.length}
+          </span>
+          {live.blank && <span className="stage-status">{t('stageBlank')}</span>}
+          {live.frozen && <span className="stage-status">{t('stageFrozen')}</span>}
+        </div>
+        <span className="stage-clock">{clock}</span>
+      </div>
+    </div>
+  );
+};
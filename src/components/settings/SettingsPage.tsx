@@ -218,6 +218,19 @@ export const SettingsPage: React.FC = () => {
             <option value="presenterView">{t('presenterView')}</option>
           </select>
         </div>
+
+        <div className="setting-row">
+          <div className="setting-label">
+            <span>{t('stageDisplay')}</span>
+            <span className="setting-hint">{t('stageDisplayHint')}</span>
+          </div>
+          <button
+            className={`toggle-switch ${localSettings.stageDisplay ? 'active' : ''}`}
+            onClick={() => updateSetting('stageDisplay', !localSettings.stageDisplay)}
+          >
+            <span className="toggle-knob"></span>
+          </button>
+        </div>
       </div>
 
       {/* Advanced */}
@@ -3,6 +3,8 @@ export interface AppSettings {
   showSlideNumbers: boolean;
   showSidebarLabels: boolean;
   presentationDisplay: 'currentWindow' | 'presenterView';
+  /** Open the stage display on a spare display while in presenter view */
+  stageDisplay: boolean;
   locale: 'en' | 'am';
 }
 
@@ -11,5 +13,6 @@ export const defaultAppSettings: AppSettings = {
   showSlideNumbers: true,
   showSidebarLabels: true,
   presentationDisplay: 'currentWindow',
+  stageDisplay: false,
   locale: 'en',
 };
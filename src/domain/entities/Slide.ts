@@ -34,6 +34,8 @@ export interface Slide {
   isDisabled: boolean;
   isDynamic: boolean;
   templateOverrideId?: string;
+  /** Stored version, sent back on update so stale writes are rejected */
+  version?: number;
 }
 
 export function getSlidePreviewText(slide: Slide): string {
@@ -14,6 +14,7 @@ export interface TemplateDefinition {
   };
   title: {
     show: boolean;
+    required?: boolean;
     fontSize: number;
     color: string;
     alignment: 'left' | 'center' | 'right';
@@ -25,6 +26,7 @@ export interface TemplateDefinition {
     color: string;
     alignment: 'left' | 'center' | 'right' | 'justify';
     lineHeight: number;
+    required?: boolean;
   }[];
   background: {
     color: string;
@@ -13,9 +13,11 @@ export interface ISlideRepository {
   create(slide: Omit<Slide, 'id'>): Promise<Slide>;
   createMany(slides: Omit<Slide, 'id'>[]): Promise<Slide[]>;
   update(id: string, slide: Partial<Omit<Slide, 'id' | 'presentationId'>>): Promise<Slide>;
-  updateOrder(slides: { id: string; slideOrder: number }[]): Promise<void>;
+  reorder(presentationId: string, orderedSlideIds: string[]): Promise<void>;
   toggleDisabled(id: string): Promise<Slide>;
   delete(id: string): Promise<void>;
   deleteByPresentationId(presentationId: string): Promise<void>;
   count(presentationId: string): Promise<number>;
+  undo(presentationId: string): Promise<string | null>;
+  redo(presentationId: string): Promise<string | null>;
 }
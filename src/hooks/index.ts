@@ -14,3 +14,5 @@ export { useVariables } from './useVariables';
 export { useSecondaryKidase } from './useSecondaryKidase';
 export { useResizablePanel } from './useResizablePanel';
 export { useUpdater } from './useUpdater';
+export { useLiveSession, useScreenBlank } from './useLiveSession';
+export { useOutputDisplay } from './useOutputDisplay';
@@ -3,6 +3,7 @@ import { useAppStore } from '../store/appStore';
 import { pdfExportService } from '../services/PdfExportService';
 import { pptxExportService } from '../services/PptxExportService';
 import { save } from '@tauri-apps/plugin-dialog';
+import { invoke } from '@tauri-apps/api/core';
 import { toast } from '../store/toastStore';
 import { Template } from '../domain/entities/Template';
 import { Variable } from '../domain/entities/Variable';
@@ -29,6 +30,25 @@ function buildPerSlideMaps(slides: Slide[]) {
   };
 }
 
+/**
+ * The backend's stable filename for an export, dated with the service the
+ * rules were evaluated for; the plain name if it can't be worked out.
+ */
+async function suggestFilename(
+  presentationId: string,
+  name: string,
+  format: 'pdf' | 'pptx',
+  meta: Record<string, unknown> | null | undefined
+): Promise<string> {
+  const date = typeof meta?.date === 'string' ? meta.date : null;
+  try {
+    return await invoke<string>('suggest_export_filename', { presentationId, format, date });
+  } catch (err) {
+    console.warn('Failed to suggest an export filename:', err);
+    return `${name}.${format}`;
+  }
+}
+
 export function useExport(displaySlides: Slide[]) {
   const { t } = useTranslation('presentation');
   const {
@@ -43,7 +63,12 @@ export function useExport(displaySlides: Slide[]) {
 
     const filePath = await save({
       filters: [{ name: 'PDF', extensions: ['pdf'] }],
-      defaultPath: `${currentPresentation.name}.pdf`,
+      defaultPath: await suggestFilename(
+        currentPresentation.id,
+        currentPresentation.name,
+        'pdf',
+        ruleContextMeta
+      ),
     });
 
     if (!filePath) return;
@@ -83,7 +108,12 @@ export function useExport(displaySlides: Slide[]) {
 
     const filePath = await save({
       filters: [{ name: 'PPTX', extensions: ['pptx'] }],
-      defaultPath: `${currentPresentation.name}.pptx`,
+      defaultPath: await suggestFilename(
+        currentPresentation.id,
+        currentPresentation.name,
+        'pptx',
+        ruleContextMeta
+      ),
     });
 
     if (!filePath) return;
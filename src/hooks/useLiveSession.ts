@@ -0,0 +1,66 @@
+import { useState, useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { usePresentationModeStore } from '../store/presentationModeStore';
+import { usePresentationDataStore } from '../store/presentationDataStore';
+import { useRuleStore } from '../store/ruleStore';
+import { liveSessionService, LiveState } from '../services/LiveSessionService';
+
+/**
+ * Keep the presenting window on the backend's live session: follow moves
+ * made from a remote or the HTTP API, and hand the backend the slide list
+ * again whenever edits or rules change it mid-presentation.
+ */
+export function useLiveSession() {
+  useEffect(() => {
+    const unlisten = listen<LiveState>('live-state-changed', (event) => {
+      usePresentationModeStore.getState().syncLiveState(event.payload);
+    });
+
+    let loadedIds = '';
+    const reload = () => {
+      const { isPresenting, getMergedEnabledSlides } = usePresentationModeStore.getState();
+      const { currentPresentation } = usePresentationDataStore.getState();
+      if (!isPresenting || !currentPresentation) {
+        loadedIds = '';
+        return;
+      }
+      const slides = getMergedEnabledSlides();
+      const ids = slides.map(s => s.id).join('\n');
+      if (ids === loadedIds) return;
+      const firstLoad = loadedIds === '';
+      loadedIds = ids;
+      // startPresentation loads the first list itself, at its start slide
+      if (firstLoad) return;
+      liveSessionService.load(currentPresentation, slides).catch((err) => {
+        console.warn('Failed to update the live slide list:', err);
+      });
+    };
+    const unsubscribeMode = usePresentationModeStore.subscribe(reload);
+    const unsubscribeData = usePresentationDataStore.subscribe(reload);
+    const unsubscribeRules = useRuleStore.subscribe(reload);
+
+    return () => {
+      unlisten.then(fn => fn());
+      unsubscribeMode();
+      unsubscribeData();
+      unsubscribeRules();
+    };
+  }, []);
+}
+
+/** Whether the audience screen is blanked, from the remote or the app */
+export function useScreenBlank() {
+  const [isBlank, setIsBlank] = useState(false);
+
+  useEffect(() => {
+    liveSessionService.getState()
+      .then(state => setIsBlank(state.blank))
+      .catch(() => {});
+    const unlisten = listen<LiveState>('live-state-changed', (event) => {
+      setIsBlank(event.payload.blank);
+    });
+    return () => { unlisten.then(fn => fn()); };
+  }, []);
+
+  return isBlank;
+}
@@ -0,0 +1,32 @@
+import { useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { useTranslation } from 'react-i18next';
+import { toast } from '../store/toastStore';
+import type { OutputKind } from '../services/AudienceWindowService';
+
+interface OutputDisplay {
+  output: OutputKind;
+}
+
+/**
+ * Warn the operator when the projector an output is on disconnects (the
+ * backend hides the output meanwhile) and again when it's back.
+ */
+export function useOutputDisplay() {
+  const { t } = useTranslation('presentation');
+
+  useEffect(() => {
+    const unlistenLost = listen<OutputDisplay>('output-display-lost', (event) => {
+      toast.error(t(event.payload.output === 'stage' ? 'stageDisplayLost' : 'outputDisplayLost'));
+    });
+    const unlistenRestored = listen<OutputDisplay>('output-display-restored', (event) => {
+      toast.success(
+        t(event.payload.output === 'stage' ? 'stageDisplayRestored' : 'outputDisplayRestored')
+      );
+    });
+    return () => {
+      unlistenLost.then(fn => fn());
+      unlistenRestored.then(fn => fn());
+    };
+  }, [t]);
+}
@@ -61,39 +61,35 @@ export function useSlides() {
       notes?: string;
     }
   ): Promise<boolean> => {
+    const loaded = currentSlides.find(s => s.id === id);
     try {
-      await slideRepository.update(id, updates);
-      updateSlide(id, updates);
+      const saved = await slideRepository.update(id, { ...updates, version: loaded?.version });
+      updateSlide(id, { ...updates, version: saved.version });
       return true;
     } catch (err) {
       console.error('Failed to update slide:', err);
+      // Saved from another window since we loaded it: show that version instead
+      if (String(err).startsWith('Conflict') && currentPresentation) {
+        setCurrentSlides(await slideRepository.getByPresentationId(currentPresentation.id));
+      }
       return false;
     }
-  }, [updateSlide]);
+  }, [currentSlides, currentPresentation, updateSlide, setCurrentSlides]);
 
   const deleteSlide = useCallback(async (id: string): Promise<boolean> => {
     // Verse slides can't be deleted individually — delete the parent dynamic slide
     const baseId = isVerseSlide(id) ? getParentSlideId(id) : id;
 
     try {
+      // Renumbers the remaining slides in the same transaction
       await slideRepository.delete(baseId);
       removeSlide(baseId);
-
-      // Update order in database
-      const remainingSlides = currentSlides
-        .filter(s => s.id !== baseId)
-        .map((s, i) => ({ id: s.id, slideOrder: i + 1 }));
-
-      if (remainingSlides.length > 0) {
-        await slideRepository.updateOrder(remainingSlides);
-      }
-
       return true;
     } catch (err) {
       console.error('Failed to delete slide:', err);
       return false;
     }
-  }, [currentSlides, removeSlide]);
+  }, [removeSlide]);
 
   const moveSlide = useCallback(async (fromIndex: number, toIndex: number): Promise<boolean> => {
     // Indices refer to currentSlides (raw, unexpanded)
@@ -106,12 +102,7 @@ export function useSlides() {
 
       // Get updated slides and persist order
       const updatedSlides = useAppStore.getState().currentSlides;
-      const orderUpdates = updatedSlides.map(s => ({
-        id: s.id,
-        slideOrder: s.slideOrder,
-      }));
-
-      await slideRepository.updateOrder(orderUpdates);
+      await slideRepository.reorder(fromSlide.presentationId, updatedSlides.map(s => s.id));
       return true;
     } catch (err) {
       console.error('Failed to move slide:', err);
@@ -124,14 +115,15 @@ export function useSlides() {
     const baseId = isVerseSlide(id) ? getParentSlideId(id) : id;
 
     try {
-      await slideRepository.toggleDisabled(baseId);
+      const saved = await slideRepository.toggleDisabled(baseId);
       toggleSlideDisabled(baseId);
+      updateSlide(baseId, { version: saved.version });
       return true;
     } catch (err) {
       console.error('Failed to toggle slide:', err);
       return false;
     }
-  }, [toggleSlideDisabled]);
+  }, [toggleSlideDisabled, updateSlide]);
 
   const setTemplateOverride = useCallback(async (
     slideId: string,
@@ -139,10 +131,11 @@ export function useSlides() {
   ): Promise<boolean> => {
     const baseId = isVerseSlide(slideId) ? getParentSlideId(slideId) : slideId;
     try {
-      await slideRepository.update(baseId, {
+      const saved = await slideRepository.update(baseId, {
         templateOverrideId: templateId ?? undefined,
       });
       useAppStore.getState().setSlideTemplateOverride(baseId, templateId);
+      useAppStore.getState().updateSlide(baseId, { version: saved.version });
       return true;
     } catch (err) {
       console.error('Failed to set template override:', err);
@@ -161,6 +154,32 @@ export function useSlides() {
     }
   }, [currentPresentation, setCurrentSlides]);
 
+  const undo = useCallback(async (): Promise<boolean> => {
+    if (!currentPresentation) return false;
+
+    try {
+      const kind = await slideRepository.undo(currentPresentation.id);
+      if (kind) await refreshSlides();
+      return kind !== null;
+    } catch (err) {
+      console.error('Failed to undo:', err);
+      return false;
+    }
+  }, [currentPresentation, refreshSlides]);
+
+  const redo = useCallback(async (): Promise<boolean> => {
+    if (!currentPresentation) return false;
+
+    try {
+      const kind = await slideRepository.redo(currentPresentation.id);
+      if (kind) await refreshSlides();
+      return kind !== null;
+    } catch (err) {
+      console.error('Failed to redo:', err);
+      return false;
+    }
+  }, [currentPresentation, refreshSlides]);
+
   return {
     // State
     slides: expandedSlides,
@@ -179,5 +198,7 @@ export function useSlides() {
     toggleDisabled,
     setTemplateOverride,
     refreshSlides,
+    undo,
+    redo,
   };
 }
@@ -3,17 +3,32 @@
  * SQLite database initialization and connection management
  */
 
+import { invoke } from '@tauri-apps/api/core';
 import Database from '@tauri-apps/plugin-sql';
 
+interface DbCompatibility {
+  storedVersion: number;
+  expectedVersion: number;
+  compatible: boolean;
+}
+
 let db: Database | null = null;
 
 /**
  * Get the database connection instance
  * Creates a new connection if one doesn't exist
+ * Refuses a database migrated by a newer version of the app
  * @returns Promise<Database> - The database instance
  */
 export async function getDatabase(): Promise<Database> {
   if (!db) {
+    const compatibility = await invoke<DbCompatibility>('can_open_database');
+    if (!compatibility.compatible) {
+      throw new Error(
+        `This database was created by a newer version of the app (schema ${compatibility.storedVersion}, ` +
+          `this version supports up to ${compatibility.expectedVersion}). Please update the app.`
+      );
+    }
     db = await Database.load('sqlite:kidase.db');
   }
   return db;
@@ -35,6 +35,9 @@ export class AppSettingsRepository {
           }
           break;
         }
+        case 'stageDisplay':
+          settings.stageDisplay = row.value === 'true';
+          break;
       }
     }
 
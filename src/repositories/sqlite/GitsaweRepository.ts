@@ -1,4 +1,4 @@
-import { v4 as uuidv4 } from 'uuid';
+import { v7 as uuidv7 } from 'uuid';
 import { getDatabase } from '../../lib/database';
 import { Gitsawe } from '../../domain/entities/Gitsawe';
 import { IGitsaweRepository } from '../../domain/interfaces/IGitsaweRepository';
@@ -71,7 +71,7 @@ export class GitsaweRepository implements IGitsaweRepository {
 
   async create(gitsawe: Omit<Gitsawe, 'id' | 'createdAt'>): Promise<Gitsawe> {
     const db = await getDatabase();
-    const id = uuidv4();
+    const id = uuidv7();
     const createdAt = new Date().toISOString();
 
     await db.execute(
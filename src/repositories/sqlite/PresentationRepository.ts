@@ -1,4 +1,4 @@
-import { v4 as uuidv4 } from 'uuid';
+import { invoke } from '@tauri-apps/api/core';
 import { getDatabase } from '../../lib/database';
 import { Presentation } from '../../domain/entities/Presentation';
 import { IPresentationRepository } from '../../domain/interfaces/IPresentationRepository';
@@ -15,6 +15,20 @@ interface PresentationRow {
   created_at: string;
 }
 
+// Shape of `db::Presentation` as the Rust commands return it
+interface PresentationRecord {
+  id: string;
+  name: string;
+  type: string;
+  templateId: string;
+  languageMap: string;
+  languageSettings: string | null;
+  isPrimary: boolean;
+  isActive: boolean;
+  createdAt: string;
+  displayScale: number | null;
+}
+
 export class PresentationRepository implements IPresentationRepository {
   private mapRowToEntity(row: PresentationRow): Presentation {
     return {
@@ -30,27 +44,52 @@ export class PresentationRepository implements IPresentationRepository {
     };
   }
 
+  private mapRecordToEntity(record: PresentationRecord): Presentation {
+    return {
+      id: record.id,
+      name: record.name,
+      type: record.type,
+      templateId: record.templateId,
+      languageMap: JSON.parse(record.languageMap),
+      languageSettings: record.languageSettings ? JSON.parse(record.languageSettings) : undefined,
+      isPrimary: record.isPrimary,
+      isActive: record.isActive,
+      createdAt: record.createdAt,
+    };
+  }
+
+  private toRecord(presentation: Presentation): PresentationRecord {
+    return {
+      id: presentation.id,
+      name: presentation.name,
+      type: presentation.type,
+      templateId: presentation.templateId,
+      languageMap: JSON.stringify(presentation.languageMap),
+      languageSettings: presentation.languageSettings ? JSON.stringify(presentation.languageSettings) : null,
+      isPrimary: presentation.isPrimary,
+      isActive: presentation.isActive,
+      createdAt: presentation.createdAt,
+      displayScale: null,
+    };
+  }
+
   async getAll(): Promise<Presentation[]> {
     const db = await getDatabase();
     const rows = await db.select<PresentationRow[]>(
-      'SELECT * FROM presentations ORDER BY created_at DESC'
+      'SELECT * FROM presentations WHERE deleted_at IS NULL ORDER BY created_at DESC'
     );
     return rows.map(this.mapRowToEntity);
   }
 
   async getById(id: string): Promise<Presentation | null> {
-    const db = await getDatabase();
-    const rows = await db.select<PresentationRow[]>(
-      'SELECT * FROM presentations WHERE id = ?',
-      [id]
-    );
-    return rows.length > 0 ? this.mapRowToEntity(rows[0]) : null;
+    const record = await invoke<PresentationRecord | null>('get_presentation', { id });
+    return record ? this.mapRecordToEntity(record) : null;
   }
 
   async getByName(name: string): Promise<Presentation | null> {
     const db = await getDatabase();
     const rows = await db.select<PresentationRow[]>(
-      'SELECT * FROM presentations WHERE name = ?',
+      'SELECT * FROM presentations WHERE name = ? AND deleted_at IS NULL',
       [name]
     );
     return rows.length > 0 ? this.mapRowToEntity(rows[0]) : null;
@@ -59,7 +98,7 @@ export class PresentationRepository implements IPresentationRepository {
   async getActive(): Promise<Presentation | null> {
     const db = await getDatabase();
     const rows = await db.select<PresentationRow[]>(
-      'SELECT * FROM presentations WHERE is_active = 1 LIMIT 1'
+      'SELECT * FROM presentations WHERE is_active = 1 AND deleted_at IS NULL LIMIT 1'
     );
     return rows.length > 0 ? this.mapRowToEntity(rows[0]) : null;
   }
@@ -67,15 +106,14 @@ export class PresentationRepository implements IPresentationRepository {
   async getPrimary(): Promise<Presentation | null> {
     const db = await getDatabase();
     const rows = await db.select<PresentationRow[]>(
-      'SELECT * FROM presentations WHERE is_primary = 1 LIMIT 1'
+      'SELECT * FROM presentations WHERE is_primary = 1 AND deleted_at IS NULL LIMIT 1'
     );
     return rows.length > 0 ? this.mapRowToEntity(rows[0]) : null;
   }
 
   async setActive(id: string): Promise<void> {
-    const db = await getDatabase();
-    await db.execute('UPDATE presentations SET is_active = 0');
-    await db.execute('UPDATE presentations SET is_active = 1 WHERE id = ?', [id]);
+    // Done in Rust so clearing the others and setting this one is one transaction
+    await invoke('set_active_presentation', { presentationId: id });
   }
 
   async clearActive(): Promise<void> {
@@ -92,55 +130,23 @@ export class PresentationRepository implements IPresentationRepository {
     return rows.map(this.mapRowToEntity);
   }
 
+  // Writes go through Rust, which validates the type, template and language
+  // JSON before anything is stored
   async create(presentation: Omit<Presentation, 'id' | 'createdAt'>): Promise<Presentation> {
-    const db = await getDatabase();
-    const id = uuidv4();
-    const createdAt = new Date().toISOString();
-
-    await db.execute(
-      `INSERT INTO presentations
-       (id, name, type, template_id, language_map, language_settings, is_primary, is_active, created_at)
-       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)`,
-      [
-        id,
-        presentation.name,
-        presentation.type,
-        presentation.templateId,
-        JSON.stringify(presentation.languageMap),
-        presentation.languageSettings ? JSON.stringify(presentation.languageSettings) : null,
-        presentation.isPrimary ? 1 : 0,
-        presentation.isActive ? 1 : 0,
-        createdAt,
-      ]
-    );
-
-    return { ...presentation, id, createdAt };
+    const record = await invoke<PresentationRecord>('save_presentation', {
+      presentation: this.toRecord({ ...presentation, id: '', createdAt: '' }),
+    });
+    return this.mapRecordToEntity(record);
   }
 
   async update(id: string, presentation: Partial<Omit<Presentation, 'id' | 'createdAt'>>): Promise<Presentation> {
-    const db = await getDatabase();
     const existing = await this.getById(id);
     if (!existing) throw new Error('Presentation not found');
 
-    const updated = { ...existing, ...presentation };
-
-    await db.execute(
-      `UPDATE presentations
-       SET name = ?, type = ?, template_id = ?, language_map = ?, language_settings = ?, is_primary = ?, is_active = ?
-       WHERE id = ?`,
-      [
-        updated.name,
-        updated.type,
-        updated.templateId,
-        JSON.stringify(updated.languageMap),
-        updated.languageSettings ? JSON.stringify(updated.languageSettings) : null,
-        updated.isPrimary ? 1 : 0,
-        updated.isActive ? 1 : 0,
-        id,
-      ]
-    );
-
-    return updated;
+    const record = await invoke<PresentationRecord>('save_presentation', {
+      presentation: this.toRecord({ ...existing, ...presentation }),
+    });
+    return this.mapRecordToEntity(record);
   }
 
   async delete(id: string): Promise<void> {
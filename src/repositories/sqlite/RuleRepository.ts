@@ -1,4 +1,5 @@
-import { v4 as uuidv4 } from 'uuid';
+import { invoke } from '@tauri-apps/api/core';
+import { v7 as uuidv7 } from 'uuid';
 import { getDatabase } from '../../lib/database';
 import { RuleDefinition, RuleScope } from '../../domain/entities/RuleDefinition';
 import { IRuleRepository } from '../../domain/interfaces/IRuleRepository';
@@ -66,9 +67,12 @@ export class RuleRepository implements IRuleRepository {
     return rows.map(r => this.mapRowToEntity(r));
   }
 
+  // Rules the Rust engine would skip are refused before they're stored;
+  // validate_rule rejects with a message naming the field at fault
   async create(rule: Omit<RuleDefinition, 'id' | 'createdAt'>): Promise<RuleDefinition> {
+    await invoke('validate_rule', { ruleJson: rule.ruleJson });
     const db = await getDatabase();
-    const id = uuidv4();
+    const id = uuidv7();
     const createdAt = new Date().toISOString();
 
     await db.execute(
@@ -97,6 +101,9 @@ export class RuleRepository implements IRuleRepository {
     if (!existing) throw new Error('Rule definition not found');
 
     const updated = { ...existing, ...rule };
+    if (rule.ruleJson !== undefined) {
+      await invoke('validate_rule', { ruleJson: updated.ruleJson });
+    }
 
     await db.execute(
       `UPDATE rule_definitions
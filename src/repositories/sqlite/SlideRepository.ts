@@ -1,4 +1,4 @@
-import { v4 as uuidv4 } from 'uuid';
+import { invoke } from '@tauri-apps/api/core';
 import { getDatabase } from '../../lib/database';
 import { Slide } from '../../domain/entities/Slide';
 import { ISlideRepository } from '../../domain/interfaces/ISlideRepository';
@@ -15,6 +15,7 @@ interface SlideRow {
   is_disabled: number;
   is_dynamic: number;
   template_override_id: string | null;
+  version: number;
 }
 
 export class SlideRepository implements ISlideRepository {
@@ -31,6 +32,7 @@ export class SlideRepository implements ISlideRepository {
       isDisabled: row.is_disabled === 1,
       isDynamic: row.is_dynamic === 1,
       templateOverrideId: row.template_override_id ?? undefined,
+      version: row.version,
     };
   }
 
@@ -71,29 +73,23 @@ export class SlideRepository implements ISlideRepository {
   }
 
   async create(slide: Omit<Slide, 'id'>): Promise<Slide> {
-    const db = await getDatabase();
-    const id = uuidv4();
-
-    await db.execute(
-      `INSERT INTO slides
-       (id, presentation_id, slide_order, line_id, title_json, blocks_json, footer_json, notes, is_disabled, is_dynamic, template_override_id)
-       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
-      [
-        id,
-        slide.presentationId,
-        slide.slideOrder,
-        slide.lineId ?? null,
-        slide.titleJson ? JSON.stringify(slide.titleJson) : null,
-        JSON.stringify(slide.blocksJson),
-        slide.footerJson ? JSON.stringify(slide.footerJson) : null,
-        slide.notes ?? null,
-        slide.isDisabled ? 1 : 0,
-        slide.isDynamic ? 1 : 0,
-        slide.templateOverrideId ?? null,
-      ]
-    );
-
-    return { ...slide, id };
+    const saved = await invoke<{ id: string; version: number }>('save_slide', {
+      slide: {
+        id: '',
+        presentationId: slide.presentationId,
+        slideOrder: slide.slideOrder,
+        lineId: slide.lineId ?? null,
+        titleJson: slide.titleJson ? JSON.stringify(slide.titleJson) : null,
+        blocksJson: JSON.stringify(slide.blocksJson),
+        footerJson: slide.footerJson ? JSON.stringify(slide.footerJson) : null,
+        notes: slide.notes ?? null,
+        isDisabled: slide.isDisabled,
+        isDynamic: slide.isDynamic,
+        templateOverrideId: slide.templateOverrideId ?? null,
+      },
+    });
+
+    return { ...slide, id: saved.id, version: saved.version };
   }
 
   async createMany(slides: Omit<Slide, 'id'>[]): Promise<Slide[]> {
@@ -107,43 +103,37 @@ export class SlideRepository implements ISlideRepository {
     return createdSlides;
   }
 
+  // Edits go through Rust so each one lands on the presentation's undo stack.
+  // Pass the `version` the caller loaded; a newer stored one rejects the write
+  // with an error starting "Conflict".
   async update(id: string, slide: Partial<Omit<Slide, 'id' | 'presentationId'>>): Promise<Slide> {
-    const db = await getDatabase();
     const existing = await this.getById(id);
     if (!existing) throw new Error('Slide not found');
 
     const updated = { ...existing, ...slide };
 
-    await db.execute(
-      `UPDATE slides
-       SET slide_order = ?, line_id = ?, title_json = ?, blocks_json = ?,
-           footer_json = ?, notes = ?, is_disabled = ?, is_dynamic = ?, template_override_id = ?
-       WHERE id = ?`,
-      [
-        updated.slideOrder,
-        updated.lineId ?? null,
-        updated.titleJson ? JSON.stringify(updated.titleJson) : null,
-        JSON.stringify(updated.blocksJson),
-        updated.footerJson ? JSON.stringify(updated.footerJson) : null,
-        updated.notes ?? null,
-        updated.isDisabled ? 1 : 0,
-        updated.isDynamic ? 1 : 0,
-        updated.templateOverrideId ?? null,
+    const version = await invoke<number>('update_slide', {
+      expectedVersion: slide.version ?? existing.version,
+      slide: {
         id,
-      ]
-    );
-
-    return updated;
+        presentationId: updated.presentationId,
+        slideOrder: updated.slideOrder,
+        lineId: updated.lineId ?? null,
+        titleJson: updated.titleJson ? JSON.stringify(updated.titleJson) : null,
+        blocksJson: JSON.stringify(updated.blocksJson),
+        footerJson: updated.footerJson ? JSON.stringify(updated.footerJson) : null,
+        notes: updated.notes ?? null,
+        isDisabled: updated.isDisabled,
+        isDynamic: updated.isDynamic,
+        templateOverrideId: updated.templateOverrideId ?? null,
+      },
+    });
+
+    return { ...updated, version };
   }
 
-  async updateOrder(slides: { id: string; slideOrder: number }[]): Promise<void> {
-    const db = await getDatabase();
-    for (const slide of slides) {
-      await db.execute(
-        'UPDATE slides SET slide_order = ? WHERE id = ?',
-        [slide.slideOrder, slide.id]
-      );
-    }
+  async reorder(presentationId: string, orderedSlideIds: string[]): Promise<void> {
+    await invoke('reorder_slides', { presentationId, orderedSlideIds });
   }
 
   async toggleDisabled(id: string): Promise<Slide> {
@@ -153,14 +143,26 @@ export class SlideRepository implements ISlideRepository {
     return this.update(id, { isDisabled: !existing.isDisabled });
   }
 
+  /** Deletes the slide and renumbers the rest 1..n. */
   async delete(id: string): Promise<void> {
-    const db = await getDatabase();
-    await db.execute('DELETE FROM slides WHERE id = ?', [id]);
+    await invoke('delete_slide', { id });
   }
 
   async deleteByPresentationId(presentationId: string): Promise<void> {
     const db = await getDatabase();
     await db.execute('DELETE FROM slides WHERE presentation_id = ?', [presentationId]);
+    // The undo history refers to the slides just removed
+    await db.execute('DELETE FROM edits WHERE presentation_id = ?', [presentationId]);
+  }
+
+  /** Reverts the latest edit; resolves to its kind, or null if there was none. */
+  async undo(presentationId: string): Promise<string | null> {
+    return invoke<string | null>('undo', { presentationId });
+  }
+
+  /** Re-applies the last undone edit; resolves to its kind, or null if there was none. */
+  async redo(presentationId: string): Promise<string | null> {
+    return invoke<string | null>('redo', { presentationId });
   }
 
   async count(presentationId: string): Promise<number> {
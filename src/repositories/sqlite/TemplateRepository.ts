@@ -1,4 +1,4 @@
-import { v4 as uuidv4 } from 'uuid';
+import { invoke } from '@tauri-apps/api/core';
 import { getDatabase } from '../../lib/database';
 import { Template } from '../../domain/entities/Template';
 import { ITemplateRepository } from '../../domain/interfaces/ITemplateRepository';
@@ -48,51 +48,36 @@ export class TemplateRepository implements ITemplateRepository {
     return rows.length > 0 ? this.mapRowToEntity(rows[0]) : null;
   }
 
+  // Writes go through Rust so definitions are validated before they're stored
   async create(template: Omit<Template, 'id' | 'createdAt'>): Promise<Template> {
-    const db = await getDatabase();
-    const id = uuidv4();
-    const createdAt = new Date().toISOString();
-
-    await db.execute(
-      `INSERT INTO templates (id, name, max_lang_count, definition_json, created_at)
-       VALUES (?, ?, ?, ?, ?)`,
-      [
-        id,
-        template.name,
-        template.maxLangCount,
-        JSON.stringify(template.definitionJson),
-        createdAt,
-      ]
-    );
+    const created = await invoke<{ id: string; createdAt: string }>('create_template', {
+      name: template.name,
+      maxLangCount: template.maxLangCount,
+      definitionJson: JSON.stringify(template.definitionJson),
+    });
 
-    return { ...template, id, createdAt };
+    return { ...template, id: created.id, createdAt: created.createdAt };
   }
 
   async update(id: string, template: Partial<Omit<Template, 'id' | 'createdAt'>>): Promise<Template> {
-    const db = await getDatabase();
     const existing = await this.getById(id);
     if (!existing) throw new Error('Template not found');
 
     const updated = { ...existing, ...template };
 
-    await db.execute(
-      `UPDATE templates
-       SET name = ?, max_lang_count = ?, definition_json = ?
-       WHERE id = ?`,
-      [
-        updated.name,
-        updated.maxLangCount,
-        JSON.stringify(updated.definitionJson),
-        id,
-      ]
-    );
+    await invoke('update_template', {
+      id,
+      name: updated.name,
+      maxLangCount: updated.maxLangCount,
+      definitionJson: JSON.stringify(updated.definitionJson),
+    });
 
     return updated;
   }
 
   async delete(id: string): Promise<void> {
-    const db = await getDatabase();
-    await db.execute('DELETE FROM templates WHERE id = ?', [id]);
+    // Refused while presentations or slides still use the template
+    await invoke('delete_template', { id });
   }
 
   async exists(id: string): Promise<boolean> {
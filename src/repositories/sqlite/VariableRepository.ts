@@ -1,4 +1,4 @@
-import { v4 as uuidv4 } from 'uuid';
+import { v7 as uuidv7 } from 'uuid';
 import { getDatabase } from '../../lib/database';
 import { Variable } from '../../domain/entities/Variable';
 import { IVariableRepository } from '../../domain/interfaces/IVariableRepository';
@@ -57,7 +57,7 @@ export class VariableRepository implements IVariableRepository {
 
   async create(variable: Omit<Variable, 'id'>): Promise<Variable> {
     const db = await getDatabase();
-    const id = uuidv4();
+    const id = uuidv7();
 
     await db.execute(
       `INSERT INTO variables (id, presentation_id, name, value, value_lang1, value_lang2, value_lang3, value_lang4)
@@ -1,4 +1,4 @@
-import { v4 as uuidv4 } from 'uuid';
+import { v7 as uuidv7 } from 'uuid';
 import { getDatabase } from '../../lib/database';
 import { Verse } from '../../domain/entities/Verse';
 import { IVerseRepository } from '../../domain/interfaces/IVerseRepository';
@@ -65,7 +65,7 @@ export class VerseRepository implements IVerseRepository {
 
   async create(verse: Omit<Verse, 'id' | 'createdAt'>): Promise<Verse> {
     const db = await getDatabase();
-    const id = uuidv4();
+    const id = uuidv7();
     const createdAt = new Date().toISOString();
 
     await db.execute(
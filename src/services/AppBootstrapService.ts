@@ -1,3 +1,4 @@
+import { invoke } from '@tauri-apps/api/core';
 import { AppSettings } from '../domain/entities/AppSettings';
 import { Template, TemplateDefinition } from '../domain/entities/Template';
 import { Verse } from '../domain/entities/Verse';
@@ -71,6 +72,11 @@ export class AppBootstrapService {
       }
     }
 
+    // Restart the remote control server if it was left on
+    await invoke('resume_remote_control').catch((error) =>
+      console.warn('Failed to start remote control:', error)
+    );
+
     return { settings, templates, verses, presentation };
   }
 }
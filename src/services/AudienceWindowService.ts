@@ -1,73 +1,108 @@
-import { WebviewWindow } from '@tauri-apps/api/webviewWindow';
 import { emit } from '@tauri-apps/api/event';
+import { invoke } from '@tauri-apps/api/core';
 import type { Monitor } from '@tauri-apps/api/window';
+import { monitorService } from './MonitorService';
 
-const AUDIENCE_LABEL = 'audience';
+/** A position in a presentation; the audience's is broadcast as `live-slide-changed` */
+export interface LiveSlide {
+  presentationId: string | null;
+  slideIndex: number;
+  slideId?: string | null;
+  languageSet: string[];
+}
 
-class AudienceWindowService {
-  private audienceWindow: WebviewWindow | null = null;
+/** The windows the backend shows fullscreen on a display of their own */
+export type OutputKind = 'audience' | 'stage';
 
-  async openAudienceWindow(monitor: Monitor): Promise<void> {
-    // Close existing if any
-    await this.closeAudienceWindow();
+/** A connected display as listed by the backend */
+export interface DisplayInfo {
+  id: string;
+  name: string | null;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  scaleFactor: number;
+  primary: boolean;
+  /** The display last chosen for the output */
+  selected: boolean;
+  /** Whether the output window is on it now */
+  output: boolean;
+}
 
-    const { position, size, scaleFactor } = monitor;
+/** Whether display `d` is where monitor `m` is */
+function at(d: DisplayInfo, m: Monitor | null): boolean {
+  return m !== null && d.x === m.position.x && d.y === m.position.y;
+}
 
-    // Monitor API returns physical pixels, but WebviewWindow options expect logical pixels
-    const scale = scaleFactor || 1;
-    const logicalX = Math.round(position.x / scale);
-    const logicalY = Math.round(position.y / scale);
-    const logicalWidth = Math.round(size.width / scale);
-    const logicalHeight = Math.round(size.height / scale);
+class AudienceWindowService {
+  private open = false;
 
-    console.log('AudienceWindow: creating window', {
-      physical: { x: position.x, y: position.y, w: size.width, h: size.height },
-      logical: { x: logicalX, y: logicalY, w: logicalWidth, h: logicalHeight },
-      scaleFactor: scale,
-    });
+  /** `selected` and `output` describe `output`, the audience unless given */
+  async listDisplays(output?: OutputKind): Promise<DisplayInfo[]> {
+    return invoke<DisplayInfo[]>('list_displays', { output: output ?? null });
+  }
 
-    this.audienceWindow = new WebviewWindow(AUDIENCE_LABEL, {
-      url: '/?view=audience',
-      title: 'Presentation',
-      x: logicalX,
-      y: logicalY,
-      width: logicalWidth,
-      height: logicalHeight,
-      fullscreen: true,
-      decorations: false,
-      alwaysOnTop: true,
-      focus: false,
-    });
+  /**
+   * Open the audience window fullscreen on the display last chosen for the
+   * output, or on `monitor` when that one isn't connected or is the
+   * operator's. The backend places
+   * the window and reopens it there after a restart.
+   */
+  async openAudienceWindow(monitor: Monitor): Promise<void> {
+    const displays = await this.listDisplays();
+    const current = await monitorService.getCurrentMonitor();
+    // The saved choice wins unless the operator's window has moved onto it
+    const target =
+      displays.find(d => d.selected && !at(d, current)) ??
+      displays.find(d => at(d, monitor));
+    if (!target) {
+      throw new Error(`No display found at ${monitor.position.x},${monitor.position.y}`);
+    }
 
-    // Wait for the window to be created
-    await new Promise<void>((resolve, reject) => {
-      this.audienceWindow!.once('tauri://created', () => {
-        console.log('AudienceWindow: created successfully');
-        resolve();
-      });
-      this.audienceWindow!.once('tauri://error', (e) => {
-        console.error('AudienceWindow: creation error:', e);
-        reject(new Error(String(e.payload)));
-      });
-    });
+    const opened = await invoke<DisplayInfo>('open_output_window', { displayId: target.id });
+    console.log('AudienceWindow: opened on', opened.name ?? opened.id);
+    this.open = true;
   }
 
   async closeAudienceWindow(): Promise<void> {
-    if (this.audienceWindow) {
-      try {
-        await this.audienceWindow.close();
-      } catch {
-        // Window may already be closed
-      }
-      this.audienceWindow = null;
+    try {
+      await invoke('close_output_window');
+    } catch (err) {
+      console.warn('AudienceWindow: failed to close:', err);
     }
+    this.open = false;
   }
 
-  async emitSlideChange(index: number): Promise<void> {
+  /**
+   * Open the stage display on the display last chosen for it, or else on the
+   * first one that's neither the operator's nor the audience's. Returns false
+   * when there's no display to spare.
+   */
+  async openStageWindow(): Promise<boolean> {
+    const [displays, audience, current] = await Promise.all([
+      this.listDisplays('stage'),
+      this.listDisplays('audience'),
+      monitorService.getCurrentMonitor(),
+    ]);
+    const taken = (d: DisplayInfo) =>
+      at(d, current) || audience.some(a => a.output && a.id === d.id);
+    const target = displays.find(d => d.selected && !taken(d)) ?? displays.find(d => !taken(d));
+    if (!target) return false;
+
+    const opened = await invoke<DisplayInfo>('open_output_window', {
+      displayId: target.id,
+      output: 'stage',
+    });
+    console.log('StageWindow: opened on', opened.name ?? opened.id);
+    return true;
+  }
+
+  async closeStageWindow(): Promise<void> {
     try {
-      await emit('slide-change', { index });
-    } catch {
-      // Audience window may not be listening yet
+      await invoke('close_output_window', { output: 'stage' });
+    } catch (err) {
+      console.warn('StageWindow: failed to close:', err);
     }
   }
 
@@ -80,7 +115,7 @@ class AudienceWindowService {
   }
 
   isOpen(): boolean {
-    return this.audienceWindow !== null;
+    return this.open;
   }
 }
 
@@ -0,0 +1,90 @@
+import { invoke } from '@tauri-apps/api/core';
+import { Presentation, getOrderedLanguages } from '../domain/entities/Presentation';
+import { Slide } from '../domain/entities/Slide';
+import type { LiveSlide } from './AudienceWindowService';
+
+/**
+ * The live session kept by the backend and broadcast as `live-state-changed`.
+ * Every window and remote moves through it, so they never disagree.
+ */
+export interface LiveState {
+  /** Where the presenter is */
+  current: LiveSlide;
+  /** Ids of the slides being presented; empty when nothing is */
+  slideIds: string[];
+  blank: boolean;
+  /** The slide the audience is held on, while frozen */
+  frozen: LiveSlide | null;
+}
+
+/** A slide leaving a region its template requires empty */
+export interface RequiredRegionGap {
+  slideId: string;
+  slideOrder: number;
+  /** `title` or the language slot */
+  regionId: string;
+}
+
+/** What the audience sees: the frozen slide, or else the presenter's */
+export function shownSlide(state: LiveState): LiveSlide {
+  return state.frozen ?? state.current;
+}
+
+class LiveSessionService {
+  /**
+   * The go-live pre-flight: slides with an empty required region. The backend
+   * refuses to start presenting while there are any.
+   */
+  preflight(presentation: Presentation): Promise<RequiredRegionGap[]> {
+    return invoke<RequiredRegionGap[]>('find_empty_required_regions', {
+      presentationId: presentation.id,
+    });
+  }
+
+  /**
+   * Present `slides` at `slideIndex`, or hand the backend a changed slide
+   * list mid-presentation by leaving `slideIndex` out.
+   */
+  load(presentation: Presentation, slides: Slide[], slideIndex?: number): Promise<LiveState> {
+    return invoke<LiveState>('live_load', {
+      presentationId: presentation.id,
+      slideIds: slides.map(s => s.id),
+      languageSet: getOrderedLanguages(presentation.languageSettings, presentation.languageMap)
+        .map(l => l.slot),
+      slideIndex: slideIndex ?? null,
+    });
+  }
+
+  next(): Promise<LiveState> {
+    return invoke<LiveState>('live_next');
+  }
+
+  previous(): Promise<LiveState> {
+    return invoke<LiveState>('live_prev');
+  }
+
+  goTo(slideIndex: number): Promise<LiveState> {
+    return invoke<LiveState>('live_goto', { slideIndex });
+  }
+
+  /** Toggles when `blank` is left out */
+  blank(blank?: boolean): Promise<LiveState> {
+    return invoke<LiveState>('live_blank', { blank: blank ?? null });
+  }
+
+  /** Toggles when `freeze` is left out */
+  freeze(freeze?: boolean): Promise<LiveState> {
+    return invoke<LiveState>('live_freeze', { freeze: freeze ?? null });
+  }
+
+  /** Lifts blank and freeze; the position is kept */
+  stop(): Promise<LiveState> {
+    return invoke<LiveState>('live_stop');
+  }
+
+  getState(): Promise<LiveState> {
+    return invoke<LiveState>('get_live_state');
+  }
+}
+
+export const liveSessionService = new LiveSessionService();
@@ -1,3 +1,4 @@
+import { invoke } from '@tauri-apps/api/core';
 import { Presentation } from '../domain/entities/Presentation';
 import { Slide } from '../domain/entities/Slide';
 import { Template, TemplateDefinition } from '../domain/entities/Template';
@@ -47,6 +48,11 @@ export class PresentationService {
       throw new Error(`Template ${presentation.templateId} not found`);
     }
 
+    // Feeds the recent files list; failing to record it shouldn't block opening
+    invoke('mark_presentation_opened', { presentationId: id }).catch(error =>
+      console.error('Failed to record recent presentation:', error)
+    );
+
     return { presentation, slides, template, variables };
   }
 
@@ -229,15 +235,11 @@ export class PresentationService {
   }
 
   /**
-   * Delete a presentation and all related data
+   * Move a presentation to the trash. Its slides, variables and rules are kept
+   * so it can be restored until the trash is purged.
    */
   async deletePresentation(id: string): Promise<void> {
-    await Promise.all([
-      slideRepository.deleteByPresentationId(id),
-      variableRepository.deleteByPresentationId(id),
-      ruleRepository.deleteByPresentationId(id),
-    ]);
-    await presentationRepository.delete(id);
+    await invoke('delete_presentation', { id });
   }
 
   /**
@@ -330,8 +332,8 @@ export class PresentationService {
    * Check if a template can be deleted (not referenced by any presentation)
    */
   async canDeleteTemplate(templateId: string): Promise<{ canDelete: boolean; usedByCount: number }> {
-    const presentations = await presentationRepository.getAll();
-    const usedBy = presentations.filter(p => p.templateId === templateId);
+    // Includes presentations in the trash, which still need their template to be restored
+    const usedBy = await presentationRepository.getByTemplateId(templateId);
     return { canDelete: usedBy.length === 0, usedByCount: usedBy.length };
   }
 
@@ -352,7 +354,8 @@ export class PresentationService {
    * Set a presentation as active
    */
   async setActivePresentation(id: string): Promise<void> {
-    await presentationRepository.setActive(id);
+    // Also restarts the live slide so the audience window follows the new presentation
+    await invoke('set_active_presentation', { presentationId: id });
   }
 
   /**
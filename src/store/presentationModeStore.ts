@@ -6,6 +6,9 @@ import { usePresentationDataStore } from './presentationDataStore';
 import { useRuleStore } from './ruleStore';
 import { monitorService } from '../services/MonitorService';
 import { audienceWindowService } from '../services/AudienceWindowService';
+import { liveSessionService, LiveState } from '../services/LiveSessionService';
+import { toast } from './toastStore';
+import i18n from '../i18n';
 
 interface PresentationModeState {
   isPresenting: boolean;
@@ -17,6 +20,8 @@ interface PresentationModeState {
   nextSlide: () => void;
   previousSlide: () => void;
   goToSlide: (index: number) => void;
+  /** Follow the backend's live session, however it was moved */
+  syncLiveState: (state: LiveState) => void;
 
   // Computed
   getMergedEnabledSlides: () => Slide[];
@@ -49,6 +54,20 @@ export const usePresentationModeStore = create<PresentationModeState>()((set, ge
 
     const index = Math.min(Math.max(startIndex ?? 0, 0), slides.length - 1);
 
+    // Don't open the output only to have the backend refuse to go live
+    const { currentPresentation: presentation } = usePresentationDataStore.getState();
+    if (presentation) {
+      const gaps = await liveSessionService.preflight(presentation).catch((err) => {
+        console.warn('Failed to run the go-live pre-flight:', err);
+        return [];
+      });
+      if (gaps.length > 0) {
+        const regions = gaps.map(g => `${g.slideOrder} (${g.regionId})`).join(', ');
+        toast.error(i18n.t('presentation:emptyRequiredRegions', { regions }));
+        return;
+      }
+    }
+
     // Check setting: should we use presenter view?
     const { appSettings } = usePresentationDataStore.getState();
     const usePresenterView = appSettings.presentationDisplay === 'presenterView';
@@ -66,8 +85,13 @@ export const usePresentationModeStore = create<PresentationModeState>()((set, ge
           getCurrentWindow().setFullscreen(true).catch((e) => {
             console.warn('Failed to enter fullscreen for presenter:', e);
           });
-          // Send initial slide index to audience
-          await audienceWindowService.emitSlideChange(index);
+          if (appSettings.stageDisplay) {
+            audienceWindowService.openStageWindow().then((opened) => {
+              if (!opened) console.warn('No spare display for the stage display');
+            }).catch((e) => {
+              console.warn('Failed to open the stage display:', e);
+            });
+          }
         } catch (err) {
           console.error('Failed to open audience window, falling back to single-window:', err);
           set({ isPresenting: true, isPresenterMode: false, currentSlideIndex: index });
@@ -89,12 +113,23 @@ export const usePresentationModeStore = create<PresentationModeState>()((set, ge
         console.warn('Failed to enter fullscreen:', err);
       });
     }
+
+    // The backend moves through the slides from here on
+    const { currentPresentation } = usePresentationDataStore.getState();
+    if (currentPresentation) {
+      await liveSessionService.load(currentPresentation, slides, index).catch((err) => {
+        console.warn('Failed to start the live session:', err);
+      });
+    }
   },
 
   stopPresentation: () => {
     const { isPresenterMode } = get();
     set({ isPresenting: false, isPresenterMode: false, currentSlideIndex: 0 });
 
+    // Don't leave the screen blanked or frozen for the next presentation
+    liveSessionService.stop().catch(() => {});
+
     // Always exit fullscreen on main window
     getCurrentWindow().setFullscreen(false).catch((err) => {
       console.warn('Failed to exit fullscreen:', err);
@@ -104,28 +139,27 @@ export const usePresentationModeStore = create<PresentationModeState>()((set, ge
       // Close audience window
       audienceWindowService.emitPresentationStop().catch(() => {});
       audienceWindowService.closeAudienceWindow().catch(() => {});
+      audienceWindowService.closeStageWindow().catch(() => {});
     }
   },
 
   nextSlide: () => {
-    const { currentSlideIndex } = get();
-    const slides = computeMergedSlides();
-    if (currentSlideIndex < slides.length - 1) {
-      set({ currentSlideIndex: currentSlideIndex + 1 });
-    }
+    liveSessionService.next().then(get().syncLiveState).catch(() => {});
   },
 
   previousSlide: () => {
-    const { currentSlideIndex } = get();
-    if (currentSlideIndex > 0) {
-      set({ currentSlideIndex: currentSlideIndex - 1 });
-    }
+    liveSessionService.previous().then(get().syncLiveState).catch(() => {});
   },
 
   goToSlide: (index) => {
-    const slides = computeMergedSlides();
-    if (index >= 0 && index < slides.length) {
-      set({ currentSlideIndex: index });
+    liveSessionService.goTo(index).then(get().syncLiveState).catch(() => {});
+  },
+
+  syncLiveState: (state) => {
+    const { isPresenting, currentSlideIndex } = get();
+    if (!isPresenting || state.slideIds.length === 0) return;
+    if (state.current.slideIndex !== currentSlideIndex) {
+      set({ currentSlideIndex: state.current.slideIndex });
     }
   },
 